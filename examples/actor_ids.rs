@@ -0,0 +1,49 @@
+use agency::{prelude::*, ActorId, AgencyBuilder};
+use std::{collections::HashMap, time::Instant};
+
+const ACTORS: usize = 20_000;
+const LOOKUPS: usize = 1_000_000;
+
+/// Stops as soon as it starts, like a short-lived per-request actor.
+struct Oneshot;
+
+#[async_trait]
+impl Actor for Oneshot {
+    type Msg = ();
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        ctx.stop();
+    }
+}
+
+async fn measure(label: &str, builder: AgencyBuilder) {
+    let (agency, handle) = builder.build();
+
+    let start = Instant::now();
+    let ids: Vec<ActorId> = (0..ACTORS).map(|_| agency.hire(Oneshot).id()).collect();
+    let hired = start.elapsed();
+
+    let start = Instant::now();
+    let mut map = HashMap::with_capacity(ACTORS);
+    for (i, id) in ids.iter().enumerate() {
+        map.insert(*id, i);
+    }
+    let mut found = 0;
+    for i in 0..LOOKUPS {
+        found += map[&ids[i % ACTORS]];
+    }
+    let keyed = start.elapsed();
+
+    println!(
+        "{}: hired {} actors in {:?}, then {} inserts and {} lookups in {:?} ({})",
+        label, ACTORS, hired, ACTORS, LOOKUPS, keyed, found
+    );
+    drop(agency);
+    handle.wait().await;
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    measure("uuid ids", Agency::builder()).await;
+    measure("sequential ids", Agency::builder().sequential_ids()).await;
+}
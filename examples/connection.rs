@@ -0,0 +1,126 @@
+use agency::{
+    prelude::*, CurrentState, State, StateMachine, StateMachineMsg, Transition, UnhandledPolicy,
+};
+
+enum ConnectionEvent {
+    Connected,
+    Send(String),
+    Drain,
+    Flushed,
+}
+
+impl From<ConnectionEvent> for StateMachineMsg<ConnectionEvent> {
+    fn from(event: ConnectionEvent) -> Self {
+        Self::Event(event)
+    }
+}
+
+type Ctx = Context<StateMachine<ConnectionEvent>>;
+
+struct Connecting;
+
+#[async_trait]
+impl State<ConnectionEvent> for Connecting {
+    fn name(&self) -> &'static str {
+        "connecting"
+    }
+
+    async fn on_enter(&mut self, _ctx: &mut Ctx) {
+        println!("connecting...");
+    }
+
+    async fn on_message(
+        self: Box<Self>,
+        _ctx: &mut Ctx,
+        msg: ConnectionEvent,
+    ) -> Transition<ConnectionEvent> {
+        match msg {
+            ConnectionEvent::Connected => Transition::to(Ready { sent: 0 }),
+            msg => Transition::Unhandled(self, msg),
+        }
+    }
+}
+
+struct Ready {
+    sent: usize,
+}
+
+#[async_trait]
+impl State<ConnectionEvent> for Ready {
+    fn name(&self) -> &'static str {
+        "ready"
+    }
+
+    async fn on_enter(&mut self, _ctx: &mut Ctx) {
+        println!("connected");
+    }
+
+    async fn on_message(
+        mut self: Box<Self>,
+        _ctx: &mut Ctx,
+        msg: ConnectionEvent,
+    ) -> Transition<ConnectionEvent> {
+        match msg {
+            ConnectionEvent::Send(payload) => {
+                self.sent += 1;
+                println!("sending #{}: {}", self.sent, payload);
+                Transition::Stay(self)
+            }
+            ConnectionEvent::Drain => Transition::to(Draining),
+            msg => Transition::Unhandled(self, msg),
+        }
+    }
+}
+
+struct Draining;
+
+#[async_trait]
+impl State<ConnectionEvent> for Draining {
+    fn name(&self) -> &'static str {
+        "draining"
+    }
+
+    async fn on_enter(&mut self, _ctx: &mut Ctx) {
+        println!("draining...");
+    }
+
+    async fn on_message(
+        self: Box<Self>,
+        _ctx: &mut Ctx,
+        msg: ConnectionEvent,
+    ) -> Transition<ConnectionEvent> {
+        match msg {
+            ConnectionEvent::Flushed => {
+                println!("closed");
+                Transition::Stop
+            }
+            msg => Transition::Unhandled(self, msg),
+        }
+    }
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let (agency, handle) = Agency::new();
+    let connection =
+        agency.hire(StateMachine::new(Connecting).on_unhandled(UnhandledPolicy::Stash));
+
+    // Sent before the connection is ready, so this is stashed until we're connected
+    connection
+        .send(ConnectionEvent::Send("hello".into()))
+        .await
+        .unwrap();
+    println!("state: {}", connection.request(CurrentState).await.unwrap());
+
+    connection.send(ConnectionEvent::Connected).await.unwrap();
+    connection
+        .send(ConnectionEvent::Send("world".into()))
+        .await
+        .unwrap();
+    println!("state: {}", connection.request(CurrentState).await.unwrap());
+
+    connection.send(ConnectionEvent::Drain).await.unwrap();
+    connection.send(ConnectionEvent::Flushed).await.unwrap();
+
+    handle.wait().await;
+}
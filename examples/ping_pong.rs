@@ -0,0 +1,68 @@
+use agency::{prelude::*, Either};
+
+struct Ping(u32);
+
+/// Replies to every ping with the count it was sent, stopping after the third.
+struct Ponger;
+
+#[async_trait]
+impl Actor for Ponger {
+    type Msg = Request<Ping, u32>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        ctx.dispatch(self).await
+    }
+}
+
+#[async_trait]
+impl Handler for Ponger {
+    async fn handle(&mut self, ctx: &mut Context<Self>, request: Self::Msg) {
+        let count = request.payload().0;
+        // Already counted against the actor if the pinger gave up waiting
+        let _ = request.respond(count);
+        if count == 3 {
+            ctx.stop();
+        }
+    }
+}
+
+/// Pings the ponger a few times, one at a time, then stops. Each ping is raced against its own
+/// mailbox, so it can still be told to stop while waiting for a pong.
+struct Pinger {
+    ponger: Addr<Ponger>,
+    count: u32,
+}
+
+#[async_trait]
+impl Actor for Pinger {
+    type Msg = ();
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        self.count += 1;
+        let ping = self.ponger.request(Ping(self.count));
+        match ctx.select(ping).await {
+            Either::Left(()) => {
+                println!("told to stop while waiting for pong {}", self.count);
+                ctx.stop();
+            }
+            Either::Right(Ok(pong)) => {
+                println!("pong {}", pong);
+                if pong == 3 {
+                    ctx.stop();
+                }
+            }
+            Either::Right(Err(err)) => {
+                println!("ponger went away: {}", err);
+                ctx.stop();
+            }
+        }
+    }
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let (agency, handle) = Agency::new();
+    let ponger = agency.hire(Ponger);
+    agency.hire(Pinger { ponger, count: 0 });
+    handle.wait().await;
+}
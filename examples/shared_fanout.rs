@@ -0,0 +1,96 @@
+use agency::{prelude::*, RecipientGroup, SharedRecipient};
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+const SUBSCRIBERS: usize = 50;
+const SNAPSHOT_SIZE: usize = 1024 * 1024;
+const ROUNDS: usize = 20;
+
+/// Receives its own copy of every snapshot, stopping after the last.
+struct CopySubscriber {
+    received: usize,
+}
+
+#[async_trait]
+impl Actor for CopySubscriber {
+    type Msg = Vec<u8>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        ctx.message().await;
+        self.received += 1;
+        if self.received == ROUNDS {
+            ctx.stop();
+        }
+    }
+}
+
+/// Receives a handle on the one snapshot, noting where it lives, stopping after the last.
+struct SharedSubscriber {
+    received: usize,
+    seen: Arc<Mutex<HashSet<usize>>>,
+}
+
+#[async_trait]
+impl Actor for SharedSubscriber {
+    type Msg = Arc<Vec<u8>>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let snapshot = ctx.message().await;
+        self.seen
+            .lock()
+            .unwrap()
+            .insert(Arc::as_ptr(&snapshot) as usize);
+        self.received += 1;
+        if self.received == ROUNDS {
+            ctx.stop();
+        }
+    }
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let (agency, handle) = Agency::new();
+    let snapshot = vec![0u8; SNAPSHOT_SIZE];
+
+    let mut copies = RecipientGroup::new();
+    for _ in 0..SUBSCRIBERS {
+        copies.add(agency.hire(CopySubscriber { received: 0 }));
+    }
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        copies.send(snapshot.clone()).await;
+    }
+    println!(
+        "cloned fan-out: {:?} per snapshot",
+        start.elapsed() / ROUNDS as u32
+    );
+
+    let seen = Arc::new(Mutex::new(HashSet::new()));
+    let mut shared = RecipientGroup::new();
+    for _ in 0..SUBSCRIBERS {
+        let subscriber = agency.hire(SharedSubscriber {
+            received: 0,
+            seen: seen.clone(),
+        });
+        shared.add(SharedRecipient::new(subscriber));
+    }
+    let snapshot = Arc::new(snapshot);
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        shared.send_shared(snapshot.clone()).await;
+    }
+    println!(
+        "shared fan-out: {:?} per snapshot",
+        start.elapsed() / ROUNDS as u32
+    );
+
+    drop((copies, shared, agency));
+    handle.wait().await;
+    println!(
+        "shared subscribers saw {} distinct snapshot(s)",
+        seen.lock().unwrap().len()
+    );
+}
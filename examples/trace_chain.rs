@@ -0,0 +1,98 @@
+use agency::{prelude::*, TraceId};
+use std::sync::{Arc, Mutex};
+
+type Seen = Arc<Mutex<Vec<(&'static str, Option<TraceId>)>>>;
+
+/// Looks orders up for the checkout, answering each request with the order's total.
+struct Orders {
+    seen: Seen,
+}
+
+#[async_trait]
+impl Actor for Orders {
+    type Msg = Request<u32, u32>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let request = ctx.message().await;
+        self.seen
+            .lock()
+            .unwrap()
+            .push(("orders", ctx.current_trace()));
+        let total = request.payload() * 100;
+        let _ = request.respond(total);
+        ctx.stop();
+    }
+}
+
+/// Asks the orders actor for an order's total as part of checking it out.
+struct Checkout {
+    orders: Addr<Orders>,
+    seen: Seen,
+}
+
+#[async_trait]
+impl Actor for Checkout {
+    type Msg = u32;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let order = ctx.message().await;
+        self.seen
+            .lock()
+            .unwrap()
+            .push(("checkout", ctx.current_trace()));
+        match self.orders.request(order).await {
+            Ok(total) => println!("order {} comes to {}", order, total),
+            Err(err) => println!("orders went away: {}", err),
+        }
+        ctx.stop();
+    }
+}
+
+/// Takes orders from outside the agency and passes them on to be checked out.
+struct Front {
+    checkout: Addr<Checkout>,
+    seen: Seen,
+}
+
+#[async_trait]
+impl Actor for Front {
+    type Msg = u32;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let order = ctx.message().await;
+        self.seen
+            .lock()
+            .unwrap()
+            .push(("front", ctx.current_trace()));
+        let _ = self.checkout.send(order).await;
+        ctx.stop();
+    }
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let (agency, handle) = Agency::new();
+    let seen = Seen::default();
+    let orders = agency.hire(Orders { seen: seen.clone() });
+    let checkout = agency.hire(Checkout {
+        orders,
+        seen: seen.clone(),
+    });
+    let front = agency.hire(Front {
+        checkout,
+        seen: seen.clone(),
+    });
+
+    // Sent from outside any trace, so this starts a new one
+    let _ = front.send(7u32).await;
+    drop(agency);
+    handle.wait().await;
+
+    let seen = seen.lock().unwrap();
+    for (actor, trace) in seen.iter() {
+        println!("{} handled it as part of trace {:?}", actor, trace);
+    }
+    assert_eq!(seen.len(), 3);
+    assert!(seen[0].1.is_some());
+    assert!(seen.iter().all(|(_, trace)| *trace == seen[0].1));
+}
@@ -1,29 +1,166 @@
 use crate::context::{Context, Stopped};
 use async_trait::async_trait;
+use std::{any::Any, error::Error, fmt::Display, ops::ControlFlow};
 
+/// What an actor does once it has been asked to stop, returned from [`Actor::stopping`] and
+/// [`Actor::on_panic`].
 pub enum StoppingResult {
+    /// Carry on running, restarting the run loop with the same context and mailboxes.
+    ///
+    /// Nothing sent to the actor is lost across a recovery, whether it was queued before the actor
+    /// started stopping or sent while it was deciding. Messages are received in the same order as
+    /// if the actor had never stopped: stashed and replayed messages first, then priority messages
+    /// in the order they were sent, then regular ones in the order they were sent.
     Recover,
     Stop,
 }
 
+/// Why an actor is stopping, passed to [`Actor::on_stop`] and kept on its context, see
+/// [`Context::stop_reason`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StopReason {
+    /// The actor called [`Context::stop`].
+    SelfStopped,
+    /// The actor was asked to stop with [`Addr::stop`](crate::Addr::stop), or by a supervisor
+    /// or parent.
+    Requested,
+    /// The agency is shutting down, see [`Agency::shutdown`](crate::Agency::shutdown).
+    AgencyShutdown,
+    /// Every address to the actor besides its context's was dropped, so nothing more could
+    /// arrive in its mailbox, see [`Context::detach`].
+    ///
+    /// This is as close as an actor gets to its mailbox closing: the context holds a sender of
+    /// its own, so the mailboxes only close once the actor has stopped.
+    Orphaned,
+    /// The actor waited longer than its idle timeout for a message, see
+    /// [`Context::set_idle_timeout`].
+    IdleTimeout,
+    /// `init` or `run` panicked, see [`Actor::on_panic`].
+    Panicked,
+}
+
+/// Details of a panic caught while an actor was running, passed to [`Actor::on_panic`].
+#[derive(Debug, Clone)]
+pub struct PanicInfo {
+    /// The panic message, if the panic was raised with a string.
+    pub payload: Option<String>,
+    /// The name of the message being handled, if the actor was dispatching it with
+    /// [`Context::dispatch`](crate::Context::dispatch).
+    pub message: Option<&'static str>,
+}
+
+impl PanicInfo {
+    pub(crate) fn new(payload: Box<dyn Any + Send>, message: Option<&'static str>) -> Self {
+        let payload = match payload.downcast::<String>() {
+            Ok(payload) => Some(*payload),
+            Err(payload) => payload.downcast_ref::<&str>().map(|s| s.to_string()),
+        };
+        Self { payload, message }
+    }
+}
+
 #[async_trait]
 pub trait Actor: Send + Sync + Sized {
     type Msg: 'static + Send + Sync;
 
     async fn run(&mut self, ctx: &mut Context<Self>);
 
+    /// Whether a message should always go to the priority mailbox, whichever method it's sent
+    /// with, such as the control variants of a message enum that also carries bulk data.
+    ///
+    /// Messages routed this way skip the regular mailbox's backpressure, since the priority
+    /// mailbox is unbounded, so only mark messages that are small and infrequent.
+    fn is_priority(_msg: &Self::Msg) -> bool {
+        false
+    }
+
+    /// The name this actor's tracing span is recorded under. Defaults to the actor's type name.
+    #[cfg(feature = "tracing")]
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+
     async fn init(&mut self, _ctx: &mut Context<Self>) {}
 
-    /// Called after ctx.stop() is called.
+    /// Called in place of [`Actor::init`], with the chance to refuse to start.
+    ///
+    /// Breaking with an [`InitAbort`] skips the run loop, along with [`Actor::stopping`] and
+    /// [`Actor::stopped`]. The mailboxes are closed straight away, anything already queued is
+    /// reported to the agency's [`Observer::dead_letter`](crate::Observer::dead_letter), and the
+    /// abort itself to [`Observer::init_aborted`](crate::Observer::init_aborted). Defaults to
+    /// calling [`Actor::init`] and continuing.
+    async fn try_init(&mut self, ctx: &mut Context<Self>) -> ControlFlow<InitAbort> {
+        self.init(ctx).await;
+        ControlFlow::Continue(())
+    }
+
+    /// Called after ctx.stop() is called, or the actor is otherwise asked to stop.
     ///
-    /// Can be used to restart try and recover the actor and restart the run loop.
+    /// Can be used to restart try and recover the actor and restart the run loop. Messages keep
+    /// arriving in the mailboxes while this runs, and can be received here with
+    /// [`Context::message`] like anywhere else. Whatever's left is picked up by the recovered run
+    /// loop, see [`StoppingResult::Recover`].
     async fn stopping(&mut self, _ctx: &mut Context<Self>) -> StoppingResult {
         StoppingResult::Stop
     }
 
+    /// Called in place of [`Actor::stopping`], with why the actor is stopping. Defaults to
+    /// calling [`Actor::stopping`].
+    async fn on_stop(&mut self, ctx: &mut Context<Self>, _reason: StopReason) -> StoppingResult {
+        self.stopping(ctx).await
+    }
+
+    /// Called after `init` or `run` panics, in place of [`Actor::on_stop`].
+    ///
+    /// Returning [`StoppingResult::Recover`] restarts the run loop with the actor as the panic
+    /// left it. If the actor stops, it's treated as having panicked, for instance by a
+    /// supervisor, and reported by [`AgencyHandle::wait`](crate::AgencyHandle::wait). Defaults to
+    /// calling [`Actor::on_stop`] with [`StopReason::Panicked`].
+    async fn on_panic(&mut self, ctx: &mut Context<Self>, _panic: PanicInfo) -> StoppingResult {
+        self.on_stop(ctx, StopReason::Panicked).await
+    }
+
+    /// Called once the actor has finished stopping, with the remains of its context.
     async fn stopped(self, _ctx: Context<Self, Stopped>) {}
+
+    /// Called in place of [`Actor::stopped`], with the chance to hand the actor back to the
+    /// [`ActorHandle`](crate::ActorHandle) it was hired with, so its final state can be
+    /// inspected. Return `None` to consume it here instead.
+    ///
+    /// Defaults to handing the actor back if it was hired with one, without calling
+    /// [`Actor::stopped`], and calling [`Actor::stopped`] otherwise.
+    async fn on_stopped(self, ctx: Context<Self, Stopped>) -> Option<Self> {
+        if ctx.is_joinable() {
+            return Some(self);
+        }
+        self.stopped(ctx).await;
+        None
+    }
 }
 
+/// Why an actor refused to start, returned from [`Actor::try_init`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InitAbort {
+    pub reason: String,
+}
+
+impl InitAbort {
+    pub fn new(reason: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+        }
+    }
+}
+
+impl Display for InitAbort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "init aborted: {}", self.reason)
+    }
+}
+
+impl Error for InitAbort {}
+
 #[async_trait]
 pub trait Setup: Actor {
     type Args: Send + Sync;
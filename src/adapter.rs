@@ -0,0 +1,121 @@
+#[cfg(feature = "tower")]
+use crate::addr::RecipientPermit;
+use crate::addr::{DeliveryError, Recipient, RecipientSender};
+use async_trait::async_trait;
+#[cfg(feature = "tower")]
+use futures_util::future::{BoxFuture, FutureExt};
+use std::{sync::Arc, time::Duration};
+
+type Convert<N, M> = Arc<dyn Fn(N) -> Option<M> + Send + Sync>;
+
+/// Converts each message before passing it on to another recipient, see
+/// [`Recipient::filter_map`].
+pub(crate) struct FilterMap<N, M>
+where
+    M: 'static,
+{
+    recipient: Recipient<M>,
+    convert: Convert<N, M>,
+}
+
+impl<N, M> FilterMap<N, M>
+where
+    M: 'static + Send,
+{
+    pub(crate) fn new<F>(recipient: Recipient<M>, convert: F) -> Self
+    where
+        F: Fn(N) -> Option<M> + Send + Sync + 'static,
+    {
+        Self {
+            recipient,
+            convert: Arc::new(convert),
+        }
+    }
+}
+
+impl<N, M> Clone for FilterMap<N, M> {
+    fn clone(&self) -> Self {
+        Self {
+            recipient: self.recipient.clone(),
+            convert: self.convert.clone(),
+        }
+    }
+}
+
+// Only the message as sent can be handed back, so each send checks for a stopped recipient before
+// converting it. Once converted, a message the recipient refuses is dropped.
+#[async_trait]
+impl<N, M> RecipientSender<N> for FilterMap<N, M>
+where
+    N: 'static + Send,
+    M: 'static + Send,
+{
+    async fn send_to_recipient(&self, msg: N) -> Result<(), DeliveryError<N>> {
+        if self.recipient.is_closed() {
+            return Err(DeliveryError::Closed(msg));
+        }
+        if let Some(msg) = (self.convert)(msg) {
+            let _ = self.recipient.send(msg).await;
+        }
+        Ok(())
+    }
+
+    fn try_send_to_recipient(&self, msg: N) -> Result<(), DeliveryError<N>> {
+        if self.recipient.is_closed() {
+            return Err(DeliveryError::Closed(msg));
+        }
+        if let Some(msg) = (self.convert)(msg) {
+            let _ = self.recipient.try_send(msg);
+        }
+        Ok(())
+    }
+
+    async fn send_timeout_to_recipient(
+        &self,
+        msg: N,
+        timeout: Duration,
+    ) -> Result<(), DeliveryError<N>> {
+        if self.recipient.is_closed() {
+            return Err(DeliveryError::Closed(msg));
+        }
+        if let Some(msg) = (self.convert)(msg) {
+            let _ = self.recipient.send_timeout(msg, timeout).await;
+        }
+        Ok(())
+    }
+
+    fn send_priority_to_recipient(&self, msg: N) -> Result<(), DeliveryError<N>> {
+        if self.recipient.is_closed() {
+            return Err(DeliveryError::Closed(msg));
+        }
+        if let Some(msg) = (self.convert)(msg) {
+            let _ = self.recipient.send_priority(msg);
+        }
+        Ok(())
+    }
+
+    fn load(&self) -> Option<usize> {
+        self.recipient.load()
+    }
+
+    fn is_closed(&self) -> bool {
+        self.recipient.is_closed()
+    }
+
+    #[cfg(feature = "tower")]
+    fn reserve_recipient(
+        &self,
+    ) -> BoxFuture<'static, Result<RecipientPermit<N>, DeliveryError<()>>> {
+        let convert = self.convert.clone();
+        let reserve = self.recipient.reserve();
+        async move {
+            let permit = reserve.await?;
+            Ok(RecipientPermit::new(move |msg| {
+                if let Some(msg) = convert(msg) {
+                    permit.send(msg);
+                }
+            }))
+        }
+        .boxed()
+    }
+}
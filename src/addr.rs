@@ -1,25 +1,565 @@
+#[cfg(feature = "chaos")]
+use crate::chaos::{Fault, Holdback};
+use crate::id::ActorId;
+#[cfg(feature = "journal")]
+use crate::journal::JournalState;
 use crate::{
     actor::Actor,
-    request::{Request, RequestError, RequestTimeoutError},
+    adapter::FilterMap,
+    agency::AgencyLink,
+    balanced::Balanced,
+    journal::Queue,
+    mailbox::{MailboxFactory, Mailer, Permit, WeakMailer},
+    observer::{DeadLetter, ResponseUndelivered},
+    priority::PrioritySlot,
+    request::{Ask, AskError, Request, RequestError, RequestTimeoutError},
+    session::{Session, SessionHandle},
+    stats::{ActorStats, ActorStatsSnapshot},
 };
 use async_trait::async_trait;
 use dyn_clone::DynClone;
+#[cfg(feature = "tower")]
+use futures_util::future::{BoxFuture, FutureExt};
 use std::{
+    any::Any,
+    borrow::Cow,
+    convert::{Infallible, TryInto},
     error::Error,
     fmt::{Debug, Display},
+    future::Future,
     hash::Hash,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, Weak,
+    },
+    task::{Context, Poll},
     time::Duration,
 };
-use tokio::{sync::mpsc, time::timeout};
-use uuid::Uuid;
+use tokio::{
+    sync::{mpsc, oneshot, watch, Notify},
+    task,
+    time::{timeout, Instant},
+};
+
+/// Record the actor being sent to on the current span, if it has a `sent_to` field for it, see
+/// [`Addr::send`].
+#[cfg(feature = "tracing")]
+fn record_target(id: ActorId) {
+    tracing::Span::current().record("sent_to", tracing::field::display(id));
+}
+
+/// How an actor's task finished.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Exit {
+    Stopped,
+    SetupFailed,
+    InitAborted,
+    Panicked,
+    Aborted,
+}
+
+impl Exit {
+    pub(crate) fn is_failure(self) -> bool {
+        matches!(self, Self::Panicked | Self::Aborted)
+    }
+}
+
+tokio::task_local! {
+    /// The actor whose task this is, so responses it fails to deliver are counted against it.
+    pub(crate) static RESPONDER: Responder;
+}
+
+pub(crate) struct Responder {
+    inner: Arc<AddrInner>,
+    actor_type: &'static str,
+}
+
+impl Responder {
+    pub(crate) fn new<A: Actor>(inner: Arc<AddrInner>) -> Self {
+        Self {
+            inner,
+            actor_type: std::any::type_name::<A>(),
+        }
+    }
+}
+
+/// The id of the actor running in the current task, if there is one.
+pub(crate) fn current_actor() -> Option<ActorId> {
+    RESPONDER.try_with(|responder| responder.inner.id).ok()
+}
+
+/// Count a delivered response against the actor running in the current task, if there is one.
+pub(crate) fn response_delivered() {
+    let _ = RESPONDER.try_with(|responder| responder.inner.stats.response_delivered());
+}
+
+/// Count a response that couldn't be delivered against the actor running in the current task, if
+/// there is one.
+pub(crate) fn response_undelivered<Res>() {
+    let _ = RESPONDER.try_with(|responder| {
+        let inner = &responder.inner;
+        inner.stats.response_undelivered();
+        inner.agency.response_undelivered(&ResponseUndelivered {
+            actor_id: inner.id,
+            actor_type: responder.actor_type,
+            response: std::any::type_name::<Res>(),
+        });
+    });
+}
+
+type Mailers<M> = (Mailer<M>, Arc<PrioritySlot<M>>);
+type WeakMailers<M> = (WeakMailer<M>, Weak<PrioritySlot<M>>);
+
+/// State shared between an actor's context and every address that refers to it.
+pub(crate) struct AddrInner {
+    id: ActorId,
+    stop: watch::Sender<bool>,
+    pause: watch::Sender<bool>,
+    interrupt: Notify,
+    exit: watch::Sender<Option<Exit>>,
+    /// Set once the actor has been set up and initialised.
+    ready: watch::Sender<bool>,
+    stats: ActorStats,
+    name: Mutex<Option<String>>,
+    /// Shared by every address besides the context's own, so the actor can tell once nobody
+    /// else can reach it.
+    leases: Mutex<Weak<Lease>>,
+    /// Set while no address besides the context's own is left.
+    orphaned: watch::Sender<bool>,
+    /// Whether the actor keeps running once orphaned, see [`Context::detach`](crate::Context::detach).
+    detached: AtomicBool,
+    /// The task running the actor, once it's been spawned.
+    task: Mutex<Option<task::AbortHandle>>,
+    /// The actor to take over once the current one stops, see [`Agency::replace`](crate::Agency::replace).
+    replacement: Mutex<Option<Box<dyn Any + Send>>>,
+    /// Where to hand the actor back once it stops, for an [`ActorHandle`](crate::ActorHandle).
+    joiner: Mutex<Option<Box<dyn Any + Send>>>,
+    /// Weak senders for the mailboxes of the latest incarnation, once the actor's been respawned,
+    /// so addresses still holding the old, closed mailboxes can follow it.
+    respawned: Mutex<Option<Box<dyn Any + Send + Sync>>>,
+    /// The `MailboxFactory` the actor was hired with, if it doesn't use the default mailbox, so
+    /// respawns get the same kind.
+    mailbox: std::sync::OnceLock<Box<dyn Any + Send + Sync>>,
+    /// The capacity the actor was hired with, if it isn't the agency's, so respawns get the
+    /// same.
+    capacity: std::sync::OnceLock<usize>,
+    /// The actor's `JournalState`, if it has one.
+    #[cfg(feature = "journal")]
+    journal: std::sync::OnceLock<Box<dyn Any + Send + Sync>>,
+    #[cfg(feature = "chaos")]
+    holdback: Holdback,
+    agency: AgencyLink,
+}
+
+impl AddrInner {
+    fn new(agency: AgencyLink) -> Self {
+        Self {
+            id: agency.next_id(),
+            stop: watch::channel(false).0,
+            pause: watch::channel(false).0,
+            interrupt: Notify::new(),
+            exit: watch::channel(None).0,
+            ready: watch::channel(false).0,
+            stats: ActorStats::new(),
+            name: Mutex::new(None),
+            leases: Mutex::new(Weak::new()),
+            orphaned: watch::channel(false).0,
+            detached: AtomicBool::new(false),
+            task: Mutex::new(None),
+            respawned: Mutex::new(None),
+            mailbox: std::sync::OnceLock::new(),
+            capacity: std::sync::OnceLock::new(),
+            #[cfg(feature = "journal")]
+            journal: std::sync::OnceLock::new(),
+            #[cfg(feature = "chaos")]
+            holdback: Holdback::default(),
+            replacement: Mutex::new(None),
+            joiner: Mutex::new(None),
+            agency,
+        }
+    }
+
+    pub(crate) fn id(&self) -> ActorId {
+        self.id
+    }
+
+    pub(crate) fn stats(&self) -> &ActorStats {
+        &self.stats
+    }
+
+    pub(crate) fn name(&self) -> Option<String> {
+        self.name.lock().unwrap().clone()
+    }
+
+    pub(crate) fn task_id(&self) -> Option<task::Id> {
+        self.task
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(task::AbortHandle::id)
+    }
+
+    pub(crate) fn set_task(&self, task: task::AbortHandle) {
+        *self.task.lock().unwrap() = Some(task);
+    }
+
+    /// Abort the actor's task, if it's been spawned as one.
+    pub(crate) fn abort(&self) {
+        if let Some(task) = &*self.task.lock().unwrap() {
+            task.abort();
+        }
+    }
+
+    /// Claim a stopped actor for respawning, returning false if it's still running or someone else
+    /// got there first.
+    pub(crate) fn begin_respawn(&self) -> bool {
+        let claimed = self.exit.send_if_modified(|exit| exit.take().is_some());
+        if claimed {
+            self.clear_stop();
+            self.clear_pause();
+            self.ready.send_replace(false);
+        }
+        claimed
+    }
+
+    #[cfg(feature = "journal")]
+    pub(crate) fn journal<M: 'static>(&self) -> Option<&JournalState<M>> {
+        self.journal.get()?.downcast_ref()
+    }
+
+    #[cfg(feature = "journal")]
+    pub(crate) fn set_journal<M: 'static>(&self, journal: JournalState<M>) {
+        let _ = self.journal.set(Box::new(journal));
+    }
+
+    pub(crate) fn mailbox_factory<M: 'static>(&self) -> Option<&MailboxFactory<M>> {
+        self.mailbox.get()?.downcast_ref()
+    }
+
+    pub(crate) fn set_mailbox_factory<M: 'static>(&self, factory: MailboxFactory<M>) {
+        let _ = self.mailbox.set(Box::new(factory));
+    }
+
+    pub(crate) fn capacity(&self) -> Option<usize> {
+        self.capacity.get().copied()
+    }
+
+    pub(crate) fn set_capacity(&self, capacity: usize) {
+        let _ = self.capacity.set(capacity);
+    }
+
+    /// Put a message in a mailbox with `send`, recording it in the actor's journal first if it
+    /// has one.
+    #[cfg_attr(not(feature = "journal"), allow(unused_variables))]
+    pub(crate) fn enqueue<M: 'static, T>(
+        &self,
+        queue: Queue,
+        msg: M,
+        send: impl FnOnce(M) -> Result<(), T>,
+    ) -> Result<(), T> {
+        #[cfg(feature = "journal")]
+        if let Some(journal) = self.journal::<M>() {
+            return journal.enqueue(queue, msg, send);
+        }
+        send(msg)
+    }
+
+    fn respawned_mailers<M: 'static>(&self) -> Option<Mailers<M>> {
+        let respawned = self.respawned.lock().unwrap();
+        let (mailer, priority_mailer) = respawned.as_ref()?.downcast_ref::<WeakMailers<M>>()?;
+        Some((mailer.upgrade()?, priority_mailer.upgrade()?))
+    }
+
+    pub(crate) fn set_name(&self, name: String) {
+        *self.name.lock().unwrap() = Some(name);
+    }
+
+    /// Ask the actor to stop the next time it waits for a message.
+    pub(crate) fn request_stop(&self) {
+        #[cfg(feature = "tracing")]
+        if !*self.stop.borrow() {
+            tracing::debug!(actor_id = %self.id, "stop requested");
+        }
+        self.stop.send_replace(true);
+    }
+
+    pub(crate) fn stop_requested(&self) -> bool {
+        *self.stop.borrow()
+    }
+
+    /// Forget a stop request, used when the actor recovers from stopping.
+    pub(crate) fn clear_stop(&self) {
+        self.stop.send_replace(false);
+    }
+
+    pub(crate) fn stop_signal(&self) -> watch::Receiver<bool> {
+        self.stop.subscribe()
+    }
+
+    /// A lease for a new address, bringing the actor back from being orphaned if need be.
+    fn lease(self: &Arc<Self>) -> Arc<Lease> {
+        let mut leases = self.leases.lock().unwrap();
+        if let Some(lease) = leases.upgrade() {
+            return lease;
+        }
+        let lease = Arc::new(Lease(Arc::downgrade(self)));
+        *leases = Arc::downgrade(&lease);
+        self.orphaned.send_replace(false);
+        lease
+    }
+
+    pub(crate) fn is_orphaned(&self) -> bool {
+        *self.orphaned.borrow()
+    }
+
+    pub(crate) fn orphan_signal(&self) -> watch::Receiver<bool> {
+        self.orphaned.subscribe()
+    }
+
+    pub(crate) fn detach(&self) {
+        self.detached.store(true, Ordering::Relaxed);
+    }
+
+    pub(crate) fn is_detached(&self) -> bool {
+        self.detached.load(Ordering::Relaxed)
+    }
+
+    /// Queue up an actor to take over the mailboxes once the current one stops, replacing any
+    /// that's already queued.
+    pub(crate) fn set_replacement<A: 'static + Actor>(&self, actor: A) {
+        *self.replacement.lock().unwrap() = Some(Box::new(actor));
+    }
+
+    pub(crate) fn take_replacement<A: 'static + Actor>(&self) -> Option<A> {
+        let replacement = self.replacement.lock().unwrap().take()?;
+        replacement.downcast().ok().map(|actor| *actor)
+    }
+
+    /// Whether there's an [`ActorHandle`](crate::ActorHandle) to hand the actor back to.
+    pub(crate) fn is_joinable(&self) -> bool {
+        self.joiner.lock().unwrap().is_some()
+    }
+
+    pub(crate) fn set_joiner<A: 'static + Actor>(&self, joiner: oneshot::Sender<A>) {
+        *self.joiner.lock().unwrap() = Some(Box::new(joiner));
+    }
+
+    /// Hand a stopped actor back to its [`ActorHandle`](crate::ActorHandle), if it has one.
+    pub(crate) fn join<A: 'static + Actor>(&self, actor: A) {
+        let joiner = self.joiner.lock().unwrap().take();
+        if let Some(joiner) = joiner.and_then(|joiner| joiner.downcast::<oneshot::Sender<A>>().ok())
+        {
+            let _ = joiner.send(actor);
+        }
+    }
+
+    pub(crate) fn has_replacement(&self) -> bool {
+        self.replacement.lock().unwrap().is_some()
+    }
+
+    /// Ask the actor to pause the next time it waits for a message or finishes a call to `run`.
+    pub(crate) fn request_pause(&self) {
+        self.pause.send_replace(true);
+    }
+
+    pub(crate) fn clear_pause(&self) {
+        self.pause.send_replace(false);
+    }
+
+    pub(crate) fn pause_requested(&self) -> bool {
+        *self.pause.borrow()
+    }
+
+    pub(crate) fn pause_signal(&self) -> watch::Receiver<bool> {
+        self.pause.subscribe()
+    }
+
+    /// Wake the run loop so it abandons the current `run` call, used when a stop is observed
+    /// while the actor is parked waiting for a message.
+    pub(crate) fn interrupt(&self) {
+        self.interrupt.notify_waiters();
+    }
+
+    pub(crate) async fn interrupted(&self) {
+        self.interrupt.notified().await
+    }
+
+    pub(crate) fn has_exited(&self) -> bool {
+        self.exit.borrow().is_some()
+    }
+
+    pub(crate) fn exit_signal(&self) -> watch::Receiver<Option<Exit>> {
+        self.exit.subscribe()
+    }
+
+    pub(crate) fn set_ready(&self) {
+        self.ready.send_replace(true);
+    }
+
+    pub(crate) fn ready_signal(&self) -> watch::Receiver<bool> {
+        self.ready.subscribe()
+    }
+}
+
+/// What it takes to deliver to an actor from a background task, for messages the chaos policy
+/// held back.
+#[cfg(feature = "chaos")]
+struct Detached<M> {
+    inner: Arc<AddrInner>,
+    mailers: Mailers<M>,
+    dead_letter: DeadLetter,
+    /// Held until the message is delivered, as it would be if it were waiting in the mailbox.
+    _lease: Option<Arc<Lease>>,
+}
+
+#[cfg(feature = "chaos")]
+impl<M> Detached<M>
+where
+    M: 'static + Send,
+{
+    fn deliver_after(self, delay: Duration, queue: Queue, msg: M) {
+        let agency = self.inner.agency.clone();
+        let sleep = agency.sleep_until(Instant::now() + delay);
+        agency.spawn(async move {
+            sleep.await;
+            self.deliver(queue, msg).await;
+        });
+    }
+
+    fn release(self, queue: Queue, msg: M) {
+        let agency = self.inner.agency.clone();
+        agency.spawn(async move { self.deliver(queue, msg).await });
+    }
+
+    /// Deliver a held back message once its window has passed, unless a later message has
+    /// released it already.
+    fn release_after(self, window: Duration, ticket: u64) {
+        let agency = self.inner.agency.clone();
+        let sleep = agency.sleep_until(Instant::now() + window);
+        agency.spawn(async move {
+            sleep.await;
+            if let Some((queue, msg)) = self.inner.holdback.take(Some(ticket)) {
+                self.deliver(queue, msg).await;
+            }
+        });
+    }
+
+    async fn deliver(&self, queue: Queue, msg: M) {
+        let (mailer, priority_mailer) = &self.mailers;
+        let stats = self.inner.stats();
+        let delivered = match queue {
+            Queue::Regular | Queue::Overflow => match mailer.reserve().await {
+                Ok(permit) => self.inner.enqueue(queue, msg, |msg| {
+                    permit.send(msg);
+                    Ok(())
+                }),
+                Err(_) => Err(msg),
+            },
+            Queue::Priority => {
+                stats.priority_enqueued();
+                self.inner.enqueue(queue, msg, |msg| {
+                    priority_mailer
+                        .send(msg)
+                        .inspect_err(|_| stats.priority_dequeued())
+                })
+            }
+        };
+        if delivered.is_err() {
+            self.inner.agency.dead_letter(&self.dead_letter);
+        }
+    }
+}
+
+/// Records how an actor's task finished, treating a task that never completes normally as
+/// having panicked or been aborted.
+pub(crate) struct ExitGuard {
+    inner: Arc<AddrInner>,
+    exit: Option<Exit>,
+}
+
+impl ExitGuard {
+    pub(crate) fn new(inner: Arc<AddrInner>) -> Self {
+        Self { inner, exit: None }
+    }
+
+    pub(crate) fn complete(mut self, exit: Exit) {
+        self.exit = Some(exit);
+    }
+}
+
+impl Drop for ExitGuard {
+    fn drop(&mut self) {
+        let exit = self.exit.unwrap_or(if std::thread::panicking() {
+            Exit::Panicked
+        } else {
+            Exit::Aborted
+        });
+        // Panics caught by the run loop have already been counted
+        if self.exit.is_none() && exit == Exit::Panicked {
+            self.inner.stats.error();
+        }
+        self.inner.stats.stopped();
+        self.inner.exit.send_replace(Some(exit));
+        // Only once the exit is recorded, so the handle can tell why there's no actor
+        self.inner.joiner.lock().unwrap().take();
+    }
+}
+
+/// Resolves once an actor has stopped, see [`Addr::watch`].
+pub struct StopListener {
+    stopped: Pin<Box<dyn Future<Output = ()> + Send>>,
+}
+
+impl StopListener {
+    pub(crate) fn new(mut exit: watch::Receiver<Option<Exit>>) -> Self {
+        Self {
+            stopped: Box::pin(async move {
+                let _ = exit.wait_for(Option::is_some).await;
+            }),
+        }
+    }
+}
+
+impl Future for StopListener {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        self.stopped.as_mut().poll(cx)
+    }
+}
+
+impl Debug for StopListener {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("StopListener(..)")
+    }
+}
+
+/// Held by every address besides an actor's own context's, orphaning the actor once the last
+/// one is dropped.
+struct Lease(Weak<AddrInner>);
+
+impl Drop for Lease {
+    fn drop(&mut self) {
+        if let Some(inner) = self.0.upgrade() {
+            let leases = inner.leases.lock().unwrap();
+            // Unless a new lease was taken out while this one was being dropped
+            if leases.strong_count() == 0 {
+                inner.orphaned.send_replace(true);
+            }
+        }
+    }
+}
 
 pub struct Addr<A>
 where
     A: Actor,
 {
-    id: Uuid,
-    mailer: mpsc::Sender<A::Msg>,
-    priority_mailer: mpsc::UnboundedSender<A::Msg>,
+    inner: Arc<AddrInner>,
+    mailer: Mailer<A::Msg>,
+    priority_mailer: Arc<PrioritySlot<A::Msg>>,
+    /// `None` for the context's own address.
+    lease: Option<Arc<Lease>>,
 }
 
 impl<A> Addr<A>
@@ -27,25 +567,428 @@ where
     A: Actor,
 {
     pub(crate) fn new(
-        mailer: mpsc::Sender<A::Msg>,
-        priority_mailer: mpsc::UnboundedSender<A::Msg>,
+        mailer: Mailer<A::Msg>,
+        priority_mailer: Arc<PrioritySlot<A::Msg>>,
+        agency: AgencyLink,
+    ) -> Self {
+        Self {
+            inner: Arc::new(AddrInner::new(agency)),
+            mailer,
+            priority_mailer,
+            lease: None,
+        }
+    }
+
+    /// A copy of this address that keeps the actor from being orphaned, for handing out from its
+    /// context.
+    pub(crate) fn leased(&self) -> Self {
+        Self {
+            lease: Some(self.inner.lease()),
+            ..self.clone()
+        }
+    }
+
+    /// Give the actor fresh mailboxes for a new incarnation, returning an address for them.
+    ///
+    /// Existing addresses switch over to the new mailboxes once they notice their own are closed.
+    pub(crate) fn reattach(
+        &self,
+        mailer: Mailer<A::Msg>,
+        priority_mailer: Arc<PrioritySlot<A::Msg>>,
     ) -> Self {
+        let weak: WeakMailers<A::Msg> = (mailer.downgrade(), Arc::downgrade(&priority_mailer));
+        #[cfg(feature = "journal")]
+        if let Some(journal) = self.inner.journal::<A::Msg>() {
+            journal.reset();
+        }
+        *self.inner.respawned.lock().unwrap() = Some(Box::new(weak));
         Self {
-            id: Uuid::new_v4(),
+            inner: self.inner.clone(),
             mailer,
             priority_mailer,
+            lease: None,
         }
     }
 
+    /// This address, or one for the actor's latest incarnation if it's been respawned since this
+    /// address was made.
+    fn current(&self) -> Cow<'_, Self> {
+        if !self.mailer.is_closed() {
+            return Cow::Borrowed(self);
+        }
+        match self.inner.respawned_mailers() {
+            Some((mailer, priority_mailer)) => Cow::Owned(Self {
+                inner: self.inner.clone(),
+                mailer,
+                priority_mailer,
+                lease: self.lease.clone(),
+            }),
+            None => Cow::Borrowed(self),
+        }
+    }
+
+    pub(crate) fn inner(&self) -> &Arc<AddrInner> {
+        &self.inner
+    }
+
+    pub(crate) fn mailer(&self) -> &Mailer<A::Msg> {
+        &self.mailer
+    }
+
+    /// The unique id of this actor, shared by every address and recipient that refers to it.
+    pub fn id(&self) -> ActorId {
+        self.inner.id
+    }
+
+    /// How many messages are waiting in this actor's priority mailbox.
+    ///
+    /// The priority mailbox is unbounded, so keep an eye on this if you send to it often.
+    pub fn priority_depth(&self) -> usize {
+        self.inner.stats().priority_depth()
+    }
+
+    /// How many messages are waiting in this actor's regular mailbox, not counting the
+    /// [`priority_depth`](Addr::priority_depth).
+    pub fn mailbox_len(&self) -> usize {
+        self.current().mailer.depth().0
+    }
+
+    /// How many messages fit in this actor's regular mailbox, set with
+    /// [`AgencyBuilder::capacity`](crate::AgencyBuilder::capacity) or when hiring it.
+    ///
+    /// Custom mailboxes report their own, see [`MailboxSender::capacity`](crate::MailboxSender::capacity).
+    pub fn mailbox_capacity(&self) -> usize {
+        self.current().mailer.depth().1
+    }
+
+    /// The id of the tokio task running this actor, for correlating it with tokio-console or a
+    /// runtime dump.
+    ///
+    /// This is only `None` when called from inside the actor before [`Agency::hire`] has returned.
+    ///
+    /// [`Agency::hire`]: crate::Agency::hire
+    pub fn task_id(&self) -> Option<task::Id> {
+        self.inner.task_id()
+    }
+
+    /// Ask the actor to stop, going through [`Actor::stopping`] as usual.
+    ///
+    /// The actor stops the next time it waits for a message, or once its current call to `run`
+    /// finishes. Actors waiting on something else for a long time can notice sooner with
+    /// [`Context::shutdown_requested`](crate::Context::shutdown_requested).
+    pub fn stop(&self) {
+        self.inner.request_stop();
+    }
+
+    /// Pause the actor, leaving messages to build up in its mailboxes until it's resumed.
+    ///
+    /// The actor pauses the next time it waits for a message, or once its current call to `run`
+    /// finishes.
+    pub fn pause(&self) {
+        self.inner.request_pause();
+    }
+
+    pub fn resume(&self) {
+        self.inner.clear_pause();
+    }
+
+    /// Whether the actor is currently paused, rather than just asked to pause.
+    pub fn is_paused(&self) -> bool {
+        self.inner.stats().is_paused()
+    }
+
+    /// Whether the actor has stopped, so sends to it would fail, without having to send anything.
+    ///
+    /// The actor's mailboxes close as it stops, before [`Actor::stopped`](crate::Actor::stopped)
+    /// runs. A respawned actor counts as running again.
+    pub fn is_stopped(&self) -> bool {
+        self.current().mailer.is_closed()
+    }
+
+    /// Get a future that resolves once the actor has stopped, however it stopped: normally, by
+    /// failing to set up or initialise, by panicking, or by being aborted. It resolves straight
+    /// away if the actor has already stopped.
+    ///
+    /// Unlike [`Addr::is_stopped`], this waits for [`Actor::stopped`](crate::Actor::stopped) to
+    /// have run. The listener doesn't keep the actor running.
+    pub fn watch(&self) -> StopListener {
+        StopListener::new(self.inner.exit_signal())
+    }
+
+    /// Get a snapshot of this actor's message counters.
+    ///
+    /// This remains available after the actor has stopped, frozen at their final values.
+    pub fn stats(&self) -> ActorStatsSnapshot {
+        self.inner.stats.snapshot()
+    }
+
     /// Send a message to this actor.
     ///
-    /// This will block (asynchronously) if the actor's mailbox is full
+    /// This will block (asynchronously) if the actor's mailbox is full. Messages the actor marks
+    /// with [`Actor::is_priority`](crate::Actor::is_priority) go to the priority mailbox instead,
+    /// so they never block.
+    ///
+    /// With the `tracing` feature, the actor's id is recorded as `sent_to` on the current span,
+    /// so the sender's trace shows who it last sent to. Fields can't be added to a span once it's
+    /// been created, so this only works for spans that declare `sent_to`, as every actor's own
+    /// span does. Other spans can declare it as `sent_to = tracing::field::Empty` to have it
+    /// filled in, and the id is also on the `sending` event at trace level either way.
     ///
     /// # Errors
     ///
-    /// This will error if the actor is no longer running.
-    pub async fn send(&self, msg: impl Into<A::Msg>) -> Result<(), SendError> {
-        self.mailer.send(msg.into()).await.map_err(|_| SendError)
+    /// This will error with [`DeliveryError::Closed`] if the actor is no longer running, or
+    /// [`DeliveryError::Rejected`] if a custom mailbox refuses the message.
+    pub async fn send(&self, msg: impl Into<A::Msg>) -> Result<(), DeliveryError<A::Msg>> {
+        self.deliver(msg.into()).await
+    }
+
+    /// Put a message in whichever mailbox the actor wants it in.
+    async fn deliver(&self, msg: A::Msg) -> Result<(), DeliveryError<A::Msg>> {
+        #[cfg(feature = "tracing")]
+        record_target(self.inner.id);
+        #[cfg(feature = "tracing")]
+        tracing::trace!(to = %self.inner.id, "sending");
+        if A::is_priority(&msg) {
+            self.send_priority(msg)
+        } else {
+            #[cfg(feature = "chaos")]
+            let msg = match self.disrupt(Queue::Regular, msg) {
+                Some(msg) => msg,
+                None => return Ok(()),
+            };
+            let addr = self.current();
+            let permit = match addr.mailer.reserve().await {
+                Ok(permit) => permit,
+                Err(err) => return Err(err.map(|()| msg)),
+            };
+            self.inner.enqueue(Queue::Regular, msg, |msg| {
+                permit.send(msg);
+                Ok(())
+            })?;
+            #[cfg(feature = "chaos")]
+            self.release_held();
+            Ok(())
+        }
+    }
+
+    /// Send a message to this actor only if there's room in its mailbox right now, such as from a
+    /// hot path that would rather drop messages than wait.
+    ///
+    /// Messages the actor marks with [`Actor::is_priority`](crate::Actor::is_priority) go to the
+    /// priority mailbox, as with [`Addr::send`], so they're never refused for being full.
+    ///
+    /// # Errors
+    ///
+    /// This will error with [`DeliveryError::Full`] if the mailbox is full, or
+    /// [`DeliveryError::Closed`] if the actor is no longer running.
+    pub fn try_send(&self, msg: impl Into<A::Msg>) -> Result<(), DeliveryError<A::Msg>> {
+        let msg = msg.into();
+        if A::is_priority(&msg) {
+            return self.send_priority(msg);
+        }
+        let addr = self.current();
+        let permit = match addr.mailer.try_reserve() {
+            Ok(permit) => permit,
+            Err(err) => return Err(err.map(|()| msg)),
+        };
+        self.send_reserved(permit, msg);
+        Ok(())
+    }
+
+    /// Send a message to this actor, waiting no longer than `timeout` for room in its mailbox.
+    ///
+    /// Room is reserved before the message is handed over, so a message that times out was
+    /// definitely not delivered, and can be sent again, such as with [`Addr::send_priority`].
+    /// Messages the actor marks with [`Actor::is_priority`](crate::Actor::is_priority) go to the
+    /// priority mailbox, as with [`Addr::send`], so they never time out.
+    ///
+    /// # Errors
+    ///
+    /// This will error with [`DeliveryError::Timeout`] if there wasn't room in time, or
+    /// [`DeliveryError::Closed`] if the actor is no longer running.
+    pub async fn send_timeout(
+        &self,
+        msg: impl Into<A::Msg>,
+        timeout: Duration,
+    ) -> Result<(), DeliveryError<A::Msg>> {
+        let msg = msg.into();
+        if A::is_priority(&msg) {
+            return self.send_priority(msg);
+        }
+        let addr = self.current();
+        let permit = match addr.reserve_within(timeout).await {
+            Ok(permit) => permit,
+            Err(err) => return Err(err.map(|()| msg)),
+        };
+        self.send_reserved(permit, msg);
+        Ok(())
+    }
+
+    /// Wait no longer than `duration` for room in the regular mailbox.
+    async fn reserve_within(
+        &self,
+        duration: Duration,
+    ) -> Result<Permit<'_, A::Msg>, DeliveryError<()>> {
+        match timeout(duration, self.mailer.reserve()).await {
+            Ok(res) => res,
+            Err(_) => Err(DeliveryError::Timeout(())),
+        }
+    }
+
+    /// Send a message with room already reserved for it in the regular mailbox, or to the
+    /// priority mailbox if the actor wants it there.
+    ///
+    /// This can't fail. A priority message that finds the actor has stopped since the room was
+    /// reserved takes the room instead, which stays valid as the mailbox closes, so it's drained
+    /// along with everything else sent before then.
+    fn send_reserved(&self, permit: Permit<'_, A::Msg>, msg: A::Msg) {
+        let msg = if A::is_priority(&msg) {
+            match self.send_priority(msg) {
+                Ok(()) => return,
+                Err(err) => err.into_inner(),
+            }
+        } else {
+            #[cfg(feature = "chaos")]
+            let msg = match self.disrupt(Queue::Regular, msg) {
+                Some(msg) => msg,
+                None => return,
+            };
+            msg
+        };
+        let _ = self.inner.enqueue(Queue::Regular, msg, |msg| {
+            permit.send(msg);
+            Ok::<_, Infallible>(())
+        });
+        #[cfg(feature = "chaos")]
+        self.release_held();
+    }
+
+    fn dead_letter(&self) -> DeadLetter {
+        DeadLetter {
+            actor_id: self.id(),
+            actor_type: std::any::type_name::<A>(),
+            message: std::any::type_name::<A::Msg>(),
+        }
+    }
+
+    /// Apply the agency's [`ChaosPolicy`](crate::ChaosPolicy) to a message, returning it if it
+    /// should be delivered as normal.
+    #[cfg(feature = "chaos")]
+    fn disrupt(&self, queue: Queue, msg: A::Msg) -> Option<A::Msg> {
+        // Leave sends to stopped actors to fail as normal
+        if self.current().mailer.is_closed() {
+            return Some(msg);
+        }
+        let fault = match self.inner.agency.chaos() {
+            Some(chaos) => chaos.fault(std::any::type_name::<A>(), queue),
+            None => return Some(msg),
+        };
+        match fault {
+            None => Some(msg),
+            Some(Fault::Drop) => {
+                self.inner.stats.chaos_dropped();
+                self.inner.agency.dead_letter(&self.dead_letter());
+                None
+            }
+            Some(Fault::Delay(delay)) => {
+                self.detached().deliver_after(delay, queue, msg);
+                None
+            }
+            Some(Fault::Reorder(window)) => match self.inner.holdback.hold(queue, msg) {
+                Ok(ticket) => {
+                    self.detached().release_after(window, ticket);
+                    None
+                }
+                Err(msg) => Some(msg),
+            },
+        }
+    }
+
+    /// Deliver the message held back to go after the one just sent, if there is one.
+    #[cfg(feature = "chaos")]
+    fn release_held(&self) {
+        if let Some((queue, msg)) = self.inner.holdback.take(None) {
+            self.detached().release(queue, msg);
+        }
+    }
+
+    #[cfg(feature = "chaos")]
+    fn detached(&self) -> Detached<A::Msg> {
+        let addr = self.current();
+        Detached {
+            inner: self.inner.clone(),
+            mailers: (addr.mailer.clone(), addr.priority_mailer.clone()),
+            dead_letter: self.dead_letter(),
+            _lease: self.lease.clone(),
+        }
+    }
+
+    /// Send a message to this actor without waiting, even if its mailbox is full.
+    ///
+    /// If there's room in the mailbox the message is sent straight away, otherwise it's handed to
+    /// a background task that waits for room. Either way this returns immediately, so it can be
+    /// used where awaiting isn't possible, such as in a `Drop` impl.
+    ///
+    /// Unlike [`Addr::send_priority`], the message goes through the regular mailbox, so it's still
+    /// ordered with other regular messages, though a later [`Addr::send`] may overtake it while
+    /// it's waiting in the background. Messages that can't be delivered because the actor has
+    /// stopped are reported to the agency's [`Observer::dead_letter`](crate::Observer::dead_letter).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mailbox is full and this is called outside of a tokio runtime, unless the
+    /// agency was built with its own runtime.
+    pub fn do_send(&self, msg: impl Into<A::Msg>) {
+        let dead_letter = self.dead_letter();
+        let msg = msg.into();
+        if A::is_priority(&msg) {
+            if self.send_priority(msg).is_err() {
+                self.inner.agency.dead_letter(&dead_letter);
+            }
+            return;
+        }
+        #[cfg(feature = "chaos")]
+        let msg = match self.disrupt(Queue::Regular, msg) {
+            Some(msg) => msg,
+            None => return,
+        };
+        let addr = self.current();
+        match addr.mailer.try_reserve() {
+            Ok(permit) => {
+                let _ = self.inner.enqueue(Queue::Regular, msg, |msg| {
+                    permit.send(msg);
+                    Ok::<_, Infallible>(())
+                });
+                #[cfg(feature = "chaos")]
+                self.release_held();
+                return;
+            }
+            Err(DeliveryError::Full(())) => {}
+            Err(_) => {
+                self.inner.agency.dead_letter(&dead_letter);
+                return;
+            }
+        }
+
+        let mailer = addr.mailer.clone();
+        let inner = self.inner.clone();
+        #[cfg(feature = "chaos")]
+        let detached = self.detached();
+        self.inner.agency.spawn(async move {
+            match mailer.reserve().await {
+                Ok(permit) => {
+                    let _ = inner.enqueue(Queue::Regular, msg, |msg| {
+                        permit.send(msg);
+                        Ok::<_, Infallible>(())
+                    });
+                    #[cfg(feature = "chaos")]
+                    if let Some((queue, msg)) = inner.holdback.take(None) {
+                        detached.deliver(queue, msg).await;
+                    }
+                }
+                Err(_) => inner.agency.dead_letter(&dead_letter),
+            }
+        });
     }
 
     /// Send a message to this actor, with a higher priority over regular messages.
@@ -55,35 +998,185 @@ where
     ///
     /// # Errors
     ///
-    /// This will error if the actor is no longer running.
-    pub fn send_priority(&self, msg: impl Into<A::Msg>) -> Result<(), SendError> {
-        self.priority_mailer.send(msg.into()).map_err(|_| SendError)
+    /// This will error with [`DeliveryError::Closed`] if the actor is no longer running.
+    pub fn send_priority(&self, msg: impl Into<A::Msg>) -> Result<(), DeliveryError<A::Msg>> {
+        let msg = msg.into();
+        #[cfg(feature = "tracing")]
+        record_target(self.inner.id);
+        #[cfg(feature = "tracing")]
+        tracing::trace!(to = %self.inner.id, "sending to the priority mailbox");
+        #[cfg(feature = "chaos")]
+        let msg = match self.disrupt(Queue::Priority, msg) {
+            Some(msg) => msg,
+            None => return Ok(()),
+        };
+        let stats = self.inner.stats();
+        stats.priority_enqueued();
+        let addr = self.current();
+        self.inner.enqueue(Queue::Priority, msg, |msg| {
+            addr.priority_mailer.send(msg).map_err(|msg| {
+                stats.priority_dequeued();
+                DeliveryError::Closed(msg)
+            })
+        })?;
+        #[cfg(feature = "chaos")]
+        self.release_held();
+        Ok(())
     }
 
     pub fn recipient<M>(self) -> Recipient<M>
     where
+        A: 'static,
         M: 'static + Into<A::Msg> + Send,
     {
         self.into()
     }
 
+    /// Get a recipient for a message type that can only sometimes be converted into one of this
+    /// actor's messages, with [`TryInto`] handing back the original when it can't.
+    ///
+    /// Sending a message that can't be converted fails with [`DeliveryError::Incompatible`], and
+    /// a request that can't be fails with [`RequestError::Incompatible`].
+    pub fn try_recipient<M>(self) -> Recipient<M>
+    where
+        A: 'static,
+        M: 'static + Send + TryInto<A::Msg, Error = M>,
+    {
+        Recipient {
+            id: self.inner.id,
+            sender: Box::new(Converting(self)),
+        }
+    }
+
+    /// Get a recipient for a message type that's converted into one of this actor's messages by
+    /// `f`, for types that can't be given an [`Into`] impl, such as when both come from other
+    /// crates. See [`Recipient::filter_map`] for how the converted messages are sent.
+    pub fn recipient_map<M, F>(self, f: F) -> Recipient<M>
+    where
+        A: 'static,
+        M: 'static + Send,
+        F: Fn(M) -> A::Msg + Send + Sync + 'static,
+    {
+        Recipient::<A::Msg>::from(self).filter_map(move |msg| Some(f(msg)))
+    }
+
+    /// Get a handle to this actor that doesn't keep its mailbox open, see [`WeakAddr`].
+    pub fn downgrade(&self) -> WeakAddr<A> {
+        let addr = self.current();
+        WeakAddr {
+            id: self.inner.id,
+            inner: Arc::downgrade(&self.inner),
+            mailer: addr.mailer.downgrade(),
+            priority_mailer: Arc::downgrade(&addr.priority_mailer),
+        }
+    }
+
+    /// Get a recipient for each message type in a tuple, all sharing this actor's id.
+    ///
+    /// ```
+    /// # use agency::prelude::*;
+    /// # struct Start;
+    /// # struct Stop;
+    /// # enum Msg {
+    /// #     Start(Start),
+    /// #     Stop(Stop),
+    /// # }
+    /// # impl From<Start> for Msg {
+    /// #     fn from(msg: Start) -> Self {
+    /// #         Self::Start(msg)
+    /// #     }
+    /// # }
+    /// # impl From<Stop> for Msg {
+    /// #     fn from(msg: Stop) -> Self {
+    /// #         Self::Stop(msg)
+    /// #     }
+    /// # }
+    /// # struct Machine;
+    /// # #[async_trait]
+    /// # impl Actor for Machine {
+    /// #     type Msg = Msg;
+    /// #     async fn run(&mut self, ctx: &mut Context<Self>) {
+    /// #         ctx.message().await;
+    /// #     }
+    /// # }
+    /// # fn example(addr: Addr<Machine>) -> (Recipient<Start>, Recipient<Stop>) {
+    /// let (start, stop) = addr.recipients::<(Start, Stop)>();
+    /// # (start, stop)
+    /// # }
+    /// ```
+    pub fn recipients<T>(&self) -> T::Recipients
+    where
+        T: RecipientTuple<A>,
+    {
+        T::recipients(self)
+    }
+
     /// Send a [`Request`](crate::Request) to this actor and await the response.
     ///
     /// This could wait indefinitely if the actor never responds, however it will error if the actor
     /// is stopped before or during the request, or if the response sender is otherwise dropped.
+    ///
+    /// The actor's id is recorded on the current span as with [`Addr::send`].
     pub async fn request<Req, Res>(&self, payload: Req) -> Result<Res, RequestError>
     where
         Request<Req, Res>: Into<A::Msg>,
     {
         let (request, receiver) = Request::new(payload);
-        self.mailer
-            .send(request.into())
+        self.deliver(request.into())
             .await
             .map_err(|_| RequestError::ActorStopped)?;
         let res = receiver.await.map_err(|_| RequestError::SenderDropped)?;
         Ok(res)
     }
 
+    /// Pass a request being handled on to this actor, to respond to straight to the original
+    /// requester, see [`Request::forward_to`](crate::Request::forward_to). Requests whose sender
+    /// has already stopped listening are dropped rather than forwarded. Convert the payload
+    /// first with [`Request::map_payload`](crate::Request::map_payload) if this actor expects a
+    /// different one.
+    ///
+    /// # Errors
+    ///
+    /// This will error with [`DeliveryError::Closed`] if the actor is no longer running.
+    pub async fn forward_request<Req, Res>(
+        &self,
+        request: Request<Req, Res>,
+    ) -> Result<(), DeliveryError<A::Msg>>
+    where
+        Request<Req, Res>: Into<A::Msg>,
+    {
+        if request.is_closed() {
+            return Ok(());
+        }
+        self.deliver(request.into()).await
+    }
+
+    /// Open a [`Session`] with this actor, for a conversation that takes more than one response.
+    ///
+    /// # Errors
+    ///
+    /// This will error with [`DeliveryError::Closed`] if the actor is no longer running.
+    pub async fn open_session<Req, ClientMsg, ServerMsg>(
+        &self,
+        initial: Req,
+    ) -> Result<SessionHandle<ClientMsg, ServerMsg>, DeliveryError<A::Msg>>
+    where
+        Session<Req, ClientMsg, ServerMsg>: Into<A::Msg>,
+    {
+        let (session, handle) = Session::new(initial);
+        self.deliver(session.into()).await?;
+        Ok(handle)
+    }
+
+    /// Send a fallible request, declared with [`Ask`](crate::Ask), and await the response,
+    /// flattening the actor's error into the delivery errors.
+    pub async fn ask<Req, T, E>(&self, payload: Req) -> Result<T, AskError<E>>
+    where
+        Ask<Req, T, E>: Into<A::Msg>,
+    {
+        self.request(payload).await?.map_err(AskError::Failed)
+    }
+
     /// Send a [`Request`](crate::Request) to this actor and await the response.
     ///
     /// This will error if the timeout is reached, if the actor is stopped before or during the
@@ -94,35 +1187,202 @@ where
         duration: Duration,
     ) -> Result<Res, RequestTimeoutError>
     where
-        Request<Req, Res>: Into<A::Msg>,
+        Request<Req, Res>: Into<A::Msg>,
+    {
+        let (request, receiver) = Request::new(payload);
+        let request = request.with_deadline(Instant::now() + duration);
+        self.deliver(request.into())
+            .await
+            .map_err(|_| RequestTimeoutError::ActorStopped)?;
+        let res = timeout(duration, receiver)
+            .await
+            .map_err(|_| RequestTimeoutError::Timeout)?
+            .map_err(|_| RequestTimeoutError::SenderDropped)?;
+        Ok(res)
+    }
+
+    /// Send a [`Request`](crate::Request) through this actor's priority mailbox and await the
+    /// response, for control-plane queries that shouldn't queue behind a backed up regular
+    /// mailbox.
+    ///
+    /// Sending never waits, as with [`Addr::send_priority`], but otherwise this behaves like
+    /// [`Addr::request`].
+    pub async fn request_priority<Req, Res>(&self, payload: Req) -> Result<Res, RequestError>
+    where
+        Request<Req, Res>: Into<A::Msg>,
+    {
+        let (request, receiver) = Request::new(payload);
+        self.send_priority(request)
+            .map_err(|_| RequestError::ActorStopped)?;
+        let res = receiver.await.map_err(|_| RequestError::SenderDropped)?;
+        Ok(res)
+    }
+
+    /// Like [`Addr::request_priority`], giving up once the timeout is reached as with
+    /// [`Addr::request_timeout`].
+    pub async fn request_priority_timeout<Req, Res>(
+        &self,
+        payload: Req,
+        duration: Duration,
+    ) -> Result<Res, RequestTimeoutError>
+    where
+        Request<Req, Res>: Into<A::Msg>,
+    {
+        let (request, receiver) = Request::new(payload);
+        let request = request.with_deadline(Instant::now() + duration);
+        self.send_priority(request)
+            .map_err(|_| RequestTimeoutError::ActorStopped)?;
+        let res = timeout(duration, receiver)
+            .await
+            .map_err(|_| RequestTimeoutError::Timeout)?
+            .map_err(|_| RequestTimeoutError::SenderDropped)?;
+        Ok(res)
+    }
+
+    /// Send a [`Request`](crate::Request) while handling `parent`, giving up once the parent's
+    /// requester would have, less a small margin for the response to make its way back.
+    ///
+    /// If `parent` has no deadline this waits as long as [`Addr::request`].
+    ///
+    /// # Errors
+    ///
+    /// As with [`Addr::request_timeout`], and fails with [`RequestTimeoutError::BudgetExhausted`]
+    /// without sending anything if the parent has no time left to pass on.
+    pub async fn request_within<PReq, PRes, Req, Res>(
+        &self,
+        parent: &Request<PReq, PRes>,
+        payload: Req,
+    ) -> Result<Res, RequestTimeoutError>
+    where
+        Request<Req, Res>: Into<A::Msg>,
+    {
+        match parent.nested_timeout()? {
+            Some(timeout) => self.request_timeout(payload, timeout).await,
+            None => Ok(self.request(payload).await?),
+        }
+    }
+}
+
+impl<A> Clone for Addr<A>
+where
+    A: Actor,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            mailer: self.mailer.clone(),
+            priority_mailer: self.priority_mailer.clone(),
+            lease: self.lease.clone(),
+        }
+    }
+}
+
+impl<A> Hash for Addr<A>
+where
+    A: Actor,
+{
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        state.write(b"addr:");
+        self.inner.id.hash(state)
+    }
+}
+
+impl<A> PartialEq for Addr<A>
+where
+    A: Actor,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.inner.id == other.inner.id
+    }
+}
+
+impl<A> Eq for Addr<A> where A: Actor {}
+
+/// A handle to an actor that doesn't keep its mailbox open, for registering an actor somewhere
+/// without that registration keeping it alive.
+///
+/// A weak address can be upgraded while the actor is running and someone else still holds a
+/// strong address to it, and never once it has stopped. An actor that's been
+/// [detached](crate::Context::detach) can be reached through its weak addresses for as long as
+/// it runs.
+pub struct WeakAddr<A>
+where
+    A: Actor,
+{
+    id: ActorId,
+    inner: Weak<AddrInner>,
+    mailer: WeakMailer<A::Msg>,
+    priority_mailer: Weak<PrioritySlot<A::Msg>>,
+}
+
+impl<A> WeakAddr<A>
+where
+    A: Actor,
+{
+    pub fn id(&self) -> ActorId {
+        self.id
+    }
+
+    /// Get a strong address to the actor, or `None` if it has stopped or is about to, with no
+    /// strong addresses to it left.
+    pub fn upgrade(&self) -> Option<Addr<A>> {
+        let inner = self.inner.upgrade()?;
+        if inner.has_exited() || (inner.is_orphaned() && !inner.is_detached()) {
+            return None;
+        }
+        let (mailer, priority_mailer) =
+            match (self.mailer.upgrade(), self.priority_mailer.upgrade()) {
+                (Some(mailer), Some(priority_mailer)) if !mailer.is_closed() => {
+                    (mailer, priority_mailer)
+                }
+                _ => inner.respawned_mailers()?,
+            };
+        Some(Addr {
+            lease: Some(inner.lease()),
+            inner,
+            mailer,
+            priority_mailer,
+        })
+    }
+
+    /// Get a weak recipient for one of the actor's message types.
+    pub fn recipient<M>(&self) -> WeakRecipient<M>
+    where
+        A: 'static,
+        M: 'static + Into<A::Msg> + Send,
     {
-        let (request, receiver) = Request::new(payload);
-        self.mailer
-            .send(request.into())
-            .await
-            .map_err(|_| RequestTimeoutError::ActorStopped)?;
-        let res = timeout(duration, receiver)
-            .await
-            .map_err(|_| RequestTimeoutError::Timeout)?
-            .map_err(|_| RequestTimeoutError::SenderDropped)?;
-        Ok(res)
+        let addr = self.clone();
+        WeakRecipient {
+            id: self.id,
+            upgrade: Arc::new(move || addr.upgrade().map(Recipient::from)),
+        }
     }
 }
 
-impl<A> Clone for Addr<A>
+impl<A> Clone for WeakAddr<A>
 where
     A: Actor,
 {
     fn clone(&self) -> Self {
         Self {
             id: self.id,
+            inner: self.inner.clone(),
             mailer: self.mailer.clone(),
             priority_mailer: self.priority_mailer.clone(),
         }
     }
 }
 
-impl<A> Hash for Addr<A>
+impl<A> Debug for WeakAddr<A>
+where
+    A: Actor,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WeakAddr").field("id", &self.id).finish()
+    }
+}
+
+impl<A> Hash for WeakAddr<A>
 where
     A: Actor,
 {
@@ -132,7 +1392,7 @@ where
     }
 }
 
-impl<A> PartialEq for Addr<A>
+impl<A> PartialEq for WeakAddr<A>
 where
     A: Actor,
 {
@@ -141,22 +1401,186 @@ where
     }
 }
 
-impl<A> Eq for Addr<A> where A: Actor {}
+impl<A> Eq for WeakAddr<A> where A: Actor {}
+
+type Upgrade<M> = Arc<dyn Fn() -> Option<Recipient<M>> + Send + Sync>;
+
+/// A [`Recipient`] that doesn't keep the actor's mailbox open, see [`WeakAddr`].
+pub struct WeakRecipient<M>
+where
+    M: 'static,
+{
+    id: ActorId,
+    upgrade: Upgrade<M>,
+}
+
+impl<M> WeakRecipient<M> {
+    pub fn id(&self) -> ActorId {
+        self.id
+    }
+
+    /// Get a strong recipient, or `None` if the actor has stopped.
+    pub fn upgrade(&self) -> Option<Recipient<M>> {
+        (self.upgrade)()
+    }
+}
+
+impl<M> Clone for WeakRecipient<M> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            upgrade: self.upgrade.clone(),
+        }
+    }
+}
+
+impl<M> Hash for WeakRecipient<M> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        state.write(b"recipient:");
+        self.id.hash(state)
+    }
+}
+
+impl<M> PartialEq for WeakRecipient<M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<M> Eq for WeakRecipient<M> {}
+
+/// Why a message couldn't be delivered, handing it back so it can be retried or logged.
+#[non_exhaustive]
+pub enum DeliveryError<M> {
+    /// The actor has stopped.
+    Closed(M),
+    /// The mailbox was full.
+    Full(M),
+    /// There wasn't room in the mailbox in time.
+    Timeout(M),
+    /// The mailbox refused the message.
+    Rejected(M),
+    /// The message couldn't be converted into one the actor accepts, see
+    /// [`Addr::try_recipient`].
+    Incompatible(M),
+}
+
+impl<M> DeliveryError<M> {
+    pub fn is_closed(&self) -> bool {
+        matches!(self, Self::Closed(_))
+    }
+
+    /// Take back the message that couldn't be delivered.
+    pub fn into_inner(self) -> M {
+        match self {
+            Self::Closed(msg)
+            | Self::Full(msg)
+            | Self::Timeout(msg)
+            | Self::Rejected(msg)
+            | Self::Incompatible(msg) => msg,
+        }
+    }
+
+    pub(crate) fn map<T>(self, f: impl FnOnce(M) -> T) -> DeliveryError<T> {
+        match self {
+            Self::Closed(msg) => DeliveryError::Closed(f(msg)),
+            Self::Full(msg) => DeliveryError::Full(f(msg)),
+            Self::Timeout(msg) => DeliveryError::Timeout(f(msg)),
+            Self::Rejected(msg) => DeliveryError::Rejected(f(msg)),
+            Self::Incompatible(msg) => DeliveryError::Incompatible(f(msg)),
+        }
+    }
+}
+
+impl<M> Debug for DeliveryError<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Closed(_) => write!(f, "Closed(..)"),
+            Self::Full(_) => write!(f, "Full(..)"),
+            Self::Timeout(_) => write!(f, "Timeout(..)"),
+            Self::Rejected(_) => write!(f, "Rejected(..)"),
+            Self::Incompatible(_) => write!(f, "Incompatible(..)"),
+        }
+    }
+}
+
+impl<M> Display for DeliveryError<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Closed(_) => write!(f, "actor stopped"),
+            Self::Full(_) => write!(f, "mailbox full"),
+            Self::Timeout(_) => write!(f, "timed out waiting for room in the mailbox"),
+            Self::Rejected(_) => write!(f, "message rejected by the mailbox"),
+            Self::Incompatible(_) => write!(f, "message not accepted by the actor"),
+        }
+    }
+}
 
+impl<M> Error for DeliveryError<M> {}
+
+#[deprecated(note = "sends now fail with a `DeliveryError`, which hands back the message")]
 #[derive(Debug)]
 pub struct SendError;
 
+#[allow(deprecated)]
 impl Display for SendError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "actor stopped")
     }
 }
 
+#[allow(deprecated)]
 impl Error for SendError {}
 
+#[allow(deprecated)]
+impl<M> From<DeliveryError<M>> for SendError {
+    fn from(_: DeliveryError<M>) -> Self {
+        Self
+    }
+}
+
 #[async_trait]
-trait RecipientSender<M>: 'static + Send + Send + DynClone {
-    async fn send_to_recipient(&self, msg: M) -> Result<(), SendError>;
+pub(crate) trait RecipientSender<M>: 'static + Send + Send + DynClone {
+    async fn send_to_recipient(&self, msg: M) -> Result<(), DeliveryError<M>>;
+
+    /// Send a message only if there's room for it right now.
+    fn try_send_to_recipient(&self, msg: M) -> Result<(), DeliveryError<M>>;
+
+    /// Send a message, waiting no longer than `timeout` for room for it.
+    async fn send_timeout_to_recipient(
+        &self,
+        msg: M,
+        timeout: Duration,
+    ) -> Result<(), DeliveryError<M>>;
+
+    /// Send a message ahead of the regular ones, if the recipient has a priority mailbox.
+    fn send_priority_to_recipient(&self, msg: M) -> Result<(), DeliveryError<M>>;
+
+    /// How many messages are waiting to be received, if it's known.
+    fn load(&self) -> Option<usize>;
+
+    fn is_closed(&self) -> bool;
+
+    /// Wait for room in the mailbox, holding it until the permit is used or dropped.
+    #[cfg(feature = "tower")]
+    fn reserve_recipient(
+        &self,
+    ) -> BoxFuture<'static, Result<RecipientPermit<M>, DeliveryError<()>>>;
+}
+
+/// Room for one message in a recipient's mailbox, see [`Recipient::reserve`].
+#[cfg(feature = "tower")]
+pub(crate) struct RecipientPermit<M>(Box<dyn FnOnce(M) + Send>);
+
+#[cfg(feature = "tower")]
+impl<M> RecipientPermit<M> {
+    pub(crate) fn new(send: impl FnOnce(M) + Send + 'static) -> Self {
+        Self(Box::new(send))
+    }
+
+    pub(crate) fn send(self, msg: M) {
+        (self.0)(msg)
+    }
 }
 
 dyn_clone::clone_trait_object!(<M> RecipientSender<M>);
@@ -167,43 +1591,456 @@ where
     S: 'static + Send,
     M: 'static + Send + Into<S>,
 {
-    async fn send_to_recipient(&self, msg: M) -> Result<(), SendError> {
-        self.send(msg.into()).await.map_err(|_| SendError)
+    async fn send_to_recipient(&self, msg: M) -> Result<(), DeliveryError<M>> {
+        // Reserve first, so it's the message as sent that's handed back
+        match self.reserve().await {
+            Ok(permit) => {
+                permit.send(msg.into());
+                Ok(())
+            }
+            Err(_) => Err(DeliveryError::Closed(msg)),
+        }
+    }
+
+    fn try_send_to_recipient(&self, msg: M) -> Result<(), DeliveryError<M>> {
+        match self.try_reserve() {
+            Ok(permit) => {
+                permit.send(msg.into());
+                Ok(())
+            }
+            Err(mpsc::error::TrySendError::Full(())) => Err(DeliveryError::Full(msg)),
+            Err(mpsc::error::TrySendError::Closed(())) => Err(DeliveryError::Closed(msg)),
+        }
+    }
+
+    async fn send_timeout_to_recipient(
+        &self,
+        msg: M,
+        duration: Duration,
+    ) -> Result<(), DeliveryError<M>> {
+        match timeout(duration, self.reserve()).await {
+            Ok(Ok(permit)) => {
+                permit.send(msg.into());
+                Ok(())
+            }
+            Ok(Err(_)) => Err(DeliveryError::Closed(msg)),
+            Err(_) => Err(DeliveryError::Timeout(msg)),
+        }
+    }
+
+    fn send_priority_to_recipient(&self, msg: M) -> Result<(), DeliveryError<M>> {
+        // There's only the one channel, so the best that can be done is not waiting
+        self.try_send_to_recipient(msg)
+    }
+
+    fn load(&self) -> Option<usize> {
+        Some(self.max_capacity() - self.capacity())
+    }
+
+    fn is_closed(&self) -> bool {
+        mpsc::Sender::is_closed(self)
+    }
+
+    #[cfg(feature = "tower")]
+    fn reserve_recipient(
+        &self,
+    ) -> BoxFuture<'static, Result<RecipientPermit<M>, DeliveryError<()>>> {
+        let sender = self.clone();
+        async move {
+            let permit = sender
+                .reserve_owned()
+                .await
+                .map_err(|_| DeliveryError::Closed(()))?;
+            Ok(RecipientPermit(Box::new(move |msg: M| {
+                permit.send(msg.into());
+            })))
+        }
+        .boxed()
+    }
+}
+
+#[async_trait]
+impl<A, M> RecipientSender<M> for Addr<A>
+where
+    A: 'static + Actor,
+    M: 'static + Send + Into<A::Msg>,
+{
+    async fn send_to_recipient(&self, msg: M) -> Result<(), DeliveryError<M>> {
+        // Only the message as sent can be handed back, so it's only converted once there's room
+        // for it, which can't be lost to the actor stopping. That means a message the actor
+        // would take as priority waits for room like any other.
+        let addr = self.current();
+        let permit = match addr.mailer.reserve().await {
+            Ok(permit) => permit,
+            Err(err) => return Err(err.map(|()| msg)),
+        };
+        self.send_reserved(permit, msg.into());
+        Ok(())
+    }
+
+    fn try_send_to_recipient(&self, msg: M) -> Result<(), DeliveryError<M>> {
+        // Reserve first for the same reason, so a full or stopped mailbox hands back the message
+        // as sent, and once there's room the message can't fail to go in
+        let addr = self.current();
+        let permit = match addr.mailer.try_reserve() {
+            Ok(permit) => permit,
+            Err(err) => return Err(err.map(|()| msg)),
+        };
+        self.send_reserved(permit, msg.into());
+        Ok(())
+    }
+
+    async fn send_timeout_to_recipient(
+        &self,
+        msg: M,
+        timeout: Duration,
+    ) -> Result<(), DeliveryError<M>> {
+        let addr = self.current();
+        let permit = match addr.reserve_within(timeout).await {
+            Ok(permit) => permit,
+            Err(err) => return Err(err.map(|()| msg)),
+        };
+        self.send_reserved(permit, msg.into());
+        Ok(())
+    }
+
+    fn send_priority_to_recipient(&self, msg: M) -> Result<(), DeliveryError<M>> {
+        // Only the message as sent can be handed back, so the priority mailbox is held open while
+        // it's converted and sent, which then can't fail
+        let addr = self.current();
+        if addr.mailer.is_closed() {
+            return Err(DeliveryError::Closed(msg));
+        }
+        let _open = match addr.priority_mailer.hold_open() {
+            Some(open) => open,
+            None => return Err(DeliveryError::Closed(msg)),
+        };
+        let _ = addr.send_priority(msg);
+        Ok(())
+    }
+
+    fn load(&self) -> Option<usize> {
+        Some(self.current().mailer.depth().0)
+    }
+
+    fn is_closed(&self) -> bool {
+        self.current().mailer.is_closed()
+    }
+
+    #[cfg(feature = "tower")]
+    fn reserve_recipient(
+        &self,
+    ) -> BoxFuture<'static, Result<RecipientPermit<M>, DeliveryError<()>>> {
+        let addr = self.current().into_owned();
+        async move {
+            let permit = addr.mailer.reserve_owned().await?;
+            Ok(RecipientPermit(Box::new(move |msg: M| {
+                let msg = msg.into();
+                if A::is_priority(&msg) {
+                    drop(permit);
+                    let _ = addr.send_priority(msg);
+                } else {
+                    #[cfg(feature = "chaos")]
+                    let msg = match addr.disrupt(Queue::Regular, msg) {
+                        Some(msg) => msg,
+                        None => return,
+                    };
+                    let _ = addr.inner.enqueue(Queue::Regular, msg, |msg| {
+                        permit.send(msg);
+                        Ok::<_, Infallible>(())
+                    });
+                    #[cfg(feature = "chaos")]
+                    addr.release_held();
+                }
+            })))
+        }
+        .boxed()
+    }
+}
+
+/// An address whose recipients convert messages with [`TryInto`], see [`Addr::try_recipient`].
+struct Converting<A: Actor>(Addr<A>);
+
+impl<A: Actor> Clone for Converting<A> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+#[async_trait]
+impl<A, M> RecipientSender<M> for Converting<A>
+where
+    A: 'static + Actor,
+    M: 'static + Send + TryInto<A::Msg, Error = M>,
+{
+    async fn send_to_recipient(&self, msg: M) -> Result<(), DeliveryError<M>> {
+        let addr = &self.0;
+        let current = addr.current();
+        let permit = match current.mailer.reserve().await {
+            Ok(permit) => permit,
+            Err(err) => return Err(err.map(|()| msg)),
+        };
+        let msg = msg.try_into().map_err(DeliveryError::Incompatible)?;
+        addr.send_reserved(permit, msg);
+        Ok(())
+    }
+
+    fn try_send_to_recipient(&self, msg: M) -> Result<(), DeliveryError<M>> {
+        let addr = &self.0;
+        let current = addr.current();
+        let permit = match current.mailer.try_reserve() {
+            Ok(permit) => permit,
+            Err(err) => return Err(err.map(|()| msg)),
+        };
+        let msg = msg.try_into().map_err(DeliveryError::Incompatible)?;
+        addr.send_reserved(permit, msg);
+        Ok(())
+    }
+
+    async fn send_timeout_to_recipient(
+        &self,
+        msg: M,
+        timeout: Duration,
+    ) -> Result<(), DeliveryError<M>> {
+        let addr = &self.0;
+        let current = addr.current();
+        let permit = match current.reserve_within(timeout).await {
+            Ok(permit) => permit,
+            Err(err) => return Err(err.map(|()| msg)),
+        };
+        let msg = msg.try_into().map_err(DeliveryError::Incompatible)?;
+        addr.send_reserved(permit, msg);
+        Ok(())
+    }
+
+    fn send_priority_to_recipient(&self, msg: M) -> Result<(), DeliveryError<M>> {
+        // Held open while the message is converted and sent, as for an address's recipients
+        let addr = self.0.current();
+        if addr.mailer.is_closed() {
+            return Err(DeliveryError::Closed(msg));
+        }
+        let _open = match addr.priority_mailer.hold_open() {
+            Some(open) => open,
+            None => return Err(DeliveryError::Closed(msg)),
+        };
+        let msg = msg.try_into().map_err(DeliveryError::Incompatible)?;
+        let _ = addr.send_priority(msg);
+        Ok(())
+    }
+
+    fn load(&self) -> Option<usize> {
+        RecipientSender::<A::Msg>::load(&self.0)
+    }
+
+    fn is_closed(&self) -> bool {
+        RecipientSender::<A::Msg>::is_closed(&self.0)
+    }
+
+    #[cfg(feature = "tower")]
+    fn reserve_recipient(
+        &self,
+    ) -> BoxFuture<'static, Result<RecipientPermit<M>, DeliveryError<()>>> {
+        let addr = self.0.clone();
+        let reserve = RecipientSender::<A::Msg>::reserve_recipient(&self.0);
+        async move {
+            let permit = reserve.await?;
+            Ok(RecipientPermit(Box::new(move |msg: M| {
+                match msg.try_into() {
+                    Ok(msg) => permit.send(msg),
+                    // Too late to hand it back, so it's a dead letter like any other
+                    Err(_) => addr.inner.agency.dead_letter(&addr.dead_letter()),
+                }
+            })))
+        }
+        .boxed()
     }
 }
 
 impl<A, M> From<Addr<A>> for Recipient<M>
 where
-    A: Actor,
+    A: 'static + Actor,
     M: 'static + Send + Into<A::Msg>,
 {
     fn from(addr: Addr<A>) -> Self {
         Self {
-            id: addr.id,
-            sender: Box::new(addr.mailer),
+            id: addr.inner.id,
+            sender: Box::new(addr),
         }
     }
 }
 
+/// A tuple of message types that can each be sent to an actor, see [`Addr::recipients`].
+///
+/// Implemented for tuples of up to eight message types.
+pub trait RecipientTuple<A>
+where
+    A: Actor,
+{
+    type Recipients;
+
+    fn recipients(addr: &Addr<A>) -> Self::Recipients;
+}
+
+macro_rules! impl_recipient_tuple {
+    ($($msg:ident),+) => {
+        impl<A, $($msg),+> RecipientTuple<A> for ($($msg,)+)
+        where
+            A: 'static + Actor,
+            $($msg: 'static + Send + Into<A::Msg>,)+
+        {
+            type Recipients = ($(Recipient<$msg>,)+);
+
+            fn recipients(addr: &Addr<A>) -> Self::Recipients {
+                ($(addr.clone().recipient::<$msg>(),)+)
+            }
+        }
+    };
+}
+
+impl_recipient_tuple!(M1);
+impl_recipient_tuple!(M1, M2);
+impl_recipient_tuple!(M1, M2, M3);
+impl_recipient_tuple!(M1, M2, M3, M4);
+impl_recipient_tuple!(M1, M2, M3, M4, M5);
+impl_recipient_tuple!(M1, M2, M3, M4, M5, M6);
+impl_recipient_tuple!(M1, M2, M3, M4, M5, M6, M7);
+impl_recipient_tuple!(M1, M2, M3, M4, M5, M6, M7, M8);
+
 pub struct Recipient<M>
 where
     M: 'static,
 {
-    id: Uuid,
+    id: ActorId,
     sender: Box<dyn RecipientSender<M> + Send + Sync>,
 }
 
+impl<M> Recipient<M>
+where
+    M: 'static + Send,
+{
+    /// Create a recipient that sends straight into a channel rather than an actor's mailbox.
+    pub(crate) fn from_channel(sender: mpsc::Sender<M>) -> Self {
+        Self {
+            id: ActorId::random(),
+            sender: Box::new(sender),
+        }
+    }
+
+    /// Combine several recipients into one that delivers each message to just one of them,
+    /// such as to spread work over actors of different types that take the same message.
+    ///
+    /// Each message goes to the member with the fewest messages waiting, taking turns between
+    /// those that are equally loaded, or simply round-robin if any member's load isn't known.
+    /// Members that have stopped are skipped. Clones share the same turn order.
+    pub fn balanced(members: impl IntoIterator<Item = Recipient<M>>) -> Self {
+        Self::from_sender(Balanced::new(members))
+    }
+
+    /// Get a recipient for another message type, converting each message with `f` before it's
+    /// sent on to this one, and silently dropping those it returns `None` for.
+    ///
+    /// The adapted recipient has the same [`Recipient::id`], so it compares equal to this one.
+    /// Only the message as sent can be handed back, so sending errors if this recipient has
+    /// already stopped, but once converted, a message it refuses, such as by being full, is
+    /// dropped.
+    pub fn filter_map<N, F>(self, f: F) -> Recipient<N>
+    where
+        N: 'static + Send,
+        F: Fn(N) -> Option<M> + Send + Sync + 'static,
+    {
+        Recipient {
+            id: self.id,
+            sender: Box::new(FilterMap::new(self, f)),
+        }
+    }
+
+    /// Create a recipient that isn't any one actor, but sends through something else.
+    pub(crate) fn from_sender(sender: impl RecipientSender<M> + Sync) -> Self {
+        Self {
+            id: ActorId::random(),
+            sender: Box::new(sender),
+        }
+    }
+}
+
 impl<M> Recipient<M> {
+    /// The unique id of the actor this recipient sends to.
+    pub fn id(&self) -> ActorId {
+        self.id
+    }
+
     /// Send a message to the recipient.
     ///
-    /// This will block (asynchronously) if the recipient's buffer is full
+    /// This will block (asynchronously) if the recipient's buffer is full, even for a message
+    /// the actor takes as priority, so use [`Recipient::send_priority`] to skip the queue.
     ///
     /// # Errors
     ///
-    /// This will error if the recipient is no longer running.
-    pub async fn send(&self, msg: impl Into<M>) -> Result<(), SendError> {
+    /// This will error with [`DeliveryError::Closed`] if the recipient is no longer running,
+    /// including if it stops while waiting for room, handing back the message as it was sent.
+    pub async fn send(&self, msg: impl Into<M>) -> Result<(), DeliveryError<M>> {
         self.sender.send_to_recipient(msg.into()).await
     }
+
+    /// Send a message to the recipient only if there's room for it right now, see
+    /// [`Addr::try_send`].
+    ///
+    /// # Errors
+    ///
+    /// This will error with [`DeliveryError::Full`] if the recipient's buffer is full, or
+    /// [`DeliveryError::Closed`] if the recipient is no longer running, handing back the message
+    /// as it was sent either way.
+    pub fn try_send(&self, msg: impl Into<M>) -> Result<(), DeliveryError<M>> {
+        self.sender.try_send_to_recipient(msg.into())
+    }
+
+    /// Send a message to the recipient, waiting no longer than `timeout` for room for it, see
+    /// [`Addr::send_timeout`].
+    ///
+    /// # Errors
+    ///
+    /// This will error with [`DeliveryError::Timeout`] if there wasn't room in time, or
+    /// [`DeliveryError::Closed`] if the recipient is no longer running, handing back the message
+    /// as it was sent either way. A message that timed out was definitely not delivered.
+    pub async fn send_timeout(
+        &self,
+        msg: impl Into<M>,
+        timeout: Duration,
+    ) -> Result<(), DeliveryError<M>> {
+        self.sender
+            .send_timeout_to_recipient(msg.into(), timeout)
+            .await
+    }
+
+    /// Send a message to the recipient ahead of its regular messages, without waiting, see
+    /// [`Addr::send_priority`].
+    ///
+    /// Recipients that aren't actors, such as a [`ClassRouter`](crate::ClassRouter)'s classes,
+    /// have no priority mailbox, so for them this is the same as [`Recipient::try_send`].
+    ///
+    /// # Errors
+    ///
+    /// This will error with [`DeliveryError::Closed`] if the recipient is no longer running.
+    pub fn send_priority(&self, msg: impl Into<M>) -> Result<(), DeliveryError<M>> {
+        self.sender.send_priority_to_recipient(msg.into())
+    }
+
+    /// How many messages are waiting for the recipient, if it's known.
+    pub(crate) fn load(&self) -> Option<usize> {
+        self.sender.load()
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+
+    /// Wait for room in the recipient's mailbox, to send a message into later without waiting.
+    #[cfg(feature = "tower")]
+    pub(crate) fn reserve(
+        &self,
+    ) -> BoxFuture<'static, Result<RecipientPermit<M>, DeliveryError<()>>> {
+        self.sender.reserve_recipient()
+    }
 }
 
 impl<Req, Res> Recipient<Request<Req, Res>> {
@@ -216,7 +2053,7 @@ impl<Req, Res> Recipient<Request<Req, Res>> {
         self.sender
             .send_to_recipient(request)
             .await
-            .map_err(|_| RequestError::ActorStopped)?;
+            .map_err(RequestError::undelivered)?;
         let res = receiver.await.map_err(|_| RequestError::SenderDropped)?;
         Ok(res)
     }
@@ -231,16 +2068,60 @@ impl<Req, Res> Recipient<Request<Req, Res>> {
         duration: Duration,
     ) -> Result<Res, RequestTimeoutError> {
         let (request, receiver) = Request::new(payload);
+        let request = request.with_deadline(Instant::now() + duration);
         self.sender
             .send_to_recipient(request)
             .await
-            .map_err(|_| RequestTimeoutError::ActorStopped)?;
+            .map_err(RequestError::undelivered)?;
+        let res = timeout(duration, receiver)
+            .await
+            .map_err(|_| RequestTimeoutError::Timeout)?
+            .map_err(|_| RequestTimeoutError::SenderDropped)?;
+        Ok(res)
+    }
+
+    /// Send a [`Request`](crate::Request) ahead of the actor's regular messages and await the
+    /// response, see [`Addr::request_priority`].
+    ///
+    /// For recipients that aren't actors, the request is sent as with [`Recipient::try_send`].
+    pub async fn request_priority(&self, payload: Req) -> Result<Res, RequestError> {
+        let (request, receiver) = Request::new(payload);
+        self.sender
+            .send_priority_to_recipient(request)
+            .map_err(RequestError::undelivered)?;
+        let res = receiver.await.map_err(|_| RequestError::SenderDropped)?;
+        Ok(res)
+    }
+
+    /// Like [`Recipient::request_priority`], giving up once the timeout is reached.
+    pub async fn request_priority_timeout(
+        &self,
+        payload: Req,
+        duration: Duration,
+    ) -> Result<Res, RequestTimeoutError> {
+        let (request, receiver) = Request::new(payload);
+        let request = request.with_deadline(Instant::now() + duration);
+        self.sender
+            .send_priority_to_recipient(request)
+            .map_err(RequestError::undelivered)?;
         let res = timeout(duration, receiver)
             .await
             .map_err(|_| RequestTimeoutError::Timeout)?
             .map_err(|_| RequestTimeoutError::SenderDropped)?;
         Ok(res)
     }
+
+    /// Send a [`Request`](crate::Request) while handling `parent`, see [`Addr::request_within`].
+    pub async fn request_within<PReq, PRes>(
+        &self,
+        parent: &Request<PReq, PRes>,
+        payload: Req,
+    ) -> Result<Res, RequestTimeoutError> {
+        match parent.nested_timeout()? {
+            Some(timeout) => self.request_timeout(payload, timeout).await,
+            None => Ok(self.request(payload).await?),
+        }
+    }
 }
 
 impl<M> Clone for Recipient<M> {
@@ -1,23 +1,71 @@
+#[cfg(feature = "chaos")]
+use crate::chaos::{Chaos, ChaosPolicy};
+#[cfg(feature = "serde")]
+use crate::persistence::{Persistent, SnapshotStore};
+#[cfg(feature = "test-util")]
+use crate::test_util::Deadlines;
 use crate::{
-    actor::{Actor, Setup, StoppingResult},
-    addr::Addr,
+    actor::{Actor, InitAbort, PanicInfo, Setup, StopReason, StoppingResult},
+    addr::{Addr, AddrInner, Exit, ExitGuard, Recipient, Responder, WeakRecipient, RESPONDER},
+    broadcast::Broadcasts,
+    census::{ActorFailure, ActorSnapshot, AgencySnapshot, Census, CensusGuard},
     context::Context,
+    handler::Handler,
+    id::ActorId,
+    layer::{AgencyLayer, Layer, LayerFactory, Layers},
+    mailbox::{Envelope, Mailbox, MailboxFactory},
+    observer::{DeadLetter, InitAborted, Observer, ResponseUndelivered, TaskUntracked},
+    registry::Registry,
+    shards::Shards,
+    watchdog,
+};
+use futures_util::{
+    future::{ready, BoxFuture},
+    stream::FuturesUnordered,
+    FutureExt,
+};
+use std::{
+    error::Error,
+    fmt::{self, Debug, Display},
+    future::Future,
+    hash::Hash,
+    ops::ControlFlow,
+    panic::AssertUnwindSafe,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
 };
-use futures_util::stream::FuturesUnordered;
-use std::{fmt::Debug, future::Future};
 use tokio::{
+    runtime::{self, Handle},
     select,
-    sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
-    task::JoinHandle,
+    sync::{
+        mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+        oneshot, watch, OwnedSemaphorePermit, Semaphore,
+    },
+    task::{AbortHandle, JoinHandle},
+    time::{self, error::Elapsed, Instant},
 };
 use tokio_stream::StreamExt;
 
+/// How long the actors have to stay idle for [`Agency::wait_idle`] to resolve.
+const IDLE_DEBOUNCE: Duration = Duration::from_millis(10);
+
+/// How long a scope's actors have to stop once it ends before they're aborted, see
+/// [`Agency::scope`].
+const SCOPE_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
 pub struct AgencyHandle {
     futures: FuturesUnordered<JoinHandle<()>>,
     channel: (
         UnboundedSender<JoinHandle<()>>,
         UnboundedReceiver<JoinHandle<()>>,
     ),
+    /// Set once the handle's been detached, so spawns don't report being untracked.
+    detached: Arc<AtomicBool>,
+    census: Arc<Census>,
 }
 
 impl AgencyHandle {
@@ -25,14 +73,36 @@ impl AgencyHandle {
         Self {
             futures: FuturesUnordered::new(),
             channel: unbounded_channel(),
+            detached: Arc::default(),
+            census: Arc::default(),
         }
     }
 
-    fn spawner(&self) -> Spawner {
-        Spawner::new(self.channel.0.clone())
+    fn spawner(&self, runtime: Option<Handle>, observer: Option<Arc<dyn Observer>>) -> Spawner {
+        Spawner {
+            sender: self.channel.0.clone(),
+            runtime,
+            detached: self.detached.clone(),
+            observer,
+        }
+    }
+
+    /// Let the agency carry on without anything waiting for its tasks, such as when it's
+    /// embedded somewhere that never shuts it down. Actors can still be hired and tasks spawned
+    /// as normal, they just aren't tracked.
+    ///
+    /// Dropping the handle without detaching it works too, but has each task spawned afterwards
+    /// reported to the agency's [`Observer::task_untracked`].
+    pub fn detach(self) {
+        self.detached.store(true, Ordering::Relaxed);
     }
 
-    pub async fn wait(mut self) {
+    /// Wait for every actor to stop, and every task the agency spawned to finish, returning the
+    /// actors that stopped because they panicked, such as to exit with an error.
+    ///
+    /// Actors a supervisor restarted after a panic are included, as each incarnation that
+    /// panicked is.
+    pub async fn wait(mut self) -> Vec<ActorFailure> {
         loop {
             select! {
                 biased;
@@ -40,106 +110,1607 @@ impl AgencyHandle {
                     self.futures.push(fut.expect("sender is held by the handle"));
                 }
                 res = self.futures.next() => {
-                    // TODO: i think we can catch and log panics here?
                     if res.is_none() {
-                        return;
+                        return self.census.take_failures();
                     }
                 }
             }
         }
     }
+
+    /// Ask every actor to stop, as with [`Agency::shutdown`], and wait for them, aborting any
+    /// still running once `grace` has passed, along with any other task the agency spawned.
+    ///
+    /// Returns the actors that were still alive when the grace period ran out, so they can be
+    /// logged, or nothing if they all stopped in time.
+    pub async fn shutdown_timeout(mut self, grace: Duration) -> Vec<ActorSnapshot> {
+        self.census.stop_all();
+        self.wind_down(time::sleep(grace)).await
+    }
+
+    /// Wait for the agency's tasks until the grace period is up, then abort any still going, and
+    /// any spawned afterwards, and wait for them to finish too, returning the actors that were
+    /// still alive when they were aborted.
+    async fn wind_down(&mut self, grace: impl Future<Output = ()>) -> Vec<ActorSnapshot> {
+        tokio::pin!(grace);
+        let mut aborted = false;
+        let mut alive = Vec::new();
+        loop {
+            select! {
+                biased;
+                fut = self.channel.1.recv() => {
+                    let fut = fut.expect("sender is held by the handle");
+                    if aborted {
+                        fut.abort();
+                    }
+                    self.futures.push(fut);
+                }
+                _ = &mut grace, if !aborted => {
+                    aborted = true;
+                    alive = self.census.snapshot().actors;
+                    // Actors off the agency's runtime run as tasks of their own runtimes
+                    self.census.abort_all();
+                    self.futures.iter().for_each(JoinHandle::abort);
+                }
+                res = self.futures.next() => {
+                    if res.is_none() {
+                        return alive;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Abort all of the agency's tasks without waiting for them.
+    fn abort(&mut self) {
+        while let Ok(fut) = self.channel.1.try_recv() {
+            self.futures.push(fut);
+        }
+        self.census.abort_all();
+        self.futures.iter().for_each(JoinHandle::abort);
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 struct Spawner {
     sender: UnboundedSender<JoinHandle<()>>,
+    runtime: Option<Handle>,
+    detached: Arc<AtomicBool>,
+    observer: Option<Arc<dyn Observer>>,
 }
 
 impl Spawner {
-    fn new(sender: UnboundedSender<JoinHandle<()>>) -> Self {
-        Self { sender }
+    /// Spawn a task that the agency handle waits on, if it's still around to wait.
+    fn spawn<T>(&self, fut: T) -> AbortHandle
+    where
+        T: Future<Output = ()> + Send + 'static,
+    {
+        self.track(self.spawn_detached(fut))
     }
 
-    fn spawn<T>(&self, fut: T)
+    /// Run a blocking closure on the runtime's blocking pool, as a task the agency handle waits
+    /// on if it's still around to wait.
+    fn spawn_blocking<F>(&self, f: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let handle = match &self.runtime {
+            Some(runtime) => runtime.spawn_blocking(f),
+            None => tokio::task::spawn_blocking(f),
+        };
+        self.track(handle);
+    }
+
+    fn track(&self, handle: JoinHandle<()>) -> AbortHandle {
+        let abort = handle.abort_handle();
+        if self.sender.send(handle).is_err() && !self.detached.load(Ordering::Relaxed) {
+            if let Some(observer) = &self.observer {
+                observer.task_untracked(&TaskUntracked {
+                    task_id: abort.id(),
+                });
+            }
+        }
+        abort
+    }
+
+    /// Spawn a task on the agency's runtime without the agency handle waiting on it.
+    fn spawn_detached<T>(&self, fut: T) -> JoinHandle<()>
     where
         T: Future<Output = ()> + Send + 'static,
     {
-        let handle = tokio::task::spawn(fut);
-        self.sender
-            .send(handle)
-            .expect("attempt to spawn task after the handler was dropped");
+        match &self.runtime {
+            Some(runtime) => runtime.spawn(fut),
+            None => tokio::task::spawn(fut),
+        }
+    }
+
+    /// Whether there's a runtime to spawn onto, which there might not be while things are being
+    /// dropped.
+    fn has_runtime(&self) -> bool {
+        self.runtime.is_some() || Handle::try_current().is_ok()
+    }
+}
+
+/// The parts of an agency an address needs to finish sends in the background, without
+/// referring back to the census and keeping every actor's shared state alive.
+#[derive(Clone)]
+pub(crate) struct AgencyLink {
+    spawner: Spawner,
+    config: Arc<AgencyConfig>,
+}
+
+impl AgencyLink {
+    pub(crate) fn next_id(&self) -> ActorId {
+        self.config.next_id()
+    }
+
+    pub(crate) fn dead_letter(&self, event: &DeadLetter) {
+        #[cfg(feature = "tracing")]
+        tracing::warn!(
+            to = %event.actor_id,
+            actor = event.actor_type,
+            message_type = event.message,
+            "message undelivered"
+        );
+        if let Some(observer) = &self.config.observer {
+            observer.dead_letter(event);
+        }
+    }
+
+    pub(crate) fn response_undelivered(&self, event: &ResponseUndelivered) {
+        if let Some(observer) = &self.config.observer {
+            observer.response_undelivered(event);
+        }
+    }
+
+    #[cfg(feature = "chaos")]
+    pub(crate) fn chaos(&self) -> Option<&Chaos> {
+        self.config.chaos.as_ref()
+    }
+
+    #[cfg(feature = "chaos")]
+    pub(crate) fn sleep_until(&self, deadline: Instant) -> impl Future<Output = ()> {
+        self.config.track(deadline, time::sleep_until(deadline))
+    }
+
+    /// Spawn a task the agency handle waits on, if it's still around to wait.
+    pub(crate) fn spawn<T>(&self, fut: T)
+    where
+        T: Future<Output = ()> + Send + 'static,
+    {
+        self.spawner.spawn(fut);
+    }
+}
+
+/// Settings shared by an agency and all of its clones.
+pub(crate) struct AgencyConfig {
+    pub(crate) capacity: usize,
+    pub(crate) observer: Option<Arc<dyn Observer>>,
+    layers: Vec<LayerFactory>,
+    /// Slots for running actors, if there's a limit.
+    limit: Option<Arc<Semaphore>>,
+    /// The next actor's number, if actors are numbered rather than given UUIDs.
+    sequential_ids: Option<AtomicU64>,
+    #[cfg(feature = "test-util")]
+    deadlines: Arc<Deadlines>,
+    #[cfg(feature = "chaos")]
+    chaos: Option<Chaos>,
+}
+
+impl AgencyConfig {
+    pub(crate) fn next_id(&self) -> ActorId {
+        match &self.sequential_ids {
+            Some(next) => ActorId::sequential(next.fetch_add(1, Ordering::Relaxed)),
+            None => ActorId::random(),
+        }
+    }
+
+    /// A fresh set of the layers applied to every actor.
+    pub(crate) fn default_layers(&self) -> Vec<Box<dyn AgencyLayer>> {
+        self.layers.iter().map(|factory| factory()).collect()
+    }
+
+    #[cfg(feature = "test-util")]
+    fn track<F: Future>(&self, deadline: Instant, timer: F) -> impl Future<Output = F::Output> {
+        crate::test_util::track(self.deadlines.clone(), deadline, timer)
+    }
+
+    #[cfg(not(feature = "test-util"))]
+    fn track<F: Future>(&self, _deadline: Instant, timer: F) -> F {
+        timer
+    }
+}
+
+/// Configures and creates an [`Agency`], see [`Agency::builder`].
+pub struct AgencyBuilder {
+    capacity: usize,
+    observer: Option<Arc<dyn Observer>>,
+    layers: Vec<LayerFactory>,
+    max_actors: Option<usize>,
+    runtime: Option<Handle>,
+    sequential_ids: bool,
+    #[cfg(feature = "chaos")]
+    chaos: Option<ChaosPolicy>,
+}
+
+impl AgencyBuilder {
+    /// Set the capacity of each actor's regular mailbox. Defaults to 16.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the capacity is zero.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        assert!(capacity > 0, "mailbox capacity must be greater than zero");
+        self.capacity = capacity;
+        self
+    }
+
+    /// Report events from every actor to the given observer.
+    pub fn observer(mut self, observer: impl Observer) -> Self {
+        self.observer = Some(Arc::new(observer));
+        self
+    }
+
+    /// Wrap the message handling of every actor in a layer, created afresh for each actor by
+    /// `factory`.
+    ///
+    /// Default layers wrap any added with [`HireBuilder::layer`], the first added being the
+    /// outermost. Like all layers, they wrap [`Context::dispatch`], so they only apply to
+    /// [`Handler`]s.
+    pub fn default_layer<L, F>(mut self, factory: F) -> Self
+    where
+        L: AgencyLayer,
+        F: Fn() -> L + Send + Sync + 'static,
+    {
+        self.layers.push(Box::new(move || Box::new(factory())));
+        self
+    }
+
+    /// Limit how many actors can be running at once. Each actor takes a slot until its task
+    /// finishes.
+    ///
+    /// [`Agency::try_hire`] fails when every slot is taken, and [`Agency::hire_when_available`]
+    /// waits for one. The other ways of hiring return straight away, but the actor doesn't start
+    /// until it gets a slot, with messages queueing up in its mailbox in the meantime.
+    pub fn max_actors(mut self, max: usize) -> Self {
+        self.max_actors = Some(max);
+        self
+    }
+
+    /// Number actors from 0 rather than giving them random UUIDs, for agencies hiring many
+    /// short-lived actors, or keying maps by their ids in hot paths. The ids are only unique
+    /// within the agency, see [`ActorId`].
+    pub fn sequential_ids(mut self) -> Self {
+        self.sequential_ids = true;
+        self
+    }
+
+    /// Run actors on the given runtime, rather than whichever runtime they're hired from.
+    pub fn runtime(mut self, runtime: Handle) -> Self {
+        self.runtime = Some(runtime);
+        self
+    }
+
+    /// Inject faults into the messages sent to every actor, for testing how they cope with slow
+    /// and lossy delivery.
+    #[cfg(feature = "chaos")]
+    pub fn chaos(mut self, policy: ChaosPolicy) -> Self {
+        self.chaos = Some(policy);
+        self
+    }
+
+    pub fn build(self) -> (Agency, AgencyHandle) {
+        let handle = AgencyHandle::new();
+        let agency = Agency {
+            spawner: handle.spawner(self.runtime, self.observer.clone()),
+            config: Arc::new(AgencyConfig {
+                capacity: self.capacity,
+                observer: self.observer,
+                layers: self.layers,
+                limit: self.max_actors.map(|max| Arc::new(Semaphore::new(max))),
+                sequential_ids: self.sequential_ids.then(|| AtomicU64::new(0)),
+                #[cfg(feature = "test-util")]
+                deadlines: Arc::default(),
+                #[cfg(feature = "chaos")]
+                chaos: self.chaos.map(Chaos::new),
+            }),
+            census: handle.census.clone(),
+            broadcasts: Arc::default(),
+            registry: Arc::default(),
+        };
+        (agency, handle)
+    }
+}
+
+impl Default for AgencyBuilder {
+    fn default() -> Self {
+        Self {
+            capacity: 16,
+            observer: None,
+            layers: Vec::new(),
+            max_actors: None,
+            runtime: None,
+            sequential_ids: false,
+            #[cfg(feature = "chaos")]
+            chaos: None,
+        }
     }
 }
 
 #[derive(Clone)]
 pub struct Agency {
     spawner: Spawner,
+    config: Arc<AgencyConfig>,
+    census: Arc<Census>,
+    broadcasts: Arc<Broadcasts>,
+    registry: Arc<Registry>,
 }
 
 impl Agency {
+    /// Create an agency with the default configuration.
     pub fn new() -> (Self, AgencyHandle) {
+        Self::builder().build()
+    }
+
+    pub fn builder() -> AgencyBuilder {
+        AgencyBuilder::default()
+    }
+
+    /// Create an agency whose actors report events to the given observer.
+    pub fn with_observer(observer: impl Observer) -> (Self, AgencyHandle) {
+        Self::builder().observer(observer).build()
+    }
+
+    pub(crate) fn config(&self) -> &AgencyConfig {
+        &self.config
+    }
+
+    /// Sleep until the deadline, as one of the crate's own timers, so tests can advance time
+    /// straight to it.
+    pub(crate) fn sleep_until(&self, deadline: Instant) -> impl Future<Output = ()> {
+        self.config.track(deadline, time::sleep_until(deadline))
+    }
+
+    /// Like [`Agency::sleep_until`], for timing out a future.
+    pub(crate) fn timeout_at<F>(
+        &self,
+        deadline: Instant,
+        fut: F,
+    ) -> impl Future<Output = Result<F::Output, Elapsed>>
+    where
+        F: Future,
+    {
+        self.config.track(deadline, time::timeout_at(deadline, fut))
+    }
+
+    #[cfg(feature = "test-util")]
+    pub(crate) fn deadlines(&self) -> &Deadlines {
+        &self.config.deadlines
+    }
+
+    /// Something that changes whenever any actor makes progress, for telling when they've all
+    /// settled.
+    #[cfg(feature = "test-util")]
+    pub(crate) fn progress(&self) -> (usize, u64, usize) {
+        self.census.progress()
+    }
+
+    pub(crate) fn link(&self) -> AgencyLink {
+        AgencyLink {
+            spawner: self.spawner.clone(),
+            config: self.config.clone(),
+        }
+    }
+
+    pub(crate) fn observer(&self) -> Option<&dyn Observer> {
+        self.config.observer.as_deref()
+    }
+
+    fn record_failure<A>(&self, inner: &AddrInner, panic: PanicInfo) {
+        self.census.record_failure(ActorFailure {
+            actor_id: inner.id(),
+            actor_type: std::any::type_name::<A>(),
+            name: inner.name(),
+            panic,
+        });
+    }
+
+    /// Add an actor to the census, starting the watchdog alongside the first one if the observer
+    /// asks for it.
+    pub(crate) fn register_actor<A>(&self, addr: &Addr<A>) -> CensusGuard
+    where
+        A: Actor,
+    {
+        if let Some(observer) = &self.config.observer {
+            if let Some(threshold) = observer.stall_threshold() {
+                if self.census.start_watchdog() {
+                    // Not spawned with the agency's spawner, or waiting on the agency would
+                    // never finish
+                    self.spawner.spawn_detached(watchdog::watch(
+                        Arc::downgrade(&self.census),
+                        observer.clone(),
+                        threshold,
+                    ));
+                }
+            }
+        }
+        self.census.register(addr)
+    }
+
+    /// Wait until every actor is waiting for its next message, with nothing left in its
+    /// mailboxes, such as once a burst of work has made its way through a pipeline.
+    ///
+    /// The actors are checked twice, 10ms apart, and have to be idle both times
+    /// without having pulled anything in between, so messages making their way from one actor to
+    /// the next aren't missed. This is only advisory while anything outside the agency is still
+    /// sending, as new work can arrive as soon as it resolves. Pending timers, and messages held
+    /// in background tasks such as those from [`Addr::do_send`] on a full mailbox, aren't waited
+    /// for, and a paused actor with messages waiting keeps this from resolving until it's resumed.
+    pub async fn wait_idle(&self) {
+        let mut last = None;
+        loop {
+            match self.census.idle() {
+                Some(progress) if last == Some(progress) => return,
+                progress => last = progress,
+            }
+            time::sleep(IDLE_DEBOUNCE).await;
+        }
+    }
+
+    /// Register a recipient for every [`Agency::broadcast`] of its message type, returning false
+    /// if it was already registered. Registrations don't keep actors running, and are forgotten
+    /// once a broadcast finds the actor has stopped.
+    ///
+    /// Actors can register themselves with [`Context::receive_broadcasts`].
+    pub fn register_broadcast_target<M>(&self, target: WeakRecipient<M>) -> bool
+    where
+        M: 'static + Send,
+    {
+        self.broadcasts.register(target)
+    }
+
+    /// Stop broadcasting a message type to an actor, returning whether it was registered for it.
+    pub fn unregister_broadcast_target<M>(&self, id: ActorId) -> bool
+    where
+        M: 'static + Send,
+    {
+        self.broadcasts.unregister::<M>(id)
+    }
+
+    /// How many recipients are registered for broadcasts of a message type, including any that
+    /// have stopped since the last broadcast.
+    pub fn broadcast_targets<M>(&self) -> usize
+    where
+        M: 'static + Send,
+    {
+        self.broadcasts.len::<M>()
+    }
+
+    /// Send a clone of the message to every recipient registered for its type, such as to
+    /// announce a configuration reload, returning how many it was delivered to.
+    ///
+    /// Recipients are sent to one at a time, waiting for room in each mailbox in turn.
+    pub async fn broadcast<M>(&self, msg: M) -> usize
+    where
+        M: 'static + Clone + Send,
+    {
+        self.broadcasts.broadcast(msg).await
+    }
+
+    /// Register a recipient under a name, for actors anywhere in the agency to find with
+    /// [`Agency::lookup`] rather than having it passed down to them, returning the recipient it
+    /// replaced if that's still running.
+    ///
+    /// Names are per message type, so recipients of different types can share one. The
+    /// registration keeps the actor reachable, so it won't stop for want of addresses, and is
+    /// forgotten once the actor stops.
+    pub fn register<M>(
+        &self,
+        name: impl Into<String>,
+        recipient: Recipient<M>,
+    ) -> Option<Recipient<M>>
+    where
+        M: 'static + Send,
+    {
+        self.registry.register(name.into(), recipient)
+    }
+
+    /// The recipient registered under a name for the message type, or `None` if there isn't one
+    /// or it has stopped.
+    pub fn lookup<M>(&self, name: &str) -> Option<Recipient<M>>
+    where
+        M: 'static + Send,
+    {
+        self.registry.lookup(name)
+    }
+
+    /// Forget the recipient registered under a name for the message type, returning it if it's
+    /// still running.
+    pub fn deregister<M>(&self, name: &str) -> Option<Recipient<M>>
+    where
+        M: 'static + Send,
+    {
+        self.registry.deregister(name)
+    }
+
+    /// Ask every running actor to stop, as with [`Addr::stop`], so the [`AgencyHandle`] finishes
+    /// once they have. Actors hired afterwards are asked to stop as soon as they're hired, so they
+    /// go straight from setting up to stopping.
+    ///
+    /// To give up on actors that don't stop in time, use [`AgencyHandle::shutdown_timeout`]
+    /// instead.
+    pub fn shutdown(&self) {
+        self.census.stop_all();
+    }
+
+    pub(crate) fn is_shutting_down(&self) -> bool {
+        self.census.is_stopping()
+    }
+
+    /// Run a future with an agency of its own, whose actors are all stopped when the future
+    /// finishes or is dropped, such as helpers hired to serve a single request.
+    ///
+    /// The scoped agency shares this one's configuration, but keeps track of its own actors, so
+    /// they don't show up in [`Agency::dump`] or hold up [`Agency::wait_idle`] or this agency's
+    /// [`AgencyHandle`]. When the scope ends its actors are asked to stop, as with
+    /// [`Agency::shutdown`], and any still running after 5 seconds are aborted, along with
+    /// anything else the scoped agency spawned. This won't resolve until they've all finished.
+    ///
+    /// If the future is dropped before it finishes, they're stopped in the background instead,
+    /// in a task this agency's handle waits on, or aborted straight away if there's no runtime
+    /// left to run it. Actors hired through the scoped agency after its scope has ended aren't
+    /// stopped.
+    ///
+    /// ```
+    /// # use agency::{prelude::*, RequestError};
+    /// # struct Helper;
+    /// # #[async_trait]
+    /// # impl Actor for Helper {
+    /// #     type Msg = Request<u32, u32>;
+    /// #     async fn run(&mut self, ctx: &mut Context<Self>) {
+    /// #         ctx.message().await;
+    /// #     }
+    /// # }
+    /// # async fn example(agency: Agency, query: u32) -> Result<u32, RequestError> {
+    /// let answer = agency
+    ///     .scope(|scoped| async move {
+    ///         let helper = scoped.hire(Helper);
+    ///         helper.request(query).await
+    ///     })
+    ///     .await;
+    /// # answer
+    /// # }
+    /// ```
+    pub async fn scope<F, Fut>(&self, f: F) -> Fut::Output
+    where
+        F: FnOnce(Agency) -> Fut,
+        Fut: Future,
+    {
+        self.scope_with_grace(SCOPE_GRACE_PERIOD, f).await
+    }
+
+    /// Like [`Agency::scope`], giving the actors as long as `grace` to stop once the scope ends.
+    pub async fn scope_with_grace<F, Fut>(&self, grace: Duration, f: F) -> Fut::Output
+    where
+        F: FnOnce(Agency) -> Fut,
+        Fut: Future,
+    {
         let handle = AgencyHandle::new();
-        (
-            Agency {
-                spawner: handle.spawner(),
-            },
-            handle,
-        )
+        let scoped = Agency {
+            spawner: handle.spawner(self.spawner.runtime.clone(), self.config.observer.clone()),
+            census: handle.census.clone(),
+            ..self.clone()
+        };
+        let mut guard = ScopeGuard {
+            parent: self.clone(),
+            scoped: scoped.clone(),
+            handle: Some(handle),
+            grace,
+        };
+        let output = f(scoped).await;
+        guard.finish().await;
+        output
     }
 
-    pub fn hire<A>(&self, mut actor: A) -> Addr<A>
+    /// Take a snapshot of every live actor hired by this agency, for debugging.
+    ///
+    /// The snapshot is assembled from state the actors share with their addresses, so it works
+    /// even if an actor is stuck and never gets round to reading its mailbox.
+    pub fn dump(&self) -> AgencySnapshot {
+        self.census.snapshot()
+    }
+
+    /// Every live actor hired by this agency, the same as the actors in [`Agency::dump`].
+    ///
+    /// Each actor's [`ActorSnapshot::task_id`] can be used to find its task in tokio-console or a
+    /// runtime dump.
+    pub fn actors(&self) -> Vec<ActorSnapshot> {
+        self.census.snapshot().actors
+    }
+
+    pub(crate) fn spawn<T>(&self, fut: T) -> AbortHandle
+    where
+        T: Future<Output = ()> + Send + 'static,
+    {
+        self.spawner.spawn(fut)
+    }
+
+    /// Spawn a task that the agency handle doesn't wait on.
+    pub(crate) fn spawn_detached<T>(&self, fut: T) -> JoinHandle<()>
+    where
+        T: Future<Output = ()> + Send + 'static,
+    {
+        self.spawner.spawn_detached(fut)
+    }
+
+    pub fn hire<A>(&self, actor: A) -> Addr<A>
+    where
+        A: 'static + Actor,
+    {
+        self.hire_in(actor, Context::new(self.clone()), self.slot())
+    }
+
+    /// Hire an actor with a backlog of messages, such as from [`Context::drain`] or a journal,
+    /// which are all delivered, in order, before anything sent to it afterwards.
+    ///
+    /// The backlog doesn't count against the mailbox's capacity.
+    pub fn hire_with_replay<A>(&self, actor: A, messages: Vec<A::Msg>) -> Addr<A>
     where
         A: 'static + Actor,
     {
         let mut ctx = Context::new(self.clone());
+        ctx.preload(messages);
+        self.hire_in(actor, ctx, self.slot())
+    }
+
+    /// Hire an actor if there's a slot free under [`AgencyBuilder::max_actors`].
+    ///
+    /// # Errors
+    ///
+    /// Gives the actor back if the agency is already running as many actors as it's allowed.
+    pub fn try_hire<A>(&self, actor: A) -> Result<Addr<A>, TooManyActors<A>>
+    where
+        A: 'static + Actor,
+    {
+        let permit = match &self.config.limit {
+            Some(limit) => match limit.clone().try_acquire_owned() {
+                Ok(permit) => Some(permit),
+                Err(_) => return Err(TooManyActors(actor)),
+            },
+            None => None,
+        };
+        Ok(self.hire_in(actor, Context::new(self.clone()), ready(permit).boxed()))
+    }
+
+    /// Hire an actor once there's a slot free under [`AgencyBuilder::max_actors`].
+    pub async fn hire_when_available<A>(&self, actor: A) -> Addr<A>
+    where
+        A: 'static + Actor,
+    {
+        let permit = self.slot().await;
+        self.hire_in(actor, Context::new(self.clone()), ready(permit).boxed())
+    }
+
+    /// Wait for a slot to run an actor in, if the agency has a limit.
+    fn slot(&self) -> BoxFuture<'static, Option<OwnedSemaphorePermit>> {
+        let limit = self.config.limit.clone();
+        async move {
+            // The semaphore is never closed
+            limit?.acquire_owned().await.ok()
+        }
+        .boxed()
+    }
+
+    fn hire_in<A>(
+        &self,
+        actor: A,
+        ctx: Context<A>,
+        slot: BoxFuture<'static, Option<OwnedSemaphorePermit>>,
+    ) -> Addr<A>
+    where
+        A: 'static + Actor,
+    {
         let addr = ctx.address();
-        self.spawner.spawn(async move {
-            actor.init(&mut ctx).await;
+        let exit = ExitGuard::new(addr.inner().clone());
+        let task = self.spawner.spawn(async move {
+            let _slot = slot.await;
+            exit.complete(run(actor, ctx).await);
+        });
+        addr.inner().set_task(task);
+        addr
+    }
 
-            loop {
-                while !ctx.stopped {
-                    actor.run(&mut ctx).await;
-                }
+    /// Hire an actor onto its own OS thread, which runs nothing else, for actors that must always
+    /// be called from the same thread, such as wrappers around thread-affine C libraries.
+    ///
+    /// The thread runs a single-threaded runtime hosting only the actor, and shuts down once the
+    /// actor stops. Tasks the actor spawns through the agency, and background sends to it, still
+    /// run on the agency's runtime, and the [`AgencyHandle`] waits for the thread to finish.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime, unless the agency was built with its own
+    /// runtime, or if the thread can't be spawned.
+    pub fn hire_on_thread<A>(&self, actor: A) -> Addr<A>
+    where
+        A: 'static + Actor,
+    {
+        let (agency, addr, host) = self.hire_off_runtime(actor);
+        let (finished, on_finished) = oneshot::channel::<()>();
+        agency.spawn(async move {
+            let _ = on_finished.await;
+        });
+        thread::Builder::new()
+            .name(format!("agency-{}", std::any::type_name::<A>()))
+            .spawn(move || {
+                // Dropped last, even if the actor panics, so the agency handle waits for the rest
+                let _finished = finished;
+                host();
+            })
+            .expect("failed to spawn the actor's thread");
+        addr
+    }
 
-                match actor.stopping(&mut ctx).await {
-                    StoppingResult::Recover => ctx.stopped = false,
-                    StoppingResult::Stop => {
-                        break;
-                    }
+    /// Hire an actor onto tokio's blocking thread pool, for actors that do synchronous or
+    /// CPU-heavy work in `run`, such as compressing each message, without stalling the runtime's
+    /// worker threads.
+    ///
+    /// As with [`Agency::hire_on_thread`], the actor gets a single-threaded runtime of its own,
+    /// everything else it spawns runs on the agency's runtime, and the address works the same as
+    /// any other. The [`AgencyHandle`] waits for it, and [`AgencyHandle::shutdown_timeout`] can
+    /// abort it, though only once it next yields, so an actor stuck in blocking code holds up the
+    /// wait until it gets back to an `.await`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime, unless the agency was built with its own
+    /// runtime.
+    pub fn hire_blocking<A>(&self, actor: A) -> Addr<A>
+    where
+        A: 'static + Actor,
+    {
+        let (agency, addr, host) = self.hire_off_runtime(actor);
+        agency.spawner.spawn_blocking(host);
+        addr
+    }
+
+    /// Set up an actor to be run away from the agency's runtime, returning a closure that hosts
+    /// it on a single-threaded runtime of its own until it stops.
+    fn hire_off_runtime<A>(&self, actor: A) -> (Agency, Addr<A>, impl FnOnce() + Send + 'static)
+    where
+        A: 'static + Actor,
+    {
+        // The actor's own runtime goes away with it, so keep everything else on the agency's
+        let runtime = match &self.spawner.runtime {
+            Some(runtime) => runtime.clone(),
+            None => Handle::current(),
+        };
+        let agency = Agency {
+            spawner: Spawner {
+                runtime: Some(runtime),
+                ..self.spawner.clone()
+            },
+            ..self.clone()
+        };
+        let ctx = Context::new(agency.clone());
+        let addr = ctx.address();
+        let exit = ExitGuard::new(addr.inner().clone());
+        let slot = self.slot();
+        let own_runtime = runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .expect("failed to build the actor's runtime");
+        // Spawned as a task of its own straight away, rather than run directly once the host
+        // starts, so it can be aborted like any other actor, even before it's started
+        let task = own_runtime.spawn(async move {
+            let _slot = slot.await;
+            exit.complete(run(actor, ctx).await);
+        });
+        addr.inner().set_task(task.abort_handle());
+        let host = move || {
+            if let Err(err) = own_runtime.block_on(task) {
+                if err.is_panic() {
+                    std::panic::resume_unwind(err.into_panic());
                 }
             }
+        };
+        (agency, addr, host)
+    }
 
-            actor.stopped(ctx.next_phase()).await;
-        });
-        addr
+    /// Hire an actor, along with an [`ActorHandle`] for getting it back once it stops, see
+    /// [`HireBuilder::hire_joinable`].
+    pub fn hire_joinable<A>(&self, actor: A) -> (Addr<A>, ActorHandle<A>)
+    where
+        A: 'static + Actor,
+    {
+        self.hire_builder(actor).hire_joinable()
+    }
+
+    /// Manage one actor per key, such as per entity id, each hired with `factory` the first time
+    /// something is sent for its key, see [`Shards`].
+    pub fn hire_shards<K, A, F>(&self, factory: F) -> Shards<K, A>
+    where
+        K: 'static + Clone + Eq + Hash + Send + Sync,
+        A: 'static + Actor,
+        F: Fn(&K) -> A + Send + Sync + 'static,
+    {
+        Shards::new(self.clone(), factory)
+    }
+
+    /// Start configuring how an actor is hired, such as to wrap it in [`Layer`]s.
+    pub fn hire_builder<A>(&self, actor: A) -> HireBuilder<A>
+    where
+        A: 'static + Actor,
+    {
+        HireBuilder {
+            agency: self.clone(),
+            actor,
+            name: None,
+            layers: Vec::new(),
+            idle_timeout: None,
+            mailbox: None,
+            capacity: None,
+            #[cfg(feature = "journal")]
+            journal: None,
+        }
+    }
+
+    pub fn hire_default<A>(&self) -> Addr<A>
+    where
+        A: 'static + Actor + Default,
+    {
+        self.hire(A::default())
+    }
+
+    /// Hire `n` actors created by the factory, such as a set of identical workers.
+    pub fn hire_many<A, F>(&self, n: usize, mut factory: F) -> Vec<Addr<A>>
+    where
+        A: 'static + Actor,
+        F: FnMut() -> A,
+    {
+        (0..n).map(|_| self.hire(factory())).collect()
+    }
+
+    /// Like [`Agency::hire_many`], but names each actor after the prefix and its index, eg.
+    /// `worker-0`, `worker-1`, and so on.
+    ///
+    /// Actors can still rename themselves with [`Context::set_name`].
+    pub fn hire_many_named<A, F>(&self, n: usize, prefix: &str, mut factory: F) -> Vec<Addr<A>>
+    where
+        A: 'static + Actor,
+        F: FnMut() -> A,
+    {
+        (0..n)
+            .map(|i| {
+                let ctx = Context::new(self.clone());
+                ctx.inner().set_name(format!("{}-{}", prefix, i));
+                self.hire_in(factory(), ctx, self.slot())
+            })
+            .collect()
+    }
+
+    /// Hire an actor and [register](Agency::register) it under a name for its message type, also
+    /// naming it in [`Agency::dump`].
+    ///
+    /// # Errors
+    ///
+    /// This will error, without hiring the actor, if a running actor is already registered under
+    /// the name for the same message type. Use [`Agency::register`] to replace it instead.
+    pub fn hire_named<A>(&self, name: impl Into<String>, actor: A) -> Result<Addr<A>, NameTaken<A>>
+    where
+        A: 'static + Actor,
+    {
+        let name = name.into();
+        self.registry
+            .register_vacant(name.clone(), actor, |actor| {
+                let ctx = Context::new(self.clone());
+                ctx.inner().set_name(name);
+                let addr = self.hire_in(actor, ctx, self.slot());
+                (Recipient::<A::Msg>::from(addr.clone()), addr)
+            })
+            .map_err(NameTaken)
+    }
+
+    /// Swap the actor behind `addr` for `new_actor`, keeping its id and mailboxes so existing
+    /// addresses carry on working.
+    ///
+    /// The old actor is asked to stop and goes through [`Actor::stopping`] and
+    /// [`Actor::stopped`] as normal, except that it can't recover. Once it's finished, `new_actor`
+    /// is initialised and picks up the messages still queued, including requests, which are
+    /// answered by whichever actor receives them. The old actor's
+    /// [`Context::drain`](crate::Context::drain) comes back empty, since its messages are left for
+    /// the new actor.
+    ///
+    /// # Errors
+    ///
+    /// Gives `new_actor` back if the actor behind `addr` has already stopped.
+    pub fn replace<A>(&self, addr: &Addr<A>, new_actor: A) -> Result<(), A>
+    where
+        A: 'static + Actor,
+    {
+        let inner = addr.inner();
+        if inner.has_exited() {
+            return Err(new_actor);
+        }
+        inner.set_replacement(new_actor);
+        inner.request_stop();
+        Ok(())
+    }
+
+    /// Start a fresh actor behind the address of one that's stopped, so existing addresses for it
+    /// work again.
+    ///
+    /// The new actor gets new mailboxes, which existing addresses switch over to, keeping the same
+    /// id. Anything sent while no actor was running has already failed, and stays failed.
+    ///
+    /// # Errors
+    ///
+    /// Gives the actor back if the previous one hasn't finished stopping.
+    pub fn respawn<A>(&self, addr: &Addr<A>, actor: A) -> Result<(), RespawnError<A>>
+    where
+        A: 'static + Actor,
+    {
+        if !addr.inner().begin_respawn() {
+            return Err(RespawnError(actor));
+        }
+        self.hire_in(actor, Context::respawn(self.clone(), addr), self.slot());
+        Ok(())
     }
 
     pub fn hire_with<A>(&self, args: A::Args) -> Addr<A>
     where
         A: 'static + Setup,
     {
+        self.hire_setup(Context::new(self.clone()), false, move |ctx| {
+            A::setup(ctx, args)
+        })
+    }
+
+    /// Like [`Agency::hire_with`], with the actor's regular mailbox holding `capacity` messages
+    /// rather than the agency's [`capacity`](AgencyBuilder::capacity).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the capacity is zero.
+    pub fn hire_with_capacity<A>(&self, args: A::Args, capacity: usize) -> Addr<A>
+    where
+        A: 'static + Setup,
+    {
+        assert!(capacity > 0, "mailbox capacity must be greater than zero");
+        let ctx = Context::with_mailbox(self.clone(), None, Some(capacity));
+        self.hire_setup(ctx, false, move |ctx| A::setup(ctx, args))
+    }
+
+    /// Like [`Agency::hire_with`], along with an [`ActorHandle`] for getting the actor back once
+    /// it stops. If [`Setup::setup`] returns `None`, [`ActorHandle::join`] errors with
+    /// [`JoinError::NeverStarted`].
+    pub fn hire_with_joinable<A>(&self, args: A::Args) -> (Addr<A>, ActorHandle<A>)
+    where
+        A: 'static + Setup,
+    {
+        let ctx = Context::new(self.clone());
+        let handle = ActorHandle::new(&ctx);
+        let addr = self.hire_setup(ctx, false, move |ctx| A::setup(ctx, args));
+        (addr, handle)
+    }
+
+    /// Like [`Agency::hire_with`], but also returns a [`SetupWatch`] for finding out whether the
+    /// actor managed to start.
+    ///
+    /// The address works straight away as usual, with messages queueing until the actor's ready.
+    /// Callers that don't care can drop the watch.
+    pub fn hire_with_watch<A>(&self, args: A::Args) -> (Addr<A>, SetupWatch)
+    where
+        A: 'static + Setup,
+    {
+        let addr = self.hire_with(args);
+        let watch = SetupWatch {
+            ready: addr.inner().ready_signal(),
+            exit: addr.inner().exit_signal(),
+        };
+        (addr, watch)
+    }
+
+    /// Like [`Agency::hire_with`], but [`Setup::setup`] isn't run until the first message
+    /// arrives, for actors that are expensive to start and rarely used.
+    ///
+    /// Until then the actor's task only watches its mailboxes, which fill up as usual, so senders
+    /// still wait once the mailbox is full. A slot under [`AgencyBuilder::max_actors`] isn't
+    /// taken until the actor starts.
+    pub fn hire_lazy_with<A>(&self, args: A::Args) -> Addr<A>
+    where
+        A: 'static + Setup,
+    {
+        self.hire_setup(Context::new(self.clone()), true, move |ctx| {
+            A::setup(ctx, args)
+        })
+    }
+
+    /// Hire a [`Persistent`] actor under `key`, restoring it from its latest snapshot in `store`
+    /// if there is one, or with [`Setup::setup`] if not.
+    ///
+    /// Snapshots saved with [`Context::save_snapshot`] go to the same store and key. The key is
+    /// also used as the actor's name in [`Agency::dump`].
+    #[cfg(feature = "serde")]
+    pub fn hire_restored<A>(
+        &self,
+        key: impl Into<String>,
+        args: A::Args,
+        store: impl SnapshotStore<A::Snapshot>,
+    ) -> Addr<A>
+    where
+        A: 'static + Persistent,
+    {
+        let key = key.into();
+        let store = Arc::new(store);
         let mut ctx = Context::new(self.clone());
+        ctx.inner().set_name(key.clone());
+
+        let save_store = store.clone();
+        let save_key = key.clone();
+        ctx.set_persist(Box::new(move |actor: &A| {
+            save_store.save(&save_key, actor.snapshot())
+        }));
+
+        self.hire_setup(ctx, false, move |ctx| match store.load(&key) {
+            Some(snapshot) => ready(Some(A::restore(snapshot, args))).boxed(),
+            None => A::setup(ctx, args),
+        })
+    }
+
+    fn hire_setup<A, F>(&self, mut ctx: Context<A>, lazy: bool, setup: F) -> Addr<A>
+    where
+        A: 'static + Actor,
+        F: for<'a> FnOnce(&'a mut Context<A>) -> BoxFuture<'a, Option<A>> + Send + 'static,
+    {
         let addr = ctx.address();
-        self.spawner.spawn(async move {
-            if let Some(mut actor) = A::setup(&mut ctx, args).await {
-                actor.init(&mut ctx).await;
+        let exit = ExitGuard::new(addr.inner().clone());
+        let slot = self.slot();
+        let task = self.spawner.spawn(async move {
+            if lazy && !ctx.activated().await {
+                return exit.complete(Exit::Stopped);
+            }
+            let _slot = slot.await;
+            match setup(&mut ctx).await {
+                Some(actor) => {
+                    exit.complete(run(actor, ctx).await);
+                }
+                None => exit.complete(Exit::SetupFailed),
+            }
+        });
+        addr.inner().set_task(task);
+        addr
+    }
+}
 
-                loop {
-                    while !ctx.stopped {
-                        actor.run(&mut ctx).await;
-                    }
+/// Stops a scoped agency's actors when its scope ends, however it ends, see [`Agency::scope`].
+struct ScopeGuard {
+    parent: Agency,
+    scoped: Agency,
+    /// Taken once the actors have all finished.
+    handle: Option<AgencyHandle>,
+    grace: Duration,
+}
+
+impl ScopeGuard {
+    async fn finish(&mut self) {
+        if let Some(handle) = &mut self.handle {
+            self.scoped.shutdown();
+            let deadline = Instant::now() + self.grace;
+            handle.wind_down(self.scoped.sleep_until(deadline)).await;
+            self.handle = None;
+        }
+    }
+}
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        let mut handle = match self.handle.take() {
+            Some(handle) => handle,
+            None => return,
+        };
+        if !self.parent.spawner.has_runtime() {
+            handle.abort();
+            return;
+        }
+        self.scoped.shutdown();
+        let deadline = Instant::now() + self.grace;
+        let grace = self.scoped.sleep_until(deadline);
+        self.parent.spawn(async move {
+            handle.wind_down(grace).await;
+        });
+    }
+}
+
+/// Returned by [`Agency::try_hire`] when every slot under [`AgencyBuilder::max_actors`] is
+/// taken, with the actor that couldn't be hired.
+pub struct TooManyActors<A>(pub A);
+
+impl<A> Debug for TooManyActors<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TooManyActors(..)")
+    }
+}
+
+impl<A> Display for TooManyActors<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "too many actors running")
+    }
+}
 
-                    match actor.stopping(&mut ctx).await {
-                        StoppingResult::Recover => ctx.stopped = false,
-                        StoppingResult::Stop => {
+impl<A> Error for TooManyActors<A> {}
+
+/// Returned by [`Agency::hire_named`] when the name is already taken, with the actor that
+/// couldn't be hired.
+pub struct NameTaken<A>(pub A);
+
+impl<A> Debug for NameTaken<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("NameTaken(..)")
+    }
+}
+
+impl<A> Display for NameTaken<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "name is already registered")
+    }
+}
+
+impl<A> Error for NameTaken<A> {}
+
+/// Returned by [`Agency::respawn`] when the actor's previous incarnation is still running, with
+/// the actor that couldn't be respawned.
+pub struct RespawnError<A>(pub A);
+
+impl<A> Debug for RespawnError<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RespawnError(..)")
+    }
+}
+
+impl<A> Display for RespawnError<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "actor is still running")
+    }
+}
+
+impl<A> Error for RespawnError<A> {}
+
+/// Resolves once an actor hired with [`Agency::hire_with_watch`] has started, or failed to.
+#[derive(Debug)]
+pub struct SetupWatch {
+    ready: watch::Receiver<bool>,
+    exit: watch::Receiver<Option<Exit>>,
+}
+
+impl SetupWatch {
+    /// Wait until [`Setup::setup`] and [`Actor::init`] have both completed.
+    ///
+    /// # Errors
+    ///
+    /// This will error if the actor stopped before it was ready: `setup` returned `None`,
+    /// [`Actor::try_init`] aborted, or either panicked.
+    pub async fn ready(mut self) -> Result<(), SetupFailed> {
+        select! {
+            biased;
+            Ok(_) = self.ready.wait_for(|ready| *ready) => return Ok(()),
+            _ = self.exit.wait_for(Option::is_some) => {}
+        }
+        // Ready may have been set just before the actor exited
+        if self.is_ready() {
+            Ok(())
+        } else {
+            Err(SetupFailed)
+        }
+    }
+
+    /// Whether the actor is ready, without waiting.
+    pub fn is_ready(&self) -> bool {
+        *self.ready.borrow()
+    }
+}
+
+/// The error returned by [`SetupWatch::ready`] when the actor failed to start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetupFailed;
+
+impl Display for SetupFailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "actor failed to start")
+    }
+}
+
+impl Error for SetupFailed {}
+
+/// Drive an actor through its lifecycle, from `init` through to `stopped`.
+///
+/// Panics in `init` and `run` are caught and handed to [`Actor::on_panic`], and if the actor then
+/// stops it's recorded as having panicked. Panics while stopping stop the actor there and then.
+async fn run<A>(actor: A, ctx: Context<A>) -> Exit
+where
+    A: 'static + Actor,
+{
+    let agency = ctx.agency.clone();
+    let inner = ctx.inner().clone();
+    let responder = Responder::new::<A>(inner.clone());
+    #[cfg(feature = "tracing")]
+    let span = tracing::info_span!(
+        "actor",
+        name = actor.name(),
+        id = %inner.id(),
+        sent_to = tracing::field::Empty,
+    );
+    #[cfg(feature = "tracing")]
+    span.in_scope(|| tracing::debug!("hired"));
+    let lifecycle = lifecycle(actor, ctx);
+    #[cfg(feature = "trace")]
+    let lifecycle = crate::trace::scope(lifecycle);
+    let lifecycle = AssertUnwindSafe(RESPONDER.scope(responder, lifecycle)).catch_unwind();
+    #[cfg(feature = "tracing")]
+    let lifecycle = tracing::Instrument::instrument(lifecycle, span.clone());
+    match lifecycle.await {
+        Ok(exit) => exit,
+        Err(payload) => {
+            #[cfg(feature = "tracing")]
+            span.in_scope(|| tracing::error!("panicked while stopping"));
+            inner.stats().error();
+            agency.record_failure::<A>(&inner, PanicInfo::new(payload, None));
+            Exit::Panicked
+        }
+    }
+}
+
+async fn lifecycle<A>(mut actor: A, mut ctx: Context<A>) -> Exit
+where
+    A: 'static + Actor,
+{
+    let inner = ctx.inner().clone();
+    inner.stats().started();
+    let mut init_panic = match init(&mut actor, &mut ctx).await {
+        ControlFlow::Break(abort) => return init_aborted(ctx, abort).await,
+        ControlFlow::Continue(panic) => panic,
+    };
+
+    loop {
+        let mut panic = init_panic.take();
+        while panic.is_none() && !ctx.stopped {
+            if inner.pause_requested() {
+                ctx = ctx.pause_phase().resumed().await;
+                continue;
+            }
+
+            let interrupted = inner.interrupted();
+            select! {
+                biased;
+                _ = interrupted => ctx.abandon_received(),
+                res = AssertUnwindSafe(actor.run(&mut ctx)).catch_unwind() => {
+                    match res {
+                        Ok(()) => {
+                            ctx.commit_received();
+                            // Asked to stop while busy with something other than the mailbox
+                            if inner.stop_requested() {
+                                ctx.stop_on_request();
+                            }
+                        }
+                        Err(payload) => {
+                            ctx.abandon_received();
+                            panic = Some(PanicInfo::new(payload, ctx.take_handling()));
                             break;
                         }
                     }
                 }
+            }
+        }
 
-                actor.stopped(ctx.next_phase()).await;
+        if panic.is_some() {
+            ctx.stop_reason = Some(StopReason::Panicked);
+        }
+        let panicked = panic.clone();
+        let mut result = match panic {
+            Some(panic) => {
+                inner.stats().error();
+                actor.on_panic(&mut ctx, panic).await
             }
+            None => {
+                let reason = ctx.stop_reason.unwrap_or(StopReason::SelfStopped);
+                actor.on_stop(&mut ctx, reason).await
+            }
+        };
+        if inner.has_replacement() {
+            // Being replaced, so the old actor doesn't get to recover
+            result = StoppingResult::Stop;
+        }
+        #[cfg(feature = "tracing")]
+        match result {
+            StoppingResult::Recover => tracing::debug!("recovering"),
+            StoppingResult::Stop => tracing::debug!("stopping"),
+        }
+        match result {
+            StoppingResult::Recover => {
+                inner.clear_stop();
+                inner.stats().restarted();
+                // Only still unset if it was `init` that panicked
+                inner.set_ready();
+                ctx.stopped = false;
+                ctx.stop_reason = None;
+            }
+            StoppingResult::Stop => {
+                if let Some(replacement) = inner.take_replacement::<A>() {
+                    // The handle waits for whichever actor is last behind the address
+                    let _ = actor.stopped(ctx.hand_over()).await;
+                    actor = replacement;
+                    inner.clear_stop();
+                    ctx.stopped = false;
+                    ctx.stop_reason = None;
+                    init_panic = match init(&mut actor, &mut ctx).await {
+                        ControlFlow::Break(abort) => return init_aborted(ctx, abort).await,
+                        ControlFlow::Continue(panic) => panic,
+                    };
+                    continue;
+                }
+                let agency = ctx.agency.clone();
+                let mut ctx = ctx.next_phase();
+                ctx.stop_children().await;
+                let actor = actor.on_stopped(ctx).await;
+                #[cfg(feature = "tracing")]
+                tracing::debug!(panicked = panicked.is_some(), "stopped");
+                if let Some(panic) = panicked {
+                    agency.record_failure::<A>(&inner, panic);
+                    return Exit::Panicked;
+                }
+                if let Some(actor) = actor {
+                    inner.join(actor);
+                }
+                return Exit::Stopped;
+            }
+        }
+    }
+}
+
+/// Initialise the actor, marking it ready if it starts, or handing back the panic if it panicked
+/// so it can go on to [`Actor::on_panic`].
+async fn init<A>(actor: &mut A, ctx: &mut Context<A>) -> ControlFlow<InitAbort, Option<PanicInfo>>
+where
+    A: 'static + Actor,
+{
+    match AssertUnwindSafe(actor.try_init(ctx)).catch_unwind().await {
+        Ok(ControlFlow::Break(abort)) => ControlFlow::Break(abort),
+        Ok(ControlFlow::Continue(())) => {
+            #[cfg(feature = "tracing")]
+            tracing::debug!("initialised");
+            ctx.inner().set_ready();
+            ControlFlow::Continue(None)
+        }
+        Err(payload) => ControlFlow::Continue(Some(PanicInfo::new(payload, None))),
+    }
+}
+
+/// Shut down an actor that refused to start, dead-lettering everything already sent to it.
+async fn init_aborted<A>(ctx: Context<A>, abort: InitAbort) -> Exit
+where
+    A: 'static + Actor,
+{
+    #[cfg(feature = "tracing")]
+    tracing::debug!(reason = %abort.reason, "init aborted");
+    let agency = ctx.agency.clone();
+    let addr = ctx.address();
+    let mut ctx = ctx.next_phase();
+    ctx.stop_children().await;
+    let undelivered = ctx.drain().await;
+    if let Some(observer) = agency.observer() {
+        observer.init_aborted(&InitAborted {
+            actor_id: addr.id(),
+            actor_type: std::any::type_name::<A>(),
+            name: addr.inner().name(),
+            reason: abort.reason,
         });
-        addr
+        let dead_letter = DeadLetter {
+            actor_id: addr.id(),
+            actor_type: std::any::type_name::<A>(),
+            message: std::any::type_name::<A::Msg>(),
+        };
+        for _ in undelivered {
+            observer.dead_letter(&dead_letter);
+        }
     }
+    Exit::InitAborted
 }
+
+/// Configures how a single actor is hired, see [`Agency::hire_builder`].
+pub struct HireBuilder<A>
+where
+    A: Actor,
+{
+    agency: Agency,
+    actor: A,
+    name: Option<String>,
+    layers: Layers<A>,
+    idle_timeout: Option<Duration>,
+    mailbox: Option<MailboxFactory<A::Msg>>,
+    capacity: Option<usize>,
+    #[cfg(feature = "journal")]
+    journal: Option<crate::journal::JournalState<A::Msg>>,
+}
+
+impl<A> HireBuilder<A>
+where
+    A: 'static + Actor,
+{
+    /// Set the name the actor is listed under in [`Agency::dump`].
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Wrap the actor's message handling in a layer. The first layer added is the outermost.
+    ///
+    /// Layers wrap [`Context::dispatch`], so they only apply to [`Handler`]s.
+    pub fn layer(mut self, layer: impl Layer<A>) -> Self
+    where
+        A: Handler,
+    {
+        self.layers.push(Box::new(layer));
+        self
+    }
+
+    /// Stop the actor once it's waited this long for a message, see
+    /// [`Context::set_idle_timeout`].
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the capacity of the actor's regular mailbox, instead of the agency's
+    /// [`capacity`](AgencyBuilder::capacity), such as for an actor that takes in bursts of
+    /// messages. Respawns of the actor get the same.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the capacity is zero.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        assert!(capacity > 0, "mailbox capacity must be greater than zero");
+        self.capacity = Some(capacity);
+        self
+    }
+
+    /// Give the actor a different kind of regular mailbox, such as [`DropOldest`], built by
+    /// `factory` from the actor's capacity. Respawns of the actor get a fresh one the same way.
+    ///
+    /// [`DropOldest`]: crate::DropOldest
+    pub fn mailbox<F, B>(mut self, factory: F) -> Self
+    where
+        F: Fn(usize) -> B + Send + Sync + 'static,
+        B: Mailbox<Envelope<A::Msg>>,
+    {
+        self.mailbox = Some(Arc::new(move |capacity| Box::new(factory(capacity))));
+        self
+    }
+
+    /// Record every message sent to the actor in a journal before it's delivered, committing
+    /// each once it's been handled, see [`Journal`](crate::Journal).
+    #[cfg(feature = "journal")]
+    pub fn journal(mut self, journal: impl crate::Journal<A::Msg>) -> Self {
+        self.journal = Some(crate::journal::JournalState::new(journal));
+        self
+    }
+
+    pub fn hire(self) -> Addr<A> {
+        let (agency, actor, ctx) = self.into_parts();
+        agency.hire_in(actor, ctx, agency.slot())
+    }
+
+    /// Hire the actor, along with an [`ActorHandle`] for getting it back once it stops.
+    ///
+    /// The actor is handed back in place of calling [`Actor::stopped`], unless it overrides
+    /// [`Actor::on_stopped`] to do otherwise.
+    pub fn hire_joinable(self) -> (Addr<A>, ActorHandle<A>) {
+        let (agency, actor, ctx) = self.into_parts();
+        let handle = ActorHandle::new(&ctx);
+        (agency.hire_in(actor, ctx, agency.slot()), handle)
+    }
+
+    fn into_parts(self) -> (Agency, A, Context<A>) {
+        let mut ctx = Context::with_mailbox(self.agency.clone(), self.mailbox, self.capacity);
+        #[cfg(feature = "journal")]
+        if let Some(journal) = self.journal {
+            ctx.inner().set_journal(journal);
+        }
+        if let Some(name) = self.name {
+            ctx.inner().set_name(name);
+        }
+        ctx.set_layers(self.layers);
+        ctx.set_idle_timeout(self.idle_timeout);
+        (self.agency, self.actor, ctx)
+    }
+}
+
+/// Hands an actor back once it stops, see [`HireBuilder::hire_joinable`].
+pub struct ActorHandle<A> {
+    actor: oneshot::Receiver<A>,
+    exit: watch::Receiver<Option<Exit>>,
+    inner: Arc<AddrInner>,
+}
+
+impl<A> ActorHandle<A>
+where
+    A: Actor,
+{
+    /// A handle for the actor behind a context that hasn't been hired yet.
+    fn new(ctx: &Context<A>) -> Self
+    where
+        A: 'static,
+    {
+        let inner = ctx.inner().clone();
+        let (joiner, actor) = oneshot::channel();
+        inner.set_joiner(joiner);
+        Self {
+            actor,
+            exit: inner.exit_signal(),
+            inner,
+        }
+    }
+
+    /// Whether the actor has finished, having stopped, failed to start, panicked or been aborted.
+    pub fn is_finished(&self) -> bool {
+        self.exit.borrow().is_some()
+    }
+
+    /// Abort the actor's task, so it stops without going through [`Actor::stopping`] or
+    /// [`Actor::stopped`], and [`ActorHandle::join`] errors with [`JoinError::Aborted`].
+    ///
+    /// As with any tokio task, this takes effect the next time the actor yields.
+    pub fn abort(&self) {
+        self.inner.abort();
+    }
+
+    /// Wait for the actor to stop, and take it back with its final state.
+    ///
+    /// If the actor is replaced with [`Agency::replace`], this waits for the replacement instead.
+    ///
+    /// # Errors
+    ///
+    /// This will error if the actor panicked, its task was aborted, it never started, or
+    /// [`Actor::on_stopped`] kept it rather than handing it back.
+    pub async fn join(self) -> Result<A, JoinError> {
+        if let Ok(actor) = self.actor.await {
+            return Ok(actor);
+        }
+        // The exit is always recorded before the handle is let go
+        let exit = *self.exit.borrow();
+        Err(match exit {
+            Some(Exit::Panicked) => JoinError::Panicked,
+            Some(Exit::SetupFailed | Exit::InitAborted) => JoinError::NeverStarted,
+            Some(Exit::Stopped) => JoinError::Kept,
+            Some(Exit::Aborted) | None => JoinError::Aborted,
+        })
+    }
+}
+
+impl<A> Debug for ActorHandle<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ActorHandle(..)")
+    }
+}
+
+/// The error returned by [`ActorHandle::join`] when there's no actor to hand back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinError {
+    Panicked,
+    Aborted,
+    NeverStarted,
+    /// [`Actor::on_stopped`] returned `None`.
+    Kept,
+}
+
+impl Display for JoinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Panicked => write!(f, "actor panicked"),
+            Self::Aborted => write!(f, "actor's task was aborted"),
+            Self::NeverStarted => write!(f, "actor never started"),
+            Self::Kept => write!(f, "actor wasn't handed back when it stopped"),
+        }
+    }
+}
+
+impl Error for JoinError {}
@@ -0,0 +1,186 @@
+use crate::{actor::Actor, addr::Recipient, context::Context, request::Request};
+use async_trait::async_trait;
+use std::time::Duration;
+use tokio::{select, time::Instant};
+
+/// Add an item to an [`Aggregator`]'s current batch.
+pub struct Item<In>(pub In);
+
+/// Emit an [`Aggregator`]'s current batch immediately, responding with whether there was
+/// anything to emit.
+pub struct Flush;
+
+/// Ask an [`Aggregator`] about its current batch.
+pub struct GetBatch;
+
+/// The state of an [`Aggregator`]'s current batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchInfo {
+    /// The number of items folded into the batch so far.
+    pub items: usize,
+    /// When the batch will be emitted if the count threshold isn't reached first.
+    pub deadline: Option<Instant>,
+}
+
+pub enum AggregatorMsg<In> {
+    Item(Item<In>),
+    Flush(Request<Flush, bool>),
+    GetBatch(Request<GetBatch, BatchInfo>),
+}
+
+impl<In> From<Item<In>> for AggregatorMsg<In> {
+    fn from(item: Item<In>) -> Self {
+        Self::Item(item)
+    }
+}
+
+impl<In> From<Request<Flush, bool>> for AggregatorMsg<In> {
+    fn from(request: Request<Flush, bool>) -> Self {
+        Self::Flush(request)
+    }
+}
+
+impl<In> From<Request<GetBatch, BatchInfo>> for AggregatorMsg<In> {
+    fn from(request: Request<GetBatch, BatchInfo>) -> Self {
+        Self::GetBatch(request)
+    }
+}
+
+type Fold<In, Out> = Box<dyn Fn(&mut Out, In) + Send + Sync>;
+
+struct Batch<Out> {
+    acc: Out,
+    items: usize,
+    deadline: Instant,
+}
+
+/// An actor that folds individual items into batches, emitting each batch downstream once it
+/// reaches a count threshold or once its window has elapsed, whichever comes first.
+///
+/// The window starts when the first item of a batch arrives, so items arriving after a batch
+/// has been emitted start a new one. If the downstream recipient stops, so does the aggregator.
+pub struct Aggregator<In, Out: 'static> {
+    downstream: Recipient<Out>,
+    init: Box<dyn Fn() -> Out + Send + Sync>,
+    fold: Fold<In, Out>,
+    max_items: usize,
+    window: Duration,
+    batch: Option<Batch<Out>>,
+}
+
+impl<In, Out> Aggregator<In, Out>
+where
+    In: 'static + Send + Sync,
+    Out: 'static + Send + Sync,
+{
+    /// Create an aggregator that starts each batch with `init` and folds items into it with
+    /// `fold`. Defaults to batches of up to 100 items over a one second window.
+    pub fn new(
+        downstream: Recipient<Out>,
+        init: impl Fn() -> Out + Send + Sync + 'static,
+        fold: impl Fn(&mut Out, In) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            downstream,
+            init: Box::new(init),
+            fold: Box::new(fold),
+            max_items: 100,
+            window: Duration::from_secs(1),
+            batch: None,
+        }
+    }
+
+    /// Emit a batch as soon as it holds this many items.
+    pub fn max_items(mut self, max_items: usize) -> Self {
+        self.max_items = max_items.max(1);
+        self
+    }
+
+    /// Emit a batch once this long has passed since its first item arrived.
+    pub fn window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    fn info(&self) -> BatchInfo {
+        BatchInfo {
+            items: self.batch.as_ref().map_or(0, |batch| batch.items),
+            deadline: self.batch.as_ref().map(|batch| batch.deadline),
+        }
+    }
+
+    async fn add(&mut self, ctx: &mut Context<Self>, item: In) {
+        // Items can queue up behind each other past the deadline, so they start the next batch
+        self.emit_expired(ctx).await;
+        let (init, window) = (&self.init, self.window);
+        let batch = self.batch.get_or_insert_with(|| Batch {
+            acc: init(),
+            items: 0,
+            deadline: Instant::now() + window,
+        });
+        (self.fold)(&mut batch.acc, item);
+        batch.items += 1;
+
+        if batch.items >= self.max_items {
+            self.emit(ctx).await;
+        }
+    }
+
+    /// Emit the current batch if its window has already passed.
+    async fn emit_expired(&mut self, ctx: &mut Context<Self>) {
+        let now = Instant::now();
+        if matches!(&self.batch, Some(batch) if batch.deadline <= now) {
+            self.emit(ctx).await;
+        }
+    }
+
+    async fn emit(&mut self, ctx: &mut Context<Self>) -> bool {
+        match self.batch.take() {
+            Some(batch) => {
+                if self.downstream.send(batch.acc).await.is_err() {
+                    ctx.stop();
+                }
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[async_trait]
+impl<In, Out> Actor for Aggregator<In, Out>
+where
+    In: 'static + Send + Sync,
+    Out: 'static + Send + Sync,
+{
+    type Msg = AggregatorMsg<In>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        // Messages are picked over the flush, so under steady traffic it has to be checked here
+        self.emit_expired(ctx).await;
+        let deadline = self.batch.as_ref().map(|batch| batch.deadline);
+        let flush = ctx
+            .agency
+            .sleep_until(deadline.unwrap_or_else(Instant::now));
+        select! {
+            biased;
+            msg = ctx.message() => match msg {
+                AggregatorMsg::Item(Item(item)) => self.add(ctx, item).await,
+                AggregatorMsg::Flush(request) => {
+                    let emitted = self.emit(ctx).await;
+                    if let Some((_, reply_to)) = request.handle() {
+                        let _ = reply_to.send(emitted);
+                    }
+                }
+                AggregatorMsg::GetBatch(request) => {
+                    if let Some((_, reply_to)) = request.handle() {
+                        let _ = reply_to.send(self.info());
+                    }
+                }
+            },
+            _ = flush, if deadline.is_some() => {
+                self.emit(ctx).await;
+            }
+        }
+    }
+}
@@ -0,0 +1,161 @@
+#[cfg(feature = "tower")]
+use crate::addr::RecipientPermit;
+use crate::addr::{DeliveryError, Recipient, RecipientSender};
+use async_trait::async_trait;
+#[cfg(feature = "tower")]
+use futures_util::future::{BoxFuture, FutureExt};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::time::Instant;
+
+/// Sends each message to one of several recipients, see [`Recipient::balanced`].
+pub(crate) struct Balanced<M>
+where
+    M: 'static,
+{
+    members: Arc<[Recipient<M>]>,
+    /// Where the next search for a member starts, so ties go round-robin.
+    cursor: Arc<AtomicUsize>,
+}
+
+impl<M> Balanced<M>
+where
+    M: 'static + Send,
+{
+    pub(crate) fn new(members: impl IntoIterator<Item = Recipient<M>>) -> Self {
+        Self {
+            members: members.into_iter().collect(),
+            cursor: Arc::default(),
+        }
+    }
+
+    /// The members still running, in the order to try them: the least loaded first, or
+    /// round-robin if any of them can't say how loaded they are.
+    fn candidates(&self) -> Vec<&Recipient<M>> {
+        let len = self.members.len();
+        if len == 0 {
+            return Vec::new();
+        }
+        let start = self.cursor.fetch_add(1, Ordering::Relaxed) % len;
+        let mut candidates: Vec<_> = (0..len)
+            .map(|i| &self.members[(start + i) % len])
+            .filter(|member| !member.is_closed())
+            .collect();
+        let loads: Option<Vec<usize>> = candidates.iter().map(|member| member.load()).collect();
+        if let Some(loads) = loads {
+            // Stable, so equally loaded members keep their round-robin order
+            let mut indexed: Vec<_> = candidates.into_iter().zip(loads).collect();
+            indexed.sort_by_key(|(_, load)| *load);
+            candidates = indexed.into_iter().map(|(member, _)| member).collect();
+        }
+        candidates
+    }
+}
+
+impl<M> Clone for Balanced<M> {
+    fn clone(&self) -> Self {
+        Self {
+            members: self.members.clone(),
+            cursor: self.cursor.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl<M> RecipientSender<M> for Balanced<M>
+where
+    M: 'static + Send,
+{
+    async fn send_to_recipient(&self, mut msg: M) -> Result<(), DeliveryError<M>> {
+        for member in self.candidates() {
+            // Stopped since it was picked, so the message goes to the next one instead
+            match member.send(msg).await {
+                Err(DeliveryError::Closed(returned)) => msg = returned,
+                res => return res,
+            }
+        }
+        Err(DeliveryError::Closed(msg))
+    }
+
+    fn try_send_to_recipient(&self, mut msg: M) -> Result<(), DeliveryError<M>> {
+        let mut full = false;
+        for member in self.candidates() {
+            // Full or stopped, so the message goes to the next one instead
+            match member.try_send(msg) {
+                Err(DeliveryError::Closed(returned)) => msg = returned,
+                Err(DeliveryError::Full(returned)) => {
+                    full = true;
+                    msg = returned;
+                }
+                res => return res,
+            }
+        }
+        if full {
+            Err(DeliveryError::Full(msg))
+        } else {
+            Err(DeliveryError::Closed(msg))
+        }
+    }
+
+    async fn send_timeout_to_recipient(
+        &self,
+        mut msg: M,
+        timeout: Duration,
+    ) -> Result<(), DeliveryError<M>> {
+        // One deadline for the whole search, however many members have stopped along the way
+        let deadline = Instant::now() + timeout;
+        for member in self.candidates() {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match member.send_timeout(msg, remaining).await {
+                Err(DeliveryError::Closed(returned)) => msg = returned,
+                res => return res,
+            }
+        }
+        Err(DeliveryError::Closed(msg))
+    }
+
+    fn send_priority_to_recipient(&self, mut msg: M) -> Result<(), DeliveryError<M>> {
+        for member in self.candidates() {
+            match member.send_priority(msg) {
+                Err(DeliveryError::Closed(returned)) => msg = returned,
+                res => return res,
+            }
+        }
+        Err(DeliveryError::Closed(msg))
+    }
+
+    fn load(&self) -> Option<usize> {
+        let loads: Option<Vec<_>> = self
+            .members
+            .iter()
+            .filter(|member| !member.is_closed())
+            .map(Recipient::load)
+            .collect();
+        loads.and_then(|loads| loads.into_iter().min())
+    }
+
+    fn is_closed(&self) -> bool {
+        self.members.iter().all(Recipient::is_closed)
+    }
+
+    #[cfg(feature = "tower")]
+    fn reserve_recipient(
+        &self,
+    ) -> BoxFuture<'static, Result<RecipientPermit<M>, DeliveryError<()>>> {
+        let candidates: Vec<_> = self.candidates().into_iter().cloned().collect();
+        async move {
+            for member in candidates {
+                if let Ok(permit) = member.reserve().await {
+                    return Ok(permit);
+                }
+            }
+            Err(DeliveryError::Closed(()))
+        }
+        .boxed()
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{actor::Actor, context::Context, handler::Handler};
+use async_trait::async_trait;
+
+/// The most behaviors that can be stacked up with [`Context::become_`] at once.
+pub const MAX_BEHAVIORS: usize = 32;
+
+/// A way of handling messages that takes over from an actor's [`Handler`] for a while, such as
+/// for one phase of a protocol, see [`Context::become_`].
+///
+/// Behaviors are given the actor along with each message, so its state is shared between all of
+/// them. Messages still pass through the actor's layers first.
+#[async_trait]
+pub trait Behavior<A>: Send + 'static
+where
+    A: Actor,
+{
+    async fn handle(&mut self, actor: &mut A, ctx: &mut Context<A>, msg: A::Msg);
+}
+
+/// The behaviors an actor has switched to, the current one last.
+///
+/// The current behavior is taken out of its slot while it's handling a message, so it can
+/// become or unbecome without the stack being borrowed.
+pub(crate) struct Behaviors<A: Actor> {
+    stack: Vec<Option<Box<dyn Behavior<A>>>>,
+}
+
+impl<A: Actor> Behaviors<A> {
+    pub(crate) fn new() -> Self {
+        Self { stack: Vec::new() }
+    }
+
+    pub(crate) fn push(&mut self, behavior: Box<dyn Behavior<A>>) {
+        assert!(
+            self.stack.len() < MAX_BEHAVIORS,
+            "more than {} behaviors stacked up",
+            MAX_BEHAVIORS
+        );
+        self.stack.push(Some(behavior));
+    }
+
+    pub(crate) fn pop(&mut self) -> bool {
+        self.stack.pop().is_some()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.stack.clear();
+    }
+}
+
+/// Hand a message to the actor's current behavior, or its [`Handler`] if it hasn't switched to
+/// one.
+pub(crate) async fn handle<A>(actor: &mut A, ctx: &mut Context<A>, msg: A::Msg)
+where
+    A: 'static + Handler,
+{
+    let depth = ctx.behaviors.stack.len();
+    let current = depth
+        .checked_sub(1)
+        .and_then(|top| ctx.behaviors.stack[top].take());
+    match current {
+        Some(mut behavior) => {
+            behavior.handle(actor, ctx, msg).await;
+            // Unless it unbecame while handling the message, it takes up its slot again
+            if let Some(slot @ None) = ctx.behaviors.stack.get_mut(depth - 1) {
+                *slot = Some(behavior);
+            }
+        }
+        None => actor.handle(ctx, msg).await,
+    }
+}
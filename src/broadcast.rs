@@ -0,0 +1,106 @@
+use crate::addr::WeakRecipient;
+use crate::id::ActorId;
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    sync::Mutex,
+};
+
+/// The recipients registered for each message type, for [`Agency::broadcast`], shared between
+/// an agency and all of its clones.
+///
+/// [`Agency::broadcast`]: crate::Agency::broadcast
+#[derive(Default)]
+pub(crate) struct Broadcasts {
+    /// A `Vec<WeakRecipient<M>>` for each message type `M`.
+    targets: Mutex<HashMap<TypeId, Box<dyn Any + Send>>>,
+}
+
+impl Broadcasts {
+    /// Add a target, returning false if it was already registered for the message type.
+    pub(crate) fn register<M>(&self, target: WeakRecipient<M>) -> bool
+    where
+        M: 'static,
+    {
+        let mut targets = self.targets.lock().unwrap();
+        let targets = targets
+            .entry(TypeId::of::<M>())
+            .or_insert_with(|| Box::new(Vec::<WeakRecipient<M>>::new()))
+            .downcast_mut::<Vec<WeakRecipient<M>>>()
+            .expect("targets are keyed by their message type");
+        if targets.iter().any(|existing| existing.id() == target.id()) {
+            return false;
+        }
+        targets.push(target);
+        true
+    }
+
+    /// Remove a target by id, returning whether it was registered for the message type.
+    pub(crate) fn unregister<M>(&self, id: ActorId) -> bool
+    where
+        M: 'static,
+    {
+        let mut targets = self.targets.lock().unwrap();
+        let targets = match Self::targets_mut::<M>(&mut targets) {
+            Some(targets) => targets,
+            None => return false,
+        };
+        let before = targets.len();
+        targets.retain(|target| target.id() != id);
+        targets.len() != before
+    }
+
+    /// Send a clone of the message to every target registered for its type, returning how many
+    /// it was delivered to, and forgetting any that have stopped.
+    pub(crate) async fn broadcast<M>(&self, msg: M) -> usize
+    where
+        M: 'static + Clone + Send,
+    {
+        // Sent without the lock, so targets can register or broadcast in turn
+        let snapshot = match Self::targets_mut::<M>(&mut self.targets.lock().unwrap()) {
+            Some(targets) => targets.clone(),
+            None => return 0,
+        };
+
+        let mut delivered = 0;
+        let mut dead = Vec::new();
+        for target in &snapshot {
+            let sent = match target.upgrade() {
+                Some(recipient) => recipient.send(msg.clone()).await.is_ok(),
+                None => false,
+            };
+            if sent {
+                delivered += 1;
+            } else {
+                dead.push(target.id());
+            }
+        }
+
+        if !dead.is_empty() {
+            if let Some(targets) = Self::targets_mut::<M>(&mut self.targets.lock().unwrap()) {
+                targets.retain(|target| !dead.contains(&target.id()));
+            }
+        }
+        delivered
+    }
+
+    /// How many targets are registered for a message type, including any that have stopped
+    /// since the last broadcast.
+    pub(crate) fn len<M>(&self) -> usize
+    where
+        M: 'static,
+    {
+        Self::targets_mut::<M>(&mut self.targets.lock().unwrap()).map_or(0, |targets| targets.len())
+    }
+
+    fn targets_mut<M>(
+        targets: &mut HashMap<TypeId, Box<dyn Any + Send>>,
+    ) -> Option<&mut Vec<WeakRecipient<M>>>
+    where
+        M: 'static,
+    {
+        targets
+            .get_mut(&TypeId::of::<M>())
+            .and_then(|targets| targets.downcast_mut())
+    }
+}
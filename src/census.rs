@@ -0,0 +1,347 @@
+use crate::id::ActorId;
+use crate::{
+    actor::{Actor, PanicInfo},
+    addr::{Addr, AddrInner},
+    observer::ActorStalled,
+};
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, Weak,
+    },
+    time::{Duration, SystemTime},
+};
+use tokio::{task, time::Instant};
+
+/// Every live actor hired by an agency, and those that have stopped by panicking, shared between
+/// the agency, all of its clones, and its handle.
+#[derive(Default)]
+pub(crate) struct Census {
+    actors: Mutex<HashMap<ActorId, Entry>>,
+    failures: Mutex<Vec<ActorFailure>>,
+    watchdog: AtomicBool,
+    /// Set once every actor's been asked to stop, so those registered afterwards are too.
+    stopping: AtomicBool,
+}
+
+struct Entry {
+    inner: Arc<AddrInner>,
+    actor_type: &'static str,
+    mailbox: Box<dyn Fn() -> (usize, usize) + Send + Sync>,
+}
+
+impl Census {
+    /// Add an actor to the census, removing it again when the returned guard is dropped.
+    pub(crate) fn register<A>(self: &Arc<Self>, addr: &Addr<A>) -> CensusGuard
+    where
+        A: Actor,
+    {
+        let id = addr.id();
+        // Only hold a weak sender, otherwise the census would keep every mailbox open
+        let mailer = addr.mailer().downgrade();
+        let entry = Entry {
+            inner: addr.inner().clone(),
+            actor_type: std::any::type_name::<A>(),
+            mailbox: Box::new(move || match mailer.upgrade() {
+                Some(mailer) => mailer.depth(),
+                None => (0, 0),
+            }),
+        };
+        let mut actors = self.actors.lock().unwrap();
+        if self.stopping.load(Ordering::Relaxed) {
+            entry.inner.request_stop();
+        }
+        actors.insert(id, entry);
+        drop(actors);
+
+        CensusGuard {
+            census: Arc::downgrade(self),
+            id,
+        }
+    }
+
+    /// How many actors there are, how many messages they've pulled in total, and how many are
+    /// waiting for them.
+    #[cfg(feature = "test-util")]
+    pub(crate) fn progress(&self) -> (usize, u64, usize) {
+        let actors = self.actors.lock().unwrap();
+        let (processed, queued) = actors.values().fold((0, 0), |(processed, queued), entry| {
+            let stats = entry.inner.stats().snapshot();
+            let (mailbox_depth, _) = (entry.mailbox)();
+            (
+                processed + stats.processed,
+                queued + mailbox_depth + stats.priority_depth,
+            )
+        });
+        (actors.len(), processed, queued)
+    }
+
+    /// How many actors there are and how many messages they've pulled in total, if every one of
+    /// them is waiting for a message with nothing in its mailboxes.
+    pub(crate) fn idle(&self) -> Option<(usize, u64)> {
+        let actors = self.actors.lock().unwrap();
+        let mut processed = 0;
+        for entry in actors.values() {
+            let stats = entry.inner.stats();
+            let (mailbox_depth, _) = (entry.mailbox)();
+            if stats.is_busy() || mailbox_depth + stats.priority_depth() > 0 {
+                return None;
+            }
+            processed += stats.snapshot().processed;
+        }
+        Some((actors.len(), processed))
+    }
+
+    /// Whether every actor has been asked to stop.
+    pub(crate) fn is_stopping(&self) -> bool {
+        self.stopping.load(Ordering::Relaxed)
+    }
+
+    /// Ask every actor to stop, along with any registered from now on.
+    pub(crate) fn stop_all(&self) {
+        let actors = self.actors.lock().unwrap();
+        self.stopping.store(true, Ordering::Relaxed);
+        for entry in actors.values() {
+            entry.inner.request_stop();
+        }
+    }
+
+    /// Abort every actor's task.
+    pub(crate) fn abort_all(&self) {
+        for entry in self.actors.lock().unwrap().values() {
+            entry.inner.abort();
+        }
+    }
+
+    pub(crate) fn record_failure(&self, failure: ActorFailure) {
+        self.failures.lock().unwrap().push(failure);
+    }
+
+    pub(crate) fn take_failures(&self) -> Vec<ActorFailure> {
+        std::mem::take(&mut *self.failures.lock().unwrap())
+    }
+
+    /// Returns true the first time it's called, so the watchdog is only started once.
+    pub(crate) fn start_watchdog(&self) -> bool {
+        !self.watchdog.swap(true, Ordering::Relaxed)
+    }
+
+    /// Every actor with messages waiting that hasn't pulled one within the threshold.
+    pub(crate) fn stalls(&self, now: Instant, threshold: Duration) -> Vec<Stall> {
+        self.actors
+            .lock()
+            .unwrap()
+            .values()
+            .filter_map(|entry| {
+                let stats = entry.inner.stats().snapshot();
+                // Actors still in setup haven't started pulling messages yet
+                let since = stats.last_active.or(stats.started_at)?;
+                let stalled_for = now.saturating_duration_since(since);
+                let (mailbox_depth, _) = (entry.mailbox)();
+                let priority_depth = stats.priority_depth;
+                if mailbox_depth + priority_depth == 0
+                    || stalled_for < threshold
+                    || stats.stopped_at.is_some()
+                {
+                    return None;
+                }
+
+                Some(Stall {
+                    since,
+                    event: ActorStalled {
+                        actor_id: entry.inner.id(),
+                        actor_type: entry.actor_type,
+                        name: entry.inner.name(),
+                        stalled_for,
+                        mailbox_depth,
+                        priority_depth,
+                    },
+                })
+            })
+            .collect()
+    }
+
+    pub(crate) fn snapshot(&self) -> AgencySnapshot {
+        let now = Instant::now();
+        let wall_now = SystemTime::now();
+
+        let mut actors: Vec<_> = self
+            .actors
+            .lock()
+            .unwrap()
+            .values()
+            .map(|entry| {
+                let stats = entry.inner.stats().snapshot();
+                let (mailbox_depth, mailbox_capacity) = (entry.mailbox)();
+                ActorSnapshot {
+                    id: entry.inner.id(),
+                    task_id: entry.inner.task_id(),
+                    actor_type: entry.actor_type,
+                    name: entry.inner.name(),
+                    mailbox_depth,
+                    mailbox_capacity,
+                    priority_depth: stats.priority_depth,
+                    restarts: stats.restarts,
+                    last_active: stats
+                        .last_active
+                        .map(|at| wall_now - now.saturating_duration_since(at)),
+                    busy: entry.inner.stats().is_busy(),
+                    paused: entry.inner.stats().is_paused(),
+                }
+            })
+            .collect();
+        actors.sort_by(|a, b| (a.actor_type, &a.name, a.id).cmp(&(b.actor_type, &b.name, b.id)));
+
+        AgencySnapshot { actors }
+    }
+}
+
+pub(crate) struct Stall {
+    /// When the stalled actor last made progress.
+    pub(crate) since: Instant,
+    pub(crate) event: ActorStalled,
+}
+
+/// Removes an actor from the census once its task finishes, however it finishes.
+pub(crate) struct CensusGuard {
+    census: Weak<Census>,
+    id: ActorId,
+}
+
+impl Drop for CensusGuard {
+    fn drop(&mut self) {
+        if let Some(census) = self.census.upgrade() {
+            census.actors.lock().unwrap().remove(&self.id);
+        }
+    }
+}
+
+/// An actor that stopped because it panicked, as returned by
+/// [`AgencyHandle::wait`](crate::AgencyHandle::wait).
+#[derive(Debug, Clone)]
+pub struct ActorFailure {
+    pub actor_id: ActorId,
+    pub actor_type: &'static str,
+    pub name: Option<String>,
+    /// The last panic, which may have come from [`Actor::stopping`] or [`Actor::stopped`] rather
+    /// than the panic the actor stopped over.
+    pub panic: PanicInfo,
+}
+
+/// The state of every live actor in an agency, as returned by
+/// [`Agency::dump`](crate::Agency::dump).
+///
+/// The [`Display`] impl renders the snapshot as a table, one row per actor.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AgencySnapshot {
+    /// Sorted by type, then name, then id.
+    pub actors: Vec<ActorSnapshot>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ActorSnapshot {
+    pub id: ActorId,
+    /// The id of the tokio task running the actor.
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_task_id"))]
+    pub task_id: Option<task::Id>,
+    pub actor_type: &'static str,
+    /// The name set with [`Context::set_name`](crate::Context::set_name), if any.
+    pub name: Option<String>,
+    /// Messages waiting in the regular mailbox.
+    pub mailbox_depth: usize,
+    pub mailbox_capacity: usize,
+    /// Messages waiting in the unbounded priority mailbox.
+    pub priority_depth: usize,
+    /// How many times the actor has recovered from stopping.
+    pub restarts: u64,
+    /// When the actor last pulled a message.
+    pub last_active: Option<SystemTime>,
+    /// Whether the actor is handling a message, rather than waiting for its next one.
+    pub busy: bool,
+    /// Whether the actor is paused, see [`Addr::pause`](crate::Addr::pause).
+    pub paused: bool,
+}
+
+#[cfg(feature = "serde")]
+fn serialize_task_id<S>(id: &Option<task::Id>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.collect_str(&id.map_or_else(|| "-".to_string(), |id| id.to_string()))
+}
+
+impl Display for AgencySnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let now = SystemTime::now();
+        let rows: Vec<[String; 9]> = self
+            .actors
+            .iter()
+            .map(|actor| {
+                [
+                    actor.id.to_string(),
+                    actor
+                        .task_id
+                        .map_or_else(|| "-".to_string(), |id| id.to_string()),
+                    actor.actor_type.to_string(),
+                    actor.name.clone().unwrap_or_else(|| "-".to_string()),
+                    format!("{}/{}", actor.mailbox_depth, actor.mailbox_capacity),
+                    actor.priority_depth.to_string(),
+                    actor.restarts.to_string(),
+                    actor.last_active.map_or_else(
+                        || "never".to_string(),
+                        |at| {
+                            let ago = now.duration_since(at).unwrap_or(Duration::ZERO);
+                            format!("{:.3}s ago", ago.as_secs_f64())
+                        },
+                    ),
+                    if actor.paused {
+                        "paused"
+                    } else if actor.busy {
+                        "busy"
+                    } else {
+                        "idle"
+                    }
+                    .to_string(),
+                ]
+            })
+            .collect();
+
+        let header = [
+            "ID",
+            "TASK",
+            "TYPE",
+            "NAME",
+            "MAILBOX",
+            "PRIORITY",
+            "RESTARTS",
+            "LAST ACTIVE",
+            "STATE",
+        ];
+        let mut widths = header.map(str::len);
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+
+        let mut write_row = |cells: &[&str]| -> fmt::Result {
+            for (i, (cell, width)) in cells.iter().zip(&widths).enumerate() {
+                if i + 1 == cells.len() {
+                    writeln!(f, "{}", cell)?;
+                } else {
+                    write!(f, "{:<width$}  ", cell, width = width)?;
+                }
+            }
+            Ok(())
+        };
+        write_row(&header)?;
+        for row in &rows {
+            write_row(&row.each_ref().map(String::as_str))?;
+        }
+        Ok(())
+    }
+}
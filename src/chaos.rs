@@ -0,0 +1,222 @@
+//! Injected delivery faults, for checking that actors cope with slow and lossy messaging before it
+//! happens for real, behind the `chaos` feature.
+
+use crate::{actor::Actor, journal::Queue};
+use std::{
+    any::Any,
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+/// How often each kind of fault is injected into the messages sent to an actor.
+///
+/// Each message gets at most one fault. Where the probabilities add up to more than 1, drops take
+/// precedence, then delays.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ChaosRates {
+    drop: f64,
+    delay: f64,
+    max_delay: Duration,
+    reorder: f64,
+    reorder_window: Duration,
+}
+
+impl ChaosRates {
+    /// Rates that inject nothing, to build on with the other methods.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drop messages with the given probability, counting them in
+    /// [`ActorStatsSnapshot::chaos_dropped`](crate::ActorStatsSnapshot::chaos_dropped) and
+    /// reporting them as dead letters. The sender isn't told.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the probability isn't between 0 and 1.
+    pub fn drops(mut self, probability: f64) -> Self {
+        self.drop = checked(probability);
+        self
+    }
+
+    /// Hold messages back for a random time up to `max` with the given probability, letting later
+    /// messages overtake them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the probability isn't between 0 and 1.
+    pub fn delays(mut self, probability: f64, max: Duration) -> Self {
+        self.delay = checked(probability);
+        self.max_delay = max;
+        self
+    }
+
+    /// Hold messages back with the given probability until the next message to the same actor has
+    /// been delivered, or until `window` has passed if there isn't one.
+    ///
+    /// Only one message per actor is held back at a time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the probability isn't between 0 and 1.
+    pub fn reorders(mut self, probability: f64, window: Duration) -> Self {
+        self.reorder = checked(probability);
+        self.reorder_window = window;
+        self
+    }
+
+    fn is_calm(&self) -> bool {
+        self.drop == 0.0 && self.delay == 0.0 && self.reorder == 0.0
+    }
+}
+
+fn checked(probability: f64) -> f64 {
+    assert!(
+        (0.0..=1.0).contains(&probability),
+        "probability must be between 0 and 1"
+    );
+    probability
+}
+
+/// Which faults to inject into the messages sent to an agency's actors, see
+/// [`AgencyBuilder::chaos`](crate::AgencyBuilder::chaos).
+///
+/// Faults are drawn from a generator seeded with the policy's seed, so the same seed injects the
+/// same faults into the same sequence of sends. On a single-threaded runtime with the clock paused
+/// that makes a whole run reproducible.
+#[derive(Debug, Clone)]
+pub struct ChaosPolicy {
+    seed: u64,
+    rates: ChaosRates,
+    overrides: HashMap<&'static str, ChaosRates>,
+    priority: bool,
+}
+
+impl ChaosPolicy {
+    /// Inject faults at the given rates into messages for every actor.
+    pub fn new(seed: u64, rates: ChaosRates) -> Self {
+        Self {
+            seed,
+            rates,
+            overrides: HashMap::new(),
+            priority: false,
+        }
+    }
+
+    /// Use different rates for one type of actor, such as [`ChaosRates::new`] to leave it alone.
+    pub fn for_actor<A: Actor>(mut self, rates: ChaosRates) -> Self {
+        self.overrides.insert(std::any::type_name::<A>(), rates);
+        self
+    }
+
+    /// Disrupt priority messages too. They're left alone by default, as they tend to be the
+    /// control traffic used to recover from everything else going wrong.
+    pub fn include_priority(mut self) -> Self {
+        self.priority = true;
+        self
+    }
+}
+
+/// A fault to inject into a single message.
+pub(crate) enum Fault {
+    Drop,
+    Delay(Duration),
+    Reorder(Duration),
+}
+
+/// An agency's chaos policy, along with its generator.
+pub(crate) struct Chaos {
+    policy: ChaosPolicy,
+    state: Mutex<u64>,
+}
+
+impl Chaos {
+    pub(crate) fn new(policy: ChaosPolicy) -> Self {
+        Self {
+            state: Mutex::new(policy.seed),
+            policy,
+        }
+    }
+
+    /// Decide on the fault for a message, if any.
+    ///
+    /// Nothing is drawn for messages the policy leaves alone, so they don't change the faults
+    /// others get.
+    pub(crate) fn fault(&self, actor_type: &'static str, queue: Queue) -> Option<Fault> {
+        if queue == Queue::Priority && !self.policy.priority {
+            return None;
+        }
+        let rates = self
+            .policy
+            .overrides
+            .get(actor_type)
+            .unwrap_or(&self.policy.rates);
+        if rates.is_calm() {
+            return None;
+        }
+        let roll = self.unit();
+        if roll < rates.drop {
+            Some(Fault::Drop)
+        } else if roll < rates.drop + rates.delay {
+            Some(Fault::Delay(rates.max_delay.mul_f64(self.unit())))
+        } else if roll < rates.drop + rates.delay + rates.reorder {
+            Some(Fault::Reorder(rates.reorder_window))
+        } else {
+            None
+        }
+    }
+
+    /// The next number from the generator, between 0 and 1.
+    fn unit(&self) -> f64 {
+        // SplitMix64, which is plenty for picking faults and needs no dependencies
+        let mut state = self.state.lock().unwrap();
+        *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// A held back message, with its ticket and the mailbox it was meant for.
+type Held = (u64, Queue, Box<dyn Any + Send>);
+
+/// The message an actor has held back to deliver after the next one, see
+/// [`ChaosRates::reorders`].
+#[derive(Default)]
+pub(crate) struct Holdback {
+    tickets: AtomicU64,
+    held: Mutex<Option<Held>>,
+}
+
+impl Holdback {
+    /// Hold a message back, returning a ticket for taking it again, or giving it back if there's
+    /// already one held.
+    pub(crate) fn hold<M: 'static + Send>(&self, queue: Queue, msg: M) -> Result<u64, M> {
+        let mut held = self.held.lock().unwrap();
+        if held.is_some() {
+            return Err(msg);
+        }
+        let ticket = self.tickets.fetch_add(1, Ordering::Relaxed);
+        *held = Some((ticket, queue, Box::new(msg)));
+        Ok(ticket)
+    }
+
+    /// Take the held message, if there is one, and it's the one for the ticket if given.
+    pub(crate) fn take<M: 'static>(&self, ticket: Option<u64>) -> Option<(Queue, M)> {
+        let mut held = self.held.lock().unwrap();
+        match &*held {
+            Some((held_ticket, ..)) if ticket.is_none_or(|ticket| ticket == *held_ticket) => {
+                let (_, queue, msg) = held.take()?;
+                // An actor's messages are always the same type, even across respawns
+                Some((queue, *msg.downcast().ok()?))
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use crate::addr::{AddrInner, StopListener};
+use std::sync::Arc;
+
+/// The actors hired with [`Context::hire_child`], which stop along with the actor that hired
+/// them.
+///
+/// Only the shared state is held, not an address, so children can still be orphaned and stop on
+/// their own.
+///
+/// [`Context::hire_child`]: crate::Context::hire_child
+#[derive(Default)]
+pub(crate) struct Children(Vec<Arc<AddrInner>>);
+
+impl Children {
+    /// Keep track of a child, forgetting any that have already stopped.
+    pub(crate) fn add(&mut self, child: Arc<AddrInner>) {
+        self.0.retain(|child| !child.has_exited());
+        self.0.push(child);
+    }
+
+    /// Ask every child to stop, then wait for all of them to finish.
+    pub(crate) async fn stop(&mut self) {
+        for child in &self.0 {
+            child.request_stop();
+        }
+        for child in self.0.drain(..) {
+            StopListener::new(child.exit_signal()).await;
+        }
+    }
+}
+
+impl Drop for Children {
+    // For parents that panic while stopping, or are aborted, before their children are stopped
+    fn drop(&mut self) {
+        for child in &self.0 {
+            child.request_stop();
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use crate::{addr::Recipient, agency::Agency};
+use std::{future::poll_fn, task::Poll};
+use tokio::sync::mpsc;
+
+/// Builds a [`ClassRouter`], adding classes in the order they're indexed.
+pub struct ClassRouterBuilder {
+    classes: Vec<(usize, usize)>,
+}
+
+impl ClassRouterBuilder {
+    /// Add a class of traffic, served up to `weight` messages at a time and queueing up to
+    /// `capacity` messages before senders in this class are made to wait.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the capacity is zero.
+    pub fn class(mut self, weight: usize, capacity: usize) -> Self {
+        assert!(capacity > 0, "class capacity must be greater than zero");
+        self.classes.push((weight.max(1), capacity));
+        self
+    }
+
+    /// Start forwarding into the target, returning the router for the configured classes.
+    pub fn spawn<M>(self, agency: &Agency, target: Recipient<M>) -> ClassRouter<M>
+    where
+        M: 'static + Send,
+    {
+        let mut senders = Vec::with_capacity(self.classes.len());
+        let mut queues = Vec::with_capacity(self.classes.len());
+        for (weight, capacity) in self.classes {
+            let (sender, receiver) = mpsc::channel(capacity);
+            senders.push(sender);
+            queues.push((weight, receiver));
+        }
+        agency.spawn(forward(queues, target));
+        ClassRouter { senders }
+    }
+}
+
+/// Forwards several classes of traffic into one recipient using weighted scheduling.
+///
+/// Each class has its own bounded queue, so a backlog in one class applies backpressure only to
+/// the senders of that class. While several classes have messages waiting, each is served up to
+/// its weight in turn, so weights of 4 and 1 forward four messages of the first class for every
+/// one of the second without starving either.
+///
+/// Forwarding stops once the target can no longer be sent to, or once the router and every
+/// class recipient have been dropped and the queues are drained.
+pub struct ClassRouter<M> {
+    senders: Vec<mpsc::Sender<M>>,
+}
+
+impl<M> ClassRouter<M>
+where
+    M: 'static + Send,
+{
+    pub fn builder() -> ClassRouterBuilder {
+        ClassRouterBuilder {
+            classes: Vec::new(),
+        }
+    }
+
+    /// Get a recipient that sends into the given class.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the class index is out of range.
+    pub fn class(&self, class: usize) -> Recipient<M> {
+        Recipient::from_channel(self.senders[class].clone())
+    }
+
+    /// The number of messages currently queued in the given class.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the class index is out of range.
+    pub fn depth(&self, class: usize) -> usize {
+        let sender = &self.senders[class];
+        sender.max_capacity() - sender.capacity()
+    }
+
+    /// The number of configured classes.
+    pub fn classes(&self) -> usize {
+        self.senders.len()
+    }
+}
+
+async fn forward<M>(mut queues: Vec<(usize, mpsc::Receiver<M>)>, target: Recipient<M>)
+where
+    M: 'static + Send,
+{
+    loop {
+        let mut forwarded = false;
+        for (weight, queue) in &mut queues {
+            for _ in 0..*weight {
+                match queue.try_recv() {
+                    Ok(msg) => {
+                        if target.send(msg).await.is_err() {
+                            return;
+                        }
+                        forwarded = true;
+                    }
+                    Err(_) => break,
+                }
+            }
+        }
+
+        if !forwarded {
+            // Every queue is empty, so wait for whichever class receives something first
+            let next = poll_fn(|cx| {
+                let mut closed = 0;
+                for (_, queue) in &mut queues {
+                    match queue.poll_recv(cx) {
+                        Poll::Ready(Some(msg)) => return Poll::Ready(Some(msg)),
+                        Poll::Ready(None) => closed += 1,
+                        Poll::Pending => {}
+                    }
+                }
+                if closed == queues.len() {
+                    Poll::Ready(None)
+                } else {
+                    Poll::Pending
+                }
+            })
+            .await;
+
+            match next {
+                Some(msg) => {
+                    if target.send(msg).await.is_err() {
+                        return;
+                    }
+                }
+                None => return,
+            }
+        }
+    }
+}
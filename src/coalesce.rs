@@ -0,0 +1,243 @@
+use crate::{
+    addr::Recipient,
+    request::{Request, RequestError, RequestTimeoutError},
+};
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{
+    sync::oneshot,
+    task::{self, AbortHandle},
+    time::timeout,
+};
+
+type Waiters<Res> = Vec<oneshot::Sender<Result<Res, RequestError>>>;
+type InFlightMap<K, Res> = Arc<Mutex<InFlight<K, Res>>>;
+
+/// Coalesces concurrent requests with equal keys into a single request to the underlying
+/// recipient, handing every waiter a clone of the one response.
+pub struct Coalesce<Req, Res, K>
+where
+    Req: 'static,
+    Res: 'static,
+{
+    recipient: Recipient<Request<Req, Res>>,
+    key_fn: Arc<dyn Fn(&Req) -> K + Send + Sync>,
+    in_flight: InFlightMap<K, Res>,
+}
+
+impl<Req, Res, K> Coalesce<Req, Res, K>
+where
+    Req: 'static + Send,
+    Res: 'static + Clone + Send,
+    K: 'static + Hash + Eq + Clone + Send,
+{
+    pub fn new(
+        recipient: Recipient<Request<Req, Res>>,
+        key_fn: impl Fn(&Req) -> K + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            recipient,
+            key_fn: Arc::new(key_fn),
+            in_flight: Arc::new(Mutex::new(InFlight {
+                requests: HashMap::new(),
+                next_id: 0,
+            })),
+        }
+    }
+
+    /// Send a [`Request`](crate::Request), or join an identical one that's already in flight.
+    ///
+    /// The underlying request runs in a task of its own, so it carries on for everyone else
+    /// waiting on it if the caller that started it is cancelled. Once every caller has stopped
+    /// waiting, it's abandoned and the key released.
+    pub async fn request(&self, payload: Req) -> Result<Res, RequestError> {
+        let key = (self.key_fn)(&payload);
+        let (sender, receiver) = oneshot::channel();
+        {
+            let mut in_flight = self.in_flight.lock().expect("in-flight lock poisoned");
+            match in_flight.requests.get_mut(&key) {
+                Some(pending) => pending.waiters.push(sender),
+                None => {
+                    let id = in_flight.next_id;
+                    in_flight.next_id += 1;
+                    let task = task::spawn(issue(
+                        self.recipient.clone(),
+                        payload,
+                        self.in_flight.clone(),
+                        key.clone(),
+                        id,
+                    ));
+                    let pending = Pending {
+                        id,
+                        waiters: vec![sender],
+                        task: task.abort_handle(),
+                    };
+                    in_flight.requests.insert(key.clone(), pending);
+                }
+            }
+        }
+
+        let mut waiting = Waiting {
+            in_flight: &self.in_flight,
+            key: Some(key),
+            receiver,
+        };
+        let res = (&mut waiting.receiver).await;
+        waiting.key = None;
+        res.unwrap_or(Err(RequestError::SenderDropped))
+    }
+
+    /// Like [`Coalesce::request`], but gives up waiting after the given duration.
+    ///
+    /// Timing out only affects this caller, unless it was the last one waiting, in which case the
+    /// underlying request is abandoned so the next caller tries again.
+    pub async fn request_timeout(
+        &self,
+        payload: Req,
+        duration: Duration,
+    ) -> Result<Res, RequestTimeoutError> {
+        timeout(duration, self.request(payload))
+            .await
+            .map_err(|_| RequestTimeoutError::Timeout)?
+            .map_err(RequestTimeoutError::from)
+    }
+}
+
+impl<Req, Res, K> Clone for Coalesce<Req, Res, K> {
+    fn clone(&self) -> Self {
+        Self {
+            recipient: self.recipient.clone(),
+            key_fn: self.key_fn.clone(),
+            in_flight: self.in_flight.clone(),
+        }
+    }
+}
+
+/// Make the underlying request for a key, handing the response to everyone waiting on it.
+async fn issue<Req, Res, K>(
+    recipient: Recipient<Request<Req, Res>>,
+    payload: Req,
+    in_flight: InFlightMap<K, Res>,
+    key: K,
+    id: u64,
+) where
+    Req: 'static + Send,
+    Res: 'static + Clone + Send,
+    K: Hash + Eq,
+{
+    let mut issued = Issued {
+        in_flight: &in_flight,
+        key: Some(key),
+        id,
+    };
+    let res = recipient.request(payload).await;
+    for waiter in issued.finish() {
+        let _ = waiter.send(res.clone());
+    }
+}
+
+struct InFlight<K, Res> {
+    requests: HashMap<K, Pending<Res>>,
+    next_id: u64,
+}
+
+/// An underlying request and the callers waiting on it.
+struct Pending<Res> {
+    /// Tells this request apart from later ones for the same key.
+    id: u64,
+    waiters: Waiters<Res>,
+    task: AbortHandle,
+}
+
+impl<K, Res> InFlight<K, Res>
+where
+    K: Hash + Eq,
+{
+    /// Take the waiters for a request, if it's still the one in flight for its key.
+    fn finish(&mut self, key: &K, id: u64) -> Waiters<Res> {
+        match self.requests.get(key) {
+            Some(pending) if pending.id == id => self
+                .requests
+                .remove(key)
+                .map(|pending| pending.waiters)
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Forget the callers that have stopped waiting on a key, abandoning its request if that's
+    /// all of them.
+    fn abandon(&mut self, key: &K) {
+        if let Some(pending) = self.requests.get_mut(key) {
+            pending.waiters.retain(|waiter| !waiter.is_closed());
+            if pending.waiters.is_empty() {
+                pending.task.abort();
+                self.requests.remove(key);
+            }
+        }
+    }
+}
+
+/// Releases an in-flight key when the underlying request completes or its task is dropped.
+struct Issued<'a, K, Res>
+where
+    K: Hash + Eq,
+{
+    in_flight: &'a InFlightMap<K, Res>,
+    key: Option<K>,
+    id: u64,
+}
+
+impl<K, Res> Issued<'_, K, Res>
+where
+    K: Hash + Eq,
+{
+    fn finish(&mut self) -> Waiters<Res> {
+        match self.key.take() {
+            Some(key) => self
+                .in_flight
+                .lock()
+                .expect("in-flight lock poisoned")
+                .finish(&key, self.id),
+            None => Vec::new(),
+        }
+    }
+}
+
+impl<K, Res> Drop for Issued<'_, K, Res>
+where
+    K: Hash + Eq,
+{
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// Stops a caller waiting on an in-flight key if it's dropped before the response arrives.
+struct Waiting<'a, K, Res>
+where
+    K: Hash + Eq,
+{
+    in_flight: &'a InFlightMap<K, Res>,
+    key: Option<K>,
+    receiver: oneshot::Receiver<Result<Res, RequestError>>,
+}
+
+impl<K, Res> Drop for Waiting<'_, K, Res>
+where
+    K: Hash + Eq,
+{
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            self.receiver.close();
+            self.in_flight
+                .lock()
+                .expect("in-flight lock poisoned")
+                .abandon(&key);
+        }
+    }
+}
@@ -1,50 +1,257 @@
-use crate::{actor::Actor, addr::Addr, agency::Agency};
-use std::marker::PhantomData;
-use tokio::{select, sync::mpsc};
-use tokio_stream::{
-    wrappers::{ReceiverStream, UnboundedReceiverStream},
-    StreamExt,
+use crate::{
+    actor::Actor,
+    actor::{Setup, StopReason},
+    addr::{Addr, AddrInner, DeliveryError, Responder, WeakAddr, WeakRecipient, RESPONDER},
+    agency::Agency,
+    behavior::{self, Behavior, Behaviors},
+    census::CensusGuard,
+    children::Children,
+    event_stream::{EventSink, EventStream},
+    handler::Handler,
+    journal::{Cursor, Queue},
+    layer::{LayerStack, Layers, Next},
+    mailbox::{self, Inbox, MailboxFactory},
+    observer::{MessageHandled, SlowMessage},
+    priority::PriorityMailbox,
+    request::Request,
+    stream::StreamHandle,
+    timer::{Timer, TimerHandle},
+};
+use futures_util::{
+    future::{BoxFuture, Either},
+    stream::{self, Stream, StreamExt},
+};
+use std::{
+    collections::VecDeque,
+    error::Error,
+    fmt::Display,
+    future::{pending, ready, Future},
+    marker::PhantomData,
+    pin::Pin,
+    sync::{atomic::AtomicBool, Arc},
+    time::Duration,
+};
+use tokio::{
+    select,
+    sync::{mpsc, watch, Mutex},
+    time::Instant,
 };
 
+#[cfg(feature = "serde")]
+type Persist<A> = Box<dyn Fn(&A) + Send + Sync>;
+
 pub struct Running;
+/// The actor isn't being run, such as while its state is swapped out, but messages still build up
+/// in its mailboxes.
+pub struct Paused;
 pub struct Stopped;
 
 pub trait Phase {}
 impl Phase for Running {}
+impl Phase for Paused {}
 impl Phase for Stopped {}
 
 pub struct Context<A: Actor, P: Phase = Running> {
-    mailbox: ReceiverStream<A::Msg>,
-    priority_mailbox: UnboundedReceiverStream<A::Msg>,
+    mailbox: Inbox<A::Msg>,
+    priority_mailbox: PriorityMailbox<A::Msg>,
+    /// Messages from [`Context::notify_queued`] that didn't fit in the mailbox.
+    overflow: VecDeque<A::Msg>,
+    /// Messages set aside with [`Context::stash`].
+    stash: VecDeque<A::Msg>,
+    /// Messages that were received but set aside, delivered again before anything else.
+    replay: VecDeque<A::Msg>,
+    /// The message returned by [`Context::peek`], delivered before anything else.
+    peeked: Option<A::Msg>,
+    timers: (
+        mpsc::UnboundedSender<Timer<A>>,
+        mpsc::UnboundedReceiver<Timer<A>>,
+    ),
+    /// The tasks waiting to queue each timer, aborted when the actor stops.
+    pending_timers: Vec<TimerHandle>,
+    /// The actors hired with [`Context::hire_child`], stopped before this one's `stopped`.
+    children: Children,
+    /// The name of the message being dispatched, for reporting panics.
+    handling: Option<&'static str>,
+    /// Middleware wrapped around [`Context::dispatch`], behind a lock so they can be borrowed
+    /// alongside the context.
+    layers: Option<Arc<Mutex<LayerStack<A>>>>,
+    /// Behaviors switched to with [`Context::become_`].
+    pub(crate) behaviors: Behaviors<A>,
+    /// How long to wait for a message before stopping, see [`Context::set_idle_timeout`].
+    idle_timeout: Option<Duration>,
     pub(crate) stopped: bool,
+    /// Why the actor is stopping, once it is, see [`Context::stop_reason`].
+    pub(crate) stop_reason: Option<StopReason>,
+    /// Which received messages are waiting to be committed to the actor's journal.
+    journal: Cursor,
+    /// Saves a snapshot of the actor, if it was hired with
+    /// [`Agency::hire_restored`](crate::Agency::hire_restored).
+    #[cfg(feature = "serde")]
+    persist: Option<Persist<A>>,
+    stop_signal: watch::Receiver<bool>,
+    pause_signal: watch::Receiver<bool>,
     addr: Addr<A>,
     pub agency: Agency,
+    /// Only `None` for the context handed to an actor that's been replaced.
+    _census: Option<CensusGuard>,
     _phase: PhantomData<P>,
 }
 
 impl<A: Actor> Context<A, Running> {
     pub(crate) fn new(agency: Agency) -> Self {
-        let (priority_mailer, priority_mailbox) = mpsc::unbounded_channel();
-        let (mailer, mailbox) = mpsc::channel(16);
+        let (priority_mailer, priority_mailbox) = PriorityMailbox::new();
+        let (mailer, mailbox) = mailbox::open(None, agency.config().capacity);
+        let addr = Addr::new(mailer, priority_mailer, agency.link());
+        Self::with_mailboxes(agency, addr, mailbox, priority_mailbox)
+    }
+
+    /// A context whose regular mailbox is built by `factory` rather than being the default
+    /// channel, or has its own capacity rather than the agency's, now and whenever the actor is
+    /// respawned.
+    pub(crate) fn with_mailbox(
+        agency: Agency,
+        factory: Option<MailboxFactory<A::Msg>>,
+        capacity: Option<usize>,
+    ) -> Self {
+        let (priority_mailer, priority_mailbox) = PriorityMailbox::new();
+        let (mailer, mailbox) = mailbox::open(
+            factory.as_ref(),
+            capacity.unwrap_or(agency.config().capacity),
+        );
+        let addr = Addr::new(mailer, priority_mailer, agency.link());
+        if let Some(factory) = factory {
+            addr.inner().set_mailbox_factory(factory);
+        }
+        if let Some(capacity) = capacity {
+            addr.inner().set_capacity(capacity);
+        }
+        Self::with_mailboxes(agency, addr, mailbox, priority_mailbox)
+    }
+
+    /// A context for a new incarnation of a stopped actor, with fresh mailboxes behind its
+    /// existing address.
+    pub(crate) fn respawn(agency: Agency, addr: &Addr<A>) -> Self {
+        let (priority_mailer, priority_mailbox) = PriorityMailbox::new();
+        let capacity = addr.inner().capacity().unwrap_or(agency.config().capacity);
+        let (mailer, mailbox) = mailbox::open(addr.inner().mailbox_factory(), capacity);
+        let addr = addr.reattach(mailer, priority_mailer);
+        Self::with_mailboxes(agency, addr, mailbox, priority_mailbox)
+    }
+
+    fn with_mailboxes(
+        agency: Agency,
+        addr: Addr<A>,
+        mailbox: Inbox<A::Msg>,
+        priority_mailbox: PriorityMailbox<A::Msg>,
+    ) -> Self {
+        let census = agency.register_actor(&addr);
+        let layers = LayerStack {
+            agency: agency.config().default_layers(),
+            actor: Vec::new(),
+        };
         Self {
-            mailbox: ReceiverStream::new(mailbox),
-            priority_mailbox: UnboundedReceiverStream::new(priority_mailbox),
+            mailbox,
+            priority_mailbox,
+            overflow: VecDeque::new(),
+            stash: VecDeque::new(),
+            replay: VecDeque::new(),
+            peeked: None,
+            timers: mpsc::unbounded_channel(),
+            pending_timers: Vec::new(),
+            children: Children::default(),
+            handling: None,
+            layers: (!layers.is_empty()).then(|| Arc::new(Mutex::new(layers))),
+            behaviors: Behaviors::new(),
+            idle_timeout: None,
             stopped: false,
-            addr: Addr::new(mailer, priority_mailer),
+            stop_reason: None,
+            journal: Cursor::new(),
+            #[cfg(feature = "serde")]
+            persist: None,
+            stop_signal: addr.inner().stop_signal(),
+            pause_signal: addr.inner().pause_signal(),
+            addr,
             agency,
+            _census: Some(census),
             _phase: PhantomData,
         }
     }
 
     /// Pull the next message off the stack, waiting if there are none
+    ///
+    /// If the actor is asked to stop while waiting here, the context is marked as stopped and the
+    /// current call to [`Actor::run`](crate::Actor::run) is abandoned. The same happens, without
+    /// marking the context as stopped, if the actor is asked to pause.
+    ///
+    /// Messages returned to the mailbox with [`Context::unstash_all`] or passed over by
+    /// [`Context::wait_for`] are delivered again first, in the order they originally arrived.
+    ///
+    /// Once every address to the actor besides its context's has been dropped, and everything
+    /// sent before then has been received, the actor is stopped here as if it had been asked to,
+    /// unless it has been [detached](Context::detach).
     pub async fn message(&mut self) -> A::Msg {
+        self.receive(true).await
+    }
+
+    async fn receive(&mut self, replay: bool) -> A::Msg {
+        match self.next(replay, false).await {
+            Received::Message(msg) => msg,
+            Received::Timer(_) => unreachable!("timers are only received when asked for"),
+        }
+    }
+
+    async fn next(&mut self, replay: bool, timers: bool) -> Received<A> {
+        self.addr.inner().stats().idle();
+        // Once stopping there's no run loop left to interrupt, so messages are received as normal,
+        // such as by `Actor::stopping` deciding whether to recover
+        let interruptible = !self.stopped;
+        let idle_deadline = self.idle_timeout.map(|timeout| Instant::now() + timeout);
+        let orphanable = interruptible && !self.addr.inner().is_detached();
+        let mut orphan_signal = self.addr.inner().orphan_signal();
         select! {
             biased;
-            Some(msg) = self.priority_mailbox.next() => {
-                msg
+            _ = stop_requested(&mut self.stop_signal), if interruptible => {
+                self.stop_on_request();
+                self.addr.inner().interrupt();
+                pending().await
             }
-            Some(msg) = self.mailbox.next() => {
-                msg
+            _ = stop_requested(&mut self.pause_signal), if interruptible => {
+                self.addr.inner().interrupt();
+                pending().await
+            }
+            Some(timer) = self.timers.1.recv(), if timers => Received::Timer(timer),
+            _ = ready(()), if replay && self.peeked.is_some() => {
+                Received::Message(self.peeked.take().expect("peeked is some"))
+            }
+            _ = ready(()), if replay && !self.replay.is_empty() => {
+                self.addr.inner().stats().active();
+                Received::Message(self.replay.pop_front().expect("replay is not empty"))
+            }
+            Some(msg) = self.priority_mailbox.recv() => {
+                self.received(Queue::Priority);
+                Received::Message(msg)
+            }
+            _ = ready(()), if !self.overflow.is_empty() => {
+                self.received(Queue::Overflow);
+                Received::Message(self.overflow.pop_front().expect("overflow is not empty"))
+            }
+            Some(msg) = self.mailbox.recv() => {
+                self.received(Queue::Regular);
+                Received::Message(msg)
+            }
+            // Only once the mailboxes are empty, so nothing sent before the last address was
+            // dropped goes unhandled
+            _ = stop_requested(&mut orphan_signal), if orphanable => {
+                self.addr.inner().request_stop();
+                self.stop_because(StopReason::Orphaned);
+                self.addr.inner().interrupt();
+                pending().await
+            }
+            _ = idle(&self.agency, idle_deadline), if interruptible && idle_deadline.is_some() => {
+                self.addr.inner().request_stop();
+                self.stop_because(StopReason::IdleTimeout);
+                self.addr.inner().interrupt();
+                pending().await
             }
             else => {
                 unreachable!("mailboxes live at least as long as the running context");
@@ -52,40 +259,1183 @@ impl<A: Actor> Context<A, Running> {
         }
     }
 
+    /// Wait for the first message to arrive, leaving it to be received as normal, so a lazy actor
+    /// can be started. Returns false if the actor is asked to stop first.
+    pub(crate) async fn activated(&mut self) -> bool {
+        let mut orphan_signal = self.addr.inner().orphan_signal();
+        let msg = select! {
+            biased;
+            _ = stop_requested(&mut self.stop_signal) => return false,
+            Some(msg) = self.priority_mailbox.recv() => {
+                self.received(Queue::Priority);
+                msg
+            }
+            Some(msg) = self.mailbox.recv() => {
+                self.received(Queue::Regular);
+                msg
+            }
+            _ = stop_requested(&mut orphan_signal) => return false,
+            else => return false,
+        };
+        self.peeked = Some(msg);
+        true
+    }
+
+    /// Look at the next message without taking it, returning `None` if there isn't one waiting.
+    ///
+    /// The message stays at the front of the queue, so it's returned by the next call to
+    /// [`Context::message`], [`Context::try_message`] or [`Context::take_peeked`].
+    pub fn peek(&mut self) -> Option<&A::Msg> {
+        if self.peeked.is_none() {
+            self.peeked = self.try_next();
+        }
+        self.peeked.as_ref()
+    }
+
+    /// Take the message last returned by [`Context::peek`], if it hasn't been taken since.
+    pub fn take_peeked(&mut self) -> Option<A::Msg> {
+        self.peeked.take()
+    }
+
+    /// Borrow the priority and regular mailboxes as separate streams, in that order, for a custom
+    /// `select!` over them alongside other sources of events, with its own prioritisation.
+    ///
+    /// The regular stream yields anything that overflowed from [`Context::notify_queued`] first,
+    /// as [`Context::message`] would. Messages that have been peeked or set aside aren't in
+    /// either stream, and the streams don't notice if the actor has been asked to stop, so only
+    /// use them for as long as the custom loop needs. Nothing is lost in between: once the
+    /// streams are dropped, [`Context::message`] carries on from where they left off.
+    pub fn split_streams(
+        &mut self,
+    ) -> (
+        impl Stream<Item = A::Msg> + '_,
+        impl Stream<Item = A::Msg> + '_,
+    ) {
+        let Self {
+            mailbox,
+            priority_mailbox,
+            overflow,
+            journal,
+            addr,
+            ..
+        } = self;
+        let addr = &*addr;
+        // Both streams record what they yield in the journal's cursor
+        let journal = Arc::new(std::sync::Mutex::new(journal));
+        let received = move |journal: &std::sync::Mutex<&mut Cursor>, queue| {
+            addr.inner().stats().received(queue == Queue::Priority);
+            journal.lock().unwrap().received(addr, queue);
+        };
+
+        let priority = stream::unfold(
+            (priority_mailbox, journal.clone()),
+            move |(priority_mailbox, journal)| async move {
+                let msg = priority_mailbox.recv().await?;
+                received(&journal, Queue::Priority);
+                Some((msg, (priority_mailbox, journal)))
+            },
+        );
+        let regular = stream::unfold(
+            (mailbox, overflow, journal),
+            move |(mailbox, overflow, journal)| async move {
+                let msg = match overflow.pop_front() {
+                    Some(msg) => {
+                        received(&journal, Queue::Overflow);
+                        msg
+                    }
+                    None => {
+                        let msg = mailbox.recv().await?;
+                        received(&journal, Queue::Regular);
+                        msg
+                    }
+                };
+                Some((msg, (mailbox, overflow, journal)))
+            },
+        );
+        (priority, regular)
+    }
+
+    /// Wait for either the next message, as from [`Context::message`], or a future to finish,
+    /// whichever comes first, such as a request sent by this actor.
+    ///
+    /// The future is polled first, so when both are ready it's the future's output that's
+    /// returned and the message stays queued. If a message arrives first the future is dropped,
+    /// so use [`Context::select_pinned`] to carry on waiting for one that can't be restarted.
+    pub async fn select<F>(&mut self, fut: F) -> Either<A::Msg, F::Output>
+    where
+        F: Future,
+    {
+        futures_util::pin_mut!(fut);
+        self.select_pinned(fut).await
+    }
+
+    /// Like [`Context::select`], but borrowing the future, so it can be raced against the
+    /// mailbox again if a message arrives first.
+    ///
+    /// ```
+    /// # use agency::{prelude::*, Either};
+    /// # use std::future::Future;
+    /// # struct Client;
+    /// # impl Client {
+    /// #     fn fetch(&self, url: String) -> impl Future<Output = String> {
+    /// #         async move { url }
+    /// #     }
+    /// # }
+    /// # struct Crawler {
+    /// #     client: Client,
+    /// #     queued: Vec<String>,
+    /// # }
+    /// # impl Crawler {
+    /// #     fn queue(&mut self, url: String) {
+    /// #         self.queued.push(url);
+    /// #     }
+    /// # }
+    /// # #[async_trait]
+    /// # impl Actor for Crawler {
+    /// #     type Msg = String;
+    /// #     async fn run(&mut self, ctx: &mut Context<Self>) {
+    /// #         let url = ctx.message().await;
+    /// let fetch = self.client.fetch(url);
+    /// tokio::pin!(fetch);
+    /// let page = loop {
+    ///     match ctx.select_pinned(fetch.as_mut()).await {
+    ///         Either::Left(msg) => self.queue(msg),
+    ///         Either::Right(page) => break page,
+    ///     }
+    /// };
+    /// #         drop(page);
+    /// #     }
+    /// # }
+    /// ```
+    pub async fn select_pinned<F>(&mut self, mut fut: Pin<&mut F>) -> Either<A::Msg, F::Output>
+    where
+        F: Future,
+    {
+        // Receiving is cancel safe: a message is only taken once its branch has won
+        select! {
+            biased;
+            output = fut.as_mut() => Either::Right(output),
+            msg = self.receive(true) => Either::Left(msg),
+        }
+    }
+
+    /// Take the next message if there's one waiting, without waiting for one to arrive.
+    ///
+    /// Unlike [`Context::message`], this doesn't notice if the actor has been asked to stop.
+    pub fn try_message(&mut self) -> Option<A::Msg> {
+        self.peeked.take().or_else(|| self.try_next())
+    }
+
+    /// Wait for the next message, as with [`Context::message`], then take whichever others are
+    /// already waiting, up to `max` in all, so a burst can be handled in one go, such as by
+    /// writing it out in a single batch.
+    ///
+    /// The batch is in the order [`Context::message`] would have delivered it, so priority
+    /// messages come before regular ones.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max` is 0.
+    pub async fn messages(&mut self, max: usize) -> Vec<A::Msg> {
+        assert!(max > 0, "a batch must have room for at least one message");
+        let first = self.message().await;
+        let mut batch = Vec::with_capacity(max.min(self.mailbox_depth() + 1));
+        batch.push(first);
+        while batch.len() < max {
+            if let Some(msg) = self.try_next_ahead() {
+                batch.push(msg);
+                continue;
+            }
+            let limit = max - batch.len();
+            for _ in 0..self.mailbox.try_recv_many(&mut batch, limit) {
+                self.received(Queue::Regular);
+            }
+            break;
+        }
+        batch
+    }
+
+    /// Take the next waiting message, in the same order as [`Context::message`].
+    fn try_next(&mut self) -> Option<A::Msg> {
+        if let Some(msg) = self.try_next_ahead() {
+            return Some(msg);
+        }
+        let msg = self.mailbox.try_recv()?;
+        self.received(Queue::Regular);
+        Some(msg)
+    }
+
+    /// Take the next message waiting ahead of the regular mailbox.
+    fn try_next_ahead(&mut self) -> Option<A::Msg> {
+        if let Some(msg) = self.replay.pop_front() {
+            self.addr.inner().stats().active();
+            return Some(msg);
+        }
+        if let Some(msg) = self.priority_mailbox.try_recv() {
+            self.received(Queue::Priority);
+            return Some(msg);
+        }
+        let msg = self.overflow.pop_front()?;
+        self.received(Queue::Overflow);
+        Some(msg)
+    }
+
+    /// Save a snapshot of the actor to the store it was hired from with
+    /// [`Agency::hire_restored`](crate::Agency::hire_restored). Returns false, without doing
+    /// anything, if it was hired some other way.
+    #[cfg(feature = "serde")]
+    pub fn save_snapshot(&self, actor: &A) -> bool
+    where
+        A: crate::Persistent,
+    {
+        match &self.persist {
+            Some(persist) => {
+                persist(actor);
+                true
+            }
+            None => false,
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn set_persist(&mut self, persist: Persist<A>) {
+        self.persist = Some(persist);
+    }
+
+    /// Queue up messages to be delivered before anything in the mailboxes.
+    pub(crate) fn preload(&mut self, msgs: Vec<A::Msg>) {
+        self.replay.extend(msgs);
+    }
+
+    /// Account for a message taken from one of the queues.
+    fn received(&mut self, queue: Queue) {
+        self.addr.inner().stats().received(queue == Queue::Priority);
+        self.journal.received(&self.addr, queue);
+    }
+
+    /// Commit the messages received during a call to `run` that's returned.
+    pub(crate) fn commit_received(&mut self) {
+        self.journal.commit(&self.addr);
+    }
+
+    /// Leave the messages received during a call to `run` that didn't finish uncommitted.
+    pub(crate) fn abandon_received(&mut self) {
+        self.journal.abandon();
+    }
+
+    /// Wait for the first message matching the predicate, setting aside everything else.
+    ///
+    /// Messages that have already been set aside are checked first, then new messages from both
+    /// mailboxes as they arrive, so priority messages can match too. Messages that don't match are
+    /// delivered again by [`Context::message`] afterwards, in the order they arrived, whether or
+    /// not a match is found in time.
+    ///
+    /// # Errors
+    ///
+    /// This will error if no matching message arrives before the timeout.
+    pub async fn wait_for<F>(&mut self, mut pred: F, timeout: Duration) -> Result<A::Msg, WaitError>
+    where
+        F: FnMut(&A::Msg) -> bool,
+    {
+        if let Some(msg) = self.peeked.take() {
+            self.replay.push_front(msg);
+        }
+        if let Some(index) = self.replay.iter().position(&mut pred) {
+            self.addr.inner().stats().active();
+            return Ok(self.replay.remove(index).expect("index is in bounds"));
+        }
+
+        let deadline = Instant::now() + timeout;
+        let agency = self.agency.clone();
+        loop {
+            // Messages are set aside as soon as they're received, so none are lost if we're
+            // interrupted by a stop or the caller gives up on us
+            let msg = agency
+                .timeout_at(deadline, self.receive(false))
+                .await
+                .map_err(|_| WaitError::Timeout)?;
+            if pred(&msg) {
+                return Ok(msg);
+            }
+            self.replay.push_back(msg);
+        }
+    }
+
+    /// Set a message aside until [`Context::unstash_all`] is called.
+    pub fn stash(&mut self, msg: A::Msg) {
+        self.stash.push_back(msg);
+    }
+
+    /// Return every stashed message to be delivered again by [`Context::message`], ahead of
+    /// anything that hasn't been delivered yet.
+    pub fn unstash_all(&mut self) {
+        let mut replay = std::mem::take(&mut self.stash);
+        replay.append(&mut self.replay);
+        self.replay = replay;
+    }
+
+    /// Pull the next message and hand it to the actor's [`Handler`], timing how long it takes.
+    pub async fn dispatch(&mut self, actor: &mut A)
+    where
+        A: 'static + Handler,
+    {
+        match self.next(true, true).await {
+            Received::Timer(timer) => timer.run(actor, self),
+            Received::Message(msg) => {
+                let depth = self.mailbox_depth();
+                let name = A::message_name(&msg);
+                let start = Instant::now();
+                self.handling = Some(name);
+                match self.layers.clone() {
+                    Some(layers) => {
+                        let mut layers = layers.lock().await;
+                        Next::new(&mut layers).run(actor, self, msg).await;
+                    }
+                    None => behavior::handle(actor, self, msg).await,
+                }
+                self.handling = None;
+                self.report_handled(name, start.elapsed(), depth);
+            }
+        }
+    }
+
+    /// Switch to handling messages with a [`Behavior`], rather than the actor's
+    /// [`Handler`](crate::Handler), until it's undone with [`Context::unbecome`]. Behaviors stack
+    /// up, so each one unbecomes back to the last.
+    ///
+    /// Like the handler, behaviors are only used by [`Context::dispatch`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are already [`MAX_BEHAVIORS`](crate::MAX_BEHAVIORS) stacked up.
+    pub fn become_(&mut self, behavior: impl Behavior<A>) {
+        self.behaviors.push(Box::new(behavior));
+    }
+
+    /// Go back to the behavior before the current one, or the actor's handler, returning false if
+    /// it was already using its handler.
+    pub fn unbecome(&mut self) -> bool {
+        self.behaviors.pop()
+    }
+
+    /// Run a closure against the actor after a delay, such as to flush a buffer.
+    ///
+    /// The closure is run by [`Context::dispatch`] between messages, so it's never run for actors
+    /// that pull their own messages in `run`. It's dropped without running if the actor stops
+    /// first.
+    pub fn run_later<F>(&mut self, delay: Duration, f: F) -> TimerHandle
+    where
+        A: 'static,
+        F: FnOnce(&mut A, &mut Context<A>) + Send + 'static,
+    {
+        let (timer, cancelled) = Timer::new(f);
+        let sender = self.timers.0.clone();
+        let sleep = self.agency.sleep_until(Instant::now() + delay);
+        self.spawn_timer(cancelled, async move {
+            sleep.await;
+            let _ = sender.send(timer);
+        })
+    }
+
+    /// Send a message back to this actor after a delay, through the priority mailbox like
+    /// [`Context::notify`].
+    ///
+    /// Unlike [`Context::run_later`], the message arrives like any other, so it works for actors
+    /// that pull their own messages in `run`. It's dropped without being sent if the actor stops
+    /// first.
+    pub fn notify_later(&mut self, msg: impl Into<A::Msg>, delay: Duration) -> TimerHandle
+    where
+        A: 'static,
+    {
+        let msg = msg.into();
+        // Weak, so a pending message doesn't keep the mailbox open
+        let addr = self.addr.downgrade();
+        let sleep = self.agency.sleep_until(Instant::now() + delay);
+        self.spawn_timer(Arc::default(), async move {
+            sleep.await;
+            if let Some(addr) = addr.upgrade() {
+                let _ = addr.send_priority(msg);
+            }
+        })
+    }
+
+    /// Send a message back to this actor, through the priority mailbox like
+    /// [`Context::notify`], once another actor has stopped, see [`Addr::watch`].
+    ///
+    /// The message is sent however the other actor stopped, and straight away if it already has.
+    /// It's dropped without being sent if this actor stops first, or the returned handle is
+    /// cancelled.
+    pub fn watch<B>(&mut self, addr: &Addr<B>, msg: impl Into<A::Msg>) -> TimerHandle
+    where
+        A: 'static,
+        B: Actor,
+    {
+        let msg = msg.into();
+        let stopped = addr.watch();
+        let addr = self.addr.downgrade();
+        self.spawn_timer(Arc::default(), async move {
+            stopped.await;
+            if let Some(addr) = addr.upgrade() {
+                let _ = addr.send_priority(msg);
+            }
+        })
+    }
+
+    /// Send a message built by `factory` back to this actor every `interval`, starting one
+    /// interval from now, such as for a heartbeat, until it's cancelled or the actor stops.
+    ///
+    /// The messages go through the priority mailbox, like [`Context::notify_later`], which has
+    /// no backpressure, so they'll pile up if they come faster than the actor handles them.
+    pub fn notify_interval<F, M>(&mut self, mut factory: F, interval: Duration) -> TimerHandle
+    where
+        A: 'static,
+        F: FnMut() -> M + Send + 'static,
+        M: Into<A::Msg>,
+    {
+        let addr = self.addr.downgrade();
+        let agency = self.agency.clone();
+        self.spawn_timer(Arc::default(), async move {
+            let mut next = Instant::now() + interval;
+            loop {
+                agency.sleep_until(next).await;
+                match addr.upgrade() {
+                    Some(addr) if addr.send_priority(factory()).is_ok() => {}
+                    _ => return,
+                }
+                next += interval;
+            }
+        })
+    }
+
+    /// Feed a stream's items into this actor's regular mailbox, waiting for room for each one so
+    /// the stream is read no faster than the actor keeps up, such as for frames from a socket.
+    ///
+    /// Any number of streams can be added, each read in its own task until it ends, it's
+    /// cancelled through the returned handle, or the actor stops.
+    pub fn add_stream<S>(&mut self, stream: S) -> StreamHandle
+    where
+        A: 'static,
+        S: Stream + Send + 'static,
+        S::Item: Into<A::Msg> + Send,
+    {
+        self.forward_stream(stream, None)
+    }
+
+    /// Like [`Context::add_stream`], sending `end` once the stream has ended, after the last of
+    /// its items.
+    pub fn add_stream_then<S>(&mut self, stream: S, end: impl Into<A::Msg>) -> StreamHandle
+    where
+        A: 'static,
+        S: Stream + Send + 'static,
+        S::Item: Into<A::Msg> + Send,
+    {
+        self.forward_stream(stream, Some(end.into()))
+    }
+
+    fn forward_stream<S>(&mut self, stream: S, end: Option<A::Msg>) -> StreamHandle
+    where
+        A: 'static,
+        S: Stream + Send + 'static,
+        S::Item: Into<A::Msg> + Send,
+    {
+        let addr = self.addr.downgrade();
+        let (finished, finished_signal) = watch::channel(false);
+        let task = self.spawn_timer(Arc::default(), async move {
+            futures_util::pin_mut!(stream);
+            while let Some(item) = stream.next().await {
+                // Stopping cancels this task, but the actor may have stopped without it yet
+                match addr.upgrade() {
+                    Some(addr) if addr.send(item).await.is_ok() => {}
+                    _ => return,
+                }
+            }
+            finished.send_replace(true);
+            if let (Some(end), Some(addr)) = (end, addr.upgrade()) {
+                let _ = addr.send(end).await;
+            }
+        });
+        StreamHandle::new(task, finished_signal)
+    }
+
+    /// Spawn a timer's task, keeping hold of its handle so it's cancelled if the actor stops.
+    fn spawn_timer<F>(&mut self, cancelled: Arc<AtomicBool>, fut: F) -> TimerHandle
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let task = self.agency.spawn_detached(fut);
+        self.track(TimerHandle::new(task.abort_handle(), cancelled))
+    }
+
+    /// Run a future in its own task alongside the actor, such as to answer a request without
+    /// holding up the next message. The agency handle waits for the task, and it's aborted if the
+    /// actor stops first.
+    ///
+    /// Responses the task sends with [`Request::respond`] are counted against the actor, as if it
+    /// had sent them itself.
+    ///
+    /// ```
+    /// # use agency::prelude::*;
+    /// # #[derive(Clone)]
+    /// # struct Pool;
+    /// # struct Conn;
+    /// # struct User;
+    /// # struct GetUser {
+    /// #     id: u32,
+    /// # }
+    /// # impl Pool {
+    /// #     async fn get(&self) -> Conn {
+    /// #         Conn
+    /// #     }
+    /// # }
+    /// # impl Conn {
+    /// #     async fn fetch_user(&self, _id: u32) -> User {
+    /// #         User
+    /// #     }
+    /// # }
+    /// # enum DbMsg {
+    /// #     GetUser(Request<GetUser, User>),
+    /// # }
+    /// # struct Db {
+    /// #     pool: Pool,
+    /// # }
+    /// # #[async_trait]
+    /// # impl Actor for Db {
+    /// #     type Msg = DbMsg;
+    /// // A database actor answering requests concurrently, each on its own connection
+    /// async fn run(&mut self, ctx: &mut Context<Self>) {
+    ///     match ctx.message().await {
+    ///         DbMsg::GetUser(request) => {
+    ///             let pool = self.pool.clone();
+    ///             ctx.spawn(async move {
+    ///                 let user = pool.get().await.fetch_user(request.payload().id).await;
+    ///                 let _ = request.respond(user);
+    ///             });
+    ///         }
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn spawn<F>(&mut self, fut: F) -> TimerHandle
+    where
+        A: 'static,
+        F: Future<Output = ()> + Send + 'static,
+    {
+        // Carries on the trace of the message being handled
+        #[cfg(feature = "trace")]
+        let fut = crate::trace::outgoing().scope(fut);
+        let fut = RESPONDER.scope(Responder::new::<A>(self.addr.inner().clone()), fut);
+        let task = self.agency.spawn(fut);
+        self.track(TimerHandle::new(task, Arc::default()))
+    }
+
+    /// Hire an actor that stops along with this one, such as a worker for each connection a
+    /// listener accepts.
+    ///
+    /// Once this actor has decided to stop, rather than recover, its children are asked to stop
+    /// and [`Actor::stopped`](crate::Actor::stopped) only runs after every one of them has
+    /// finished. Children that stop sooner on their own are simply forgotten. A child that keeps
+    /// recovering from being stopped keeps this actor waiting.
+    pub fn hire_child<C>(&mut self, child: C) -> Addr<C>
+    where
+        C: 'static + Actor,
+    {
+        let addr = self.agency.hire(child);
+        self.children.add(addr.inner().clone());
+        addr
+    }
+
+    /// Like [`Context::hire_child`], for a child that sets itself up from `args` as with
+    /// [`Agency::hire_with`](crate::Agency::hire_with).
+    pub fn hire_child_with<C>(&mut self, args: C::Args) -> Addr<C>
+    where
+        C: 'static + Setup,
+    {
+        let addr = self.agency.hire_with::<C>(args);
+        self.children.add(addr.inner().clone());
+        addr
+    }
+
+    /// Keep hold of a task's handle, so it's cancelled if the actor stops.
+    fn track(&mut self, handle: TimerHandle) -> TimerHandle {
+        self.pending_timers.retain(TimerHandle::is_pending);
+        self.pending_timers.push(handle.clone());
+        handle
+    }
+
+    /// Time a block of work, reporting it to the agency's [`Observer`](crate::Observer) as if it
+    /// were a message with the given label.
+    ///
+    /// This is the manual equivalent of [`Context::dispatch`] for actors that pull their own
+    /// messages in `run`.
+    pub async fn time_block<F>(&mut self, label: &'static str, fut: F) -> F::Output
+    where
+        F: Future,
+    {
+        let depth = self.mailbox_depth();
+        let start = Instant::now();
+        let output = fut.await;
+        self.report_handled(label, start.elapsed(), depth);
+        output
+    }
+
+    /// Set the actor's own layers, beneath any the agency applies to every actor.
+    pub(crate) fn set_layers(&mut self, layers: Layers<A>) {
+        if layers.is_empty() {
+            return;
+        }
+        match &mut self.layers {
+            Some(stack) => {
+                let stack = Arc::get_mut(stack).expect("layers are set before the actor starts");
+                stack.get_mut().actor = layers;
+            }
+            None => {
+                self.layers = Some(Arc::new(Mutex::new(LayerStack {
+                    agency: Vec::new(),
+                    actor: layers,
+                })))
+            }
+        }
+    }
+
+    pub(crate) fn take_handling(&mut self) -> Option<&'static str> {
+        self.handling.take()
+    }
+
+    fn mailbox_depth(&self) -> usize {
+        self.mailbox.len() + self.priority_mailbox.len() + self.overflow.len()
+    }
+
+    fn report_handled(&self, message: &'static str, duration: Duration, mailbox_depth: usize) {
+        let observer = match self.agency.observer() {
+            Some(observer) => observer,
+            None => return,
+        };
+        let actor_id = self.addr.id();
+        let actor_type = std::any::type_name::<A>();
+
+        observer.message_handled(&MessageHandled {
+            actor_id,
+            actor_type,
+            message,
+            duration,
+        });
+        if let Some(threshold) = observer.slow_message_threshold() {
+            if duration > threshold {
+                observer.slow_message(&SlowMessage {
+                    actor_id,
+                    actor_type,
+                    message,
+                    duration,
+                    mailbox_depth,
+                });
+            }
+        }
+    }
+
+    /// Respond to a request with the output of the future built from its payload.
+    ///
+    /// If the requester has already stopped listening, such as after a timeout, the future isn't
+    /// built at all. Returns whether the response was delivered.
+    pub async fn reply<Req, Res, F, Fut>(&mut self, request: Request<Req, Res>, f: F) -> bool
+    where
+        F: FnOnce(Req) -> Fut,
+        Fut: Future<Output = Res>,
+    {
+        match request.handle() {
+            Some((payload, reply_to)) => reply_to.send(f(payload).await).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Like [`Context::reply`], but the context is also passed to the closure so it can be used
+    /// while computing the response.
+    ///
+    /// ```
+    /// # use agency::prelude::*;
+    /// # struct Double(u32);
+    /// # struct Refresh;
+    /// # enum Msg {
+    /// #     Double(Request<Double, u32>),
+    /// #     Refresh(Refresh),
+    /// # }
+    /// # impl From<Refresh> for Msg {
+    /// #     fn from(msg: Refresh) -> Self {
+    /// #         Self::Refresh(msg)
+    /// #     }
+    /// # }
+    /// # struct Doubler;
+    /// # #[async_trait]
+    /// # impl Actor for Doubler {
+    /// #     type Msg = Msg;
+    /// #     async fn run(&mut self, ctx: &mut Context<Self>) {
+    /// #         if let Msg::Double(request) = ctx.message().await {
+    /// ctx.reply_with(request, |ctx, payload| Box::pin(async move {
+    ///     ctx.notify(Refresh);
+    ///     payload.0 * 2
+    /// }))
+    /// .await;
+    /// #         }
+    /// #     }
+    /// # }
+    /// ```
+    pub async fn reply_with<Req, Res, F>(&mut self, request: Request<Req, Res>, f: F) -> bool
+    where
+        F: for<'a> FnOnce(&'a mut Self, Req) -> BoxFuture<'a, Res>,
+    {
+        match request.handle() {
+            Some((payload, reply_to)) => reply_to.send(f(self, payload).await).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Like [`Context::reply`], but the future runs in its own task so long computations don't
+    /// hold up the actor's next message.
+    ///
+    /// The future is dropped early if the requester stops listening while it runs.
+    pub fn reply_spawned<Req, Res, F, Fut>(&self, request: Request<Req, Res>, f: F)
+    where
+        Res: 'static + Send,
+        F: FnOnce(Req) -> Fut,
+        Fut: Future<Output = Res> + Send + 'static,
+    {
+        if let Some((payload, mut reply_to)) = request.handle() {
+            let fut = f(payload);
+            // Carries on the trace of the request it's answering
+            #[cfg(feature = "trace")]
+            let fut = crate::trace::outgoing().scope(fut);
+            self.agency.spawn(async move {
+                let res = select! {
+                    res = fut => Some(res),
+                    _ = reply_to.closed() => None,
+                };
+                if let Some(res) = res {
+                    let _ = reply_to.send(res);
+                }
+            });
+        }
+    }
+
+    /// The trace of the message being handled, which anything sent while handling it carries on,
+    /// see [`TraceId`](crate::TraceId).
+    ///
+    /// This is the trace of the last message taken from the regular or priority mailbox, so it's
+    /// `None` until the first one arrives.
+    #[cfg(feature = "trace")]
+    pub fn current_trace(&self) -> Option<crate::TraceId> {
+        crate::TraceId::current()
+    }
+
+    /// Set the name this actor is listed under in [`Agency::dump`](crate::Agency::dump).
+    pub fn set_name(&mut self, name: impl Into<String>) {
+        self.addr.inner().set_name(name.into());
+    }
+
     pub fn stop(&mut self) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!("stop requested");
+        self.stop_because(StopReason::SelfStopped);
+    }
+
+    /// Mark the actor as stopping, keeping the first reason it was given.
+    fn stop_because(&mut self, reason: StopReason) {
         self.stopped = true;
+        self.stop_reason.get_or_insert(reason);
+    }
+
+    /// Mark the actor as stopping after it was asked to from outside.
+    pub(crate) fn stop_on_request(&mut self) {
+        let reason = if self.agency.is_shutting_down() {
+            StopReason::AgencyShutdown
+        } else {
+            StopReason::Requested
+        };
+        self.stop_because(reason);
+    }
+
+    /// Keep the actor running once every other address to it has been dropped, rather than
+    /// stopping it then, for daemon-style actors that only stop when asked to.
+    pub fn detach(&mut self) {
+        self.addr.inner().detach();
+    }
+
+    /// Resolves once the actor has been asked to stop, by [`Addr::stop`] or
+    /// [`Agency::shutdown`](crate::Agency::shutdown), for racing against long waits on anything
+    /// other than the mailbox in the actor's own `select!`s.
+    ///
+    /// Returning from `run` once it's resolved stops the actor, as if it had called
+    /// [`Context::stop`].
+    ///
+    /// ```
+    /// # use agency::prelude::*;
+    /// # use tokio::{io::AsyncReadExt, net::TcpStream, select};
+    /// # struct Connection {
+    /// #     socket: TcpStream,
+    /// # }
+    /// # impl Connection {
+    /// #     fn received(&mut self, _read: std::io::Result<usize>) {}
+    /// # }
+    /// # #[async_trait]
+    /// # impl Actor for Connection {
+    /// #     type Msg = ();
+    /// #     async fn run(&mut self, ctx: &mut Context<Self>) {
+    /// #         let mut buf = [0; 1024];
+    /// select! {
+    ///     read = self.socket.read(&mut buf) => self.received(read),
+    ///     _ = ctx.shutdown_requested() => {}
+    /// }
+    /// #     }
+    /// # }
+    /// ```
+    pub fn shutdown_requested(&self) -> impl Future<Output = ()> + '_ {
+        let mut signal = self.stop_signal.clone();
+        async move { stop_requested(&mut signal).await }
+    }
+
+    /// Stop the actor once it's waited this long for a message, such as to retire actors that
+    /// are only needed while there's traffic for them. `None`, the default, waits indefinitely.
+    ///
+    /// The wait starts afresh each time the actor waits for a message, and stopping goes through
+    /// [`Actor::stopping`](crate::Actor::stopping) as usual, so the actor can still recover.
+    pub fn set_idle_timeout(&mut self, timeout: Option<Duration>) {
+        self.idle_timeout = timeout;
     }
 
     pub fn address(&self) -> Addr<A> {
-        self.addr.clone()
+        self.addr.leased()
+    }
+
+    /// Get a weak address to this actor, for registering it somewhere without the registration
+    /// keeping it alive.
+    ///
+    /// Weak addresses don't count as senders, so an actor that's only known through them has no
+    /// one left to talk to it besides itself.
+    pub fn address_weak(&self) -> WeakAddr<A> {
+        self.addr.downgrade()
+    }
+
+    /// Create a sink for events this actor emits, along with a first subscriber's stream of them,
+    /// for consumers that aren't actors.
+    ///
+    /// Hand out more streams with [`EventSink::subscribe`]. Each subscriber can fall up to
+    /// `buffer` events behind before its [`LagPolicy`](crate::LagPolicy) applies, and every
+    /// stream ends once this actor stops.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffer` is zero.
+    pub fn event_stream<E>(&self, buffer: usize) -> (EventSink<E>, EventStream<E>)
+    where
+        E: 'static + Clone + Send,
+    {
+        let sink = EventSink::new(buffer, self.addr.inner().exit_signal());
+        let stream = sink.subscribe();
+        (sink, stream)
+    }
+
+    /// Get a weak recipient for one of this actor's message types, see
+    /// [`Context::address_weak`].
+    pub fn weak_recipient<M>(&self) -> WeakRecipient<M>
+    where
+        A: 'static,
+        M: 'static + Into<A::Msg> + Send,
+    {
+        self.addr.downgrade().recipient()
+    }
+
+    /// Receive every [`Agency::broadcast`](crate::Agency::broadcast) of a message type, returning
+    /// false if this actor was already registered for it.
+    pub fn receive_broadcasts<M>(&self) -> bool
+    where
+        A: 'static,
+        M: 'static + Into<A::Msg> + Send,
+    {
+        self.agency
+            .register_broadcast_target(self.weak_recipient::<M>())
     }
 
     /// Send a message back to this actor.
     ///
-    /// Messages sent this way take priority over regular messages.
+    /// Messages sent this way take priority over regular messages. If the priority mailbox has
+    /// somehow closed already the message is dropped, with a warning under the `tracing` feature.
     pub fn notify(&mut self, msg: impl Into<A::Msg>) {
-        self.addr
-            .send_priority(msg)
-            .expect("mailboxes live at least as long as the context");
+        if self.addr.send_priority(msg).is_err() {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(
+                actor_id = %self.addr.id(),
+                actor = std::any::type_name::<A>(),
+                "notify failed, the mailbox has closed"
+            );
+        }
+    }
+
+    /// Send a message back to this actor through the regular mailbox, so it's queued behind
+    /// messages that have already arrived rather than jumping ahead of them like
+    /// [`Context::notify`].
+    ///
+    /// Waiting for room in our own mailbox would never finish, so if it's full the message goes
+    /// into an overflow buffer instead. The overflow buffer is drained after the priority mailbox
+    /// but before the regular mailbox, so a message that overflowed overtakes everything already
+    /// waiting in the mailbox, including earlier messages sent this way. Once something has
+    /// overflowed, later messages are buffered behind it until the buffer is empty again.
+    pub fn notify_queued(&mut self, msg: impl Into<A::Msg>) {
+        let msg = msg.into();
+        // Once anything has overflowed, later messages have to follow it to keep their order
+        if !self.overflow.is_empty() {
+            self.journal.overflowed(&self.addr, &msg);
+            self.overflow.push_back(msg);
+            return;
+        }
+        match self.addr.mailer().try_reserve() {
+            Ok(permit) => {
+                let _ = self.addr.inner().enqueue(Queue::Regular, msg, |msg| {
+                    permit.send(msg);
+                    Ok::<_, ()>(())
+                });
+            }
+            Err(DeliveryError::Full(())) => {
+                self.journal.overflowed(&self.addr, &msg);
+                self.overflow.push_back(msg);
+            }
+            Err(_) => unreachable!("mailboxes live at least as long as the context"),
+        }
     }
 
     pub(crate) fn next_phase(mut self) -> Context<A, Stopped> {
         self.mailbox.close();
         self.priority_mailbox.close();
+        for timer in self.pending_timers.drain(..) {
+            timer.cancel();
+        }
+        self.timers.1.close();
+
+        let mut ctx = self.into_phase();
+        ctx.stopped = true;
+        ctx.layers = None;
+        ctx
+    }
+
+    /// Build a stopped context for an actor that's being replaced, leaving the mailboxes, and
+    /// anything stashed or set aside, in this context for its replacement.
+    ///
+    /// The handed over context has nothing left to drain, and timers queued by the old actor are
+    /// cancelled rather than run against the new one.
+    pub(crate) fn hand_over(&mut self) -> Context<A, Stopped> {
+        for timer in self.pending_timers.drain(..) {
+            timer.cancel();
+        }
+        self.timers = mpsc::unbounded_channel();
+        self.handling = None;
+        self.behaviors.clear();
+
+        // Mailboxes that are already closed, so they read as drained
+        let mailbox = Inbox::closed();
+        let priority_mailbox = PriorityMailbox::closed();
+        let mut timers = mpsc::unbounded_channel();
+        timers.1.close();
+
+        Context {
+            mailbox,
+            priority_mailbox,
+            overflow: VecDeque::new(),
+            stash: VecDeque::new(),
+            replay: VecDeque::new(),
+            peeked: None,
+            timers,
+            pending_timers: Vec::new(),
+            children: Children::default(),
+            handling: None,
+            layers: None,
+            behaviors: Behaviors::new(),
+            idle_timeout: None,
+            stopped: true,
+            stop_reason: self.stop_reason,
+            journal: Cursor::new(),
+            #[cfg(feature = "serde")]
+            persist: None,
+            stop_signal: self.stop_signal.clone(),
+            pause_signal: self.pause_signal.clone(),
+            addr: self.addr.clone(),
+            agency: self.agency.clone(),
+            _census: None,
+            _phase: PhantomData,
+        }
+    }
+
+    /// Move into the paused phase, where the actor isn't run and messages build up in its
+    /// mailboxes until it's resumed.
+    pub(crate) fn pause_phase(self) -> Context<A, Paused> {
+        self.addr.inner().stats().set_paused(true);
+        self.into_phase()
+    }
+}
+
+impl<A: Actor> Context<A, Paused> {
+    pub fn address(&self) -> Addr<A> {
+        self.addr.leased()
+    }
+
+    /// Return to the running phase.
+    pub fn resume(self) -> Context<A, Running> {
+        self.addr.inner().clear_pause();
+        self.addr.inner().stats().set_paused(false);
+        self.into_phase()
+    }
+
+    /// Wait until the actor is asked to resume or stop, then return to the running phase.
+    ///
+    /// A stop request is noticed as soon as the actor next waits for a message.
+    pub(crate) async fn resumed(mut self) -> Context<A, Running> {
+        select! {
+            biased;
+            _ = stop_requested(&mut self.stop_signal) => {}
+            _ = resume_requested(&mut self.pause_signal) => {}
+        }
+        self.resume()
+    }
+}
+
+impl<A: Actor, P: Phase> Context<A, P> {
+    /// The state shared with the actor's addresses, without handing out an address that would
+    /// keep it from being orphaned.
+    pub(crate) fn inner(&self) -> &Arc<AddrInner> {
+        self.addr.inner()
+    }
+
+    /// Whether the actor was hired with an [`ActorHandle`](crate::ActorHandle) to hand it back
+    /// to.
+    pub(crate) fn is_joinable(&self) -> bool {
+        self.inner().is_joinable()
+    }
+
+    /// Why the actor is stopping, from when it's decided to stop until it's finished, or `None`
+    /// while it's running. Recovering clears it again.
+    pub fn stop_reason(&self) -> Option<StopReason> {
+        self.stop_reason
+    }
+
+    fn into_phase<Q: Phase>(self) -> Context<A, Q> {
         Context {
             mailbox: self.mailbox,
             priority_mailbox: self.priority_mailbox,
-            stopped: true,
+            overflow: self.overflow,
+            stash: self.stash,
+            replay: self.replay,
+            peeked: self.peeked,
+            timers: self.timers,
+            pending_timers: self.pending_timers,
+            children: self.children,
+            handling: self.handling,
+            layers: self.layers,
+            behaviors: self.behaviors,
+            idle_timeout: self.idle_timeout,
+            stopped: self.stopped,
+            stop_reason: self.stop_reason,
+            journal: self.journal,
+            #[cfg(feature = "serde")]
+            persist: self.persist,
+            stop_signal: self.stop_signal,
+            pause_signal: self.pause_signal,
             addr: self.addr,
             agency: self.agency,
+            _census: self._census,
             _phase: PhantomData,
         }
     }
 }
 
 impl<A: Actor> Context<A, Stopped> {
+    /// Stop every child hired with [`Context::hire_child`], waiting for them to finish.
+    ///
+    /// Called once the mailboxes are closed, so a child sending to its parent while stopping
+    /// gets an error rather than waiting for room that never comes.
+    pub(crate) async fn stop_children(&mut self) {
+        self.children.stop().await;
+    }
+
     /// Collect all of the remaining, unhandled messages
     pub async fn drain(self) -> Vec<A::Msg> {
-        self.priority_mailbox.chain(self.mailbox).collect().await
+        self.drain_up_to(usize::MAX).await.messages
     }
+
+    /// Collect up to `max` of the remaining, unhandled messages, in the same order as
+    /// [`Context::drain`], dropping the rest.
+    ///
+    /// Requests that are dropped fail straight away with
+    /// [`RequestError::SenderDropped`](crate::RequestError::SenderDropped), so an actor with a
+    /// large backlog at shutdown can pass on the few it cares about without collecting everything.
+    pub async fn drain_up_to(mut self, max: usize) -> Drained<A::Msg> {
+        let mut messages = Vec::new();
+        let mut discarded = 0;
+        let mut keep = |msg| {
+            if messages.len() < max {
+                messages.push(msg);
+            } else {
+                discarded += 1;
+            }
+        };
+        let peeked = self.peeked.take();
+        peeked
+            .into_iter()
+            .chain(self.stash.drain(..))
+            .chain(self.replay.drain(..))
+            .for_each(&mut keep);
+        while let Some(msg) = self.priority_mailbox.recv().await {
+            self.addr.inner().stats().priority_dequeued();
+            keep(msg);
+        }
+        self.overflow.drain(..).for_each(&mut keep);
+        while let Some(msg) = self.mailbox.recv().await {
+            keep(msg);
+        }
+        Drained {
+            messages,
+            discarded,
+        }
+    }
+}
+
+enum Received<A: Actor> {
+    Message(A::Msg),
+    Timer(Timer<A>),
+}
+
+/// What was left in a stopped actor's mailboxes, from [`Context::drain_up_to`].
+#[derive(Debug)]
+pub struct Drained<M> {
+    /// The messages collected, in the order they would have been received.
+    pub messages: Vec<M>,
+    /// How many more there were, which were dropped.
+    pub discarded: usize,
+}
+
+/// The error returned by [`Context::wait_for`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitError {
+    Timeout,
+}
+
+impl Display for WaitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Timeout => write!(f, "timeout waiting for a matching message"),
+        }
+    }
+}
+
+impl Error for WaitError {}
+
+/// Wait for the idle deadline, if there is one.
+async fn idle(agency: &Agency, deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => agency.sleep_until(deadline).await,
+        None => pending().await,
+    }
+}
+
+async fn stop_requested(signal: &mut watch::Receiver<bool>) {
+    let _ = signal.wait_for(|stop| *stop).await;
+}
+
+async fn resume_requested(signal: &mut watch::Receiver<bool>) {
+    let _ = signal.wait_for(|pause| !*pause).await;
 }
@@ -0,0 +1,210 @@
+use crate::{
+    actor::Actor,
+    context::Context,
+    request::{RequestError, RequestTimeoutError},
+};
+use std::{
+    collections::HashMap,
+    future::Future,
+    hash::Hash,
+    marker::PhantomData,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{task::AbortHandle, time::Instant};
+
+/// Keeps track of requests an actor has in flight, delivering each response back to it tagged
+/// with the key it was tracked under.
+///
+/// Each response future runs in its own task, so the actor carries on handling messages while
+/// it waits. Results arrive through the priority mailbox as `(K, Result<Res,
+/// RequestTimeoutError>)`, so the actor's message type needs a `From` impl for that tuple.
+///
+/// ```
+/// # use agency::{prelude::*, Correlator, RequestTimeoutError};
+/// # struct GetUser(u32);
+/// # struct User;
+/// # struct Db;
+/// # #[async_trait]
+/// # impl Actor for Db {
+/// #     type Msg = Request<GetUser, User>;
+/// #     async fn run(&mut self, ctx: &mut Context<Self>) {
+/// #         ctx.message().await;
+/// #     }
+/// # }
+/// # enum Msg {
+/// #     Lookup(u32),
+/// #     Found(u32, Result<User, RequestTimeoutError>),
+/// # }
+/// # impl From<(u32, Result<User, RequestTimeoutError>)> for Msg {
+/// #     fn from((user_id, user): (u32, Result<User, RequestTimeoutError>)) -> Self {
+/// #         Self::Found(user_id, user)
+/// #     }
+/// # }
+/// # struct Users {
+/// #     db: Addr<Db>,
+/// #     correlator: Correlator<u32, User>,
+/// # }
+/// # #[async_trait]
+/// # impl Actor for Users {
+/// #     type Msg = Msg;
+/// #     async fn run(&mut self, ctx: &mut Context<Self>) {
+/// #         if let Msg::Lookup(user_id) = ctx.message().await {
+/// let lookup = self.db.clone();
+/// self.correlator.track(ctx, user_id, async move { lookup.request(GetUser(user_id)).await });
+/// #         }
+/// #     }
+/// # }
+/// ```
+pub struct Correlator<K, Res> {
+    pending: Arc<Mutex<HashMap<K, Pending>>>,
+    timeout: Option<Duration>,
+    next_id: u64,
+    _response: PhantomData<fn() -> Res>,
+}
+
+struct Pending {
+    id: u64,
+    task: AbortHandle,
+}
+
+impl<K, Res> Correlator<K, Res>
+where
+    K: 'static + Eq + Hash + Clone + Send,
+    Res: 'static + Send,
+{
+    pub fn new() -> Self {
+        Self {
+            pending: Arc::default(),
+            timeout: None,
+            next_id: 0,
+            _response: PhantomData,
+        }
+    }
+
+    /// Give up on requests tracked with [`Correlator::track`] after the given time, delivering
+    /// [`RequestTimeoutError::Timeout`] for them instead.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Start tracking a response under `key`, replacing anything already tracked under it.
+    pub fn track<A, F>(&mut self, ctx: &Context<A>, key: K, response: F)
+    where
+        A: 'static + Actor,
+        F: Future<Output = Result<Res, RequestError>> + Send + 'static,
+        (K, Result<Res, RequestTimeoutError>): Into<A::Msg>,
+    {
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        self.track_inner(ctx, key, response, deadline);
+    }
+
+    /// Like [`Correlator::track`], but gives up on this request at the given deadline rather than
+    /// after the correlator's timeout.
+    pub fn track_until<A, F>(&mut self, ctx: &Context<A>, key: K, response: F, deadline: Instant)
+    where
+        A: 'static + Actor,
+        F: Future<Output = Result<Res, RequestError>> + Send + 'static,
+        (K, Result<Res, RequestTimeoutError>): Into<A::Msg>,
+    {
+        self.track_inner(ctx, key, response, Some(deadline));
+    }
+
+    fn track_inner<A, F>(
+        &mut self,
+        ctx: &Context<A>,
+        key: K,
+        response: F,
+        deadline: Option<Instant>,
+    ) where
+        A: 'static + Actor,
+        F: Future<Output = Result<Res, RequestError>> + Send + 'static,
+        (K, Result<Res, RequestTimeoutError>): Into<A::Msg>,
+    {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let addr = ctx.address();
+        let agency = ctx.agency.clone();
+        let shared = self.pending.clone();
+        let task_key = key.clone();
+        // Held until the request is recorded, so a quick response can't miss its entry
+        let mut pending = self.pending.lock().unwrap();
+        let task = ctx.agency.spawn_detached(async move {
+            let res = match deadline {
+                Some(deadline) => match agency.timeout_at(deadline, response).await {
+                    Ok(res) => res.map_err(RequestTimeoutError::from),
+                    Err(_) => Err(RequestTimeoutError::Timeout),
+                },
+                None => response.await.map_err(RequestTimeoutError::from),
+            };
+            // Only deliver if this is still the request tracked under the key
+            let current = {
+                let mut pending = shared.lock().unwrap();
+                match pending.get(&task_key) {
+                    Some(entry) if entry.id == id => pending.remove(&task_key).is_some(),
+                    _ => false,
+                }
+            };
+            if current {
+                let _ = addr.send_priority((task_key, res));
+            }
+        });
+
+        let replaced = pending.insert(
+            key,
+            Pending {
+                id,
+                task: task.abort_handle(),
+            },
+        );
+        if let Some(replaced) = replaced {
+            replaced.task.abort();
+        }
+    }
+
+    /// Stop tracking the request under `key`, so its response is never delivered. Returns false
+    /// if there wasn't one in flight.
+    pub fn cancel(&mut self, key: &K) -> bool {
+        match self.pending.lock().unwrap().remove(key) {
+            Some(pending) => {
+                pending.task.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether a response is still awaited under `key`.
+    pub fn is_pending(&self, key: &K) -> bool {
+        self.pending.lock().unwrap().contains_key(key)
+    }
+
+    /// How many responses are still awaited.
+    pub fn len(&self) -> usize {
+        self.pending.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<K, Res> Default for Correlator<K, Res>
+where
+    K: 'static + Eq + Hash + Clone + Send,
+    Res: 'static + Send,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, Res> Drop for Correlator<K, Res> {
+    fn drop(&mut self) {
+        for (_, pending) in self.pending.lock().unwrap().drain() {
+            pending.task.abort();
+        }
+    }
+}
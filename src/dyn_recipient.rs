@@ -0,0 +1,127 @@
+use crate::addr::Recipient;
+use crate::id::ActorId;
+use async_trait::async_trait;
+use dyn_clone::DynClone;
+use std::{
+    any::Any,
+    error::Error,
+    fmt::{self, Debug, Display},
+    hash::Hash,
+};
+
+/// A [`Recipient`] with its message type erased, so recipients of different types can live in
+/// the same collection, such as a routing table keyed by name.
+///
+/// Payloads are boxed as [`Any`] and downcast back to the recipient's message type on delivery.
+/// Create one with [`Recipient::erase`].
+pub struct DynRecipient {
+    id: ActorId,
+    message_type: &'static str,
+    sender: Box<dyn AnySender>,
+}
+
+impl DynRecipient {
+    /// The unique id of the actor this recipient sends to.
+    pub fn id(&self) -> ActorId {
+        self.id
+    }
+
+    /// The name of the message type this recipient accepts.
+    pub fn message_type(&self) -> &'static str {
+        self.message_type
+    }
+
+    /// Send a boxed message to the recipient.
+    ///
+    /// This will block (asynchronously) if the recipient's buffer is full
+    ///
+    /// # Errors
+    ///
+    /// This will error if the message isn't of the recipient's type, handing the box back, or if
+    /// the recipient is no longer running.
+    pub async fn send_any(&self, msg: Box<dyn Any + Send>) -> Result<(), DynSendError> {
+        self.sender.send_any(msg).await
+    }
+}
+
+impl<M> Recipient<M>
+where
+    M: 'static + Send,
+{
+    /// Erase the message type of this recipient, see [`DynRecipient`].
+    pub fn erase(self) -> DynRecipient {
+        DynRecipient {
+            id: self.id(),
+            message_type: std::any::type_name::<M>(),
+            sender: Box::new(self),
+        }
+    }
+}
+
+impl Clone for DynRecipient {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            message_type: self.message_type,
+            sender: dyn_clone::clone_box(&*self.sender),
+        }
+    }
+}
+
+impl Debug for DynRecipient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynRecipient")
+            .field("id", &self.id)
+            .field("message_type", &self.message_type)
+            .finish()
+    }
+}
+
+impl Hash for DynRecipient {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        state.write(b"recipient:");
+        self.id.hash(state)
+    }
+}
+
+impl PartialEq for DynRecipient {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for DynRecipient {}
+
+#[async_trait]
+trait AnySender: 'static + Send + Sync + DynClone {
+    async fn send_any(&self, msg: Box<dyn Any + Send>) -> Result<(), DynSendError>;
+}
+
+#[async_trait]
+impl<M> AnySender for Recipient<M>
+where
+    M: 'static + Send,
+{
+    async fn send_any(&self, msg: Box<dyn Any + Send>) -> Result<(), DynSendError> {
+        let msg = msg.downcast::<M>().map_err(DynSendError::Mismatch)?;
+        self.send(*msg).await.map_err(|_| DynSendError::Stopped)
+    }
+}
+
+#[derive(Debug)]
+pub enum DynSendError {
+    /// The message wasn't of the recipient's type, so here it is back.
+    Mismatch(Box<dyn Any + Send>),
+    Stopped,
+}
+
+impl Display for DynSendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Mismatch(_) => write!(f, "message type doesn't match the recipient"),
+            Self::Stopped => write!(f, "actor stopped"),
+        }
+    }
+}
+
+impl Error for DynSendError {}
@@ -0,0 +1,119 @@
+use crate::addr::Exit;
+use futures_util::stream::{self, BoxStream, Stream, StreamExt};
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::{
+    select,
+    sync::{broadcast, watch},
+};
+
+/// What an [`EventStream`] does when its subscriber falls more than the buffer behind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LagPolicy {
+    /// Skip the oldest events the subscriber missed and carry on from the oldest still buffered.
+    #[default]
+    DropOldest,
+    /// End the stream.
+    Disconnect,
+}
+
+/// The actor's side of its event streams, created with
+/// [`Context::event_stream`](crate::Context::event_stream).
+///
+/// Every subscriber gets its own copy of each event sent, and a slow subscriber doesn't hold up
+/// the actor or the other subscribers: each falls behind on its own, up to the buffer size given
+/// when the sink was created, after which its [`LagPolicy`] kicks in.
+pub struct EventSink<E> {
+    sender: broadcast::Sender<E>,
+    exit: watch::Receiver<Option<Exit>>,
+}
+
+impl<E> EventSink<E>
+where
+    E: 'static + Clone + Send,
+{
+    pub(crate) fn new(buffer: usize, exit: watch::Receiver<Option<Exit>>) -> Self {
+        Self {
+            sender: broadcast::channel(buffer).0,
+            exit,
+        }
+    }
+
+    /// Send an event to every current subscriber, returning how many there were.
+    pub fn send(&self, event: E) -> usize {
+        self.sender.send(event).unwrap_or(0)
+    }
+
+    /// Start a new stream of the events sent from now on, skipping the oldest ones if it falls
+    /// behind.
+    pub fn subscribe(&self) -> EventStream<E> {
+        self.subscribe_with(LagPolicy::DropOldest)
+    }
+
+    /// Like [`EventSink::subscribe`], with the given policy for falling behind.
+    pub fn subscribe_with(&self, policy: LagPolicy) -> EventStream<E> {
+        EventStream {
+            inner: events(self.sender.subscribe(), self.exit.clone(), policy),
+        }
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+impl<E> Clone for EventSink<E> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            exit: self.exit.clone(),
+        }
+    }
+}
+
+/// A subscriber's stream of the events sent through an [`EventSink`].
+///
+/// The stream ends once the actor that created the sink stops, after the events already sent,
+/// or once every copy of the sink has been dropped.
+pub struct EventStream<E> {
+    inner: BoxStream<'static, E>,
+}
+
+impl<E> Stream for EventStream<E> {
+    type Item = E;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<E>> {
+        self.inner.poll_next_unpin(cx)
+    }
+}
+
+fn events<E>(
+    receiver: broadcast::Receiver<E>,
+    exit: watch::Receiver<Option<Exit>>,
+    policy: LagPolicy,
+) -> BoxStream<'static, E>
+where
+    E: 'static + Clone + Send,
+{
+    stream::unfold(
+        (receiver, exit),
+        move |(mut receiver, mut exit)| async move {
+            loop {
+                let res = select! {
+                    biased;
+                    res = receiver.recv() => res,
+                    _ = exit.wait_for(Option::is_some) => return None,
+                };
+                match res {
+                    Ok(event) => return Some((event, (receiver, exit))),
+                    Err(broadcast::error::RecvError::Lagged(_))
+                        if policy == LagPolicy::DropOldest => {}
+                    Err(_) => return None,
+                }
+            }
+        },
+    )
+    .boxed()
+}
@@ -0,0 +1,111 @@
+use crate::id::ActorId;
+use crate::{
+    actor::Actor, addr::Recipient, context::Context, recipient_group::RecipientGroup,
+    request::Request, topic::Publish,
+};
+use async_trait::async_trait;
+
+/// Join a [`Group`], acknowledged once the member will receive every subsequent publish.
+pub struct Join<E: 'static>(pub Recipient<E>);
+
+/// Leave a [`Group`] by member id, responding with whether it was a member.
+pub struct Leave(pub ActorId);
+
+/// Ask a [`Group`] for the ids of its current members.
+pub struct GetMembers;
+
+pub enum GroupMsg<E: 'static> {
+    Join(Request<Join<E>, ()>),
+    Leave(Request<Leave, bool>),
+    GetMembers(Request<GetMembers, Vec<ActorId>>),
+    Publish(Publish<E>),
+}
+
+impl<E> From<Request<Join<E>, ()>> for GroupMsg<E> {
+    fn from(request: Request<Join<E>, ()>) -> Self {
+        Self::Join(request)
+    }
+}
+
+impl<E> From<Request<Leave, bool>> for GroupMsg<E> {
+    fn from(request: Request<Leave, bool>) -> Self {
+        Self::Leave(request)
+    }
+}
+
+impl<E> From<Request<GetMembers, Vec<ActorId>>> for GroupMsg<E> {
+    fn from(request: Request<GetMembers, Vec<ActorId>>) -> Self {
+        Self::GetMembers(request)
+    }
+}
+
+impl<E> From<Publish<E>> for GroupMsg<E> {
+    fn from(msg: Publish<E>) -> Self {
+        Self::Publish(msg)
+    }
+}
+
+/// A broadcast group whose membership changes at runtime.
+///
+/// Membership changes and publishes are all handled in order by the one actor, so once a
+/// [`Join`] request has been answered every publish sent afterwards reaches the new member, and
+/// once a [`Leave`] has been answered no later publish will. Members whose recipient can no
+/// longer be sent to are evicted automatically.
+///
+/// Each member gets its own clone of every event, so for large events use a `Group<Arc<E>>`,
+/// whose members all share the one event, see [`SharedRecipient`](crate::SharedRecipient).
+pub struct Group<E: 'static> {
+    members: RecipientGroup<E>,
+}
+
+impl<E> Group<E>
+where
+    E: 'static + Clone + Send + Sync,
+{
+    pub fn new() -> Self {
+        Self {
+            members: RecipientGroup::new(),
+        }
+    }
+}
+
+impl<E> Default for Group<E>
+where
+    E: 'static + Clone + Send + Sync,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl<E> Actor for Group<E>
+where
+    E: 'static + Clone + Send + Sync,
+{
+    type Msg = GroupMsg<E>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        match ctx.message().await {
+            GroupMsg::Join(request) => {
+                if let Some((Join(member), reply_to)) = request.handle() {
+                    self.members.add(member);
+                    let _ = reply_to.send(());
+                }
+            }
+            GroupMsg::Leave(request) => {
+                if let Some((Leave(id), reply_to)) = request.handle() {
+                    let _ = reply_to.send(self.members.remove(id));
+                }
+            }
+            GroupMsg::GetMembers(request) => {
+                if let Some((_, reply_to)) = request.handle() {
+                    let _ = reply_to.send(self.members.ids());
+                }
+            }
+            GroupMsg::Publish(Publish(event)) => {
+                self.members.send(event).await;
+            }
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use crate::{actor::Actor, context::Context};
+use async_trait::async_trait;
+
+/// An actor whose messages are handled one at a time by the framework.
+///
+/// Handlers are driven by [`Context::dispatch`], which times each message and reports it to the
+/// agency's [`Observer`](crate::Observer), so the actor's `run` becomes:
+///
+/// ```
+/// # use agency::prelude::*;
+/// # struct Echo;
+/// # #[async_trait]
+/// # impl Handler for Echo {
+/// #     async fn handle(&mut self, _ctx: &mut Context<Self>, _msg: u32) {}
+/// # }
+/// # #[async_trait]
+/// # impl Actor for Echo {
+/// #     type Msg = u32;
+/// async fn run(&mut self, ctx: &mut Context<Self>) {
+///     ctx.dispatch(self).await
+/// }
+/// # }
+/// ```
+#[async_trait]
+pub trait Handler: Actor {
+    async fn handle(&mut self, ctx: &mut Context<Self>, msg: Self::Msg);
+
+    /// The name a message is reported under. Defaults to the name of the message type, which
+    /// can be overridden to tell the variants of a message enum apart.
+    fn message_name(_msg: &Self::Msg) -> &'static str {
+        std::any::type_name::<Self::Msg>()
+    }
+}
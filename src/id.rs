@@ -0,0 +1,45 @@
+use std::fmt::{self, Display};
+use uuid::Uuid;
+
+/// The identity of an actor, shared by every address and recipient that refers to it.
+///
+/// Ids are random UUIDs by default, unique across agencies and processes. Agencies built with
+/// [`AgencyBuilder::sequential_ids`](crate::AgencyBuilder::sequential_ids) number their actors
+/// instead, which is cheaper to create and hash, but only unique within the agency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(transparent))]
+pub struct ActorId(Repr);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
+enum Repr {
+    Uuid(Uuid),
+    Sequential(u64),
+}
+
+impl ActorId {
+    pub(crate) fn random() -> Self {
+        Self(Repr::Uuid(Uuid::new_v4()))
+    }
+
+    pub(crate) fn sequential(n: u64) -> Self {
+        Self(Repr::Sequential(n))
+    }
+
+    /// The UUID behind this id, unless it's one of an agency's sequential ids.
+    pub fn as_uuid(&self) -> Option<Uuid> {
+        match self.0 {
+            Repr::Uuid(uuid) => Some(uuid),
+            Repr::Sequential(_) => None,
+        }
+    }
+}
+
+impl Display for ActorId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Repr::Uuid(uuid) => Display::fmt(&uuid, f),
+            Repr::Sequential(n) => write!(f, "#{}", n),
+        }
+    }
+}
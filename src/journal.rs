@@ -0,0 +1,230 @@
+//! Hooks for writing an actor's messages to a log before they're handled, behind the `journal`
+//! feature.
+
+#[cfg(feature = "journal")]
+use crate::{actor::Actor, addr::Addr};
+#[cfg(feature = "journal")]
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+
+/// Which queue a message was taken from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Queue {
+    Regular,
+    Priority,
+    Overflow,
+}
+
+/// The position of a message in a [`Journal`].
+#[cfg(feature = "journal")]
+pub type SeqNo = u64;
+
+/// Sees every message sent to an actor before it's delivered, and is told once each has been
+/// handled, attached with [`HireBuilder::journal`](crate::HireBuilder::journal).
+///
+/// [`Journal::record`] is called as each message goes into a mailbox, in the order they go in,
+/// so the journal can write them to an append-only log. [`Journal::commit`] is called for each
+/// message once the call to [`Actor::run`] that received it returns, which for
+/// [`Handler`](crate::Handler)s is once the handler has finished. Anything recorded but never
+/// committed, such as the message being handled when the actor panicked, or messages still
+/// queued when it stopped, was never fully handled.
+///
+/// This only provides the hook points. Replaying uncommitted messages, and making sure they
+/// aren't handled twice, is left to the journal and the actor.
+#[cfg(feature = "journal")]
+pub trait Journal<M>: Send + Sync + 'static {
+    fn record(&self, msg: &M) -> SeqNo;
+
+    fn commit(&self, seq: SeqNo);
+}
+
+/// A [`Journal`] that doesn't keep anything.
+#[cfg(feature = "journal")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopJournal;
+
+#[cfg(feature = "journal")]
+impl<M> Journal<M> for NoopJournal {
+    fn record(&self, _msg: &M) -> SeqNo {
+        0
+    }
+
+    fn commit(&self, _seq: SeqNo) {}
+}
+
+/// A [`Journal`] that keeps the `Debug` output of every uncommitted message in memory, for tests.
+///
+/// Clones share the same entries, so keep a clone to inspect after attaching it to an actor.
+#[cfg(feature = "journal")]
+#[derive(Debug, Clone, Default)]
+pub struct MemoryJournal {
+    entries: Arc<Mutex<MemoryEntries>>,
+}
+
+#[cfg(feature = "journal")]
+#[derive(Debug, Default)]
+struct MemoryEntries {
+    next: SeqNo,
+    uncommitted: BTreeMap<SeqNo, String>,
+}
+
+#[cfg(feature = "journal")]
+impl MemoryJournal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every message recorded but not yet committed, oldest first.
+    pub fn uncommitted(&self) -> Vec<(SeqNo, String)> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .uncommitted
+            .iter()
+            .map(|(seq, msg)| (*seq, msg.clone()))
+            .collect()
+    }
+
+    /// How many messages have been recorded in total.
+    pub fn recorded(&self) -> u64 {
+        self.entries.lock().unwrap().next
+    }
+}
+
+#[cfg(feature = "journal")]
+impl<M> Journal<M> for MemoryJournal
+where
+    M: Debug,
+{
+    fn record(&self, msg: &M) -> SeqNo {
+        let mut entries = self.entries.lock().unwrap();
+        let seq = entries.next;
+        entries.next += 1;
+        entries.uncommitted.insert(seq, format!("{:?}", msg));
+        seq
+    }
+
+    fn commit(&self, seq: SeqNo) {
+        self.entries.lock().unwrap().uncommitted.remove(&seq);
+    }
+}
+
+/// An actor's journal, shared between its addresses and context.
+#[cfg(feature = "journal")]
+pub(crate) struct JournalState<M> {
+    journal: Box<dyn Journal<M>>,
+    /// The sequence numbers of the messages in each mailbox, in the same order as the messages.
+    queues: Mutex<(VecDeque<SeqNo>, VecDeque<SeqNo>)>,
+}
+
+#[cfg(feature = "journal")]
+impl<M: 'static> JournalState<M> {
+    pub(crate) fn new(journal: impl Journal<M>) -> Self {
+        Self {
+            journal: Box::new(journal),
+            queues: Mutex::default(),
+        }
+    }
+
+    /// Record a message and put it in a mailbox, holding a lock so that sequence numbers are
+    /// queued in the same order as their messages.
+    pub(crate) fn enqueue<T>(
+        &self,
+        queue: Queue,
+        msg: M,
+        send: impl FnOnce(M) -> Result<(), T>,
+    ) -> Result<(), T> {
+        let mut queues = self.queues.lock().unwrap();
+        let seq = self.journal.record(&msg);
+        // If this fails the message was never delivered, so it's left uncommitted
+        send(msg)?;
+        match queue {
+            Queue::Priority => queues.1.push_back(seq),
+            _ => queues.0.push_back(seq),
+        }
+        Ok(())
+    }
+
+    fn dequeued(&self, queue: Queue) -> Option<SeqNo> {
+        let mut queues = self.queues.lock().unwrap();
+        match queue {
+            Queue::Priority => queues.1.pop_front(),
+            _ => queues.0.pop_front(),
+        }
+    }
+
+    /// Forget the sequence numbers of messages in the old mailboxes, when an actor is respawned
+    /// with new ones.
+    pub(crate) fn reset(&self) {
+        *self.queues.lock().unwrap() = Default::default();
+    }
+}
+
+/// The context's side of journaling: which messages have been received but not committed yet.
+#[cfg(feature = "journal")]
+#[derive(Default)]
+pub(crate) struct Cursor {
+    handling: Vec<SeqNo>,
+    /// Sequence numbers for messages in the context's overflow buffer.
+    overflow: VecDeque<SeqNo>,
+}
+
+#[cfg(feature = "journal")]
+impl Cursor {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn received<A: Actor>(&mut self, addr: &Addr<A>, queue: Queue) {
+        let journal = match addr.inner().journal::<A::Msg>() {
+            Some(journal) => journal,
+            None => return,
+        };
+        let seq = match queue {
+            Queue::Overflow => self.overflow.pop_front(),
+            queue => journal.dequeued(queue),
+        };
+        self.handling.extend(seq);
+    }
+
+    pub(crate) fn overflowed<A: Actor>(&mut self, addr: &Addr<A>, msg: &A::Msg) {
+        if let Some(journal) = addr.inner().journal::<A::Msg>() {
+            self.overflow.push_back(journal.journal.record(msg));
+        }
+    }
+
+    /// Commit everything received since the last time, once a call to `run` has returned.
+    pub(crate) fn commit<A: Actor>(&mut self, addr: &Addr<A>) {
+        if let Some(journal) = addr.inner().journal::<A::Msg>() {
+            for seq in self.handling.drain(..) {
+                journal.journal.commit(seq);
+            }
+        }
+    }
+
+    /// Leave everything received since the last commit uncommitted, after a panic.
+    pub(crate) fn abandon(&mut self) {
+        self.handling.clear();
+    }
+}
+
+/// Stands in for the journal's bookkeeping when the feature is disabled.
+#[cfg(not(feature = "journal"))]
+pub(crate) struct Cursor;
+
+#[cfg(not(feature = "journal"))]
+impl Cursor {
+    pub(crate) fn new() -> Self {
+        Self
+    }
+
+    pub(crate) fn received<A: crate::Actor>(&mut self, _addr: &crate::Addr<A>, _queue: Queue) {}
+
+    pub(crate) fn overflowed<A: crate::Actor>(&mut self, _addr: &crate::Addr<A>, _msg: &A::Msg) {}
+
+    pub(crate) fn commit<A: crate::Actor>(&mut self, _addr: &crate::Addr<A>) {}
+
+    pub(crate) fn abandon(&mut self) {}
+}
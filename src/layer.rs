@@ -0,0 +1,201 @@
+use crate::id::ActorId;
+use crate::{
+    actor::{Actor, PanicInfo},
+    behavior,
+    context::Context,
+    handler::Handler,
+};
+use async_trait::async_trait;
+use futures_util::{future::BoxFuture, FutureExt};
+use std::{panic::AssertUnwindSafe, time::Duration};
+use tokio::time::Instant;
+
+/// Middleware wrapped around a [`Handler`]'s message handling, attached with
+/// [`HireBuilder::layer`](crate::HireBuilder::layer).
+///
+/// Layers are onion-style: each one is given the message along with the [`Next`] layer, and
+/// decides whether, and how, to pass it on. The first layer added is the outermost. A layer that
+/// never calls [`Next::run`] stops the message from reaching the actor.
+#[async_trait]
+pub trait Layer<A>: Send + Sync + 'static
+where
+    A: Actor,
+{
+    async fn handle(&mut self, actor: &mut A, ctx: &mut Context<A>, msg: A::Msg, next: Next<'_, A>);
+}
+
+pub(crate) type Layers<A> = Vec<Box<dyn Layer<A>>>;
+
+/// A layer that works with any actor, so it can be applied to every actor in an agency with
+/// [`AgencyBuilder::default_layer`](crate::AgencyBuilder::default_layer).
+///
+/// Since it isn't tied to an actor type, it only sees a description of the message, and the rest
+/// of the layers as a future to await, or not.
+///
+/// Every `AgencyLayer` is also a [`Layer`], so it can be attached to a single actor too.
+#[async_trait]
+pub trait AgencyLayer: Send + Sync + 'static {
+    async fn handle(&mut self, dispatch: &Dispatch, next: BoxFuture<'_, ()>);
+}
+
+/// The message an [`AgencyLayer`] is handling.
+#[derive(Debug, Clone, Copy)]
+pub struct Dispatch {
+    pub actor_id: ActorId,
+    pub actor_type: &'static str,
+    /// From [`Handler::message_name`].
+    pub message: &'static str,
+}
+
+#[async_trait]
+impl<A, L> Layer<A> for L
+where
+    A: 'static + Handler,
+    L: AgencyLayer,
+{
+    async fn handle(
+        &mut self,
+        actor: &mut A,
+        ctx: &mut Context<A>,
+        msg: A::Msg,
+        next: Next<'_, A>,
+    ) {
+        let dispatch = Dispatch::new::<A>(ctx, &msg);
+        AgencyLayer::handle(self, &dispatch, Box::pin(next.run(actor, ctx, msg))).await;
+    }
+}
+
+impl Dispatch {
+    fn new<A: Handler>(ctx: &Context<A>, msg: &A::Msg) -> Self {
+        Self {
+            actor_id: ctx.address().id(),
+            actor_type: std::any::type_name::<A>(),
+            message: A::message_name(msg),
+        }
+    }
+}
+
+/// Creates a fresh [`AgencyLayer`] for each actor hired.
+pub(crate) type LayerFactory = Box<dyn Fn() -> Box<dyn AgencyLayer> + Send + Sync>;
+
+/// Every layer an actor's messages pass through, the agency's defaults first.
+pub(crate) struct LayerStack<A: Actor> {
+    pub(crate) agency: Vec<Box<dyn AgencyLayer>>,
+    pub(crate) actor: Layers<A>,
+}
+
+impl<A: Actor> LayerStack<A> {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.agency.is_empty() && self.actor.is_empty()
+    }
+}
+
+/// The rest of the layers beneath the current one, ending with the actor's own handler.
+pub struct Next<'a, A>
+where
+    A: Actor,
+{
+    agency: &'a mut [Box<dyn AgencyLayer>],
+    layers: &'a mut [Box<dyn Layer<A>>],
+}
+
+impl<'a, A> Next<'a, A>
+where
+    A: 'static + Handler,
+{
+    pub(crate) fn new(stack: &'a mut LayerStack<A>) -> Self {
+        Self {
+            agency: &mut stack.agency,
+            layers: &mut stack.actor,
+        }
+    }
+
+    /// Pass the message on to the next layer, or the actor if there are none left.
+    pub async fn run(self, actor: &mut A, ctx: &mut Context<A>, msg: A::Msg) {
+        self.run_boxed(actor, ctx, msg).await
+    }
+
+    // Boxed with a named type, since agency layers run the rest of the stack as a future
+    fn run_boxed<'b>(
+        self,
+        actor: &'b mut A,
+        ctx: &'b mut Context<A>,
+        msg: A::Msg,
+    ) -> BoxFuture<'b, ()>
+    where
+        'a: 'b,
+    {
+        Box::pin(async move {
+            if let Some((layer, agency)) = self.agency.split_first_mut() {
+                let dispatch = Dispatch::new::<A>(ctx, &msg);
+                let next = Next {
+                    agency,
+                    layers: self.layers,
+                };
+                return layer
+                    .handle(&dispatch, next.run_boxed(actor, ctx, msg))
+                    .await;
+            }
+
+            match self.layers.split_first_mut() {
+                Some((layer, layers)) => {
+                    let next = Next {
+                        agency: &mut [],
+                        layers,
+                    };
+                    layer.handle(actor, ctx, msg, next).await
+                }
+                None => behavior::handle(actor, ctx, msg).await,
+            }
+        })
+    }
+}
+
+type OnTiming = Box<dyn Fn(&'static str, Duration) + Send + Sync>;
+
+/// Times everything beneath it, reporting the message name and duration to a callback.
+pub struct TimingLayer {
+    report: OnTiming,
+}
+
+impl TimingLayer {
+    pub fn new(report: impl Fn(&'static str, Duration) + Send + Sync + 'static) -> Self {
+        Self {
+            report: Box::new(report),
+        }
+    }
+}
+
+#[async_trait]
+impl AgencyLayer for TimingLayer {
+    async fn handle(&mut self, dispatch: &Dispatch, next: BoxFuture<'_, ()>) {
+        let start = Instant::now();
+        next.await;
+        (self.report)(dispatch.message, start.elapsed());
+    }
+}
+
+type OnPanic = Box<dyn Fn(&PanicInfo) + Send + Sync>;
+
+/// Catches panics from everything beneath it, reporting them to a callback and carrying on with
+/// the next message, rather than leaving it to [`Actor::on_panic`].
+pub struct CatchPanicLayer {
+    report: OnPanic,
+}
+
+impl CatchPanicLayer {
+    pub fn new(report: impl Fn(&PanicInfo) + Send + Sync + 'static) -> Self {
+        Self {
+            report: Box::new(report),
+        }
+    }
+}
+
+#[async_trait]
+impl AgencyLayer for CatchPanicLayer {
+    async fn handle(&mut self, dispatch: &Dispatch, next: BoxFuture<'_, ()>) {
+        if let Err(payload) = AssertUnwindSafe(next).catch_unwind().await {
+            (self.report)(&PanicInfo::new(payload, Some(dispatch.message)));
+        }
+    }
+}
@@ -1,14 +1,106 @@
 mod actor;
+mod adapter;
 mod addr;
 mod agency;
+mod aggregator;
+mod balanced;
+mod behavior;
+mod broadcast;
+mod census;
+#[cfg(feature = "chaos")]
+mod chaos;
+mod children;
+mod class_router;
+mod coalesce;
 mod context;
+mod correlator;
+mod dyn_recipient;
+mod event_stream;
+mod group;
+mod handler;
+mod id;
+mod journal;
+mod layer;
+mod load_shed;
+mod mailbox;
+mod observer;
+#[cfg(feature = "serde")]
+mod persistence;
+pub mod prelude;
+mod priority;
+mod recipient_group;
+mod registry;
 mod request;
+mod scheduler;
+#[cfg(feature = "tower")]
+mod service;
+mod session;
+mod shards;
+mod state_machine;
+mod stats;
+mod stream;
+mod supervisor;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+mod timer;
+mod topic;
+#[cfg(feature = "trace")]
+mod trace;
+mod watchdog;
 
+#[allow(deprecated)]
+pub use crate::addr::SendError;
+#[cfg(feature = "chaos")]
+pub use crate::chaos::{ChaosPolicy, ChaosRates};
+#[cfg(feature = "journal")]
+pub use crate::journal::{Journal, MemoryJournal, NoopJournal, SeqNo};
+#[cfg(feature = "serde")]
+pub use crate::persistence::{MemorySnapshots, Persistent, SnapshotStore};
+#[cfg(feature = "tower")]
+pub use crate::service::{ActorService, ServiceActor};
+#[cfg(feature = "trace")]
+pub use crate::trace::TraceId;
 pub use crate::{
-    actor::{Actor, Setup, StoppingResult},
-    addr::{Addr, Recipient, SendError},
-    agency::{Agency, AgencyHandle},
-    context::{Context, Running, Stopped},
-    request::{Request, RequestError, RequestTimeoutError},
+    actor::{Actor, InitAbort, PanicInfo, Setup, StopReason, StoppingResult},
+    addr::{Addr, DeliveryError, Recipient, RecipientTuple, StopListener, WeakAddr, WeakRecipient},
+    agency::{
+        ActorHandle, Agency, AgencyBuilder, AgencyHandle, HireBuilder, JoinError, NameTaken,
+        RespawnError, SetupFailed, SetupWatch, TooManyActors,
+    },
+    aggregator::{Aggregator, AggregatorMsg, BatchInfo, Flush, GetBatch, Item},
+    behavior::{Behavior, MAX_BEHAVIORS},
+    census::{ActorFailure, ActorSnapshot, AgencySnapshot},
+    class_router::{ClassRouter, ClassRouterBuilder},
+    coalesce::Coalesce,
+    context::{Context, Drained, Paused, Running, Stopped, WaitError},
+    correlator::Correlator,
+    dyn_recipient::{DynRecipient, DynSendError},
+    event_stream::{EventSink, EventStream, LagPolicy},
+    group::{GetMembers, Group, GroupMsg, Join, Leave},
+    handler::Handler,
+    id::ActorId,
+    layer::{AgencyLayer, CatchPanicLayer, Dispatch, Layer, Next, TimingLayer},
+    load_shed::{LoadShed, LoadShedConfig, LoadShedError},
+    mailbox::{DropOldest, Envelope, FairMailbox, Mailbox, MailboxPermit, MailboxSender},
+    observer::{
+        ActorStalled, DeadLetter, InitAborted, MessageHandled, Observer, ResponseUndelivered,
+        SlowMessage, TaskUntracked,
+    },
+    recipient_group::{RecipientGroup, SharedRecipient},
+    request::{Ask, AskError, Request, RequestError, RequestTimeoutError, ResponseHandle},
+    scheduler::{Cancel, Schedule, ScheduleId, Scheduler, SchedulerMsg, Undelivered},
+    session::{Session, SessionClosed, SessionHandle},
+    shards::Shards,
+    state_machine::{
+        CurrentState, State, StateMachine, StateMachineMsg, Transition, UnhandledPolicy,
+    },
+    stats::ActorStatsSnapshot,
+    stream::StreamHandle,
+    supervisor::{
+        ChildSpec, GetChildren, RestartPolicy, SupervisionStrategy, Supervisor, SupervisorMsg,
+    },
+    timer::TimerHandle,
+    topic::{Publish, Subscribe, SubscriptionId, Topic, TopicMsg, Unsubscribe},
 };
 pub use async_trait::async_trait;
+pub use futures_util::future::{BoxFuture, Either};
@@ -0,0 +1,216 @@
+use crate::{
+    addr::Recipient,
+    request::{Request, RequestError},
+};
+use std::{
+    collections::VecDeque,
+    error::Error,
+    fmt::{Debug, Display},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::time::Instant;
+
+/// Configuration for a [`LoadShed`] wrapper.
+#[derive(Debug, Clone)]
+pub struct LoadShedConfig {
+    /// The latency the tracked percentile should stay under before shedding begins.
+    pub target_latency: Duration,
+    /// The percentile of recent latencies compared against the target, between 0 and 1.
+    pub percentile: f64,
+    /// How long request latencies are tracked for once they complete.
+    pub window: Duration,
+    /// The largest fraction of requests that will be shed, between 0 and 1.
+    pub max_shed_ratio: f64,
+}
+
+impl Default for LoadShedConfig {
+    fn default() -> Self {
+        Self {
+            target_latency: Duration::from_millis(100),
+            percentile: 0.9,
+            window: Duration::from_secs(10),
+            max_shed_ratio: 0.9,
+        }
+    }
+}
+
+struct ShedState {
+    /// When each tracked request completed, and how long it took.
+    samples: VecDeque<(Instant, Duration)>,
+    ratio: f64,
+    credit: f64,
+}
+
+impl ShedState {
+    fn should_shed(&mut self, config: &LoadShedConfig) -> bool {
+        self.expire(config, Instant::now());
+        if self.ratio <= 0.0 {
+            return false;
+        }
+        self.credit += self.ratio;
+        if self.credit >= 1.0 {
+            self.credit -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn record(&mut self, config: &LoadShedConfig, latency: Duration) {
+        let now = Instant::now();
+        self.samples.push_back((now, latency));
+        self.expire(config, now);
+        self.update(config);
+    }
+
+    /// Forget the samples that have aged out of the window, so shedding stops once requests
+    /// haven't been let through for a while, even if every one was being shed.
+    fn expire(&mut self, config: &LoadShedConfig, now: Instant) {
+        let before = self.samples.len();
+        while let Some((at, _)) = self.samples.front() {
+            if now.duration_since(*at) <= config.window {
+                break;
+            }
+            self.samples.pop_front();
+        }
+        if self.samples.len() != before {
+            self.update(config);
+        }
+    }
+
+    fn update(&mut self, config: &LoadShedConfig) {
+        if self.samples.is_empty() {
+            self.ratio = 0.0;
+            self.credit = 0.0;
+            return;
+        }
+
+        let mut sorted: Vec<_> = self.samples.iter().map(|(_, latency)| *latency).collect();
+        sorted.sort_unstable();
+        let rank = (config.percentile.clamp(0.0, 1.0) * sorted.len() as f64).ceil() as usize;
+        let observed = sorted[rank.clamp(1, sorted.len()) - 1];
+
+        if observed > config.target_latency {
+            let target = config.target_latency.as_secs_f64().max(f64::EPSILON);
+            let overshoot = (observed.as_secs_f64() - target) / target;
+            self.ratio = overshoot.min(config.max_shed_ratio.clamp(0.0, 1.0));
+        } else {
+            self.ratio = 0.0;
+            self.credit = 0.0;
+        }
+    }
+}
+
+/// Wraps a request recipient, rejecting a fraction of new requests while recent response
+/// latencies are above a target.
+///
+/// The fraction shed grows with how far the tracked percentile exceeds the target, up to
+/// [`LoadShedConfig::max_shed_ratio`], and falls back to zero as latencies recover, or once
+/// every tracked latency has aged out of [`LoadShedConfig::window`].
+pub struct LoadShed<Req, Res>
+where
+    Req: 'static,
+    Res: 'static,
+{
+    recipient: Recipient<Request<Req, Res>>,
+    config: Arc<LoadShedConfig>,
+    state: Arc<Mutex<ShedState>>,
+}
+
+impl<Req, Res> LoadShed<Req, Res> {
+    pub fn new(recipient: Recipient<Request<Req, Res>>, config: LoadShedConfig) -> Self {
+        Self {
+            recipient,
+            config: Arc::new(config),
+            state: Arc::new(Mutex::new(ShedState {
+                samples: VecDeque::new(),
+                ratio: 0.0,
+                credit: 0.0,
+            })),
+        }
+    }
+
+    /// The fraction of requests currently being shed.
+    pub fn shed_ratio(&self) -> f64 {
+        let mut state = self.state.lock().expect("load shed lock poisoned");
+        state.expire(&self.config, Instant::now());
+        state.ratio
+    }
+
+    /// Send a [`Request`](crate::Request) to the actor and await the response, unless the actor
+    /// is currently overloaded.
+    ///
+    /// # Errors
+    ///
+    /// This will immediately error with [`LoadShedError::Overloaded`] if the request was shed,
+    /// otherwise it errors in the same cases as [`Recipient::request`].
+    pub async fn request(&self, payload: Req) -> Result<Res, LoadShedError> {
+        if self
+            .state
+            .lock()
+            .expect("load shed lock poisoned")
+            .should_shed(&self.config)
+        {
+            return Err(LoadShedError::Overloaded);
+        }
+
+        let start = Instant::now();
+        let res = self.recipient.request(payload).await?;
+        self.state
+            .lock()
+            .expect("load shed lock poisoned")
+            .record(&self.config, start.elapsed());
+        Ok(res)
+    }
+}
+
+impl<Req, Res> Clone for LoadShed<Req, Res> {
+    fn clone(&self) -> Self {
+        Self {
+            recipient: self.recipient.clone(),
+            config: self.config.clone(),
+            state: self.state.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadShedError {
+    Overloaded,
+    ActorStopped,
+    SenderDropped,
+    /// The request couldn't be converted into a message the actor accepts.
+    Incompatible,
+}
+
+impl From<RequestError> for LoadShedError {
+    fn from(err: RequestError) -> Self {
+        match err {
+            RequestError::ActorStopped => Self::ActorStopped,
+            RequestError::SenderDropped => Self::SenderDropped,
+            RequestError::Incompatible => Self::Incompatible,
+        }
+    }
+}
+
+impl Display for LoadShedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Overloaded => {
+                write!(f, "the actor is overloaded and the request was shed")
+            }
+            Self::ActorStopped => {
+                write!(f, "the actor was stopped before the request could be sent")
+            }
+            Self::SenderDropped => {
+                write!(f, "sender was dropped before responding to the request")
+            }
+            Self::Incompatible => {
+                write!(f, "the request isn't one the actor accepts")
+            }
+        }
+    }
+}
+
+impl Error for LoadShedError {}
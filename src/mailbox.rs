@@ -0,0 +1,764 @@
+use crate::addr::{current_actor, DeliveryError};
+use crate::id::ActorId;
+#[cfg(feature = "trace")]
+use crate::trace::{self, TraceId};
+use futures_util::{
+    future::{poll_fn, ready, BoxFuture, FutureExt},
+    task::AtomicWaker,
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::{self, Debug},
+    sync::{Arc, Mutex, Weak},
+    task::{Context, Poll},
+};
+use tokio::{
+    sync::{mpsc, OwnedSemaphorePermit, Semaphore, TryAcquireError},
+    task,
+};
+
+/// The receiving side of an actor's regular mailbox, for actors that need something other than
+/// the default bounded channel, chosen with [`HireBuilder::mailbox`](crate::HireBuilder::mailbox).
+///
+/// The default mailbox is a tokio channel holding the agency's
+/// [`capacity`](crate::AgencyBuilder::capacity), used directly rather than through this trait.
+/// The priority mailbox and overflow buffer are always the built-in ones.
+///
+/// Messages arrive wrapped in an [`Envelope`], which a mailbox that orders or filters by content
+/// can look inside with [`Envelope::message`], but which should be handed back out as it is.
+pub trait Mailbox<M>: Send + 'static {
+    /// The handle addresses send through, called once when the actor is hired.
+    fn sender(&self) -> Arc<dyn MailboxSender<M>>;
+
+    /// Receive the next message, returning `None` once the mailbox has been closed and emptied.
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<M>>;
+
+    fn try_recv(&mut self) -> Option<M>;
+
+    /// Stop accepting messages, leaving those already sent to be received.
+    fn close(&mut self);
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// The sending side of a [`Mailbox`], shared by every address for one incarnation of the actor.
+pub trait MailboxSender<M>: Send + Sync + 'static {
+    /// Wait for room for one message, holding it until the permit is used or dropped.
+    ///
+    /// # Errors
+    ///
+    /// This should error with [`DeliveryError::Closed`] once the mailbox has been closed.
+    fn reserve(self: Arc<Self>) -> BoxFuture<'static, Result<MailboxPermit<M>, DeliveryError<()>>>;
+
+    /// Reserve room for one message without waiting.
+    ///
+    /// # Errors
+    ///
+    /// This should error with [`DeliveryError::Full`] if there's no room, or
+    /// [`DeliveryError::Closed`] once the mailbox has been closed.
+    fn try_reserve(&self) -> Result<MailboxPermit<M>, DeliveryError<()>>;
+
+    fn is_closed(&self) -> bool;
+
+    /// How many messages are waiting, as reported in [`Agency::dump`](crate::Agency::dump).
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn capacity(&self) -> usize;
+}
+
+/// Room for one message in a [`Mailbox`], see [`MailboxSender::reserve`].
+pub struct MailboxPermit<M>(Box<dyn FnOnce(M) + Send>);
+
+impl<M> MailboxPermit<M> {
+    /// A permit that puts the message it's given in the mailbox with `send`.
+    pub fn new(send: impl FnOnce(M) + Send + 'static) -> Self {
+        Self(Box::new(send))
+    }
+
+    pub fn send(self, msg: M) {
+        (self.0)(msg)
+    }
+}
+
+/// A [`Mailbox`] that never makes senders wait, dropping the oldest waiting message to make room
+/// for a new one once it's full.
+///
+/// This suits actors that only care about the latest state, such as one rendering the most recent
+/// readings, where a slow actor should fall behind rather than hold up everything sending to it.
+/// Dropped messages aren't reported anywhere.
+///
+/// ```
+/// # use agency::{prelude::*, DropOldest};
+/// # #[derive(Default)]
+/// # struct Display;
+/// # #[async_trait]
+/// # impl Actor for Display {
+/// #     type Msg = f64;
+/// #     async fn run(&mut self, ctx: &mut Context<Self>) {
+/// #         ctx.message().await;
+/// #     }
+/// # }
+/// # fn example(agency: &Agency) {
+/// let addr = agency
+///     .hire_builder(Display::default())
+///     .mailbox(DropOldest::new)
+///     .hire();
+/// # }
+/// ```
+pub struct DropOldest<M> {
+    ring: Arc<Ring<M>>,
+}
+
+impl<M> DropOldest<M>
+where
+    M: 'static + Send,
+{
+    /// # Panics
+    ///
+    /// Panics if the capacity is 0.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "mailbox capacity must be at least 1");
+        Self {
+            ring: Arc::new(Ring {
+                capacity,
+                state: Mutex::new(RingState {
+                    queue: VecDeque::with_capacity(capacity),
+                    closed: false,
+                }),
+                waker: AtomicWaker::new(),
+            }),
+        }
+    }
+}
+
+impl<M> Mailbox<M> for DropOldest<M>
+where
+    M: 'static + Send,
+{
+    fn sender(&self) -> Arc<dyn MailboxSender<M>> {
+        Arc::new(RingSender(self.ring.clone()))
+    }
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<M>> {
+        let mut state = self.ring.state.lock().unwrap();
+        if let Some(msg) = state.queue.pop_front() {
+            return Poll::Ready(Some(msg));
+        }
+        if state.closed {
+            return Poll::Ready(None);
+        }
+        // Pushes wake after releasing the lock, so registering while holding it can't miss one
+        self.ring.waker.register(cx.waker());
+        Poll::Pending
+    }
+
+    fn try_recv(&mut self) -> Option<M> {
+        self.ring.state.lock().unwrap().queue.pop_front()
+    }
+
+    fn close(&mut self) {
+        self.ring.state.lock().unwrap().closed = true;
+    }
+
+    fn len(&self) -> usize {
+        self.ring.len()
+    }
+}
+
+impl<M> Drop for DropOldest<M> {
+    // Like a channel's receiver, so senders see the actor has gone however its task ended
+    fn drop(&mut self) {
+        self.ring.state.lock().unwrap().closed = true;
+    }
+}
+
+struct Ring<M> {
+    capacity: usize,
+    state: Mutex<RingState<M>>,
+    waker: AtomicWaker,
+}
+
+struct RingState<M> {
+    queue: VecDeque<M>,
+    closed: bool,
+}
+
+impl<M> Ring<M> {
+    fn len(&self) -> usize {
+        self.state.lock().unwrap().queue.len()
+    }
+
+    fn push(&self, msg: M) {
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.closed {
+                return;
+            }
+            if state.queue.len() == self.capacity {
+                state.queue.pop_front();
+            }
+            state.queue.push_back(msg);
+        }
+        self.waker.wake();
+    }
+}
+
+/// The sending side of a [`DropOldest`] mailbox.
+struct RingSender<M>(Arc<Ring<M>>);
+
+impl<M> MailboxSender<M> for RingSender<M>
+where
+    M: 'static + Send,
+{
+    fn reserve(self: Arc<Self>) -> BoxFuture<'static, Result<MailboxPermit<M>, DeliveryError<()>>> {
+        ready(self.try_reserve()).boxed()
+    }
+
+    fn try_reserve(&self) -> Result<MailboxPermit<M>, DeliveryError<()>> {
+        if self.is_closed() {
+            return Err(DeliveryError::Closed(()));
+        }
+        // There's always room, as the oldest message makes way once the permit is used
+        let ring = self.0.clone();
+        Ok(MailboxPermit::new(move |msg| ring.push(msg)))
+    }
+
+    fn is_closed(&self) -> bool {
+        self.0.state.lock().unwrap().closed
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.0.capacity
+    }
+}
+
+/// A [`Mailbox`] that gives each sender its own small queue, taking turns between them, so one
+/// busy sender can't crowd out the rest.
+///
+/// A sender is the actor doing the sending, or for sends from outside an actor, the tokio task.
+/// Each gets room for `per_sender` messages, and only waits once its own queue is full, while the
+/// actor receives from each sender with messages waiting in turn. There's no limit on how many
+/// senders there are, so the mailbox as a whole is unbounded.
+///
+/// ```
+/// # use agency::{prelude::*, FairMailbox};
+/// # #[derive(Default)]
+/// # struct Shared;
+/// # #[async_trait]
+/// # impl Actor for Shared {
+/// #     type Msg = u32;
+/// #     async fn run(&mut self, ctx: &mut Context<Self>) {
+/// #         ctx.message().await;
+/// #     }
+/// # }
+/// # fn example(agency: &Agency) {
+/// let addr = agency
+///     .hire_builder(Shared::default())
+///     .mailbox(|_| FairMailbox::new(4))
+///     .hire();
+/// # }
+/// ```
+pub struct FairMailbox<M> {
+    fair: Arc<Fair<M>>,
+}
+
+impl<M> FairMailbox<M>
+where
+    M: 'static + Send,
+{
+    /// # Panics
+    ///
+    /// Panics if `per_sender` is 0.
+    pub fn new(per_sender: usize) -> Self {
+        assert!(per_sender > 0, "mailbox capacity must be at least 1");
+        Self {
+            fair: Arc::new(Fair {
+                per_sender,
+                state: Mutex::new(FairState {
+                    senders: HashMap::new(),
+                    turns: VecDeque::new(),
+                    len: 0,
+                    closed: false,
+                }),
+                waker: AtomicWaker::new(),
+            }),
+        }
+    }
+}
+
+impl<M> Mailbox<M> for FairMailbox<M>
+where
+    M: 'static + Send,
+{
+    fn sender(&self) -> Arc<dyn MailboxSender<M>> {
+        Arc::new(FairSender(self.fair.clone()))
+    }
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<M>> {
+        if let Some(msg) = self.try_recv() {
+            return Poll::Ready(Some(msg));
+        }
+        let state = self.fair.state.lock().unwrap();
+        if state.closed {
+            return Poll::Ready(None);
+        }
+        // As with `DropOldest`, pushes wake after releasing the lock
+        self.fair.waker.register(cx.waker());
+        if state.len > 0 {
+            cx.waker().wake_by_ref();
+        }
+        Poll::Pending
+    }
+
+    fn try_recv(&mut self) -> Option<M> {
+        let mut state = self.fair.state.lock().unwrap();
+        let sender = state.turns.pop_front()?;
+        let queue = state.senders.get_mut(&sender)?;
+        let (msg, room) = queue.messages.pop_front()?;
+        drop(room);
+        if !queue.messages.is_empty() {
+            state.turns.push_back(sender);
+        } else if Arc::strong_count(&queue.room) == 1 {
+            // Nothing waiting from the sender, and it's not waiting for room either
+            state.senders.remove(&sender);
+        }
+        state.len -= 1;
+        Some(msg)
+    }
+
+    fn close(&mut self) {
+        self.fair.close();
+    }
+
+    fn len(&self) -> usize {
+        self.fair.state.lock().unwrap().len
+    }
+}
+
+impl<M> Drop for FairMailbox<M> {
+    fn drop(&mut self) {
+        self.fair.close();
+    }
+}
+
+/// Who a message for a [`FairMailbox`] is from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum SenderKey {
+    Actor(ActorId),
+    Task(task::Id),
+    Unknown,
+}
+
+impl SenderKey {
+    fn current() -> Self {
+        match current_actor() {
+            Some(id) => Self::Actor(id),
+            None => task::try_id().map_or(Self::Unknown, Self::Task),
+        }
+    }
+}
+
+struct Fair<M> {
+    per_sender: usize,
+    state: Mutex<FairState<M>>,
+    waker: AtomicWaker,
+}
+
+struct FairState<M> {
+    senders: HashMap<SenderKey, SenderQueue<M>>,
+    /// The senders with messages waiting, in the order they take their turns.
+    turns: VecDeque<SenderKey>,
+    len: usize,
+    closed: bool,
+}
+
+/// One sender's messages, each holding its place until it's received.
+struct SenderQueue<M> {
+    room: Arc<Semaphore>,
+    messages: VecDeque<(M, OwnedSemaphorePermit)>,
+}
+
+impl<M> Fair<M> {
+    /// The room in the current sender's queue, or `None` if the mailbox is closed.
+    fn room(&self) -> Option<(SenderKey, Arc<Semaphore>)> {
+        let sender = SenderKey::current();
+        let mut state = self.state.lock().unwrap();
+        if state.closed {
+            return None;
+        }
+        let queue = state.senders.entry(sender).or_insert_with(|| SenderQueue {
+            room: Arc::new(Semaphore::new(self.per_sender)),
+            messages: VecDeque::new(),
+        });
+        Some((sender, queue.room.clone()))
+    }
+
+    fn push(&self, sender: SenderKey, msg: M, room: OwnedSemaphorePermit) {
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.closed {
+                return;
+            }
+            // The entry stays while the permit holds its semaphore
+            let queue = match state.senders.get_mut(&sender) {
+                Some(queue) => queue,
+                None => return,
+            };
+            queue.messages.push_back((msg, room));
+            if queue.messages.len() == 1 {
+                state.turns.push_back(sender);
+            }
+            state.len += 1;
+        }
+        self.waker.wake();
+    }
+
+    fn close(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.closed = true;
+        for queue in state.senders.values() {
+            queue.room.close();
+        }
+    }
+}
+
+/// The sending side of a [`FairMailbox`].
+struct FairSender<M>(Arc<Fair<M>>);
+
+impl<M> FairSender<M>
+where
+    M: 'static + Send,
+{
+    fn permit(&self, sender: SenderKey, room: OwnedSemaphorePermit) -> MailboxPermit<M> {
+        let fair = self.0.clone();
+        MailboxPermit::new(move |msg| fair.push(sender, msg, room))
+    }
+}
+
+impl<M> MailboxSender<M> for FairSender<M>
+where
+    M: 'static + Send,
+{
+    fn reserve(self: Arc<Self>) -> BoxFuture<'static, Result<MailboxPermit<M>, DeliveryError<()>>> {
+        // Who's sending is decided here, in their task, rather than wherever this is polled
+        let room = self.0.room();
+        async move {
+            let (sender, room) = room.ok_or(DeliveryError::Closed(()))?;
+            let room = room
+                .acquire_owned()
+                .await
+                .map_err(|_| DeliveryError::Closed(()))?;
+            Ok(self.permit(sender, room))
+        }
+        .boxed()
+    }
+
+    fn try_reserve(&self) -> Result<MailboxPermit<M>, DeliveryError<()>> {
+        let (sender, room) = self.0.room().ok_or(DeliveryError::Closed(()))?;
+        let room = room.try_acquire_owned().map_err(|err| match err {
+            TryAcquireError::NoPermits => DeliveryError::Full(()),
+            TryAcquireError::Closed => DeliveryError::Closed(()),
+        })?;
+        Ok(self.permit(sender, room))
+    }
+
+    fn is_closed(&self) -> bool {
+        self.0.state.lock().unwrap().closed
+    }
+
+    fn len(&self) -> usize {
+        self.0.state.lock().unwrap().len
+    }
+
+    /// The room each sender gets, for however many there are at the moment.
+    fn capacity(&self) -> usize {
+        self.0.per_sender * self.0.state.lock().unwrap().senders.len().max(1)
+    }
+}
+
+/// A message on its way through an actor's regular mailbox, carrying its [`TraceId`] when the
+/// `trace` feature is enabled.
+pub struct Envelope<M> {
+    msg: M,
+    #[cfg(feature = "trace")]
+    trace: TraceId,
+}
+
+impl<M> Envelope<M> {
+    pub(crate) fn new(msg: M) -> Self {
+        Self {
+            msg,
+            #[cfg(feature = "trace")]
+            trace: trace::outgoing(),
+        }
+    }
+
+    pub fn message(&self) -> &M {
+        &self.msg
+    }
+
+    #[cfg(feature = "trace")]
+    pub fn trace(&self) -> TraceId {
+        self.trace
+    }
+
+    pub(crate) fn into_message(self) -> M {
+        self.msg
+    }
+
+    /// Take the message out as it's received, making its trace the current one.
+    pub(crate) fn open(self) -> M {
+        #[cfg(feature = "trace")]
+        trace::enter(Some(self.trace));
+        self.msg
+    }
+}
+
+impl<M> Debug for Envelope<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Envelope(..)")
+    }
+}
+
+/// Builds an actor's mailbox for each of its incarnations, given the agency's capacity.
+pub(crate) type MailboxFactory<M> =
+    Arc<dyn Fn(usize) -> Box<dyn Mailbox<Envelope<M>>> + Send + Sync>;
+
+/// Open a new regular mailbox, with the factory an actor was hired with or the default channel.
+pub(crate) fn open<M>(factory: Option<&MailboxFactory<M>>, capacity: usize) -> (Mailer<M>, Inbox<M>)
+where
+    M: 'static + Send,
+{
+    match factory {
+        Some(factory) => {
+            let mailbox = factory(capacity);
+            (Mailer::Custom(mailbox.sender()), Inbox::Custom(mailbox))
+        }
+        None => {
+            let (sender, receiver) = mpsc::channel(capacity);
+            (Mailer::Channel(sender), Inbox::Channel(receiver))
+        }
+    }
+}
+
+/// The sending side of an actor's regular mailbox.
+pub(crate) enum Mailer<M> {
+    Channel(mpsc::Sender<Envelope<M>>),
+    Custom(Arc<dyn MailboxSender<Envelope<M>>>),
+}
+
+/// Room for one message in an actor's regular mailbox.
+pub(crate) enum Permit<'a, M> {
+    Channel(mpsc::Permit<'a, Envelope<M>>),
+    Custom(MailboxPermit<Envelope<M>>),
+}
+
+impl<M> Permit<'_, M> {
+    pub(crate) fn send(self, msg: M) {
+        let msg = Envelope::new(msg);
+        match self {
+            Self::Channel(permit) => permit.send(msg),
+            Self::Custom(permit) => permit.send(msg),
+        }
+    }
+}
+
+impl<M> Mailer<M>
+where
+    M: 'static + Send,
+{
+    pub(crate) async fn reserve(&self) -> Result<Permit<'_, M>, DeliveryError<()>> {
+        match self {
+            Self::Channel(sender) => sender
+                .reserve()
+                .await
+                .map(Permit::Channel)
+                .map_err(|_| DeliveryError::Closed(())),
+            Self::Custom(sender) => sender.clone().reserve().await.map(Permit::Custom),
+        }
+    }
+
+    /// Like [`Mailer::reserve`], but with a permit that doesn't borrow the mailer.
+    #[cfg(feature = "tower")]
+    pub(crate) fn reserve_owned(
+        &self,
+    ) -> BoxFuture<'static, Result<MailboxPermit<M>, DeliveryError<()>>> {
+        match self {
+            Self::Channel(sender) => {
+                let sender = sender.clone();
+                async move {
+                    let permit = sender
+                        .reserve_owned()
+                        .await
+                        .map_err(|_| DeliveryError::Closed(()))?;
+                    Ok(MailboxPermit::new(move |msg| {
+                        permit.send(Envelope::new(msg));
+                    }))
+                }
+                .boxed()
+            }
+            Self::Custom(sender) => sender
+                .clone()
+                .reserve()
+                .map(|res| {
+                    res.map(|permit| MailboxPermit::new(move |msg| permit.send(Envelope::new(msg))))
+                })
+                .boxed(),
+        }
+    }
+
+    pub(crate) fn try_reserve(&self) -> Result<Permit<'_, M>, DeliveryError<()>> {
+        match self {
+            Self::Channel(sender) => {
+                sender
+                    .try_reserve()
+                    .map(Permit::Channel)
+                    .map_err(|err| match err {
+                        mpsc::error::TrySendError::Full(()) => DeliveryError::Full(()),
+                        mpsc::error::TrySendError::Closed(()) => DeliveryError::Closed(()),
+                    })
+            }
+            Self::Custom(sender) => sender.try_reserve().map(Permit::Custom),
+        }
+    }
+}
+
+impl<M: 'static> Mailer<M> {
+    pub(crate) fn is_closed(&self) -> bool {
+        match self {
+            Self::Channel(sender) => sender.is_closed(),
+            Self::Custom(sender) => sender.is_closed(),
+        }
+    }
+
+    /// How many messages are waiting, and how many fit.
+    pub(crate) fn depth(&self) -> (usize, usize) {
+        match self {
+            Self::Channel(sender) => (
+                sender.max_capacity() - sender.capacity(),
+                sender.max_capacity(),
+            ),
+            Self::Custom(sender) => (sender.len(), sender.capacity()),
+        }
+    }
+
+    pub(crate) fn downgrade(&self) -> WeakMailer<M> {
+        match self {
+            Self::Channel(sender) => WeakMailer::Channel(sender.downgrade()),
+            Self::Custom(sender) => WeakMailer::Custom(Arc::downgrade(sender)),
+        }
+    }
+}
+
+impl<M> Clone for Mailer<M> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Channel(sender) => Self::Channel(sender.clone()),
+            Self::Custom(sender) => Self::Custom(sender.clone()),
+        }
+    }
+}
+
+/// A [`Mailer`] that doesn't keep the mailbox open.
+pub(crate) enum WeakMailer<M> {
+    Channel(mpsc::WeakSender<Envelope<M>>),
+    Custom(Weak<dyn MailboxSender<Envelope<M>>>),
+}
+
+impl<M: 'static> WeakMailer<M> {
+    pub(crate) fn upgrade(&self) -> Option<Mailer<M>> {
+        match self {
+            Self::Channel(sender) => sender.upgrade().map(Mailer::Channel),
+            Self::Custom(sender) => sender.upgrade().map(Mailer::Custom),
+        }
+    }
+}
+
+impl<M> Clone for WeakMailer<M> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Channel(sender) => Self::Channel(sender.clone()),
+            Self::Custom(sender) => Self::Custom(sender.clone()),
+        }
+    }
+}
+
+/// The receiving side of an actor's regular mailbox, held by its context.
+pub(crate) enum Inbox<M> {
+    Channel(mpsc::Receiver<Envelope<M>>),
+    Custom(Box<dyn Mailbox<Envelope<M>>>),
+}
+
+impl<M: 'static> Inbox<M> {
+    /// A mailbox that's already closed, with nothing in it.
+    pub(crate) fn closed() -> Self {
+        let (_, mut receiver) = mpsc::channel(1);
+        receiver.close();
+        Self::Channel(receiver)
+    }
+
+    pub(crate) async fn recv(&mut self) -> Option<M> {
+        let envelope = match self {
+            Self::Channel(receiver) => receiver.recv().await,
+            Self::Custom(mailbox) => poll_fn(|cx| mailbox.poll_recv(cx)).await,
+        };
+        envelope.map(Envelope::open)
+    }
+
+    pub(crate) fn try_recv(&mut self) -> Option<M> {
+        let envelope = match self {
+            Self::Channel(receiver) => receiver.try_recv().ok(),
+            Self::Custom(mailbox) => mailbox.try_recv(),
+        };
+        envelope.map(Envelope::open)
+    }
+
+    /// Take up to `limit` messages that are already waiting, without waiting for any, returning
+    /// how many were taken.
+    pub(crate) fn try_recv_many(&mut self, buf: &mut Vec<M>, limit: usize) -> usize {
+        let mut envelopes = Vec::new();
+        match self {
+            // Returns straight away while there's anything waiting
+            Self::Channel(receiver) => {
+                receiver.recv_many(&mut envelopes, limit).now_or_never();
+            }
+            Self::Custom(mailbox) => {
+                while envelopes.len() < limit {
+                    match mailbox.try_recv() {
+                        Some(envelope) => envelopes.push(envelope),
+                        None => break,
+                    }
+                }
+            }
+        }
+        let taken = envelopes.len();
+        buf.extend(envelopes.into_iter().map(Envelope::open));
+        taken
+    }
+
+    pub(crate) fn close(&mut self) {
+        match self {
+            Self::Channel(receiver) => receiver.close(),
+            Self::Custom(mailbox) => mailbox.close(),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Self::Channel(receiver) => receiver.len(),
+            Self::Custom(mailbox) => mailbox.len(),
+        }
+    }
+}
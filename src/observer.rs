@@ -0,0 +1,113 @@
+use crate::id::ActorId;
+use std::time::Duration;
+use tokio::task;
+
+/// Receives events about the actors hired by an [`Agency`](crate::Agency).
+///
+/// Every method has a no-op default, so implementors only need to handle the events they care
+/// about. Methods are called inline from the actors' tasks, so they should return quickly.
+pub trait Observer: Send + Sync + 'static {
+    /// How long handling a message may take before it's also reported as a slow message.
+    fn slow_message_threshold(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Called after an actor has handled a message.
+    fn message_handled(&self, _event: &MessageHandled) {}
+
+    /// Called after an actor took longer than the slow message threshold to handle a message.
+    fn slow_message(&self, _event: &SlowMessage) {}
+
+    /// How long an actor may go without pulling a message, while messages are waiting for it,
+    /// before it's reported as stalled.
+    ///
+    /// Returning `Some` starts a watchdog task alongside the agency's first actor. Actors with
+    /// empty mailboxes are never considered stalled, however long they've been idle.
+    fn stall_threshold(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Called once each time an actor stalls, see [`Observer::stall_threshold`].
+    fn actor_stalled(&self, _event: &ActorStalled) {}
+
+    /// Called when a message sent without waiting, such as with
+    /// [`Addr::do_send`](crate::Addr::do_send), couldn't be delivered.
+    fn dead_letter(&self, _event: &DeadLetter) {}
+
+    /// Called when an actor responds to a request whose sender has stopped waiting, such as
+    /// because it timed out, see [`Request::respond`](crate::Request::respond).
+    fn response_undelivered(&self, _event: &ResponseUndelivered) {}
+
+    /// Called when an actor refuses to start from [`Actor::try_init`](crate::Actor::try_init).
+    fn init_aborted(&self, _event: &InitAborted) {}
+
+    /// Called when a task is spawned, such as for a newly hired actor, after the
+    /// [`AgencyHandle`](crate::AgencyHandle) has been dropped without being detached, so the
+    /// task runs without anything waiting for it.
+    fn task_untracked(&self, _event: &TaskUntracked) {}
+}
+
+#[derive(Debug, Clone)]
+pub struct MessageHandled {
+    pub actor_id: ActorId,
+    pub actor_type: &'static str,
+    /// The message's name, or the label of a timed block.
+    pub message: &'static str,
+    pub duration: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct SlowMessage {
+    pub actor_id: ActorId,
+    pub actor_type: &'static str,
+    /// The message's name, or the label of a timed block.
+    pub message: &'static str,
+    pub duration: Duration,
+    /// How many messages were waiting in the mailbox when this one was dequeued.
+    pub mailbox_depth: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct ActorStalled {
+    pub actor_id: ActorId,
+    pub actor_type: &'static str,
+    /// The name set with [`Context::set_name`](crate::Context::set_name), if any.
+    pub name: Option<String>,
+    /// How long since the actor last pulled a message, or started if it never has.
+    pub stalled_for: Duration,
+    /// Messages waiting in the regular mailbox.
+    pub mailbox_depth: usize,
+    /// Messages waiting in the priority mailbox.
+    pub priority_depth: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct DeadLetter {
+    pub actor_id: ActorId,
+    pub actor_type: &'static str,
+    /// The name of the undelivered message's type.
+    pub message: &'static str,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResponseUndelivered {
+    pub actor_id: ActorId,
+    pub actor_type: &'static str,
+    /// The name of the undelivered response's type.
+    pub response: &'static str,
+}
+
+#[derive(Debug, Clone)]
+pub struct InitAborted {
+    pub actor_id: ActorId,
+    pub actor_type: &'static str,
+    /// The name set with [`Context::set_name`](crate::Context::set_name), if any.
+    pub name: Option<String>,
+    /// The reason given in the [`InitAbort`](crate::InitAbort).
+    pub reason: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct TaskUntracked {
+    pub task_id: task::Id,
+}
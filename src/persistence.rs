@@ -0,0 +1,77 @@
+use crate::actor::Setup;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+/// An actor whose state can be saved as a snapshot and restored from one, so restarting it doesn't
+/// mean rebuilding its state from scratch.
+///
+/// Hire it with [`Agency::hire_restored`](crate::Agency::hire_restored), and save snapshots
+/// whenever it suits with [`Context::save_snapshot`](crate::Context::save_snapshot).
+pub trait Persistent: Setup {
+    type Snapshot: Serialize + DeserializeOwned + Send + 'static;
+
+    fn snapshot(&self) -> Self::Snapshot;
+
+    /// Rebuild the actor from its latest snapshot, used instead of [`Setup::setup`] when there is
+    /// one.
+    fn restore(snapshot: Self::Snapshot, args: Self::Args) -> Self;
+}
+
+/// Where snapshots of [`Persistent`] actors are kept, one per key.
+///
+/// Snapshots are handed over as they are, so stores that write them somewhere can serialize them
+/// in whatever format suits.
+pub trait SnapshotStore<S>: Send + Sync + 'static {
+    fn load(&self, key: &str) -> Option<S>;
+
+    fn save(&self, key: &str, snapshot: S);
+}
+
+/// A [`SnapshotStore`] that keeps snapshots in memory, for tests.
+///
+/// Clones share the same snapshots.
+#[derive(Debug)]
+pub struct MemorySnapshots<S> {
+    snapshots: Arc<Mutex<HashMap<String, S>>>,
+}
+
+impl<S> MemorySnapshots<S> {
+    pub fn new() -> Self {
+        Self {
+            snapshots: Arc::default(),
+        }
+    }
+}
+
+impl<S> Default for MemorySnapshots<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> Clone for MemorySnapshots<S> {
+    fn clone(&self) -> Self {
+        Self {
+            snapshots: self.snapshots.clone(),
+        }
+    }
+}
+
+impl<S> SnapshotStore<S> for MemorySnapshots<S>
+where
+    S: 'static + Clone + Send,
+{
+    fn load(&self, key: &str) -> Option<S> {
+        self.snapshots.lock().unwrap().get(key).cloned()
+    }
+
+    fn save(&self, key: &str, snapshot: S) {
+        self.snapshots
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), snapshot);
+    }
+}
@@ -0,0 +1,18 @@
+//! The traits and types needed to write and run most actors.
+//!
+//! ```
+//! use agency::prelude::*;
+//! ```
+//!
+//! Error types are left out so they don't collide with your own; import them from the crate
+//! root when you need them.
+
+pub use crate::{
+    actor::{Actor, Setup, StoppingResult},
+    addr::{Addr, Recipient},
+    agency::{Agency, AgencyHandle},
+    context::Context,
+    handler::Handler,
+    request::{Ask, Request},
+};
+pub use async_trait::async_trait;
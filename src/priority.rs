@@ -0,0 +1,158 @@
+use crate::mailbox::Envelope;
+use futures_util::{future::poll_fn, task::AtomicWaker};
+use std::{
+    sync::{Arc, Mutex, OnceLock, RwLock, RwLockReadGuard},
+    task::Poll,
+};
+use tokio::sync::mpsc;
+
+/// The sending side of an actor's priority mailbox, shared by every address for one incarnation
+/// of the actor.
+///
+/// Most actors never receive a priority message, so the channel is only created by the first
+/// send, saving its allocation for the rest.
+pub(crate) struct PrioritySlot<M> {
+    /// Set by the first send, or to `None` if the mailbox was closed before anything was sent.
+    sender: OnceLock<Option<mpsc::UnboundedSender<Envelope<M>>>>,
+    /// The receiver created by the first send, until the context picks it up.
+    receiver: Mutex<Option<mpsc::UnboundedReceiver<Envelope<M>>>>,
+    /// Wakes the context waiting for the channel to be created.
+    created: AtomicWaker,
+    /// Cleared once the mailbox is closed, which waits for anything holding it open.
+    open: RwLock<bool>,
+}
+
+impl<M> PrioritySlot<M> {
+    /// Send a message, giving it back if the mailbox has been closed.
+    pub(crate) fn send(&self, msg: M) -> Result<(), M> {
+        let mut created = false;
+        let sender = self.sender.get_or_init(|| {
+            let (sender, receiver) = mpsc::unbounded_channel();
+            *self.receiver.lock().unwrap() = Some(receiver);
+            created = true;
+            Some(sender)
+        });
+        let res = match sender {
+            Some(sender) => sender
+                .send(Envelope::new(msg))
+                .map_err(|err| err.0.into_message()),
+            None => Err(msg),
+        };
+        // Only once the sender is set, or the context could look too early and miss it
+        if created {
+            self.created.wake();
+        }
+        res
+    }
+
+    /// Keep the mailbox from closing until the guard is dropped, so a message can be sent
+    /// without the risk of it being handed back, or `None` if it's already closed.
+    pub(crate) fn hold_open(&self) -> Option<RwLockReadGuard<'_, bool>> {
+        let open = self.open.read().unwrap();
+        if *open {
+            Some(open)
+        } else {
+            None
+        }
+    }
+
+    fn is_settled(&self) -> bool {
+        self.sender.get().is_some()
+    }
+}
+
+/// The receiving side of an actor's priority mailbox, held by its context.
+pub(crate) struct PriorityMailbox<M> {
+    slot: Arc<PrioritySlot<M>>,
+    receiver: Option<mpsc::UnboundedReceiver<Envelope<M>>>,
+}
+
+impl<M> PriorityMailbox<M> {
+    pub(crate) fn new() -> (Arc<PrioritySlot<M>>, Self) {
+        let slot = Arc::new(PrioritySlot {
+            sender: OnceLock::new(),
+            receiver: Mutex::new(None),
+            created: AtomicWaker::new(),
+            open: RwLock::new(true),
+        });
+        let mailbox = Self {
+            slot: slot.clone(),
+            receiver: None,
+        };
+        (slot, mailbox)
+    }
+
+    /// A mailbox that's already closed, without ever having had a channel.
+    pub(crate) fn closed() -> Self {
+        let (_, mut mailbox) = Self::new();
+        mailbox.close();
+        mailbox
+    }
+
+    /// The channel's receiver, once the first send has created it.
+    fn receiver(&mut self) -> Option<&mut mpsc::UnboundedReceiver<Envelope<M>>> {
+        if self.receiver.is_none() && self.slot.is_settled() {
+            self.receiver = self.slot.receiver.lock().unwrap().take();
+        }
+        self.receiver.as_mut()
+    }
+
+    /// Receive the next message, waiting for the channel to be created first if need be.
+    ///
+    /// Like a channel's `recv`, this only returns `None` once the mailbox has been closed and
+    /// emptied.
+    pub(crate) async fn recv(&mut self) -> Option<M> {
+        if self.receiver.is_none() {
+            let slot = &self.slot;
+            poll_fn(|cx| {
+                if slot.is_settled() {
+                    return Poll::Ready(());
+                }
+                slot.created.register(cx.waker());
+                if slot.is_settled() {
+                    Poll::Ready(())
+                } else {
+                    Poll::Pending
+                }
+            })
+            .await;
+        }
+        self.receiver()?.recv().await.map(Envelope::open)
+    }
+
+    pub(crate) fn try_recv(&mut self) -> Option<M> {
+        self.receiver()?.try_recv().ok().map(Envelope::open)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match &self.receiver {
+            Some(receiver) => receiver.len(),
+            None => self
+                .slot
+                .receiver
+                .lock()
+                .unwrap()
+                .as_ref()
+                .map_or(0, mpsc::UnboundedReceiver::len),
+        }
+    }
+
+    /// Stop accepting messages, leaving those already sent to be received.
+    pub(crate) fn close(&mut self) {
+        let slot = self.slot.clone();
+        let mut open = slot.open.write().unwrap();
+        *open = false;
+        if self.slot.sender.set(None).is_err() {
+            if let Some(receiver) = self.receiver() {
+                receiver.close();
+            }
+        }
+    }
+}
+
+impl<M> Drop for PriorityMailbox<M> {
+    fn drop(&mut self) {
+        // Closed first, so a sender holding the mailbox open never finds the receiver gone
+        self.close();
+    }
+}
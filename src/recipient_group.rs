@@ -0,0 +1,164 @@
+use crate::addr::{DeliveryError, Recipient};
+use crate::id::ActorId;
+use std::sync::Arc;
+
+/// A set of recipients to fan the same message out to, without an actor in between.
+///
+/// Members that can no longer be sent to are removed as they're found. See [`Group`] for a
+/// group whose membership is managed by an actor.
+///
+/// [`Group`]: crate::Group
+pub struct RecipientGroup<M>
+where
+    M: 'static,
+{
+    members: Vec<Recipient<M>>,
+}
+
+impl<M> RecipientGroup<M>
+where
+    M: 'static + Send,
+{
+    pub fn new() -> Self {
+        Self {
+            members: Vec::new(),
+        }
+    }
+
+    /// Add a member, returning false if it was already in the group.
+    pub fn add(&mut self, member: impl Into<Recipient<M>>) -> bool {
+        let member = member.into();
+        if self
+            .members
+            .iter()
+            .any(|existing| existing.id() == member.id())
+        {
+            return false;
+        }
+        self.members.push(member);
+        true
+    }
+
+    /// Remove a member by id, returning whether it was in the group.
+    pub fn remove(&mut self, id: ActorId) -> bool {
+        let before = self.members.len();
+        self.members.retain(|member| member.id() != id);
+        self.members.len() != before
+    }
+
+    pub fn ids(&self) -> Vec<ActorId> {
+        self.members.iter().map(Recipient::id).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Send a clone of the message to every member, returning how many it was delivered to.
+    ///
+    /// Where the message is an `Arc`, only the pointer is cloned, see
+    /// [`RecipientGroup::send_shared`].
+    pub async fn send(&mut self, msg: M) -> usize
+    where
+        M: Clone,
+    {
+        self.send_with(|| msg.clone()).await
+    }
+
+    /// Send the same event to every member, each getting its own handle on the one allocation
+    /// rather than a copy of the event, returning how many it was delivered to.
+    ///
+    /// This works for members whose message type is `Arc<E>`, such as a [`SharedRecipient`], or
+    /// that can be created from one.
+    pub async fn send_shared<E>(&mut self, event: Arc<E>) -> usize
+    where
+        M: From<Arc<E>>,
+    {
+        self.send_with(|| M::from(event.clone())).await
+    }
+
+    async fn send_with(&mut self, mut msg: impl FnMut() -> M) -> usize {
+        let mut dead = Vec::new();
+        for member in &self.members {
+            if member.send(msg()).await.is_err() {
+                dead.push(member.id());
+            }
+        }
+        self.members.retain(|member| !dead.contains(&member.id()));
+        self.members.len()
+    }
+}
+
+impl<M> Default for RecipientGroup<M>
+where
+    M: 'static + Send,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M> Clone for RecipientGroup<M> {
+    fn clone(&self) -> Self {
+        Self {
+            members: self.members.clone(),
+        }
+    }
+}
+
+/// A recipient of events shared behind an `Arc`, for fanning out large events without copying
+/// them for every recipient.
+pub struct SharedRecipient<E>
+where
+    E: 'static,
+{
+    recipient: Recipient<Arc<E>>,
+}
+
+impl<E> SharedRecipient<E>
+where
+    E: 'static + Send + Sync,
+{
+    pub fn new(recipient: impl Into<Recipient<Arc<E>>>) -> Self {
+        Self {
+            recipient: recipient.into(),
+        }
+    }
+
+    pub fn id(&self) -> ActorId {
+        self.recipient.id()
+    }
+
+    /// Send an event, either one that's already shared or one to share from now on.
+    ///
+    /// # Errors
+    ///
+    /// This will error with [`DeliveryError::Closed`] if the recipient is no longer running.
+    pub async fn send(&self, event: impl Into<Arc<E>>) -> Result<(), DeliveryError<Arc<E>>> {
+        self.recipient.send(event.into()).await
+    }
+}
+
+impl<E> Clone for SharedRecipient<E> {
+    fn clone(&self) -> Self {
+        Self {
+            recipient: self.recipient.clone(),
+        }
+    }
+}
+
+impl<E> From<Recipient<Arc<E>>> for SharedRecipient<E> {
+    fn from(recipient: Recipient<Arc<E>>) -> Self {
+        Self { recipient }
+    }
+}
+
+impl<E> From<SharedRecipient<E>> for Recipient<Arc<E>> {
+    fn from(shared: SharedRecipient<E>) -> Self {
+        shared.recipient
+    }
+}
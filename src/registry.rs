@@ -0,0 +1,104 @@
+use crate::addr::Recipient;
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    sync::Mutex,
+};
+
+type Key = (String, TypeId);
+
+/// The recipients registered under each name and message type, for [`Agency::lookup`], shared
+/// between an agency and all of its clones.
+///
+/// [`Agency::lookup`]: crate::Agency::lookup
+#[derive(Default)]
+pub(crate) struct Registry {
+    /// A `Recipient<M>` for each name and message type `M`.
+    entries: Mutex<HashMap<Key, Box<dyn Any + Send>>>,
+}
+
+impl Registry {
+    /// Register a recipient, returning the one it replaced if that's still running.
+    pub(crate) fn register<M>(&self, name: String, recipient: Recipient<M>) -> Option<Recipient<M>>
+    where
+        M: 'static + Send,
+    {
+        let mut entries = self.entries.lock().unwrap();
+        entries
+            .insert((name, TypeId::of::<M>()), Box::new(recipient))
+            .and_then(Self::running)
+    }
+
+    /// Register the recipient `make` builds from `value`, unless a running one is already
+    /// registered under the name, in which case `value` is given back.
+    pub(crate) fn register_vacant<M, T, R>(
+        &self,
+        name: String,
+        value: T,
+        make: impl FnOnce(T) -> (Recipient<M>, R),
+    ) -> Result<R, T>
+    where
+        M: 'static + Send,
+    {
+        let mut entries = self.entries.lock().unwrap();
+        let key = (name, TypeId::of::<M>());
+        if entries
+            .get(&key)
+            .and_then(|entry| Self::peek::<M>(&**entry))
+            .is_some()
+        {
+            return Err(value);
+        }
+        // Made under the lock, so nothing else can take the name in the meantime
+        let (recipient, made) = make(value);
+        entries.insert(key, Box::new(recipient));
+        Ok(made)
+    }
+
+    /// The recipient registered under the name, forgetting it if it has stopped.
+    pub(crate) fn lookup<M>(&self, name: &str) -> Option<Recipient<M>>
+    where
+        M: 'static + Send,
+    {
+        let mut entries = self.entries.lock().unwrap();
+        let key = (name.to_owned(), TypeId::of::<M>());
+        match entries.get(&key).map(|entry| Self::peek::<M>(&**entry))? {
+            Some(recipient) => Some(recipient.clone()),
+            None => {
+                entries.remove(&key);
+                None
+            }
+        }
+    }
+
+    /// Forget the recipient registered under the name, returning it if it's still running.
+    pub(crate) fn deregister<M>(&self, name: &str) -> Option<Recipient<M>>
+    where
+        M: 'static + Send,
+    {
+        let mut entries = self.entries.lock().unwrap();
+        entries
+            .remove(&(name.to_owned(), TypeId::of::<M>()))
+            .and_then(Self::running)
+    }
+
+    fn peek<M>(entry: &(dyn Any + Send)) -> Option<&Recipient<M>>
+    where
+        M: 'static + Send,
+    {
+        entry
+            .downcast_ref::<Recipient<M>>()
+            .filter(|recipient| !recipient.is_closed())
+    }
+
+    fn running<M>(entry: Box<dyn Any + Send>) -> Option<Recipient<M>>
+    where
+        M: 'static + Send,
+    {
+        entry
+            .downcast::<Recipient<M>>()
+            .ok()
+            .map(|recipient| *recipient)
+            .filter(|recipient| !recipient.is_closed())
+    }
+}
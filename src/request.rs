@@ -1,22 +1,68 @@
+use crate::addr::{DeliveryError, Recipient};
 use std::{
     error::Error,
     fmt::{Debug, Display},
+    time::Duration,
 };
-use tokio::sync::oneshot;
+use tokio::{sync::oneshot, time::Instant};
+
+/// Taken off the time left for a request when passing it on with
+/// [`Addr::request_within`](crate::Addr::request_within), to leave time for the response to make
+/// its way back.
+const BUDGET_MARGIN: Duration = Duration::from_millis(5);
 
 pub struct Request<Req, Res> {
     payload: Req,
     reply_to: oneshot::Sender<Res>,
+    deadline: Option<Instant>,
 }
 
+/// A request that can fail, for declaring fallible requests readably in a message enum.
+///
+/// ```
+/// # use agency::Ask;
+/// # struct GetUser;
+/// # struct User;
+/// # struct DbError;
+/// enum UserMsg {
+///     Get(Ask<GetUser, User, DbError>),
+/// }
+/// ```
+///
+/// Callers use [`Addr::ask`](crate::Addr::ask) to get a flattened result back.
+pub type Ask<Req, T, E> = Request<Req, Result<T, E>>;
+
 impl<Req, Res> Request<Req, Res> {
     pub(crate) fn new(payload: Req) -> (Self, oneshot::Receiver<Res>) {
         let (reply_to, receiver) = oneshot::channel();
-        (Self { payload, reply_to }, receiver)
+        let request = Self {
+            payload,
+            reply_to,
+            deadline: None,
+        };
+        (request, receiver)
+    }
+
+    /// Rebuild a request from a payload and the channel to respond on, such as one taken apart
+    /// with [`Request::handle`].
+    pub fn from_parts(payload: Req, reply_to: oneshot::Sender<Res>) -> Self {
+        Self {
+            payload,
+            reply_to,
+            deadline: None,
+        }
+    }
+
+    pub(crate) fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
     }
 
     /// Get the request payload and reponse channel. This returns None if the request sender has
     /// since stopped listening for a response, such as if it reached a timeout.
+    ///
+    /// The sender can still stop listening after this returns. Responses sent on the channel
+    /// directly aren't counted in the actor's stats, unlike with [`Request::respond`].
     pub fn handle(self) -> Option<(Req, oneshot::Sender<Res>)> {
         if self.reply_to.is_closed() {
             None
@@ -24,12 +70,189 @@ impl<Req, Res> Request<Req, Res> {
             Some((self.payload, self.reply_to))
         }
     }
+
+    /// Take the request apart into its payload and a handle to respond with later, such as once
+    /// some other event arrives, keeping the handle in the actor's state until then.
+    pub fn into_parts(self) -> (Req, ResponseHandle<Res>) {
+        let handle = ResponseHandle {
+            reply_to: self.reply_to,
+            deadline: self.deadline,
+        };
+        (self.payload, handle)
+    }
+
+    pub fn payload(&self) -> &Req {
+        &self.payload
+    }
+
+    /// Convert the payload, keeping the response channel and deadline, such as to translate a
+    /// request into a backend's own type before forwarding it.
+    pub fn map_payload<Req2, F>(self, f: F) -> Request<Req2, Res>
+    where
+        F: FnOnce(Req) -> Req2,
+    {
+        Request {
+            payload: f(self.payload),
+            reply_to: self.reply_to,
+            deadline: self.deadline,
+        }
+    }
+
+    /// Whether the requester has stopped listening, so there's no point responding.
+    pub fn is_closed(&self) -> bool {
+        self.reply_to.is_closed()
+    }
+
+    /// When the requester stops waiting for a response, if it sent the request with a timeout.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// How long is left until the [`Request::deadline`], or `None` if there isn't one.
+    pub fn remaining_budget(&self) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// The timeout for a request made while handling this one, or `None` if this one doesn't have
+    /// a deadline.
+    pub(crate) fn nested_timeout(&self) -> Result<Option<Duration>, RequestTimeoutError> {
+        match self.remaining_budget() {
+            Some(budget) => match budget.checked_sub(BUDGET_MARGIN) {
+                Some(timeout) if !timeout.is_zero() => Ok(Some(timeout)),
+                _ => Err(RequestTimeoutError::BudgetExhausted),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Send the response.
+    ///
+    /// # Errors
+    ///
+    /// Gives the response back if the request sender has stopped listening, such as because it
+    /// timed out. When this happens in an actor's task it's also counted in the actor's
+    /// [`ActorStatsSnapshot::undelivered_responses`](crate::ActorStatsSnapshot::undelivered_responses)
+    /// and reported to the agency's
+    /// [`Observer::response_undelivered`](crate::Observer::response_undelivered).
+    pub fn respond(self, response: Res) -> Result<(), Res> {
+        self.reply_to
+            .send(response)
+            .inspect(|_| crate::addr::response_delivered())
+            .inspect_err(|_| crate::addr::response_undelivered::<Res>())
+    }
+}
+
+impl<Req, Res> Request<Req, Res>
+where
+    Req: Send,
+    Res: Send,
+{
+    /// Pass the request on to another actor to respond to, straight to the original requester,
+    /// keeping its deadline. Requests whose sender has already stopped listening are dropped
+    /// rather than forwarded.
+    ///
+    /// # Errors
+    ///
+    /// This will error with [`DeliveryError::Closed`] if the recipient is no longer running,
+    /// giving the request back so it can be answered some other way. If it's dropped instead,
+    /// the requester gets [`RequestError::SenderDropped`].
+    pub async fn forward_to(
+        self,
+        recipient: &Recipient<Request<Req, Res>>,
+    ) -> Result<(), DeliveryError<Self>> {
+        if self.reply_to.is_closed() {
+            return Ok(());
+        }
+        recipient.send(self).await
+    }
+}
+
+impl<Req, T, E> Request<Req, Result<T, E>> {
+    /// Send a fallible response, converting the error so results from `?`-heavy helpers can be
+    /// passed straight through.
+    ///
+    /// # Errors
+    ///
+    /// Gives the converted response back if the request sender has stopped listening, as with
+    /// [`Request::respond`].
+    pub fn respond_result<E2>(self, result: Result<T, E2>) -> Result<(), Result<T, E>>
+    where
+        E2: Into<E>,
+    {
+        self.respond(result.map_err(Into::into))
+    }
+}
+
+/// The responding half of a [`Request`], for answering it after the message itself has been
+/// handled, see [`Request::into_parts`].
+pub struct ResponseHandle<Res> {
+    reply_to: oneshot::Sender<Res>,
+    deadline: Option<Instant>,
+}
+
+impl<Res> ResponseHandle<Res> {
+    /// Whether the requester has stopped listening, so there's no point responding.
+    pub fn is_closed(&self) -> bool {
+        self.reply_to.is_closed()
+    }
+
+    /// Wait until the requester stops listening, such as to give up on work done for it.
+    pub async fn closed(&mut self) {
+        self.reply_to.closed().await
+    }
+
+    /// When the requester stops waiting for a response, if it sent the request with a timeout.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// How long is left until the [`ResponseHandle::deadline`], or `None` if there isn't one.
+    pub fn remaining_budget(&self) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Send the response.
+    ///
+    /// # Errors
+    ///
+    /// Gives the response back if the requester has stopped listening, counting it as
+    /// undelivered as with [`Request::respond`].
+    pub fn respond(self, response: Res) -> Result<(), Res> {
+        self.reply_to
+            .send(response)
+            .inspect(|_| crate::addr::response_delivered())
+            .inspect_err(|_| crate::addr::response_undelivered::<Res>())
+    }
 }
 
-#[derive(Debug)]
+impl<Res> Debug for ResponseHandle<Res> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResponseHandle")
+            .field("closed", &self.is_closed())
+            .field("deadline", &self.deadline)
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RequestError {
     ActorStopped,
     SenderDropped,
+    /// The request couldn't be converted into a message the actor accepts, see
+    /// [`Addr::try_recipient`](crate::Addr::try_recipient).
+    Incompatible,
+}
+
+impl RequestError {
+    /// Why a request couldn't be sent.
+    pub(crate) fn undelivered<M>(err: DeliveryError<M>) -> Self {
+        match err {
+            DeliveryError::Incompatible(_) => Self::Incompatible,
+            _ => Self::ActorStopped,
+        }
+    }
 }
 
 impl Display for RequestError {
@@ -41,17 +264,25 @@ impl Display for RequestError {
             Self::SenderDropped => {
                 write!(f, "sender was dropped before responding to the request")
             }
+            Self::Incompatible => {
+                write!(f, "the request isn't one the actor accepts")
+            }
         }
     }
 }
 
 impl Error for RequestError {}
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RequestTimeoutError {
     ActorStopped,
     SenderDropped,
     Timeout,
+    /// The request being handled had too little time left to pass any on, so the nested request
+    /// wasn't sent.
+    BudgetExhausted,
+    /// The request couldn't be converted into a message the actor accepts.
+    Incompatible,
 }
 
 impl Display for RequestTimeoutError {
@@ -66,8 +297,78 @@ impl Display for RequestTimeoutError {
             Self::Timeout => {
                 write!(f, "timeout waiting for response")
             }
+            Self::BudgetExhausted => {
+                write!(f, "no time left in the parent request's deadline")
+            }
+            Self::Incompatible => {
+                write!(f, "the request isn't one the actor accepts")
+            }
         }
     }
 }
 
 impl Error for RequestTimeoutError {}
+
+impl From<RequestError> for RequestTimeoutError {
+    fn from(err: RequestError) -> Self {
+        match err {
+            RequestError::ActorStopped => Self::ActorStopped,
+            RequestError::SenderDropped => Self::SenderDropped,
+            RequestError::Incompatible => Self::Incompatible,
+        }
+    }
+}
+
+/// The error returned by [`Addr::ask`](crate::Addr::ask), either from delivering the request or
+/// from the actor handling it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AskError<E> {
+    ActorStopped,
+    SenderDropped,
+    /// The request couldn't be converted into a message the actor accepts.
+    Incompatible,
+    /// The actor responded with an error.
+    Failed(E),
+}
+
+impl<E> Display for AskError<E>
+where
+    E: Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ActorStopped => {
+                write!(f, "the actor was stopped before the request could be sent")
+            }
+            Self::SenderDropped => {
+                write!(f, "sender was dropped before responding to the request")
+            }
+            Self::Incompatible => {
+                write!(f, "the request isn't one the actor accepts")
+            }
+            Self::Failed(err) => Display::fmt(err, f),
+        }
+    }
+}
+
+impl<E> Error for AskError<E>
+where
+    E: Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Failed(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl<E> From<RequestError> for AskError<E> {
+    fn from(err: RequestError) -> Self {
+        match err {
+            RequestError::ActorStopped => Self::ActorStopped,
+            RequestError::SenderDropped => Self::SenderDropped,
+            RequestError::Incompatible => Self::Incompatible,
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use crate::{actor::Actor, addr::Recipient, context::Context, request::Request};
+use async_trait::async_trait;
+use futures_util::{future::BoxFuture, FutureExt};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    time::Duration,
+};
+use tokio::{select, time::Instant};
+
+/// Identifies a scheduled delivery, used to cancel it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ScheduleId(u64);
+
+type Deliver = Box<dyn FnMut() -> BoxFuture<'static, bool> + Send + Sync>;
+
+enum Repeat {
+    Once,
+    Every(Duration),
+    #[cfg(feature = "cron")]
+    Cron(Box<cron::Schedule>),
+}
+
+/// A delivery to be registered with a [`Scheduler`].
+pub struct Schedule {
+    at: Instant,
+    repeat: Repeat,
+    deliver: Deliver,
+}
+
+impl Schedule {
+    /// Deliver a message to the recipient at the given instant.
+    pub fn at<M>(at: Instant, recipient: Recipient<M>, msg: M) -> Self
+    where
+        M: 'static + Send + Sync,
+    {
+        let mut msg = Some(msg);
+        Self {
+            at,
+            repeat: Repeat::Once,
+            deliver: Box::new(move || match msg.take() {
+                Some(msg) => {
+                    let recipient = recipient.clone();
+                    async move { recipient.send(msg).await.is_ok() }.boxed()
+                }
+                None => async { true }.boxed(),
+            }),
+        }
+    }
+
+    /// Deliver a message to the recipient once the given delay has passed.
+    pub fn after<M>(delay: Duration, recipient: Recipient<M>, msg: M) -> Self
+    where
+        M: 'static + Send + Sync,
+    {
+        Self::at(Instant::now() + delay, recipient, msg)
+    }
+
+    /// Deliver a message created by the factory to the recipient every interval, starting one
+    /// interval from now.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the interval is zero.
+    pub fn every<M, F>(interval: Duration, recipient: Recipient<M>, factory: F) -> Self
+    where
+        M: 'static + Send + Sync,
+        F: FnMut() -> M + Send + Sync + 'static,
+    {
+        assert!(
+            !interval.is_zero(),
+            "schedule interval must be greater than zero"
+        );
+        Self {
+            at: Instant::now() + interval,
+            repeat: Repeat::Every(interval),
+            deliver: Self::repeated(recipient, factory),
+        }
+    }
+
+    /// Deliver a message created by the factory to the recipient at each upcoming time of the
+    /// cron schedule, evaluated in UTC, or `None` if the schedule has no upcoming times.
+    #[cfg(feature = "cron")]
+    pub fn cron<M, F>(schedule: cron::Schedule, recipient: Recipient<M>, factory: F) -> Option<Self>
+    where
+        M: 'static + Send + Sync,
+        F: FnMut() -> M + Send + Sync + 'static,
+    {
+        let at = next_cron(&schedule)?;
+        Some(Self {
+            at,
+            repeat: Repeat::Cron(Box::new(schedule)),
+            deliver: Self::repeated(recipient, factory),
+        })
+    }
+
+    fn repeated<M, F>(recipient: Recipient<M>, mut factory: F) -> Deliver
+    where
+        M: 'static + Send + Sync,
+        F: FnMut() -> M + Send + Sync + 'static,
+    {
+        Box::new(move || {
+            let recipient = recipient.clone();
+            let msg = factory();
+            async move { recipient.send(msg).await.is_ok() }.boxed()
+        })
+    }
+
+    fn next(&self) -> Option<Instant> {
+        match &self.repeat {
+            Repeat::Once => None,
+            Repeat::Every(interval) => Some(self.at + *interval),
+            #[cfg(feature = "cron")]
+            Repeat::Cron(schedule) => next_cron(schedule),
+        }
+    }
+}
+
+#[cfg(feature = "cron")]
+fn next_cron(schedule: &cron::Schedule) -> Option<Instant> {
+    let next = schedule.upcoming(chrono::Utc).next()?;
+    let delay = (next - chrono::Utc::now()).to_std().unwrap_or_default();
+    Some(Instant::now() + delay)
+}
+
+/// Cancel a scheduled delivery, responding with whether it was still pending.
+pub struct Cancel(pub ScheduleId);
+
+/// Reported when a scheduled delivery is dropped because its recipient has stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Undelivered(pub ScheduleId);
+
+pub enum SchedulerMsg {
+    Schedule(Request<Schedule, ScheduleId>),
+    Cancel(Request<Cancel, bool>),
+}
+
+impl From<Request<Schedule, ScheduleId>> for SchedulerMsg {
+    fn from(request: Request<Schedule, ScheduleId>) -> Self {
+        Self::Schedule(request)
+    }
+}
+
+impl From<Request<Cancel, bool>> for SchedulerMsg {
+    fn from(request: Request<Cancel, bool>) -> Self {
+        Self::Cancel(request)
+    }
+}
+
+/// An actor that delivers messages to recipients at scheduled times.
+///
+/// Deliveries are made in order of their due time. If a recipient has stopped, its delivery is
+/// dropped, any recurrence is cancelled, and an [`Undelivered`] report is sent to the reporting
+/// recipient if one is configured. Schedules are kept in memory only.
+pub struct Scheduler {
+    schedules: HashMap<ScheduleId, Schedule>,
+    queue: BinaryHeap<Reverse<(Instant, ScheduleId)>>,
+    next_id: u64,
+    report_to: Option<Recipient<Undelivered>>,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self {
+            schedules: HashMap::new(),
+            queue: BinaryHeap::new(),
+            next_id: 0,
+            report_to: None,
+        }
+    }
+
+    /// Send an [`Undelivered`] report to the given recipient whenever a delivery is dropped.
+    pub fn report_to(mut self, recipient: Recipient<Undelivered>) -> Self {
+        self.report_to = Some(recipient);
+        self
+    }
+
+    fn schedule(&mut self, schedule: Schedule) -> ScheduleId {
+        let id = ScheduleId(self.next_id);
+        self.next_id += 1;
+        self.queue.push(Reverse((schedule.at, id)));
+        self.schedules.insert(id, schedule);
+        id
+    }
+
+    async fn deliver_due(&mut self) {
+        let now = Instant::now();
+        while let Some(Reverse((at, id))) = self.queue.peek().copied() {
+            if at > now {
+                break;
+            }
+            self.queue.pop();
+
+            // Cancelled schedules are only removed from the map, so skip their queue entries
+            let mut schedule = match self.schedules.remove(&id) {
+                Some(schedule) => schedule,
+                None => continue,
+            };
+
+            if !(schedule.deliver)().await {
+                if let Some(report_to) = &self.report_to {
+                    let _ = report_to.send(Undelivered(id)).await;
+                }
+                continue;
+            }
+
+            if let Some(next) = schedule.next() {
+                schedule.at = next;
+                self.queue.push(Reverse((next, id)));
+                self.schedules.insert(id, schedule);
+            }
+        }
+    }
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Actor for Scheduler {
+    type Msg = SchedulerMsg;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let next = self.queue.peek().map(|Reverse((at, _))| *at);
+        let due = ctx.agency.sleep_until(next.unwrap_or_else(Instant::now));
+        select! {
+            biased;
+            msg = ctx.message() => match msg {
+                SchedulerMsg::Schedule(request) => {
+                    if let Some((schedule, reply_to)) = request.handle() {
+                        let _ = reply_to.send(self.schedule(schedule));
+                    }
+                }
+                SchedulerMsg::Cancel(request) => {
+                    if let Some((Cancel(id), reply_to)) = request.handle() {
+                        let _ = reply_to.send(self.schedules.remove(&id).is_some());
+                    }
+                }
+            },
+            _ = due, if next.is_some() => {
+                self.deliver_due().await;
+            }
+        }
+    }
+}
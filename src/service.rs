@@ -0,0 +1,173 @@
+//! Adapters between actors and [`tower::Service`]s, behind the `tower` feature.
+
+use crate::{
+    actor::Actor,
+    addr::{DeliveryError, Recipient, RecipientPermit},
+    context::Context,
+    handler::Handler,
+    request::{Ask, Request, RequestError},
+};
+use async_trait::async_trait;
+use futures_util::future::{poll_fn, BoxFuture, FutureExt};
+use std::{
+    marker::PhantomData,
+    sync::Mutex,
+    task::{self, Poll},
+};
+use tower::{BoxError, Service};
+
+type Reserving<M> = BoxFuture<'static, Result<RecipientPermit<M>, DeliveryError<()>>>;
+
+/// A [`tower::Service`] that sends each request to an actor as a [`Request`].
+///
+/// `poll_ready` waits for room in the actor's mailbox and holds it for the next `call`, so tower
+/// middleware sees the actor's backpressure. Errors are [`RequestError`]s, boxed to compose with
+/// middleware errors such as timeouts.
+pub struct ActorService<Req, Res>
+where
+    Req: 'static,
+    Res: 'static,
+{
+    recipient: Recipient<Request<Req, Res>>,
+    reserving: Option<Reserving<Request<Req, Res>>>,
+    permit: Option<RecipientPermit<Request<Req, Res>>>,
+}
+
+impl<Req, Res> ActorService<Req, Res>
+where
+    Req: 'static,
+    Res: 'static,
+{
+    pub fn new(recipient: impl Into<Recipient<Request<Req, Res>>>) -> Self {
+        Self {
+            recipient: recipient.into(),
+            reserving: None,
+            permit: None,
+        }
+    }
+}
+
+impl<Req, Res> Clone for ActorService<Req, Res>
+where
+    Req: 'static,
+    Res: 'static,
+{
+    /// Clones don't share the room reserved by `poll_ready`, so each needs to get ready itself.
+    fn clone(&self) -> Self {
+        Self {
+            recipient: self.recipient.clone(),
+            reserving: None,
+            permit: None,
+        }
+    }
+}
+
+impl<Req, Res> Service<Req> for ActorService<Req, Res>
+where
+    Req: 'static + Send,
+    Res: 'static + Send,
+{
+    type Response = Res;
+    type Error = BoxError;
+    type Future = BoxFuture<'static, Result<Res, BoxError>>;
+
+    fn poll_ready(&mut self, cx: &mut task::Context<'_>) -> Poll<Result<(), BoxError>> {
+        if self.permit.is_some() {
+            return Poll::Ready(Ok(()));
+        }
+        let reserving = match &mut self.reserving {
+            Some(reserving) => reserving,
+            None => self.reserving.insert(self.recipient.reserve()),
+        };
+        match reserving.poll_unpin(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(res) => {
+                self.reserving = None;
+                self.permit = Some(res.map_err(|_| RequestError::ActorStopped)?);
+                Poll::Ready(Ok(()))
+            }
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `poll_ready` hasn't returned ready since the last call.
+    fn call(&mut self, payload: Req) -> Self::Future {
+        let permit = self
+            .permit
+            .take()
+            .expect("call made before the service was ready");
+        let (request, receiver) = Request::new(payload);
+        permit.send(request);
+        async move {
+            receiver
+                .await
+                .map_err(|_| RequestError::SenderDropped.into())
+        }
+        .boxed()
+    }
+}
+
+/// An actor that hands each [`Request`] it receives to a [`tower::Service`].
+///
+/// The actor waits for the service to be ready before taking the next request, so senders see
+/// the service's backpressure. Responses are awaited in their own tasks, leaving the service free
+/// to work on several requests at once. Requests whose senders have stopped waiting aren't passed
+/// on at all.
+pub struct ServiceActor<S, Req> {
+    /// Only ever accessed through `&mut self`, so it's never locked, but it lets services that
+    /// aren't `Sync` be run by an actor.
+    service: Mutex<S>,
+    _request: PhantomData<fn(Req)>,
+}
+
+impl<S, Req> ServiceActor<S, Req> {
+    pub fn new(service: S) -> Self {
+        Self {
+            service: Mutex::new(service),
+            _request: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<S, Req> Actor for ServiceActor<S, Req>
+where
+    S: 'static + Service<Req> + Send,
+    S::Future: 'static + Send,
+    S::Response: 'static + Send,
+    S::Error: 'static + Send,
+    Req: 'static + Send + Sync,
+{
+    type Msg = Ask<Req, S::Response, S::Error>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        ctx.dispatch(self).await
+    }
+}
+
+#[async_trait]
+impl<S, Req> Handler for ServiceActor<S, Req>
+where
+    S: 'static + Service<Req> + Send,
+    S::Future: 'static + Send,
+    S::Response: 'static + Send,
+    S::Error: 'static + Send,
+    Req: 'static + Send + Sync,
+{
+    async fn handle(&mut self, ctx: &mut Context<Self>, request: Self::Msg) {
+        let service = self.service.get_mut().unwrap();
+        if let Err(err) = poll_fn(|cx| service.poll_ready(cx)).await {
+            let _ = request.respond(Err(err));
+            return;
+        }
+        let (payload, reply_to) = match request.handle() {
+            Some(request) => request,
+            None => return,
+        };
+        let response = service.call(payload);
+        ctx.agency.spawn(async move {
+            let _ = reply_to.send(response.await);
+        });
+    }
+}
@@ -0,0 +1,121 @@
+use std::{error::Error, fmt::Display};
+use tokio::sync::mpsc;
+
+/// How many messages can be in flight in each direction of a session before the sender waits.
+const SESSION_CAPACITY: usize = 16;
+
+/// The handler's side of a conversation opened with
+/// [`Addr::open_session`](crate::Addr::open_session).
+///
+/// Like a [`Request`](crate::Request), a session goes in the actor's message enum. Unlike a
+/// request it isn't over after one response: both sides keep sending until they're done, with
+/// `ClientMsg` going from the requester to the handler and `ServerMsg` coming back.
+///
+/// Either side can [`finish`](Session::finish) sending while still receiving from the other, and
+/// dropping a side closes both directions.
+pub struct Session<Req, ClientMsg, ServerMsg> {
+    payload: Req,
+    sender: Option<mpsc::Sender<ServerMsg>>,
+    receiver: mpsc::Receiver<ClientMsg>,
+}
+
+/// The requester's side of a [`Session`].
+pub struct SessionHandle<ClientMsg, ServerMsg> {
+    sender: Option<mpsc::Sender<ClientMsg>>,
+    receiver: mpsc::Receiver<ServerMsg>,
+}
+
+impl<Req, ClientMsg, ServerMsg> Session<Req, ClientMsg, ServerMsg> {
+    pub(crate) fn new(payload: Req) -> (Self, SessionHandle<ClientMsg, ServerMsg>) {
+        let (client_sender, client_receiver) = mpsc::channel(SESSION_CAPACITY);
+        let (server_sender, server_receiver) = mpsc::channel(SESSION_CAPACITY);
+        let session = Self {
+            payload,
+            sender: Some(server_sender),
+            receiver: client_receiver,
+        };
+        let handle = SessionHandle {
+            sender: Some(client_sender),
+            receiver: server_receiver,
+        };
+        (session, handle)
+    }
+
+    /// The payload the session was opened with.
+    pub fn payload(&self) -> &Req {
+        &self.payload
+    }
+
+    /// Send a message to the requester, waiting if it's behind on reading them.
+    ///
+    /// # Errors
+    ///
+    /// This will error if the requester has dropped its handle, or this side has finished.
+    pub async fn send(&self, msg: ServerMsg) -> Result<(), SessionClosed> {
+        send(&self.sender, msg).await
+    }
+
+    /// Receive the next message from the requester, or `None` once it's finished sending.
+    pub async fn recv(&mut self) -> Option<ClientMsg> {
+        self.receiver.recv().await
+    }
+
+    /// Stop sending to the requester, which sees the end of the conversation once it's read
+    /// everything already sent. Messages from the requester can still be received.
+    pub fn finish(&mut self) {
+        self.sender = None;
+    }
+
+    /// Whether nothing more can be sent, because the requester dropped its handle or this side has
+    /// finished.
+    pub fn is_closed(&self) -> bool {
+        self.sender.as_ref().is_none_or(mpsc::Sender::is_closed)
+    }
+}
+
+impl<ClientMsg, ServerMsg> SessionHandle<ClientMsg, ServerMsg> {
+    /// Send a message to the handler, waiting if it's behind on reading them.
+    ///
+    /// # Errors
+    ///
+    /// This will error if the handler has dropped its session, or this side has finished.
+    pub async fn send(&self, msg: ClientMsg) -> Result<(), SessionClosed> {
+        send(&self.sender, msg).await
+    }
+
+    /// Receive the next message from the handler, or `None` once it's finished sending.
+    pub async fn recv(&mut self) -> Option<ServerMsg> {
+        self.receiver.recv().await
+    }
+
+    /// Stop sending to the handler, which sees the end of the conversation once it's read
+    /// everything already sent. Messages from the handler can still be received.
+    pub fn finish(&mut self) {
+        self.sender = None;
+    }
+
+    /// Whether nothing more can be sent, because the handler dropped its session or this side has
+    /// finished.
+    pub fn is_closed(&self) -> bool {
+        self.sender.as_ref().is_none_or(mpsc::Sender::is_closed)
+    }
+}
+
+async fn send<M>(sender: &Option<mpsc::Sender<M>>, msg: M) -> Result<(), SessionClosed> {
+    match sender {
+        Some(sender) => sender.send(msg).await.map_err(|_| SessionClosed),
+        None => Err(SessionClosed),
+    }
+}
+
+/// The other side of a [`Session`] has gone away, or this side has finished sending.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionClosed;
+
+impl Display for SessionClosed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "session closed")
+    }
+}
+
+impl Error for SessionClosed {}
@@ -0,0 +1,139 @@
+use crate::{
+    actor::Actor,
+    addr::{Addr, DeliveryError},
+    agency::Agency,
+    request::{Request, RequestError},
+};
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+type Factory<K, A> = Arc<dyn Fn(&K) -> A + Send + Sync>;
+
+/// One actor per key, hired the first time something is sent for the key, see
+/// [`Agency::hire_shards`].
+///
+/// Shards are forgotten once they stop, so with an [idle timeout](Shards::idle_timeout) they're
+/// retired after going quiet and hired afresh when traffic for their key picks up again. Clones
+/// share the same shards.
+pub struct Shards<K, A>
+where
+    A: Actor,
+{
+    agency: Agency,
+    factory: Factory<K, A>,
+    idle_timeout: Option<Duration>,
+    shards: Arc<Mutex<HashMap<K, Addr<A>>>>,
+}
+
+impl<K, A> Shards<K, A>
+where
+    K: 'static + Clone + Eq + Hash + Send + Sync,
+    A: 'static + Actor,
+{
+    pub(crate) fn new(agency: Agency, factory: impl Fn(&K) -> A + Send + Sync + 'static) -> Self {
+        Self {
+            agency,
+            factory: Arc::new(factory),
+            idle_timeout: None,
+            shards: Arc::default(),
+        }
+    }
+
+    /// Stop each shard once it's waited this long for a message, see
+    /// [`Context::set_idle_timeout`](crate::Context::set_idle_timeout).
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// The address of the shard for a key, hiring it if there isn't one running.
+    pub fn shard(&self, key: &K) -> Addr<A> {
+        // Held while hiring, so concurrent first sends for a key share the one actor
+        let mut shards = self.shards.lock().unwrap();
+        if let Some(addr) = shards.get(key) {
+            if !addr.mailer().is_closed() {
+                return addr.clone();
+            }
+        }
+
+        let mut builder = self.agency.hire_builder((self.factory)(key));
+        if let Some(timeout) = self.idle_timeout {
+            builder = builder.idle_timeout(timeout);
+        }
+        let addr = builder.hire();
+        shards.insert(key.clone(), addr.clone());
+        self.forget_when_stopped(key.clone(), &addr);
+        addr
+    }
+
+    fn forget_when_stopped(&self, key: K, addr: &Addr<A>) {
+        let mut exit = addr.inner().exit_signal();
+        let id = addr.id();
+        let shards = Arc::downgrade(&self.shards);
+        self.agency.spawn_detached(async move {
+            let _ = exit.wait_for(Option::is_some).await;
+            if let Some(shards) = shards.upgrade() {
+                let mut shards = shards.lock().unwrap();
+                // Unless it's already been replaced by a newer shard
+                if shards.get(&key).is_some_and(|addr| addr.id() == id) {
+                    shards.remove(&key);
+                }
+            }
+        });
+    }
+
+    /// Send a message to the shard for a key, hiring it first if need be.
+    ///
+    /// # Errors
+    ///
+    /// This will error with [`DeliveryError::Closed`] if the shard stopped as the message was
+    /// sent, and the one hired to replace it did too.
+    pub async fn send(&self, key: &K, msg: impl Into<A::Msg>) -> Result<(), DeliveryError<A::Msg>> {
+        match self.shard(key).send(msg).await {
+            // Retired just as the message arrived, so it goes to a fresh shard instead
+            Err(DeliveryError::Closed(msg)) => self.shard(key).send(msg).await,
+            res => res,
+        }
+    }
+
+    /// Send a [`Request`](crate::Request) to the shard for a key and await the response, hiring
+    /// the shard first if need be.
+    pub async fn request<Req, Res>(&self, key: &K, payload: Req) -> Result<Res, RequestError>
+    where
+        Request<Req, Res>: Into<A::Msg>,
+    {
+        let (request, receiver) = Request::new(payload);
+        self.send(key, request)
+            .await
+            .map_err(|_| RequestError::ActorStopped)?;
+        let res = receiver.await.map_err(|_| RequestError::SenderDropped)?;
+        Ok(res)
+    }
+
+    /// How many shards are running.
+    pub fn len(&self) -> usize {
+        self.shards.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<K, A> Clone for Shards<K, A>
+where
+    A: Actor,
+{
+    fn clone(&self) -> Self {
+        Self {
+            agency: self.agency.clone(),
+            factory: self.factory.clone(),
+            idle_timeout: self.idle_timeout,
+            shards: self.shards.clone(),
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use crate::{actor::Actor, context::Context, request::Request};
+use async_trait::async_trait;
+use std::{collections::VecDeque, mem};
+
+/// A single state of a [`StateMachine`].
+///
+/// Each state is its own type, consuming itself when handling a message and returning the state
+/// the machine should be in afterwards.
+#[async_trait]
+pub trait State<M: 'static + Send + Sync>: Send + Sync + 'static {
+    /// The name of this state, as reported to [`CurrentState`] requests.
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    /// Called when the machine transitions into this state, including the initial state.
+    async fn on_enter(&mut self, _ctx: &mut Context<StateMachine<M>>) {}
+
+    async fn on_message(
+        self: Box<Self>,
+        ctx: &mut Context<StateMachine<M>>,
+        msg: M,
+    ) -> Transition<M>;
+}
+
+/// The outcome of a [`State`] handling a message.
+pub enum Transition<M: 'static + Send + Sync> {
+    /// Remain in the given state without running its [`State::on_enter`] hook.
+    Stay(Box<dyn State<M>>),
+    /// Move into the given state, running its [`State::on_enter`] hook.
+    To(Box<dyn State<M>>),
+    /// The message isn't valid in this state, so handle it according to the machine's
+    /// [`UnhandledPolicy`] and remain in the given state.
+    Unhandled(Box<dyn State<M>>, M),
+    /// Stop the machine.
+    Stop,
+}
+
+impl<M: 'static + Send + Sync> Transition<M> {
+    pub fn stay(state: impl State<M>) -> Self {
+        Self::Stay(Box::new(state))
+    }
+
+    pub fn to(state: impl State<M>) -> Self {
+        Self::To(Box::new(state))
+    }
+
+    pub fn unhandled(state: impl State<M>, msg: M) -> Self {
+        Self::Unhandled(Box::new(state), msg)
+    }
+}
+
+/// What a [`StateMachine`] does with messages that aren't valid in its current state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnhandledPolicy {
+    /// Drop the message.
+    Reject,
+    /// Keep the message and redeliver it, in order, after the next transition.
+    Stash,
+}
+
+/// Ask a [`StateMachine`] for the name of its current state.
+pub struct CurrentState;
+
+pub enum StateMachineMsg<M: 'static> {
+    Event(M),
+    CurrentState(Request<CurrentState, &'static str>),
+}
+
+impl<M> From<Request<CurrentState, &'static str>> for StateMachineMsg<M> {
+    fn from(request: Request<CurrentState, &'static str>) -> Self {
+        Self::CurrentState(request)
+    }
+}
+
+/// An actor driven by a set of [`State`]s, each handling the messages valid while it's active.
+pub struct StateMachine<M: 'static> {
+    state: Option<Box<dyn State<M>>>,
+    policy: UnhandledPolicy,
+    stash: VecDeque<M>,
+    replay: VecDeque<M>,
+}
+
+impl<M> StateMachine<M>
+where
+    M: 'static + Send + Sync,
+{
+    pub fn new(initial: impl State<M>) -> Self {
+        Self {
+            state: Some(Box::new(initial)),
+            policy: UnhandledPolicy::Reject,
+            stash: VecDeque::new(),
+            replay: VecDeque::new(),
+        }
+    }
+
+    /// Set what happens to messages that aren't valid in the current state. Defaults to
+    /// [`UnhandledPolicy::Reject`].
+    pub fn on_unhandled(mut self, policy: UnhandledPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    fn state_name(&self) -> &'static str {
+        self.state.as_ref().map_or("stopped", |state| state.name())
+    }
+
+    async fn next_event(&mut self, ctx: &mut Context<Self>) -> Option<M> {
+        if let Some(msg) = self.replay.pop_front() {
+            return Some(msg);
+        }
+
+        match ctx.message().await {
+            StateMachineMsg::Event(msg) => Some(msg),
+            StateMachineMsg::CurrentState(request) => {
+                if let Some((_, reply_to)) = request.handle() {
+                    let _ = reply_to.send(self.state_name());
+                }
+                None
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<M> Actor for StateMachine<M>
+where
+    M: 'static + Send + Sync,
+{
+    type Msg = StateMachineMsg<M>;
+
+    async fn init(&mut self, ctx: &mut Context<Self>) {
+        if let Some(state) = &mut self.state {
+            state.on_enter(ctx).await;
+        }
+    }
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let msg = match self.next_event(ctx).await {
+            Some(msg) => msg,
+            None => return,
+        };
+        let state = match self.state.take() {
+            Some(state) => state,
+            None => {
+                ctx.stop();
+                return;
+            }
+        };
+
+        match state.on_message(ctx, msg).await {
+            Transition::Stay(state) => self.state = Some(state),
+            Transition::To(mut state) => {
+                state.on_enter(ctx).await;
+                self.state = Some(state);
+
+                // Stashed messages are older than anything left over from a previous replay
+                let mut replay = mem::take(&mut self.stash);
+                replay.append(&mut self.replay);
+                self.replay = replay;
+            }
+            Transition::Unhandled(state, msg) => {
+                self.state = Some(state);
+                if self.policy == UnhandledPolicy::Stash {
+                    self.stash.push_back(msg);
+                }
+            }
+            Transition::Stop => ctx.stop(),
+        }
+    }
+}
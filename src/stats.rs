@@ -0,0 +1,191 @@
+use std::{
+    sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    time::Duration,
+};
+use tokio::time::Instant;
+
+const UNSET: u64 = u64::MAX;
+
+/// Counters for a single actor, shared by its context and every address that refers to it.
+///
+/// Everything uses relaxed atomics since the values are only ever read for reporting.
+pub(crate) struct ActorStats {
+    base: Instant,
+    processed: AtomicU64,
+    processed_priority: AtomicU64,
+    errors: AtomicU64,
+    restarts: AtomicU64,
+    responses: AtomicU64,
+    undelivered_responses: AtomicU64,
+    #[cfg(feature = "chaos")]
+    chaos_dropped: AtomicU64,
+    busy: AtomicBool,
+    paused: AtomicBool,
+    priority_depth: AtomicUsize,
+    started_at: AtomicU64,
+    last_active: AtomicU64,
+    stopped_at: AtomicU64,
+}
+
+impl ActorStats {
+    pub(crate) fn new() -> Self {
+        Self {
+            base: Instant::now(),
+            processed: AtomicU64::new(0),
+            processed_priority: AtomicU64::new(0),
+            errors: AtomicU64::new(0),
+            restarts: AtomicU64::new(0),
+            responses: AtomicU64::new(0),
+            undelivered_responses: AtomicU64::new(0),
+            #[cfg(feature = "chaos")]
+            chaos_dropped: AtomicU64::new(0),
+            busy: AtomicBool::new(false),
+            paused: AtomicBool::new(false),
+            priority_depth: AtomicUsize::new(0),
+            started_at: AtomicU64::new(UNSET),
+            last_active: AtomicU64::new(UNSET),
+            stopped_at: AtomicU64::new(UNSET),
+        }
+    }
+
+    fn now(&self) -> u64 {
+        Instant::now().duration_since(self.base).as_nanos() as u64
+    }
+
+    fn instant(&self, offset: &AtomicU64) -> Option<Instant> {
+        match offset.load(Ordering::Relaxed) {
+            UNSET => None,
+            nanos => Some(self.base + Duration::from_nanos(nanos)),
+        }
+    }
+
+    pub(crate) fn started(&self) {
+        self.started_at.store(self.now(), Ordering::Relaxed);
+    }
+
+    pub(crate) fn received(&self, priority: bool) {
+        self.processed.fetch_add(1, Ordering::Relaxed);
+        if priority {
+            self.processed_priority.fetch_add(1, Ordering::Relaxed);
+            self.priority_dequeued();
+        }
+        self.active();
+    }
+
+    /// Mark the actor as busy with a message it had already received, such as one it stashed.
+    pub(crate) fn active(&self) {
+        self.last_active.store(self.now(), Ordering::Relaxed);
+        self.busy.store(true, Ordering::Relaxed);
+    }
+
+    /// Count a message into the priority mailbox, which can't report its own length as it's
+    /// unbounded.
+    ///
+    /// This must be called before the message is sent, so the matching dequeue can never be
+    /// counted first.
+    pub(crate) fn priority_enqueued(&self) {
+        self.priority_depth.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn priority_dequeued(&self) {
+        self.priority_depth.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn priority_depth(&self) -> usize {
+        self.priority_depth.load(Ordering::Relaxed)
+    }
+
+    /// Mark the actor as waiting for its next message.
+    pub(crate) fn idle(&self) {
+        self.busy.store(false, Ordering::Relaxed);
+    }
+
+    pub(crate) fn is_busy(&self) -> bool {
+        self.busy.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::Relaxed);
+    }
+
+    pub(crate) fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn restarted(&self) {
+        self.restarts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn response_delivered(&self) {
+        self.responses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn response_undelivered(&self) {
+        self.undelivered_responses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "chaos")]
+    pub(crate) fn chaos_dropped(&self) {
+        self.chaos_dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn error(&self) {
+        self.errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn stopped(&self) {
+        self.idle();
+        // Anything left in the priority mailbox was dropped along with it
+        self.priority_depth.store(0, Ordering::Relaxed);
+        self.stopped_at.store(self.now(), Ordering::Relaxed);
+    }
+
+    pub(crate) fn snapshot(&self) -> ActorStatsSnapshot {
+        ActorStatsSnapshot {
+            processed: self.processed.load(Ordering::Relaxed),
+            processed_priority: self.processed_priority.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+            restarts: self.restarts.load(Ordering::Relaxed),
+            responses: self.responses.load(Ordering::Relaxed),
+            undelivered_responses: self.undelivered_responses.load(Ordering::Relaxed),
+            #[cfg(feature = "chaos")]
+            chaos_dropped: self.chaos_dropped.load(Ordering::Relaxed),
+            priority_depth: self.priority_depth(),
+            started_at: self.instant(&self.started_at),
+            last_active: self.instant(&self.last_active),
+            stopped_at: self.instant(&self.stopped_at),
+        }
+    }
+}
+
+/// A point-in-time copy of an actor's counters.
+///
+/// Once the actor has stopped, the counters no longer change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActorStatsSnapshot {
+    /// Messages pulled from either mailbox.
+    pub processed: u64,
+    /// Messages pulled from the priority mailbox, included in `processed`.
+    pub processed_priority: u64,
+    /// Panics in the actor's task.
+    pub errors: u64,
+    /// Times the actor recovered from stopping.
+    pub restarts: u64,
+    /// Responses the actor delivered to a waiting requester, see
+    /// [`Request::respond`](crate::Request::respond).
+    pub responses: u64,
+    /// Responses the actor computed after the requester had stopped waiting, see
+    /// [`Request::respond`](crate::Request::respond).
+    pub undelivered_responses: u64,
+    /// Messages dropped by the agency's [`ChaosPolicy`](crate::ChaosPolicy).
+    #[cfg(feature = "chaos")]
+    pub chaos_dropped: u64,
+    /// Messages waiting in the priority mailbox.
+    pub priority_depth: usize,
+    /// When the actor's run loop started.
+    pub started_at: Option<Instant>,
+    /// When the actor last pulled a message.
+    pub last_active: Option<Instant>,
+    /// When the actor's task finished.
+    pub stopped_at: Option<Instant>,
+}
@@ -0,0 +1,36 @@
+use crate::timer::TimerHandle;
+use tokio::sync::watch;
+
+/// Controls a stream being fed into an actor's mailbox with
+/// [`Context::add_stream`](crate::Context::add_stream).
+///
+/// Dropping the handle does not stop the stream.
+#[derive(Debug, Clone)]
+pub struct StreamHandle {
+    task: TimerHandle,
+    finished: watch::Receiver<bool>,
+}
+
+impl StreamHandle {
+    pub(crate) fn new(task: TimerHandle, finished: watch::Receiver<bool>) -> Self {
+        Self { task, finished }
+    }
+
+    /// Stop feeding the stream into the mailbox, dropping it along with any item waiting for room.
+    pub fn cancel(&self) {
+        self.task.cancel();
+    }
+
+    /// Whether the stream has ended, with every item it yielded delivered.
+    pub fn is_finished(&self) -> bool {
+        *self.finished.borrow()
+    }
+
+    /// Wait for the stream to end, returning false if it was cancelled or the actor stopped
+    /// first.
+    pub async fn finished(&self) -> bool {
+        let mut finished = self.finished.clone();
+        let ended = finished.wait_for(|finished| *finished).await.is_ok();
+        ended
+    }
+}
@@ -0,0 +1,289 @@
+use crate::id::ActorId;
+use crate::{
+    actor::{Actor, StoppingResult},
+    addr::{AddrInner, Exit, StopListener},
+    agency::Agency,
+    context::Context,
+    request::Request,
+};
+use async_trait::async_trait;
+use futures_util::future::join_all;
+use std::{collections::VecDeque, sync::Arc, time::Duration};
+use tokio::{
+    select,
+    sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+    time::Instant,
+};
+
+/// When a supervised child should be restarted after it stops.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartPolicy {
+    /// Restart the child whenever it stops.
+    Always,
+    /// Restart the child only if it panicked or was aborted.
+    OnFailure,
+    /// Never restart the child.
+    Never,
+}
+
+impl RestartPolicy {
+    fn should_restart(self, exit: Exit) -> bool {
+        match self {
+            Self::Always => true,
+            Self::OnFailure => exit.is_failure(),
+            Self::Never => false,
+        }
+    }
+}
+
+/// Which children a [`Supervisor`] restarts when one of them stops.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SupervisionStrategy {
+    /// Restart only the child that stopped.
+    OneForOne,
+    /// Stop every other child, then restart them all.
+    AllForOne,
+}
+
+type ChildFactory = Box<dyn FnMut(&Agency) -> Arc<AddrInner> + Send + Sync>;
+
+/// Describes how a [`Supervisor`] creates and restarts one of its children.
+pub struct ChildSpec {
+    name: String,
+    policy: RestartPolicy,
+    factory: ChildFactory,
+}
+
+impl ChildSpec {
+    /// Describe a child created by the given factory, restarted when it fails.
+    pub fn new<A, F>(name: impl Into<String>, mut factory: F) -> Self
+    where
+        A: 'static + Actor,
+        F: FnMut() -> A + Send + Sync + 'static,
+    {
+        Self {
+            name: name.into(),
+            policy: RestartPolicy::OnFailure,
+            factory: Box::new(move |agency| {
+                let addr = agency.hire(factory());
+                // The supervisor only keeps track of its children, it doesn't hold addresses to them
+                addr.inner().detach();
+                addr.inner().clone()
+            }),
+        }
+    }
+
+    /// Set when this child is restarted. Defaults to [`RestartPolicy::OnFailure`].
+    pub fn restart(mut self, policy: RestartPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Ask a [`Supervisor`] for the ids of its running children, in the order they were declared.
+pub struct GetChildren;
+
+pub enum SupervisorMsg {
+    GetChildren(Request<GetChildren, Vec<ActorId>>),
+}
+
+impl From<Request<GetChildren, Vec<ActorId>>> for SupervisorMsg {
+    fn from(request: Request<GetChildren, Vec<ActorId>>) -> Self {
+        Self::GetChildren(request)
+    }
+}
+
+struct Child {
+    inner: Arc<AddrInner>,
+    generation: u64,
+}
+
+struct ChildExited {
+    index: usize,
+    generation: u64,
+    exit: Exit,
+}
+
+/// An actor that hires a set of children and restarts them according to their [`ChildSpec`]s.
+///
+/// If children need restarting more than the configured number of times within the window, the
+/// supervisor gives up, stopping all of its children and then itself.
+///
+/// Children being stopped together, such as when the supervisor stops or restarts them all, are
+/// asked to stop at once and then waited for, with any still running after the stop timeout
+/// aborted.
+///
+/// Children are stopped the next time they wait for a message, so a child that never calls
+/// [`Context::message`] can't be stopped by its supervisor.
+pub struct Supervisor {
+    specs: Vec<ChildSpec>,
+    children: Vec<Option<Child>>,
+    strategy: SupervisionStrategy,
+    max_restarts: usize,
+    window: Duration,
+    stop_timeout: Duration,
+    restarts: VecDeque<Instant>,
+    generation: u64,
+    exits: (UnboundedSender<ChildExited>, UnboundedReceiver<ChildExited>),
+}
+
+impl Supervisor {
+    pub fn new(strategy: SupervisionStrategy) -> Self {
+        Self {
+            specs: Vec::new(),
+            children: Vec::new(),
+            strategy,
+            max_restarts: 3,
+            window: Duration::from_secs(5),
+            stop_timeout: Duration::from_secs(5),
+            restarts: VecDeque::new(),
+            generation: 0,
+            exits: unbounded_channel(),
+        }
+    }
+
+    pub fn child(mut self, spec: ChildSpec) -> Self {
+        self.specs.push(spec);
+        self.children.push(None);
+        self
+    }
+
+    /// Set how many restarts are allowed within the window before the supervisor gives up.
+    /// Defaults to 3 restarts in 5 seconds.
+    pub fn max_restarts(mut self, max_restarts: usize, window: Duration) -> Self {
+        self.max_restarts = max_restarts;
+        self.window = window;
+        self
+    }
+
+    /// Set how long children being stopped together get to finish before they're aborted.
+    /// Defaults to 5 seconds.
+    pub fn stop_timeout(mut self, timeout: Duration) -> Self {
+        self.stop_timeout = timeout;
+        self
+    }
+
+    fn start(&mut self, ctx: &mut Context<Self>, index: usize) {
+        let inner = (self.specs[index].factory)(&ctx.agency);
+        self.generation += 1;
+        let generation = self.generation;
+
+        let mut exit = inner.exit_signal();
+        let exits = self.exits.0.clone();
+        ctx.agency.spawn(async move {
+            let exit = exit
+                .wait_for(Option::is_some)
+                .await
+                .map_or(Exit::Aborted, |exit| exit.unwrap_or(Exit::Aborted));
+            let _ = exits.send(ChildExited {
+                index,
+                generation,
+                exit,
+            });
+        });
+
+        self.children[index] = Some(Child { inner, generation });
+    }
+
+    /// Ask every running child to stop, then wait for them all together, aborting any that
+    /// haven't finished once the stop timeout has passed.
+    async fn stop_children(&mut self, agency: &Agency) {
+        let children: Vec<_> = self.children.iter_mut().filter_map(Option::take).collect();
+        for child in &children {
+            child.inner.request_stop();
+        }
+        let stopped = join_all(
+            children
+                .iter()
+                .map(|child| StopListener::new(child.inner.exit_signal())),
+        );
+        let deadline = Instant::now() + self.stop_timeout;
+        if agency.timeout_at(deadline, stopped).await.is_err() {
+            for child in &children {
+                child.inner.abort();
+            }
+        }
+    }
+
+    /// Record a restart, returning false if the restart intensity has been exceeded.
+    fn allow_restart(&mut self) -> bool {
+        let now = Instant::now();
+        while let Some(oldest) = self.restarts.front() {
+            if now.duration_since(*oldest) > self.window {
+                self.restarts.pop_front();
+            } else {
+                break;
+            }
+        }
+        self.restarts.push_back(now);
+        self.restarts.len() <= self.max_restarts
+    }
+
+    async fn child_exited(&mut self, ctx: &mut Context<Self>, exited: ChildExited) {
+        match &self.children[exited.index] {
+            Some(child) if child.generation == exited.generation => {}
+            _ => return,
+        }
+        self.children[exited.index] = None;
+
+        if !self.specs[exited.index].policy.should_restart(exited.exit) {
+            return;
+        }
+        if !self.allow_restart() {
+            ctx.stop();
+            return;
+        }
+
+        match self.strategy {
+            SupervisionStrategy::OneForOne => self.start(ctx, exited.index),
+            SupervisionStrategy::AllForOne => {
+                self.stop_children(&ctx.agency).await;
+                for index in 0..self.specs.len() {
+                    if index == exited.index || self.specs[index].policy != RestartPolicy::Never {
+                        self.start(ctx, index);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Actor for Supervisor {
+    type Msg = SupervisorMsg;
+
+    async fn init(&mut self, ctx: &mut Context<Self>) {
+        for index in 0..self.specs.len() {
+            self.start(ctx, index);
+        }
+    }
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        select! {
+            biased;
+            Some(exited) = self.exits.1.recv() => self.child_exited(ctx, exited).await,
+            msg = ctx.message() => match msg {
+                SupervisorMsg::GetChildren(request) => {
+                    if let Some((_, reply_to)) = request.handle() {
+                        let children = self
+                            .children
+                            .iter()
+                            .flatten()
+                            .map(|child| child.inner.id())
+                            .collect();
+                        let _ = reply_to.send(children);
+                    }
+                }
+            },
+        }
+    }
+
+    async fn stopping(&mut self, ctx: &mut Context<Self>) -> StoppingResult {
+        self.stop_children(&ctx.agency).await;
+        StoppingResult::Stop
+    }
+}
@@ -0,0 +1,224 @@
+//! Helpers for testing actors against tokio's paused clock, behind the `test-util` feature.
+//!
+//! The crate's own timers, such as [`Context::run_later`](crate::Context::run_later), the
+//! [`Scheduler`](crate::Scheduler) and [`Aggregator`](crate::Aggregator) deadlines, register their
+//! deadlines with the agency, so time can be advanced exactly to each one in turn rather than by
+//! a guessed amount. Timers an actor sets up itself, such as with `tokio::time::sleep`, aren't
+//! known about.
+//!
+//! Everything here needs the clock to be paused, such as with
+//! `#[tokio::test(start_paused = true)]`.
+
+use crate::{addr::Recipient, agency::Agency};
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{sync::mpsc, time::Instant};
+
+/// How many times to yield between checks for progress while settling.
+const SETTLE_YIELDS: usize = 16;
+
+/// The deadlines of an agency's pending timers.
+#[derive(Default)]
+pub(crate) struct Deadlines {
+    pending: Mutex<BTreeMap<Instant, usize>>,
+}
+
+impl Deadlines {
+    fn register(self: &Arc<Self>, deadline: Instant) -> DeadlineGuard {
+        *self.pending.lock().unwrap().entry(deadline).or_default() += 1;
+        DeadlineGuard {
+            deadlines: self.clone(),
+            deadline,
+        }
+    }
+
+    fn next(&self) -> Option<Instant> {
+        self.pending.lock().unwrap().keys().next().copied()
+    }
+}
+
+/// Removes a deadline once its timer has fired or been dropped.
+struct DeadlineGuard {
+    deadlines: Arc<Deadlines>,
+    deadline: Instant,
+}
+
+impl Drop for DeadlineGuard {
+    fn drop(&mut self) {
+        let mut pending = self.deadlines.pending.lock().unwrap();
+        if let Some(count) = pending.get_mut(&self.deadline) {
+            *count -= 1;
+            if *count == 0 {
+                pending.remove(&self.deadline);
+            }
+        }
+    }
+}
+
+/// Wrap a timer so its deadline is registered for as long as it's being waited on.
+///
+/// The deadline is only registered once the timer is first polled, so timers in disabled
+/// `select!` branches don't count.
+pub(crate) async fn track<F>(deadlines: Arc<Deadlines>, deadline: Instant, timer: F) -> F::Output
+where
+    F: Future,
+{
+    let _guard = deadlines.register(deadline);
+    timer.await
+}
+
+pub mod time {
+    //! Advancing the paused clock alongside an agency's timers.
+
+    use super::SETTLE_YIELDS;
+    use crate::agency::Agency;
+    use tokio::time::Instant;
+
+    /// Yield until none of the agency's actors are making progress, without advancing time.
+    pub async fn settle(agency: &Agency) {
+        let mut progress = agency.progress();
+        loop {
+            for _ in 0..SETTLE_YIELDS {
+                tokio::task::yield_now().await;
+            }
+            let now = agency.progress();
+            if now == progress {
+                return;
+            }
+            progress = now;
+        }
+    }
+
+    /// The deadline of the agency's next pending timer.
+    pub fn next_deadline(agency: &Agency) -> Option<Instant> {
+        agency.deadlines().next()
+    }
+
+    /// Advance time to each pending timer's deadline in turn, settling in between, until there
+    /// are no timers left and the actors are idle.
+    ///
+    /// This never returns while a repeating schedule is active, use [`advance_until`] instead.
+    pub async fn advance_until_idle(agency: &Agency) {
+        loop {
+            settle(agency).await;
+            match next_deadline(agency) {
+                Some(deadline) => advance_to(deadline).await,
+                None => return,
+            }
+        }
+    }
+
+    /// Like [`advance_until_idle`], but stopping at `end`, leaving the clock there.
+    pub async fn advance_until(agency: &Agency, end: Instant) {
+        loop {
+            settle(agency).await;
+            match next_deadline(agency).filter(|deadline| *deadline <= end) {
+                Some(deadline) => advance_to(deadline).await,
+                None => break,
+            }
+        }
+        advance_to(end).await;
+        settle(agency).await;
+    }
+
+    async fn advance_to(deadline: Instant) {
+        let now = Instant::now();
+        if deadline > now {
+            tokio::time::advance(deadline - now).await;
+        } else {
+            tokio::task::yield_now().await;
+        }
+    }
+}
+
+/// Collects the messages sent to it, for checking what actors deliver and when.
+pub struct Probe<M>
+where
+    M: 'static,
+{
+    agency: Agency,
+    recipient: Recipient<M>,
+    receiver: mpsc::Receiver<M>,
+}
+
+impl<M> Probe<M>
+where
+    M: 'static + Send,
+{
+    pub fn new(agency: &Agency) -> Self {
+        let (sender, receiver) = mpsc::channel(agency.config().capacity);
+        Self {
+            agency: agency.clone(),
+            recipient: Recipient::from_channel(sender),
+            receiver,
+        }
+    }
+
+    /// A recipient that sends to this probe, to hand to the actors under test.
+    pub fn recipient(&self) -> Recipient<M> {
+        self.recipient.clone()
+    }
+
+    /// Take the oldest message delivered so far, without waiting or advancing time.
+    pub fn try_recv(&mut self) -> Option<M> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// Advance time through the agency's timers for up to `within`, returning the first message
+    /// delivered that matches `pred`, or `None` if there wasn't one. Messages that don't match
+    /// are discarded.
+    ///
+    /// See [`assert_delivered_within!`](crate::assert_delivered_within) for the asserting form.
+    pub async fn delivered_within<F>(&mut self, within: Duration, mut pred: F) -> Option<M>
+    where
+        F: FnMut(&M) -> bool,
+    {
+        let end = Instant::now() + within;
+        loop {
+            time::settle(&self.agency).await;
+            while let Some(msg) = self.try_recv() {
+                if pred(&msg) {
+                    return Some(msg);
+                }
+            }
+            if Instant::now() >= end {
+                return None;
+            }
+            let next = time::next_deadline(&self.agency)
+                .filter(|deadline| *deadline <= end)
+                .unwrap_or(end);
+            time::advance_until(&self.agency, next).await;
+        }
+    }
+}
+
+/// Assert that a [`Probe`] is sent a message matching the predicate within the given time,
+/// advancing the paused clock through the agency's timers to find out. Evaluates to the matching
+/// message.
+///
+/// ```
+/// # use agency::{assert_delivered_within, test_util::Probe};
+/// # use std::time::Duration;
+/// # struct Tick;
+/// # async fn example(mut probe: Probe<Tick>) {
+/// let tick = assert_delivered_within!(probe, Duration::from_secs(1), |msg| matches!(msg, Tick));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assert_delivered_within {
+    ($probe:expr, $within:expr, $pred:expr $(,)?) => {{
+        let within = $within;
+        match $probe.delivered_within(within, $pred).await {
+            Some(msg) => msg,
+            None => panic!(
+                "no message matching `{}` was delivered within {:?}",
+                stringify!($pred),
+                within
+            ),
+        }
+    }};
+}
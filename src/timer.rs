@@ -0,0 +1,74 @@
+use crate::{actor::Actor, context::Context};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use tokio::task::AbortHandle;
+
+type Job<A> = Box<dyn FnOnce(&mut A, &mut Context<A>) + Send>;
+
+/// A closure scheduled with [`Context::run_later`](crate::Context::run_later), waiting for the
+/// actor's dispatch loop to run it.
+pub(crate) struct Timer<A: Actor> {
+    job: Job<A>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl<A> Timer<A>
+where
+    A: Actor,
+{
+    pub(crate) fn new<F>(job: F) -> (Self, Arc<AtomicBool>)
+    where
+        F: FnOnce(&mut A, &mut Context<A>) + Send + 'static,
+    {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let timer = Self {
+            job: Box::new(job),
+            cancelled: cancelled.clone(),
+        };
+        (timer, cancelled)
+    }
+
+    pub(crate) fn run(self, actor: &mut A, ctx: &mut Context<A>) {
+        // The timer may have fired and queued the job just before being cancelled
+        if !self.cancelled.load(Ordering::Relaxed) {
+            (self.job)(actor, ctx);
+        }
+    }
+}
+
+/// Cancels a closure scheduled with [`Context::run_later`](crate::Context::run_later), a
+/// message scheduled with [`Context::notify_later`](crate::Context::notify_later) or
+/// [`Context::notify_interval`](crate::Context::notify_interval), or a task spawned with
+/// [`Context::spawn`](crate::Context::spawn).
+///
+/// Dropping the handle does not cancel it.
+#[derive(Debug, Clone)]
+pub struct TimerHandle {
+    abort: AbortHandle,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl TimerHandle {
+    pub(crate) fn new(abort: AbortHandle, cancelled: Arc<AtomicBool>) -> Self {
+        Self { abort, cancelled }
+    }
+
+    /// Stop the closure from running, if it hasn't already.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+        self.abort.abort();
+    }
+
+    /// Whether the delay is still running, so the closure hasn't been queued yet.
+    pub(crate) fn is_pending(&self) -> bool {
+        !self.abort.is_finished()
+    }
+
+    /// Whether the closure has been cancelled, either with [`TimerHandle::cancel`] or because the
+    /// actor stopped.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{actor::Actor, addr::Recipient, context::Context, request::Request};
+use async_trait::async_trait;
+
+/// Identifies a subscription to a [`Topic`], used to unsubscribe again later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+/// Subscribe a recipient to a [`Topic`].
+///
+/// Subscribing the same recipient twice returns the existing [`SubscriptionId`].
+pub struct Subscribe<E: 'static>(pub Recipient<E>);
+
+/// Remove a subscription from a [`Topic`].
+pub struct Unsubscribe(pub SubscriptionId);
+
+/// Publish an event to every subscriber of a [`Topic`].
+pub struct Publish<E>(pub E);
+
+pub enum TopicMsg<E: 'static> {
+    Subscribe(Request<Subscribe<E>, SubscriptionId>),
+    Unsubscribe(Unsubscribe),
+    Publish(Publish<E>),
+}
+
+impl<E> From<Request<Subscribe<E>, SubscriptionId>> for TopicMsg<E> {
+    fn from(request: Request<Subscribe<E>, SubscriptionId>) -> Self {
+        Self::Subscribe(request)
+    }
+}
+
+impl<E> From<Unsubscribe> for TopicMsg<E> {
+    fn from(msg: Unsubscribe) -> Self {
+        Self::Unsubscribe(msg)
+    }
+}
+
+impl<E> From<Publish<E>> for TopicMsg<E> {
+    fn from(msg: Publish<E>) -> Self {
+        Self::Publish(msg)
+    }
+}
+
+/// A pub/sub actor that fans published events out to all of its subscribers.
+///
+/// Subscribers whose recipient can no longer be sent to are evicted automatically.
+pub struct Topic<E: 'static> {
+    subscribers: Vec<(SubscriptionId, Recipient<E>)>,
+    next_id: u64,
+    sticky: bool,
+    last: Option<E>,
+}
+
+impl<E> Topic<E>
+where
+    E: 'static + Clone + Send + Sync,
+{
+    pub fn new() -> Self {
+        Self {
+            subscribers: Vec::new(),
+            next_id: 0,
+            sticky: false,
+            last: None,
+        }
+    }
+
+    /// Create a topic that remembers the last published event and replays it to new subscribers.
+    pub fn sticky() -> Self {
+        Self {
+            sticky: true,
+            ..Self::new()
+        }
+    }
+
+    async fn subscribe(&mut self, recipient: Recipient<E>) -> SubscriptionId {
+        if let Some((id, _)) = self
+            .subscribers
+            .iter()
+            .find(|(_, existing)| existing.id() == recipient.id())
+        {
+            return *id;
+        }
+
+        let id = SubscriptionId(self.next_id);
+        self.next_id += 1;
+
+        if let Some(last) = &self.last {
+            if recipient.send(last.clone()).await.is_err() {
+                return id;
+            }
+        }
+
+        self.subscribers.push((id, recipient));
+        id
+    }
+
+    fn unsubscribe(&mut self, id: SubscriptionId) {
+        self.subscribers.retain(|(existing, _)| *existing != id);
+    }
+
+    async fn publish(&mut self, event: E) {
+        let mut dead = Vec::new();
+        for (id, recipient) in &self.subscribers {
+            if recipient.send(event.clone()).await.is_err() {
+                dead.push(*id);
+            }
+        }
+        self.subscribers.retain(|(id, _)| !dead.contains(id));
+
+        if self.sticky {
+            self.last = Some(event);
+        }
+    }
+}
+
+impl<E> Default for Topic<E>
+where
+    E: 'static + Clone + Send + Sync,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl<E> Actor for Topic<E>
+where
+    E: 'static + Clone + Send + Sync,
+{
+    type Msg = TopicMsg<E>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        match ctx.message().await {
+            TopicMsg::Subscribe(request) => {
+                if let Some((Subscribe(recipient), reply_to)) = request.handle() {
+                    let id = self.subscribe(recipient).await;
+                    let _ = reply_to.send(id);
+                }
+            }
+            TopicMsg::Unsubscribe(Unsubscribe(id)) => self.unsubscribe(id),
+            TopicMsg::Publish(Publish(event)) => self.publish(event).await,
+        }
+    }
+}
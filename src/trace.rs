@@ -0,0 +1,80 @@
+use std::{
+    cell::Cell,
+    fmt::{self, Display},
+    future::Future,
+};
+use uuid::Uuid;
+
+tokio::task_local! {
+    /// The trace of the message being handled in the current task, set by the dispatch loop as
+    /// each message is received.
+    static CURRENT: Cell<Option<TraceId>>;
+}
+
+/// A correlation id, following a flow of messages from where it entered the agency through every
+/// message sent as a consequence.
+///
+/// A message sent from outside any traced flow starts a new trace. Anything sent while an actor
+/// is handling it, including requests, carries the same trace, and the actor can see it with
+/// [`Context::current_trace`](crate::Context::current_trace).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct TraceId(Uuid);
+
+impl TraceId {
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    /// The trace of the message being handled in the current task, if there is one.
+    pub fn current() -> Option<Self> {
+        CURRENT.try_with(Cell::get).ok().flatten()
+    }
+
+    /// Run a future as part of this trace, so messages it sends carry it, such as to continue a
+    /// trace that started in another process.
+    pub async fn scope<F: Future>(self, fut: F) -> F::Output {
+        CURRENT.scope(Cell::new(Some(self)), fut).await
+    }
+
+    pub fn as_uuid(&self) -> Uuid {
+        self.0
+    }
+}
+
+impl Default for TraceId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for TraceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl From<Uuid> for TraceId {
+    fn from(uuid: Uuid) -> Self {
+        Self(uuid)
+    }
+}
+
+/// Run an actor's task with somewhere to keep the trace of the message it's handling.
+pub(crate) async fn scope<F: Future>(fut: F) -> F::Output {
+    CURRENT.scope(Cell::new(None), fut).await
+}
+
+/// Make a received message's trace the current one.
+pub(crate) fn enter(trace: Option<TraceId>) {
+    let _ = CURRENT.try_with(|current| current.set(trace));
+}
+
+/// The trace for a message being sent: the current one, or a new one at the edge of the agency.
+pub(crate) fn outgoing() -> TraceId {
+    TraceId::current().unwrap_or_default()
+}
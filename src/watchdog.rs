@@ -0,0 +1,39 @@
+use crate::id::ActorId;
+use crate::{census::Census, observer::Observer};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Weak},
+    time::Duration,
+};
+use tokio::time::{interval, Instant, MissedTickBehavior};
+
+/// Periodically look for actors that have messages waiting but haven't pulled one for longer
+/// than the threshold, reporting each stall to the observer once.
+///
+/// Runs until the census is dropped along with the last clone of its agency.
+pub(crate) async fn watch(census: Weak<Census>, observer: Arc<dyn Observer>, threshold: Duration) {
+    let mut ticks = interval((threshold / 4).max(Duration::from_millis(1)));
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    // The dequeue each reported stall was measured from, so it's only reported again once the
+    // actor has made progress
+    let mut reported: HashMap<ActorId, Instant> = HashMap::new();
+
+    loop {
+        ticks.tick().await;
+        let census = match census.upgrade() {
+            Some(census) => census,
+            None => return,
+        };
+
+        let now = Instant::now();
+        let mut still_stalled = HashMap::new();
+        for stall in census.stalls(now, threshold) {
+            let already_reported = reported.get(&stall.event.actor_id) == Some(&stall.since);
+            if !already_reported {
+                observer.actor_stalled(&stall.event);
+            }
+            still_stalled.insert(stall.event.actor_id, stall.since);
+        }
+        reported = still_stalled;
+    }
+}
@@ -0,0 +1,180 @@
+use agency::{prelude::*, StreamHandle};
+use std::time::Duration;
+use tokio::{
+    sync::{mpsc, oneshot},
+    task, time,
+};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+#[derive(Debug, PartialEq)]
+enum Msg {
+    Frame(u32),
+    Direct(u32),
+    Ended(&'static str),
+}
+
+impl From<u32> for Msg {
+    fn from(n: u32) -> Self {
+        Self::Frame(n)
+    }
+}
+
+type Source = (mpsc::UnboundedReceiver<u32>, Option<&'static str>);
+
+/// Reads the streams it's given alongside its mailbox, recording everything it receives, once
+/// its gate opens.
+struct Reader {
+    sources: Vec<Source>,
+    handles: Option<oneshot::Sender<Vec<StreamHandle>>>,
+    gate: Option<oneshot::Receiver<()>>,
+    seen: mpsc::UnboundedSender<Msg>,
+}
+
+#[async_trait]
+impl Actor for Reader {
+    type Msg = Msg;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let handles = self
+            .sources
+            .drain(..)
+            .map(|(rx, end)| {
+                let stream = UnboundedReceiverStream::new(rx);
+                match end {
+                    Some(end) => ctx.add_stream_then(stream, Msg::Ended(end)),
+                    None => ctx.add_stream(stream),
+                }
+            })
+            .collect();
+        let _ = self.handles.take().unwrap().send(handles);
+        if let Some(gate) = self.gate.take() {
+            let _ = gate.await;
+        }
+        loop {
+            let msg = ctx.message().await;
+            let _ = self.seen.send(msg);
+        }
+    }
+}
+
+struct Hired {
+    addr: Addr<Reader>,
+    feeds: Vec<mpsc::UnboundedSender<u32>>,
+    handles: Vec<StreamHandle>,
+    seen: mpsc::UnboundedReceiver<Msg>,
+}
+
+async fn hire(
+    agency: &Agency,
+    ends: &[Option<&'static str>],
+    gate: Option<oneshot::Receiver<()>>,
+) -> Hired {
+    let (feeds, sources) = ends
+        .iter()
+        .map(|&end| {
+            let (tx, rx) = mpsc::unbounded_channel();
+            (tx, (rx, end))
+        })
+        .unzip();
+    let (handles_tx, handles) = oneshot::channel();
+    let (seen_tx, seen) = mpsc::unbounded_channel();
+    let addr = agency.hire(Reader {
+        sources,
+        handles: Some(handles_tx),
+        gate,
+        seen: seen_tx,
+    });
+    Hired {
+        addr,
+        feeds,
+        handles: handles.await.unwrap(),
+        seen,
+    }
+}
+
+/// Wait for the actor's mailbox to fill up to `len`.
+async fn queued(addr: &Addr<Reader>, len: usize) {
+    time::timeout(Duration::from_secs(1), async {
+        while addr.mailbox_len() < len {
+            task::yield_now().await;
+        }
+    })
+    .await
+    .expect("the item never reached the mailbox");
+}
+
+#[tokio::test]
+async fn stream_items_share_the_mailbox_with_other_messages() {
+    let (agency, handle) = Agency::new();
+    let (open, gate) = oneshot::channel();
+    let mut hired = hire(&agency, &[None], Some(gate)).await;
+
+    hired.feeds[0].send(1).unwrap();
+    queued(&hired.addr, 1).await;
+    hired.addr.send(Msg::Direct(1)).await.unwrap();
+    hired.feeds[0].send(2).unwrap();
+    queued(&hired.addr, 3).await;
+    hired.addr.send(Msg::Direct(2)).await.unwrap();
+    open.send(()).unwrap();
+
+    for expected in [Msg::Frame(1), Msg::Direct(1), Msg::Frame(2), Msg::Direct(2)] {
+        assert_eq!(hired.seen.recv().await, Some(expected));
+    }
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn the_end_message_follows_the_last_item() {
+    let (agency, handle) = Agency::new();
+    let mut hired = hire(&agency, &[Some("frames")], None).await;
+
+    hired.feeds[0].send(1).unwrap();
+    hired.feeds[0].send(2).unwrap();
+    assert!(!hired.handles[0].is_finished());
+    hired.feeds.clear();
+
+    assert!(hired.handles[0].finished().await);
+    assert!(hired.handles[0].is_finished());
+    for expected in [Msg::Frame(1), Msg::Frame(2), Msg::Ended("frames")] {
+        assert_eq!(hired.seen.recv().await, Some(expected));
+    }
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn each_stream_ends_on_its_own() {
+    let (agency, handle) = Agency::new();
+    let mut hired = hire(&agency, &[Some("first"), Some("second")], None).await;
+
+    hired.feeds.remove(0);
+    assert!(hired.handles[0].finished().await);
+    assert_eq!(hired.seen.recv().await, Some(Msg::Ended("first")));
+    hired.feeds[0].send(2).unwrap();
+    assert_eq!(hired.seen.recv().await, Some(Msg::Frame(2)));
+    assert!(!hired.handles[1].is_finished());
+
+    hired.feeds.clear();
+    assert!(hired.handles[1].finished().await);
+    assert_eq!(hired.seen.recv().await, Some(Msg::Ended("second")));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn streams_stop_being_read_once_the_actor_stops() {
+    let (agency, handle) = Agency::new();
+    let hired = hire(&agency, &[Some("frames")], None).await;
+
+    hired.addr.stop();
+    hired.addr.watch().await;
+    assert!(!hired.handles[0].finished().await);
+    assert!(hired.feeds[0].is_closed());
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
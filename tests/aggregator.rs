@@ -0,0 +1,112 @@
+use agency::{prelude::*, Aggregator, BatchInfo, Flush, GetBatch, Item};
+use std::time::Duration;
+use tokio::{
+    sync::mpsc,
+    time::{self, Instant},
+};
+
+/// Reports each batch it gets along with when it got it.
+struct Downstream(mpsc::UnboundedSender<(Vec<u32>, Instant)>);
+
+#[async_trait]
+impl Actor for Downstream {
+    type Msg = Vec<u32>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let batch = ctx.message().await;
+        let _ = self.0.send((batch, Instant::now()));
+    }
+}
+
+type Batches = mpsc::UnboundedReceiver<(Vec<u32>, Instant)>;
+
+fn aggregator(
+    agency: &Agency,
+    max_items: usize,
+    window: Duration,
+) -> (Addr<Aggregator<u32, Vec<u32>>>, Batches) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let downstream = agency.hire(Downstream(tx)).recipient();
+    let aggregator = Aggregator::new(downstream, Vec::new, |batch: &mut Vec<u32>, n| {
+        batch.push(n)
+    })
+    .max_items(max_items)
+    .window(window);
+    (agency.hire(aggregator), rx)
+}
+
+#[tokio::test(start_paused = true)]
+async fn emits_once_the_count_threshold_is_reached() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut batches) = aggregator(&agency, 3, Duration::from_secs(60));
+    let start = Instant::now();
+
+    for n in 1..=7 {
+        addr.send(Item(n)).await.unwrap();
+    }
+    assert_eq!(batches.recv().await, Some((vec![1, 2, 3], start)));
+    assert_eq!(batches.recv().await, Some((vec![4, 5, 6], start)));
+    let info: BatchInfo = addr.request(GetBatch).await.unwrap();
+    assert_eq!(info.items, 1);
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn emits_once_the_window_has_passed() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut batches) = aggregator(&agency, 100, Duration::from_secs(1));
+    let start = Instant::now();
+
+    addr.send(Item(1)).await.unwrap();
+    addr.send(Item(2)).await.unwrap();
+    let info: BatchInfo = addr.request(GetBatch).await.unwrap();
+    assert_eq!(info.deadline, Some(start + Duration::from_secs(1)));
+
+    assert_eq!(
+        batches.recv().await,
+        Some((vec![1, 2], start + Duration::from_secs(1)))
+    );
+    let info: BatchInfo = addr.request(GetBatch).await.unwrap();
+    assert_eq!(info.items, 0);
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn items_queued_past_the_window_start_a_new_batch() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut batches) = aggregator(&agency, 100, Duration::from_secs(1));
+
+    addr.send(Item(1)).await.unwrap();
+    let _: BatchInfo = addr.request(GetBatch).await.unwrap();
+
+    // Queue items up and move past the deadline before the aggregator gets to them
+    for n in 2..=5 {
+        addr.send(Item(n)).await.unwrap();
+    }
+    time::advance(Duration::from_secs(2)).await;
+
+    assert_eq!(batches.recv().await.map(|(batch, _)| batch), Some(vec![1]));
+    assert_eq!(
+        batches.recv().await.map(|(batch, _)| batch),
+        Some(vec![2, 3, 4, 5])
+    );
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn flush_emits_straight_away() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut batches) = aggregator(&agency, 100, Duration::from_secs(60));
+    let start = Instant::now();
+
+    assert!(!addr.request(Flush).await.unwrap());
+    addr.send(Item(1)).await.unwrap();
+    assert!(addr.request(Flush).await.unwrap());
+    assert_eq!(batches.recv().await, Some((vec![1], start)));
+    assert!(!addr.request(Flush).await.unwrap());
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,84 @@
+use agency::{prelude::*, AskError};
+use std::collections::HashMap;
+
+struct GetUser(u32);
+struct Rename(u32, String);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DbError {
+    NotFound(u32),
+}
+
+/// A lower-level error that converts into the one callers see.
+struct Missing(u32);
+
+impl From<Missing> for DbError {
+    fn from(Missing(id): Missing) -> Self {
+        Self::NotFound(id)
+    }
+}
+
+enum UserMsg {
+    Get(Ask<GetUser, String, DbError>),
+    Rename(Ask<Rename, (), DbError>),
+}
+
+impl From<Ask<GetUser, String, DbError>> for UserMsg {
+    fn from(request: Ask<GetUser, String, DbError>) -> Self {
+        Self::Get(request)
+    }
+}
+
+impl From<Ask<Rename, (), DbError>> for UserMsg {
+    fn from(request: Ask<Rename, (), DbError>) -> Self {
+        Self::Rename(request)
+    }
+}
+
+struct Users(HashMap<u32, String>);
+
+impl Users {
+    fn lookup(&mut self, id: u32) -> Result<&mut String, Missing> {
+        self.0.get_mut(&id).ok_or(Missing(id))
+    }
+}
+
+#[async_trait]
+impl Actor for Users {
+    type Msg = UserMsg;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        match ctx.message().await {
+            UserMsg::Get(request) => {
+                let GetUser(id) = *request.payload();
+                let _ = request.respond_result(self.lookup(id).map(|name| name.clone()));
+            }
+            UserMsg::Rename(request) => {
+                if let Some((Rename(id, new_name), reply_to)) = request.handle() {
+                    let res = self.lookup(id).map(|name| *name = new_name);
+                    let _ = reply_to.send(res.map_err(Into::into));
+                }
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn ask_flattens_the_actors_errors_without_annotations() {
+    let (agency, handle) = Agency::new();
+    let users = agency.hire(Users(HashMap::from([(1, "ada".to_string())])));
+
+    assert_eq!(users.ask(GetUser(1)).await, Ok("ada".to_string()));
+    assert_eq!(
+        users.ask(GetUser(2)).await,
+        Err(AskError::Failed(DbError::NotFound(2)))
+    );
+    users.ask(Rename(1, "grace".into())).await.unwrap();
+    assert_eq!(users.ask(GetUser(1)).await.unwrap(), "grace");
+
+    users.stop();
+    users.watch().await;
+    assert_eq!(users.ask(GetUser(1)).await, Err(AskError::ActorStopped));
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,103 @@
+use agency::prelude::*;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+#[derive(Debug, PartialEq, Eq)]
+enum Msg {
+    Data(u32),
+    Control(u32),
+}
+
+/// Holds off until its gate opens, then reports each message in the order it's handled.
+struct Mixed {
+    gate: Option<oneshot::Receiver<()>>,
+    seen: mpsc::UnboundedSender<Msg>,
+}
+
+#[async_trait]
+impl Actor for Mixed {
+    type Msg = Msg;
+
+    fn is_priority(msg: &Msg) -> bool {
+        matches!(msg, Msg::Control(_))
+    }
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        if let Some(gate) = self.gate.take() {
+            let _ = gate.await;
+        }
+        let _ = self.seen.send(ctx.message().await);
+    }
+}
+
+fn hire(
+    agency: &Agency,
+) -> (
+    Addr<Mixed>,
+    oneshot::Sender<()>,
+    mpsc::UnboundedReceiver<Msg>,
+) {
+    let (open, gate) = oneshot::channel();
+    let (seen, rx) = mpsc::unbounded_channel();
+    let addr = agency.hire(Mixed {
+        gate: Some(gate),
+        seen,
+    });
+    (addr, open, rx)
+}
+
+#[tokio::test]
+async fn control_messages_sent_normally_are_handled_first() {
+    let (agency, handle) = Agency::new();
+    let (addr, open, mut seen) = hire(&agency);
+    let recipient: Recipient<Msg> = addr.clone().recipient();
+
+    addr.send(Msg::Data(1)).await.unwrap();
+    addr.send(Msg::Control(1)).await.unwrap();
+    addr.try_send(Msg::Data(2)).unwrap();
+    addr.try_send(Msg::Control(2)).unwrap();
+    recipient.send(Msg::Data(3)).await.unwrap();
+    recipient.send(Msg::Control(3)).await.unwrap();
+    addr.do_send(Msg::Control(4));
+    open.send(()).unwrap();
+
+    let mut order = Vec::new();
+    for _ in 0..7 {
+        order.push(seen.recv().await.unwrap());
+    }
+    assert_eq!(
+        order,
+        vec![
+            Msg::Control(1),
+            Msg::Control(2),
+            Msg::Control(3),
+            Msg::Control(4),
+            Msg::Data(1),
+            Msg::Data(2),
+            Msg::Data(3),
+        ]
+    );
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn control_messages_skip_the_regular_mailboxs_backpressure() {
+    let (agency, handle) = Agency::builder().capacity(1).build();
+    let (addr, open, mut seen) = hire(&agency);
+
+    addr.send(Msg::Data(1)).await.unwrap();
+    assert!(addr.try_send(Msg::Data(2)).is_err());
+    tokio::time::timeout(Duration::from_secs(1), addr.send(Msg::Control(1)))
+        .await
+        .expect("control message waited for room")
+        .unwrap();
+    open.send(()).unwrap();
+
+    assert_eq!(seen.recv().await, Some(Msg::Control(1)));
+    assert_eq!(seen.recv().await, Some(Msg::Data(1)));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
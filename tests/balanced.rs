@@ -0,0 +1,144 @@
+use agency::{prelude::*, DeliveryError};
+use tokio::sync::mpsc;
+
+/// Which member handled each job.
+type Done = mpsc::UnboundedSender<(usize, u32)>;
+
+/// Takes jobs and nothing else.
+struct Worker(usize, Done);
+
+#[async_trait]
+impl Actor for Worker {
+    type Msg = u32;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let job = ctx.message().await;
+        let _ = self.1.send((self.0, job));
+    }
+}
+
+enum Msg {
+    Job(u32),
+    #[allow(dead_code)]
+    Audit,
+}
+
+impl From<u32> for Msg {
+    fn from(job: u32) -> Self {
+        Self::Job(job)
+    }
+}
+
+/// Takes jobs among other things.
+struct Auditor(usize, Done);
+
+#[async_trait]
+impl Actor for Auditor {
+    type Msg = Msg;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        if let Msg::Job(job) = ctx.message().await {
+            let _ = self.1.send((self.0, job));
+        }
+    }
+}
+
+/// Alternating workers and auditors, balanced into one recipient.
+fn members(agency: &Agency, n: usize, done: &Done) -> Vec<Recipient<u32>> {
+    (0..n)
+        .map(|i| {
+            if i % 2 == 0 {
+                agency.hire(Worker(i, done.clone())).recipient()
+            } else {
+                agency.hire(Auditor(i, done.clone())).recipient()
+            }
+        })
+        .collect()
+}
+
+async fn collect(done: &mut mpsc::UnboundedReceiver<(usize, u32)>, n: usize) -> Vec<(usize, u32)> {
+    let mut got = Vec::with_capacity(n);
+    for _ in 0..n {
+        got.push(done.recv().await.unwrap());
+    }
+    got
+}
+
+#[tokio::test]
+async fn each_message_goes_to_exactly_one_member() {
+    let (agency, handle) = Agency::new();
+    let (tx, mut done) = mpsc::unbounded_channel();
+    let balanced = Recipient::balanced(members(&agency, 2, &tx));
+
+    for job in 0..100u32 {
+        balanced.send(job).await.ok().unwrap();
+    }
+    let mut jobs: Vec<_> = collect(&mut done, 100)
+        .await
+        .into_iter()
+        .map(|(_, job)| job)
+        .collect();
+    jobs.sort_unstable();
+    assert_eq!(jobs, (0..100).collect::<Vec<_>>());
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+    assert!(done.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn stopped_members_are_skipped() {
+    let (agency, handle) = Agency::new();
+    let (tx, mut done) = mpsc::unbounded_channel();
+    let worker = agency.hire(Worker(0, tx.clone()));
+    let auditor = agency.hire(Auditor(1, tx));
+    let balanced = Recipient::balanced([worker.clone().recipient(), auditor.clone().recipient()]);
+
+    worker.stop();
+    worker.watch().await;
+    for job in 0..10u32 {
+        balanced.send(job).await.ok().unwrap();
+        balanced.try_send(job).ok().unwrap();
+        balanced.send_priority(job).ok().unwrap();
+    }
+    let got = collect(&mut done, 30).await;
+    assert!(got.iter().all(|(by, _)| *by == 1));
+
+    auditor.stop();
+    auditor.watch().await;
+    assert!(matches!(
+        balanced.send(1u32).await,
+        Err(DeliveryError::Closed(1))
+    ));
+    assert!(matches!(
+        balanced.try_send(2u32),
+        Err(DeliveryError::Closed(2))
+    ));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn work_is_spread_roughly_evenly() {
+    let (agency, handle) = Agency::new();
+    let (tx, mut done) = mpsc::unbounded_channel();
+    let balanced = Recipient::balanced(members(&agency, 4, &tx));
+
+    for job in 0..1000u32 {
+        balanced.send(job).await.ok().unwrap();
+    }
+    let mut per_member = [0; 4];
+    for (by, _) in collect(&mut done, 1000).await {
+        per_member[by] += 1;
+    }
+    // An even split would be 250 each
+    assert!(
+        per_member.iter().all(|jobs| (150..=350).contains(jobs)),
+        "{:?}",
+        per_member
+    );
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
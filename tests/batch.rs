@@ -0,0 +1,154 @@
+use agency::prelude::*;
+use tokio::sync::{mpsc, oneshot};
+
+/// Takes its messages a batch at a time, once its gate has opened, reporting each batch.
+struct Logger {
+    gate: Option<oneshot::Receiver<()>>,
+    max: usize,
+    batches: mpsc::UnboundedSender<Vec<u32>>,
+}
+
+#[async_trait]
+impl Actor for Logger {
+    type Msg = u32;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        if let Some(gate) = self.gate.take() {
+            let _ = gate.await;
+        }
+        let batch = ctx.messages(self.max).await;
+        let _ = self.batches.send(batch);
+    }
+}
+
+fn logger(
+    agency: &Agency,
+    max: usize,
+) -> (
+    Addr<Logger>,
+    oneshot::Sender<()>,
+    mpsc::UnboundedReceiver<Vec<u32>>,
+) {
+    let (open, gate) = oneshot::channel();
+    let (batches, rx) = mpsc::unbounded_channel();
+    let addr = agency
+        .hire_builder(Logger {
+            gate: Some(gate),
+            max,
+            batches,
+        })
+        .capacity(128)
+        .hire();
+    (addr, open, rx)
+}
+
+#[tokio::test]
+async fn a_burst_is_taken_in_a_few_batches() {
+    let (agency, handle) = Agency::new();
+    let (addr, open, mut batches) = logger(&agency, 32);
+
+    for n in 0..100u32 {
+        addr.send(n).await.ok().unwrap();
+    }
+    open.send(()).unwrap();
+
+    let mut got = Vec::new();
+    let mut calls = 0;
+    while got.len() < 100 {
+        got.extend(batches.recv().await.unwrap());
+        calls += 1;
+    }
+    assert_eq!(got, (0..100).collect::<Vec<_>>());
+    assert_eq!(calls, 4);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn batches_stop_at_max() {
+    let (agency, handle) = Agency::new();
+    let (addr, open, mut batches) = logger(&agency, 4);
+
+    for n in 0..10u32 {
+        addr.send(n).await.ok().unwrap();
+    }
+    open.send(()).unwrap();
+
+    assert_eq!(batches.recv().await.unwrap(), vec![0, 1, 2, 3]);
+    assert_eq!(batches.recv().await.unwrap(), vec![4, 5, 6, 7]);
+    assert_eq!(batches.recv().await.unwrap(), vec![8, 9]);
+    // Waits for more rather than handing back an empty batch
+    addr.send(10u32).await.ok().unwrap();
+    assert_eq!(batches.recv().await.unwrap(), vec![10]);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn priority_messages_come_first_in_the_batch() {
+    let (agency, handle) = Agency::new();
+    let (addr, open, mut batches) = logger(&agency, 32);
+
+    for n in 1..=3u32 {
+        addr.send(n).await.ok().unwrap();
+    }
+    addr.send_priority(9u32).ok().unwrap();
+    addr.send_priority(10u32).ok().unwrap();
+    open.send(()).unwrap();
+
+    assert_eq!(batches.recv().await.unwrap(), vec![9, 10, 1, 2, 3]);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+/// Takes whatever's waiting without waiting for more, then waits for a single message, reporting
+/// each.
+struct Flusher {
+    gate: Option<oneshot::Receiver<()>>,
+    seen: mpsc::UnboundedSender<Vec<u32>>,
+}
+
+#[async_trait]
+impl Actor for Flusher {
+    type Msg = u32;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        if let Some(gate) = self.gate.take() {
+            let _ = gate.await;
+        }
+        let mut waiting = Vec::new();
+        while let Some(msg) = ctx.try_message() {
+            waiting.push(msg);
+        }
+        let _ = self.seen.send(waiting);
+        let msg = ctx.message().await;
+        let _ = self.seen.send(vec![msg]);
+    }
+}
+
+#[tokio::test]
+async fn try_message_takes_only_whats_waiting() {
+    let (agency, handle) = Agency::new();
+    let (open, gate) = oneshot::channel();
+    let (tx, mut seen) = mpsc::unbounded_channel();
+    let addr = agency.hire(Flusher {
+        gate: Some(gate),
+        seen: tx,
+    });
+
+    addr.send(1u32).await.ok().unwrap();
+    addr.send(2u32).await.ok().unwrap();
+    addr.send_priority(3u32).ok().unwrap();
+    open.send(()).unwrap();
+    assert_eq!(seen.recv().await.unwrap(), vec![3, 1, 2]);
+
+    addr.send(4u32).await.ok().unwrap();
+    assert_eq!(seen.recv().await.unwrap(), vec![4]);
+    assert_eq!(seen.recv().await.unwrap(), vec![]);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
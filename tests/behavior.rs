@@ -0,0 +1,171 @@
+use agency::{prelude::*, Behavior, MAX_BEHAVIORS};
+
+enum Msg {
+    Login(&'static str),
+    Logout,
+    /// Steps up to admin, on top of being logged in.
+    Sudo,
+    /// Answered with who handled it.
+    Whoami(Request<(), String>),
+    /// Answered with whether there was a behavior to undo.
+    Unbecome(Request<(), bool>),
+    /// Stacks up behaviors until it can't.
+    Pile,
+}
+
+impl From<Request<(), String>> for Msg {
+    fn from(request: Request<(), String>) -> Self {
+        Self::Whoami(request)
+    }
+}
+
+impl From<Request<(), bool>> for Msg {
+    fn from(request: Request<(), bool>) -> Self {
+        Self::Unbecome(request)
+    }
+}
+
+/// Turns anonymous visitors away until they log in, counting every message it gets whichever
+/// behavior handles it.
+#[derive(Default)]
+struct Session {
+    user: Option<&'static str>,
+    handled: usize,
+}
+
+#[async_trait]
+impl Actor for Session {
+    type Msg = Msg;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        ctx.dispatch(self).await;
+    }
+}
+
+#[async_trait]
+impl Handler for Session {
+    async fn handle(&mut self, ctx: &mut Context<Self>, msg: Msg) {
+        self.handled += 1;
+        match msg {
+            Msg::Login(user) => {
+                self.user = Some(user);
+                ctx.become_(LoggedIn);
+            }
+            Msg::Whoami(request) => {
+                let _ = request.respond(format!("anonymous after {}", self.handled));
+            }
+            Msg::Unbecome(request) => {
+                let _ = request.respond(ctx.unbecome());
+            }
+            Msg::Pile => {
+                for _ in 0..=MAX_BEHAVIORS {
+                    ctx.become_(LoggedIn);
+                }
+            }
+            Msg::Logout | Msg::Sudo => {}
+        }
+    }
+}
+
+struct LoggedIn;
+
+#[async_trait]
+impl Behavior<Session> for LoggedIn {
+    async fn handle(&mut self, session: &mut Session, ctx: &mut Context<Session>, msg: Msg) {
+        session.handled += 1;
+        match msg {
+            Msg::Logout => {
+                session.user = None;
+                assert!(ctx.unbecome());
+            }
+            Msg::Sudo => ctx.become_(Admin),
+            Msg::Whoami(request) => {
+                let _ = request.respond(format!(
+                    "{} after {}",
+                    session.user.unwrap(),
+                    session.handled
+                ));
+            }
+            Msg::Unbecome(request) => {
+                let _ = request.respond(ctx.unbecome());
+            }
+            Msg::Login(_) | Msg::Pile => {}
+        }
+    }
+}
+
+struct Admin;
+
+#[async_trait]
+impl Behavior<Session> for Admin {
+    async fn handle(&mut self, session: &mut Session, ctx: &mut Context<Session>, msg: Msg) {
+        session.handled += 1;
+        match msg {
+            Msg::Whoami(request) => {
+                let _ = request.respond(format!("root after {}", session.handled));
+            }
+            Msg::Logout => {
+                assert!(ctx.unbecome());
+            }
+            _ => {}
+        }
+    }
+}
+
+async fn whoami(addr: &Addr<Session>) -> String {
+    addr.request(()).await.unwrap()
+}
+
+#[tokio::test]
+async fn logging_in_switches_behavior_until_logging_out() {
+    let (agency, handle) = Agency::new();
+    let (addr, session) = agency.hire_joinable(Session::default());
+
+    assert_eq!(whoami(&addr).await, "anonymous after 1");
+    addr.send(Msg::Login("ferris")).await.ok().unwrap();
+    assert_eq!(whoami(&addr).await, "ferris after 3");
+    assert_eq!(whoami(&addr).await, "ferris after 4");
+    addr.send(Msg::Logout).await.ok().unwrap();
+    assert_eq!(whoami(&addr).await, "anonymous after 6");
+
+    addr.stop();
+    let session = session.join().await.ok().unwrap();
+    assert_eq!(session.user, None);
+    assert_eq!(session.handled, 6);
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn behaviors_unbecome_back_to_the_one_before() {
+    let (agency, handle) = Agency::new();
+    let addr = agency.hire(Session::default());
+
+    addr.send(Msg::Login("ferris")).await.ok().unwrap();
+    addr.send(Msg::Sudo).await.ok().unwrap();
+    assert_eq!(whoami(&addr).await, "root after 3");
+    // Back to being logged in, rather than all the way to anonymous
+    addr.send(Msg::Logout).await.ok().unwrap();
+    assert_eq!(whoami(&addr).await, "ferris after 5");
+
+    let undone: bool = addr.request(()).await.unwrap();
+    assert!(undone);
+    assert_eq!(whoami(&addr).await, "anonymous after 7");
+    let undone: bool = addr.request(()).await.unwrap();
+    assert!(!undone);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn stacking_up_too_many_behaviors_panics_the_actor() {
+    let (agency, handle) = Agency::new();
+    let addr = agency.hire(Session::default());
+
+    addr.send(Msg::Pile).await.ok().unwrap();
+    addr.watch().await;
+
+    agency.shutdown();
+    assert_eq!(handle.wait().await.len(), 1);
+}
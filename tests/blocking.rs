@@ -0,0 +1,90 @@
+use agency::prelude::*;
+use std::{
+    future, thread,
+    time::{Duration, Instant},
+};
+
+/// Sleeps synchronously over each request, as a stand in for CPU-heavy work.
+struct Compressor;
+
+#[async_trait]
+impl Actor for Compressor {
+    type Msg = Request<Duration, ()>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        if let Some((work, reply_to)) = ctx.message().await.handle() {
+            thread::sleep(work);
+            let _ = reply_to.send(());
+        }
+    }
+}
+
+/// Answers straight away.
+struct Echo;
+
+#[async_trait]
+impl Actor for Echo {
+    type Msg = Request<u32, u32>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        if let Some((n, reply_to)) = ctx.message().await.handle() {
+            let _ = reply_to.send(n);
+        }
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn blocking_actors_leave_the_runtime_free_for_everyone_else() {
+    let (agency, handle) = Agency::new();
+    let compressor = agency.hire_blocking(Compressor);
+    let echoes: Vec<_> = (0..3).map(|_| agency.hire(Echo)).collect();
+
+    let start = Instant::now();
+    let work = tokio::spawn({
+        let compressor = compressor.clone();
+        async move { compressor.request(Duration::from_millis(500)).await }
+    });
+    // Give the compressor a chance to start on its work
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    for round in 0..100 {
+        for echo in &echoes {
+            assert_eq!(echo.request(round).await.unwrap(), round);
+        }
+    }
+    assert!(start.elapsed() < Duration::from_millis(400));
+    assert!(!work.is_finished());
+
+    work.await.unwrap().unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(500));
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+/// Parks forever without ever checking for a shutdown.
+struct Stubborn;
+
+#[async_trait]
+impl Actor for Stubborn {
+    type Msg = ();
+
+    async fn run(&mut self, _ctx: &mut Context<Self>) {
+        future::pending::<()>().await;
+    }
+}
+
+#[tokio::test]
+async fn actors_that_ignore_the_shutdown_are_aborted_on_the_blocking_pool() {
+    let (agency, handle) = Agency::new();
+    let addr = agency.hire_blocking(Stubborn);
+
+    let alive = tokio::time::timeout(
+        Duration::from_secs(1),
+        handle.shutdown_timeout(Duration::from_millis(100)),
+    )
+    .await
+    .expect("the blocking pool held up the shutdown");
+    assert_eq!(alive.len(), 1);
+    assert_eq!(alive[0].id, addr.id());
+    assert!(addr.is_stopped());
+}
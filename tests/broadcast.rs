@@ -0,0 +1,115 @@
+use agency::prelude::*;
+use tokio::sync::mpsc;
+
+#[derive(Debug, Clone, PartialEq)]
+struct ConfigReloaded(u32);
+
+#[derive(Debug, Clone, PartialEq)]
+struct ShuttingDownSoon;
+
+enum Msg {
+    Reloaded(ConfigReloaded),
+    ShuttingDown(ShuttingDownSoon),
+}
+
+impl From<ConfigReloaded> for Msg {
+    fn from(msg: ConfigReloaded) -> Self {
+        Self::Reloaded(msg)
+    }
+}
+
+impl From<ShuttingDownSoon> for Msg {
+    fn from(msg: ShuttingDownSoon) -> Self {
+        Self::ShuttingDown(msg)
+    }
+}
+
+/// Reports each reload it hears about, along with its own name, optionally registering itself
+/// for reloads as it starts.
+struct Listener {
+    name: &'static str,
+    register: bool,
+    heard: mpsc::UnboundedSender<(&'static str, u32)>,
+}
+
+#[async_trait]
+impl Actor for Listener {
+    type Msg = Msg;
+
+    async fn init(&mut self, ctx: &mut Context<Self>) {
+        if self.register {
+            assert!(ctx.receive_broadcasts::<ConfigReloaded>());
+            assert!(!ctx.receive_broadcasts::<ConfigReloaded>());
+        }
+    }
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        match ctx.message().await {
+            Msg::Reloaded(ConfigReloaded(version)) => {
+                let _ = self.heard.send((self.name, version));
+            }
+            Msg::ShuttingDown(ShuttingDownSoon) => {}
+        }
+    }
+}
+
+fn listener(
+    agency: &Agency,
+    name: &'static str,
+    register: bool,
+    heard: &mpsc::UnboundedSender<(&'static str, u32)>,
+) -> Addr<Listener> {
+    agency.hire(Listener {
+        name,
+        register,
+        heard: heard.clone(),
+    })
+}
+
+#[tokio::test]
+async fn broadcasts_reach_every_running_target_and_forget_the_rest() {
+    let (agency, handle) = Agency::new();
+    let (tx, mut heard) = mpsc::unbounded_channel();
+    let a = listener(&agency, "a", false, &tx);
+    let b = listener(&agency, "b", false, &tx);
+    // Registers itself, and is kept running by holding on to its address
+    let _c = listener(&agency, "c", true, &tx);
+    assert!(agency.register_broadcast_target(a.downgrade().recipient::<ConfigReloaded>()));
+    assert!(agency.register_broadcast_target(b.downgrade().recipient::<ConfigReloaded>()));
+    // Registering again changes nothing
+    assert!(!agency.register_broadcast_target(a.downgrade().recipient::<ConfigReloaded>()));
+    while agency.broadcast_targets::<ConfigReloaded>() < 3 {
+        tokio::task::yield_now().await;
+    }
+
+    b.stop();
+    b.watch().await;
+    assert_eq!(agency.broadcast(ConfigReloaded(2)).await, 2);
+    assert_eq!(agency.broadcast_targets::<ConfigReloaded>(), 2);
+
+    let mut got = vec![heard.recv().await.unwrap(), heard.recv().await.unwrap()];
+    got.sort_unstable();
+    assert_eq!(got, vec![("a", 2), ("c", 2)]);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn each_message_type_has_its_own_targets() {
+    let (agency, handle) = Agency::new();
+    let (tx, _heard) = mpsc::unbounded_channel();
+    let a = listener(&agency, "a", false, &tx);
+    agency.register_broadcast_target(a.downgrade().recipient::<ShuttingDownSoon>());
+
+    assert_eq!(agency.broadcast(ConfigReloaded(1)).await, 0);
+    assert_eq!(agency.broadcast(ShuttingDownSoon).await, 1);
+    assert_eq!(agency.broadcast_targets::<ConfigReloaded>(), 0);
+
+    assert!(agency.unregister_broadcast_target::<ShuttingDownSoon>(a.id()));
+    assert!(!agency.unregister_broadcast_target::<ShuttingDownSoon>(a.id()));
+    assert_eq!(agency.broadcast(ShuttingDownSoon).await, 0);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,96 @@
+use agency::{prelude::*, RequestTimeoutError};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::time;
+
+type Budgets = Result<Vec<Duration>, RequestTimeoutError>;
+
+/// Records its remaining budget, then passes the request down to the next level, if any, within
+/// that budget.
+struct Level {
+    next: Option<Addr<Level>>,
+    delay: Duration,
+    handled: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl Actor for Level {
+    type Msg = Request<(), Budgets>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let request = ctx.message().await;
+        self.handled.fetch_add(1, Ordering::SeqCst);
+        let budget = request.remaining_budget().expect("sent with a timeout");
+        time::sleep(self.delay).await;
+        let res = match &self.next {
+            Some(next) => match next.request_within(&request, ()).await {
+                Ok(Ok(mut budgets)) => {
+                    budgets.insert(0, budget);
+                    Ok(budgets)
+                }
+                Ok(Err(err)) | Err(err) => Err(err),
+            },
+            None => Ok(vec![budget]),
+        };
+        let _ = request.respond(res);
+    }
+}
+
+/// Three levels deep, with the top one taking `delay` before passing the request on.
+fn hire(agency: &Agency, delay: Duration) -> (Addr<Level>, Arc<AtomicUsize>) {
+    let handled = Arc::new(AtomicUsize::new(0));
+    let mut next = None;
+    for delay in [Duration::ZERO, Duration::ZERO, delay] {
+        next = Some(agency.hire(Level {
+            next,
+            delay,
+            handled: handled.clone(),
+        }));
+    }
+    (next.unwrap(), handled)
+}
+
+#[tokio::test(start_paused = true)]
+async fn each_level_sees_less_of_the_budget() {
+    let (agency, handle) = Agency::new();
+    let (top, handled) = hire(&agency, Duration::from_millis(500));
+
+    let budgets = top
+        .request_timeout((), Duration::from_secs(2))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(budgets.len(), 3);
+    assert_eq!(budgets[0], Duration::from_secs(2));
+    assert!(budgets[1] <= Duration::from_millis(1500));
+    assert!(budgets[2] < budgets[1]);
+    assert_eq!(handled.load(Ordering::SeqCst), 3);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn an_exhausted_budget_short_circuits_nested_requests() {
+    let (agency, handle) = Agency::new();
+    // Leaves less than the margin for the response to get back
+    let (top, handled) = hire(&agency, Duration::from_millis(97));
+
+    let start = time::Instant::now();
+    let res = top
+        .request_timeout((), Duration::from_millis(100))
+        .await
+        .unwrap();
+    assert_eq!(res, Err(RequestTimeoutError::BudgetExhausted));
+    assert_eq!(start.elapsed(), Duration::from_millis(97));
+    // The levels beneath were never asked
+    assert_eq!(handled.load(Ordering::SeqCst), 1);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
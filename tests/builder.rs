@@ -0,0 +1,110 @@
+use agency::{prelude::*, DeadLetter, DeliveryError, MessageHandled, Observer};
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+
+/// Records which actor types handled messages or had them dead-lettered.
+#[derive(Clone, Default)]
+struct Events {
+    handled: Arc<Mutex<Vec<&'static str>>>,
+    dead: Arc<Mutex<Vec<&'static str>>>,
+}
+
+impl Observer for Events {
+    fn message_handled(&self, event: &MessageHandled) {
+        self.handled.lock().unwrap().push(event.actor_type);
+    }
+
+    fn dead_letter(&self, event: &DeadLetter) {
+        self.dead.lock().unwrap().push(event.message);
+    }
+}
+
+/// Doesn't touch its mailbox until released.
+struct Gated(Option<oneshot::Receiver<()>>);
+
+#[async_trait]
+impl Actor for Gated {
+    type Msg = u32;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        if let Some(gate) = self.0.take() {
+            let _ = gate.await;
+        }
+        ctx.dispatch(self).await
+    }
+}
+
+#[async_trait]
+impl Handler for Gated {
+    async fn handle(&mut self, _ctx: &mut Context<Self>, _msg: u32) {}
+}
+
+/// Answers every request straight away.
+struct Echo;
+
+#[async_trait]
+impl Actor for Echo {
+    type Msg = Request<u32, u32>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        ctx.dispatch(self).await
+    }
+}
+
+#[async_trait]
+impl Handler for Echo {
+    async fn handle(&mut self, _ctx: &mut Context<Self>, request: Request<u32, u32>) {
+        let n = *request.payload();
+        let _ = request.respond(n);
+    }
+}
+
+#[tokio::test]
+async fn hired_actors_use_the_configured_capacity() {
+    let (agency, handle) = Agency::builder().capacity(3).build();
+    let (release, gate) = oneshot::channel();
+    let addr = agency.hire(Gated(Some(gate)));
+    assert_eq!(addr.mailbox_capacity(), 3);
+
+    for n in 0..3u32 {
+        addr.try_send(n).unwrap();
+    }
+    assert!(matches!(addr.try_send(3u32), Err(DeliveryError::Full(3))));
+
+    release.send(()).unwrap();
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn the_default_capacity_is_kept_without_configuration() {
+    let (agency, handle) = Agency::builder().build();
+    let addr = agency.hire(Gated(None));
+    assert_eq!(addr.mailbox_capacity(), 16);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn hired_actors_report_to_the_configured_observer() {
+    let events = Events::default();
+    let (agency, handle) = Agency::builder().observer(events.clone()).build();
+    let addr = agency.hire(Echo);
+
+    assert_eq!(addr.request(1u32).await.unwrap(), 1);
+    assert_eq!(addr.request(2u32).await.unwrap(), 2);
+    let gated = agency.hire(Gated(None));
+    gated.stop();
+    gated.watch().await;
+    gated.do_send(3u32);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+    let handled = events.handled.lock().unwrap().clone();
+    assert_eq!(handled.len(), 2);
+    assert!(handled
+        .iter()
+        .all(|actor_type| actor_type.ends_with("Echo")));
+    assert_eq!(*events.dead.lock().unwrap(), vec!["u32"]);
+}
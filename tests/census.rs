@@ -0,0 +1,127 @@
+use agency::{prelude::*, ActorId, ActorSnapshot, AgencySnapshot};
+use tokio::sync::{mpsc, oneshot};
+
+/// Reports each message it gets, then holds on to any gate it's sent until the gate opens.
+struct Gated {
+    name: &'static str,
+    started: mpsc::UnboundedSender<()>,
+}
+
+#[async_trait]
+impl Actor for Gated {
+    type Msg = Option<oneshot::Receiver<()>>;
+
+    async fn init(&mut self, ctx: &mut Context<Self>) {
+        ctx.set_name(self.name);
+    }
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let gate = ctx.message().await;
+        let _ = self.started.send(());
+        if let Some(gate) = gate {
+            let _ = gate.await;
+        }
+    }
+}
+
+fn find(snapshot: &AgencySnapshot, id: ActorId) -> &ActorSnapshot {
+    snapshot
+        .actors
+        .iter()
+        .find(|actor| actor.id == id)
+        .expect("actor should be in the snapshot")
+}
+
+#[tokio::test]
+async fn dumps_every_live_actor_as_it_is() {
+    let (agency, handle) = Agency::new();
+    let (started, mut has_started) = mpsc::unbounded_channel();
+    let idle = agency
+        .hire_builder(Gated {
+            name: "idle",
+            started: started.clone(),
+        })
+        .capacity(4)
+        .hire();
+    let busy = agency
+        .hire_builder(Gated {
+            name: "busy",
+            started: started.clone(),
+        })
+        .capacity(8)
+        .hire();
+    let gone = agency.hire(Gated {
+        name: "gone",
+        started,
+    });
+    gone.stop();
+    gone.watch().await;
+
+    let (open, gate) = oneshot::channel();
+    busy.send(Some(gate)).await.unwrap();
+    busy.send(None).await.unwrap();
+    busy.send(None).await.unwrap();
+    has_started.recv().await.unwrap();
+    // Make sure the idle actor's been set up
+    idle.send(None).await.unwrap();
+    has_started.recv().await.unwrap();
+
+    let snapshot = agency.dump();
+    assert!(snapshot.actors.iter().all(|actor| actor.id != gone.id()));
+
+    let actor = find(&snapshot, busy.id());
+    assert_eq!(actor.name.as_deref(), Some("busy"));
+    assert_eq!((actor.mailbox_depth, actor.mailbox_capacity), (2, 8));
+    assert!(actor.busy);
+    assert!(actor.last_active.is_some());
+
+    let actor = find(&snapshot, idle.id());
+    assert_eq!(actor.name.as_deref(), Some("idle"));
+    assert_eq!((actor.mailbox_depth, actor.mailbox_capacity), (0, 4));
+    assert!(!actor.busy);
+    assert_eq!(actor.restarts, 0);
+    assert!(actor.actor_type.ends_with("Gated"));
+
+    let table = snapshot.to_string();
+    assert!(table.starts_with("ID"));
+    assert!(table
+        .lines()
+        .any(|row| row.contains("busy") && row.contains("2/8")));
+    assert!(table
+        .lines()
+        .any(|row| row.contains("idle") && row.contains("0/4")));
+    assert!(!table.contains("gone"));
+
+    open.send(()).unwrap();
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn each_actor_has_its_own_task_id() {
+    let (agency, handle) = Agency::new();
+    let (started, _) = mpsc::unbounded_channel();
+    let addrs: Vec<_> = (0..3)
+        .map(|_| {
+            agency.hire(Gated {
+                name: "worker",
+                started: started.clone(),
+            })
+        })
+        .collect();
+
+    let mut ids: Vec<_> = addrs.iter().map(|addr| addr.task_id().unwrap()).collect();
+    for addr in &addrs {
+        let actor = agency
+            .actors()
+            .into_iter()
+            .find(|actor| actor.id == addr.id())
+            .unwrap();
+        assert_eq!(actor.task_id, addr.task_id());
+    }
+    ids.sort_unstable();
+    ids.dedup();
+    assert_eq!(ids.len(), 3);
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,207 @@
+#![cfg(feature = "chaos")]
+
+use agency::{prelude::*, ChaosPolicy, ChaosRates, DeadLetter, Observer};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{sync::mpsc, time::Instant};
+
+const PINGS: u32 = 20;
+
+/// What became of a ping: the pong, if one came back, and how long after the start.
+type Outcome = (u32, Option<u32>, Duration);
+
+/// Counts the dead letters for each actor type.
+#[derive(Clone, Default)]
+struct DeadLetters(Arc<Mutex<Vec<&'static str>>>);
+
+impl Observer for DeadLetters {
+    fn dead_letter(&self, event: &DeadLetter) {
+        self.0.lock().unwrap().push(event.actor_type);
+    }
+}
+
+struct Ping(u32);
+
+/// Replies to every ping with the count it was sent.
+struct Ponger;
+
+#[async_trait]
+impl Actor for Ponger {
+    type Msg = Request<Ping, u32>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        ctx.dispatch(self).await
+    }
+}
+
+#[async_trait]
+impl Handler for Ponger {
+    async fn handle(&mut self, _ctx: &mut Context<Self>, request: Self::Msg) {
+        let count = request.payload().0;
+        let _ = request.respond(count);
+    }
+}
+
+/// Pings the ponger a fixed number of times, one at a time, reporting what became of each.
+struct Pinger {
+    ponger: Addr<Ponger>,
+    count: u32,
+    start: Instant,
+    outcomes: mpsc::UnboundedSender<Outcome>,
+}
+
+#[async_trait]
+impl Actor for Pinger {
+    type Msg = ();
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        self.count += 1;
+        let pong = self.ponger.request(Ping(self.count)).await.ok();
+        let _ = self.outcomes.send((self.count, pong, self.start.elapsed()));
+        if self.count == PINGS {
+            ctx.stop();
+        }
+    }
+}
+
+fn lossy(seed: u64) -> ChaosPolicy {
+    let rates = ChaosRates::new()
+        .drops(0.2)
+        .delays(0.3, Duration::from_millis(100));
+    ChaosPolicy::new(seed, rates)
+}
+
+/// Play ping pong under the policy, returning what became of each ping, how many of the
+/// ponger's messages were dropped, and the dead letters.
+async fn ping_pong(policy: ChaosPolicy) -> (Vec<Outcome>, u64, Vec<&'static str>) {
+    let dead_letters = DeadLetters::default();
+    let (agency, handle) = Agency::builder()
+        .chaos(policy)
+        .observer(dead_letters.clone())
+        .build();
+    let ponger = agency.hire(Ponger);
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let pinger = agency.hire(Pinger {
+        ponger: ponger.clone(),
+        count: 0,
+        start: Instant::now(),
+        outcomes: tx,
+    });
+    pinger.watch().await;
+
+    let dropped = ponger.stats().chaos_dropped;
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+    let mut outcomes = Vec::new();
+    while let Some(outcome) = rx.recv().await {
+        outcomes.push(outcome);
+    }
+    let dead_letters = dead_letters.0.lock().unwrap().clone();
+    (outcomes, dropped, dead_letters)
+}
+
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn the_same_seed_gives_the_same_run() {
+    let (outcomes, dropped, dead_letters) = ping_pong(lossy(7)).await;
+    assert_eq!(outcomes.len(), PINGS as usize);
+
+    let lost = outcomes
+        .iter()
+        .filter(|(_, pong, _)| pong.is_none())
+        .count();
+    assert!(lost > 0, "nothing was dropped");
+    assert!(lost < PINGS as usize, "everything was dropped");
+    assert_eq!(dropped, lost as u64);
+    assert_eq!(dead_letters.len(), lost);
+    assert!(dead_letters
+        .iter()
+        .all(|actor_type| actor_type.ends_with("Ponger")));
+    // Everything that did get through was answered, some of it late
+    for (ping, pong, _) in &outcomes {
+        assert!(pong.is_none_or(|pong| pong == *ping));
+    }
+    assert!(outcomes.last().unwrap().2 > Duration::ZERO);
+
+    assert_eq!(
+        ping_pong(lossy(7)).await,
+        (outcomes.clone(), dropped, dead_letters)
+    );
+    assert_ne!(ping_pong(lossy(8)).await.0, outcomes);
+}
+
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn actors_can_be_left_alone() {
+    let policy = lossy(7).for_actor::<Ponger>(ChaosRates::new());
+    let (outcomes, dropped, dead_letters) = ping_pong(policy).await;
+
+    let expected: Vec<_> = (1..=PINGS)
+        .map(|ping| (ping, Some(ping), Duration::ZERO))
+        .collect();
+    assert_eq!(outcomes, expected);
+    assert_eq!(dropped, 0);
+    assert!(dead_letters.is_empty());
+}
+
+/// Reports each number it's sent, in the order it gets them.
+struct Recorder(mpsc::UnboundedSender<u32>);
+
+#[async_trait]
+impl Actor for Recorder {
+    type Msg = u32;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let _ = self.0.send(ctx.message().await);
+    }
+}
+
+async fn delivery_order(seed: u64) -> Vec<u32> {
+    let rates = ChaosRates::new().reorders(0.3, Duration::from_millis(50));
+    let (agency, handle) = Agency::builder()
+        .chaos(ChaosPolicy::new(seed, rates))
+        .build();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let addr = agency.hire(Recorder(tx));
+    for n in 0..50u32 {
+        addr.send(n).await.unwrap();
+    }
+
+    let mut order = Vec::new();
+    while order.len() < 50 {
+        order.push(rx.recv().await.unwrap());
+    }
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+    order
+}
+
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn reordered_messages_still_all_arrive_in_a_reproducible_order() {
+    let order = delivery_order(3).await;
+    assert_ne!(order, (0..50).collect::<Vec<_>>());
+    let mut sorted = order.clone();
+    sorted.sort_unstable();
+    assert_eq!(sorted, (0..50).collect::<Vec<_>>());
+
+    assert_eq!(delivery_order(3).await, order);
+}
+
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn priority_messages_are_exempt_unless_included() {
+    let ponger = |policy: ChaosPolicy| async move {
+        let (agency, handle) = Agency::builder().chaos(policy).build();
+        let addr = agency.hire(Ponger);
+        let pong = addr.request_priority(Ping(1)).await.ok();
+        agency.shutdown();
+        assert!(handle.wait().await.is_empty());
+        pong
+    };
+    let dropped = ChaosRates::new().drops(1.0);
+
+    assert_eq!(ponger(ChaosPolicy::new(1, dropped)).await, Some(1));
+    assert_eq!(
+        ponger(ChaosPolicy::new(1, dropped).include_priority()).await,
+        None
+    );
+}
@@ -0,0 +1,228 @@
+use agency::{prelude::*, Stopped};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::time;
+
+type Log = Arc<Mutex<Vec<String>>>;
+
+/// Answers with its number, taking a while to finish once stopped.
+struct Worker {
+    n: usize,
+    log: Log,
+}
+
+#[async_trait]
+impl Actor for Worker {
+    type Msg = Request<(), usize>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let _ = ctx.message().await.respond(self.n);
+    }
+
+    async fn stopping(&mut self, ctx: &mut Context<Self>) -> StoppingResult {
+        let reason = ctx.stop_reason();
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("worker {} stopping: {:?}", self.n, reason));
+        StoppingResult::Stop
+    }
+
+    async fn stopped(self, _ctx: Context<Self, Stopped>) {
+        time::sleep(Duration::from_millis(20)).await;
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("worker {} stopped", self.n));
+    }
+}
+
+#[async_trait]
+impl Setup for Worker {
+    /// Fails to set up without a number.
+    type Args = (Option<usize>, Log);
+
+    async fn setup(_ctx: &mut Context<Self>, (n, log): Self::Args) -> Option<Self> {
+        Some(Self { n: n?, log })
+    }
+}
+
+enum Msg {
+    Hire(Request<usize, Addr<Worker>>),
+    HireWith(Request<Option<usize>, Addr<Worker>>),
+}
+
+impl From<Request<usize, Addr<Worker>>> for Msg {
+    fn from(request: Request<usize, Addr<Worker>>) -> Self {
+        Self::Hire(request)
+    }
+}
+
+impl From<Request<Option<usize>, Addr<Worker>>> for Msg {
+    fn from(request: Request<Option<usize>, Addr<Worker>>) -> Self {
+        Self::HireWith(request)
+    }
+}
+
+/// Hires a worker for each request, recovering from the first few stops.
+struct Listener {
+    recoveries: usize,
+    log: Log,
+}
+
+#[async_trait]
+impl Actor for Listener {
+    type Msg = Msg;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        match ctx.message().await {
+            Msg::Hire(request) => {
+                let n = *request.payload();
+                let worker = ctx.hire_child(Worker {
+                    n,
+                    log: self.log.clone(),
+                });
+                let _ = request.respond(worker);
+            }
+            Msg::HireWith(request) => {
+                let n = *request.payload();
+                let worker = ctx.hire_child_with::<Worker>((n, self.log.clone()));
+                let _ = request.respond(worker);
+            }
+        }
+    }
+
+    async fn stopping(&mut self, _ctx: &mut Context<Self>) -> StoppingResult {
+        if self.recoveries == 0 {
+            return StoppingResult::Stop;
+        }
+        self.recoveries -= 1;
+        StoppingResult::Recover
+    }
+
+    async fn stopped(self, _ctx: Context<Self, Stopped>) {
+        self.log
+            .lock()
+            .unwrap()
+            .push("listener stopped".to_string());
+    }
+}
+
+fn listener(agency: &Agency, recoveries: usize) -> (Addr<Listener>, Log) {
+    let log = Log::default();
+    let addr = agency.hire(Listener {
+        recoveries,
+        log: log.clone(),
+    });
+    (addr, log)
+}
+
+fn entries(log: &Log) -> Vec<String> {
+    log.lock().unwrap().clone()
+}
+
+#[tokio::test]
+async fn children_finish_stopping_before_their_parent_does() {
+    let (agency, handle) = Agency::new();
+    let (listener, log) = listener(&agency, 0);
+    let first = listener.request::<_, Addr<Worker>>(1).await.unwrap();
+    let second = listener.request::<_, Addr<Worker>>(2).await.unwrap();
+    assert_eq!(first.request(()).await.unwrap(), 1);
+    assert_eq!(second.request(()).await.unwrap(), 2);
+
+    listener.stop();
+    listener.watch().await;
+    assert!(first.is_stopped());
+    assert!(second.is_stopped());
+
+    let log = entries(&log);
+    assert_eq!(log.len(), 5);
+    assert_eq!(log.last().map(String::as_str), Some("listener stopped"));
+    for n in 1..=2 {
+        assert!(log.contains(&format!("worker {} stopping: Some(Requested)", n)));
+        assert!(log.contains(&format!("worker {} stopped", n)));
+    }
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn children_that_stop_sooner_are_forgotten() {
+    let (agency, handle) = Agency::new();
+    let (listener, log) = listener(&agency, 0);
+    let worker = listener.request::<_, Addr<Worker>>(1).await.unwrap();
+    worker.stop();
+    worker.watch().await;
+
+    listener.stop();
+    time::timeout(Duration::from_secs(1), listener.watch())
+        .await
+        .expect("the listener waited on a child that had already gone");
+    assert_eq!(
+        entries(&log),
+        vec![
+            "worker 1 stopping: Some(Requested)",
+            "worker 1 stopped",
+            "listener stopped",
+        ]
+    );
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn children_outlive_a_parent_that_recovers() {
+    let (agency, handle) = Agency::new();
+    let (listener, log) = listener(&agency, 1);
+    let worker = listener.request::<_, Addr<Worker>>(1).await.unwrap();
+
+    listener.stop();
+    // Still answering once the listener has recovered and moved on to its next message
+    let other = listener.request::<_, Addr<Worker>>(2).await.unwrap();
+    assert_eq!(worker.request(()).await.unwrap(), 1);
+    assert!(entries(&log).is_empty());
+
+    listener.stop();
+    listener.watch().await;
+    assert!(worker.is_stopped());
+    assert!(other.is_stopped());
+    assert_eq!(
+        entries(&log).last().map(String::as_str),
+        Some("listener stopped")
+    );
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn children_hired_with_args_stop_with_their_parent() {
+    let (agency, handle) = Agency::new();
+    let (listener, log) = listener(&agency, 0);
+    let worker = listener.request::<_, Addr<Worker>>(Some(3)).await.unwrap();
+    assert_eq!(worker.request(()).await.unwrap(), 3);
+    // Failing to set up doesn't leave the listener waiting for it
+    let failed = listener.request::<_, Addr<Worker>>(None).await.unwrap();
+    failed.watch().await;
+
+    listener.stop();
+    time::timeout(Duration::from_secs(1), listener.watch())
+        .await
+        .expect("the listener waited on a child that never started");
+    assert!(worker.is_stopped());
+    assert_eq!(
+        entries(&log),
+        vec![
+            "worker 3 stopping: Some(Requested)",
+            "worker 3 stopped",
+            "listener stopped",
+        ]
+    );
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
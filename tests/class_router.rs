@@ -0,0 +1,106 @@
+use agency::{prelude::*, ClassRouter, DeliveryError};
+use tokio::sync::{mpsc, oneshot};
+
+/// Holds off receiving anything until its gate opens, then reports the class of each message.
+struct Target {
+    gate: Option<oneshot::Receiver<()>>,
+    seen: mpsc::UnboundedSender<usize>,
+}
+
+#[async_trait]
+impl Actor for Target {
+    type Msg = usize;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        if let Some(gate) = self.gate.take() {
+            let _ = gate.await;
+        }
+        let class = ctx.message().await;
+        let _ = self.seen.send(class);
+    }
+}
+
+#[tokio::test]
+async fn forwards_classes_in_proportion_to_their_weights() {
+    let (agency, handle) = Agency::new();
+    let (seen, mut forwarded) = mpsc::unbounded_channel();
+    let target = agency.hire(Target { gate: None, seen });
+    let router: ClassRouter<usize> = ClassRouter::<usize>::builder()
+        .class(4, 4000)
+        .class(1, 1000)
+        .spawn(&agency, target.recipient());
+
+    // Both queues fill up before the router gets to run
+    let (interactive, batch) = (router.class(0), router.class(1));
+    for _ in 0..4000 {
+        interactive.try_send(0usize).unwrap();
+    }
+    for _ in 0..1000 {
+        batch.try_send(1usize).unwrap();
+    }
+    assert_eq!((router.depth(0), router.depth(1)), (4000, 1000));
+
+    for round in 0..1000 {
+        let mut classes = Vec::new();
+        for _ in 0..5 {
+            classes.push(forwarded.recv().await.unwrap());
+        }
+        assert_eq!(classes, [0, 0, 0, 0, 1], "round {}", round);
+    }
+    // The router forwards until every way of sending into it is gone
+    drop((router, interactive, batch));
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn a_backlog_in_one_class_only_holds_up_that_class() {
+    let (agency, handle) = Agency::new();
+    let (open, gate) = oneshot::channel();
+    let (seen, mut forwarded) = mpsc::unbounded_channel();
+    let target = agency
+        .hire_builder(Target {
+            gate: Some(gate),
+            seen,
+        })
+        .capacity(1)
+        .hire();
+    let router: ClassRouter<usize> = ClassRouter::<usize>::builder()
+        .class(1, 2)
+        .class(1, 2)
+        .spawn(&agency, target.recipient());
+
+    // Keep the first class topped up until the router's stuck behind the target
+    let mut sent = 0;
+    loop {
+        match router.class(0).try_send(0usize) {
+            Ok(()) => sent += 1,
+            Err(DeliveryError::Full(_)) => break,
+            Err(err) => panic!("unexpected error: {}", err),
+        }
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+    }
+    assert_eq!(router.depth(0), 2);
+
+    router.class(1).try_send(1usize).unwrap();
+    assert_eq!(router.depth(1), 1);
+
+    open.send(()).unwrap();
+    let mut classes = Vec::new();
+    for _ in 0..sent + 1 {
+        classes.push(forwarded.recv().await.unwrap());
+    }
+    assert_eq!(classes.iter().filter(|class| **class == 0).count(), sent);
+    assert!(classes.contains(&1));
+    drop(router);
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[test]
+#[should_panic(expected = "capacity must be greater than zero")]
+fn rejects_a_zero_capacity() {
+    let _ = ClassRouter::<usize>::builder().class(1, 0);
+}
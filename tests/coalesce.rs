@@ -0,0 +1,121 @@
+use agency::{prelude::*, Coalesce, RequestError, RequestTimeoutError};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::time;
+
+/// Counts the requests it handles, taking a second over each. Holds on to the first without
+/// answering it if told to.
+struct Counting {
+    handled: Arc<AtomicUsize>,
+    ignore_first: bool,
+    ignored: Vec<Request<u32, u32>>,
+}
+
+#[async_trait]
+impl Actor for Counting {
+    type Msg = Request<u32, u32>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let request = ctx.message().await;
+        let handled = self.handled.fetch_add(1, Ordering::Relaxed);
+        if self.ignore_first && handled == 0 {
+            self.ignored.push(request);
+            return;
+        }
+        time::sleep(Duration::from_secs(1)).await;
+        if let Some((n, reply_to)) = request.handle() {
+            let _ = reply_to.send(n * 2);
+        }
+    }
+}
+
+fn counting(agency: &Agency, ignore_first: bool) -> (Coalesce<u32, u32, u32>, Arc<AtomicUsize>) {
+    let handled = Arc::new(AtomicUsize::new(0));
+    let addr = agency.hire(Counting {
+        handled: handled.clone(),
+        ignore_first,
+        ignored: Vec::new(),
+    });
+    (Coalesce::new(addr.recipient(), |n| *n), handled)
+}
+
+#[tokio::test(start_paused = true)]
+async fn concurrent_identical_requests_are_made_once() {
+    let (agency, handle) = Agency::new();
+    let (coalesce, handled) = counting(&agency, false);
+
+    let callers: Vec<_> = (0..10)
+        .map(|_| {
+            let coalesce = coalesce.clone();
+            tokio::spawn(async move { coalesce.request(21).await })
+        })
+        .collect();
+    for caller in callers {
+        assert_eq!(caller.await.unwrap(), Ok(42));
+    }
+    assert_eq!(handled.load(Ordering::Relaxed), 1);
+
+    // Once it's answered, the next request for the key is made afresh
+    assert_eq!(coalesce.request(21).await, Ok(42));
+    assert_eq!(handled.load(Ordering::Relaxed), 2);
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn followers_are_answered_when_the_first_caller_is_cancelled() {
+    let (agency, handle) = Agency::new();
+    let (coalesce, handled) = counting(&agency, false);
+
+    let first = tokio::spawn({
+        let coalesce = coalesce.clone();
+        async move { coalesce.request(5).await }
+    });
+    tokio::task::yield_now().await;
+    let followers: Vec<_> = (0..3)
+        .map(|_| {
+            let coalesce = coalesce.clone();
+            tokio::spawn(async move { coalesce.request(5).await })
+        })
+        .collect();
+    tokio::task::yield_now().await;
+    first.abort();
+
+    for follower in followers {
+        assert_eq!(follower.await.unwrap(), Ok(10));
+    }
+    assert_eq!(handled.load(Ordering::Relaxed), 1);
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_timed_out_request_releases_its_key() {
+    let (agency, handle) = Agency::new();
+    let (coalesce, handled) = counting(&agency, true);
+
+    assert_eq!(
+        coalesce.request_timeout(1, Duration::from_secs(5)).await,
+        Err(RequestTimeoutError::Timeout)
+    );
+    assert_eq!(coalesce.request(1).await, Ok(2));
+    assert_eq!(handled.load(Ordering::Relaxed), 2);
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn requests_to_a_stopped_actor_error_for_every_caller() {
+    let (agency, handle) = Agency::new();
+    let (coalesce, _) = counting(&agency, false);
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+
+    assert_eq!(coalesce.request(1).await, Err(RequestError::ActorStopped));
+    assert_eq!(coalesce.request(1).await, Err(RequestError::ActorStopped));
+}
@@ -0,0 +1,115 @@
+use agency::{prelude::*, Correlator, RequestTimeoutError};
+use std::time::Duration;
+use tokio::{sync::mpsc, time};
+
+type Response = (u32, Result<String, RequestTimeoutError>);
+
+/// Answers each lookup after as many tenths of a second as the key, without holding up the next.
+struct Backend;
+
+#[async_trait]
+impl Actor for Backend {
+    type Msg = Request<u32, String>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let request = ctx.message().await;
+        ctx.reply_spawned(request, |key| async move {
+            time::sleep(Duration::from_millis(100) * key).await;
+            format!("value {}", key)
+        });
+    }
+}
+
+enum ClientMsg {
+    Fetch(u32),
+    Cancel(u32),
+    Done(Response),
+}
+
+impl From<Response> for ClientMsg {
+    fn from(response: Response) -> Self {
+        Self::Done(response)
+    }
+}
+
+/// Pipelines lookups to the backend, passing on each response with the key it was for.
+struct Client {
+    backend: Addr<Backend>,
+    correlator: Correlator<u32, String>,
+    responses: mpsc::UnboundedSender<Response>,
+}
+
+#[async_trait]
+impl Actor for Client {
+    type Msg = ClientMsg;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        match ctx.message().await {
+            ClientMsg::Fetch(key) => {
+                let backend = self.backend.clone();
+                self.correlator
+                    .track(ctx, key, async move { backend.request(key).await });
+            }
+            ClientMsg::Cancel(key) => assert!(self.correlator.cancel(&key)),
+            ClientMsg::Done(response) => {
+                assert!(!self.correlator.is_pending(&response.0));
+                let _ = self.responses.send(response);
+            }
+        }
+    }
+}
+
+fn hire(agency: &Agency) -> (Addr<Client>, mpsc::UnboundedReceiver<Response>) {
+    let (responses, rx) = mpsc::unbounded_channel();
+    let addr = agency.hire(Client {
+        backend: agency.hire(Backend),
+        correlator: Correlator::new().timeout(Duration::from_millis(500)),
+        responses,
+    });
+    (addr, rx)
+}
+
+#[tokio::test(start_paused = true)]
+async fn responses_are_paired_with_their_keys_and_late_ones_expire() {
+    let (agency, handle) = Agency::new();
+    let (client, mut responses) = hire(&agency);
+
+    for key in [3, 1, 9] {
+        client.send(ClientMsg::Fetch(key)).await.unwrap();
+    }
+
+    assert_eq!(responses.recv().await, Some((1, Ok("value 1".to_string()))));
+    assert_eq!(responses.recv().await, Some((3, Ok("value 3".to_string()))));
+    let start = time::Instant::now();
+    assert_eq!(
+        responses.recv().await,
+        Some((9, Err(RequestTimeoutError::Timeout)))
+    );
+    // Expired at the timeout, not when the backend would have answered
+    assert!(start.elapsed() < Duration::from_millis(300));
+    time::sleep(Duration::from_secs(1)).await;
+    assert!(responses.try_recv().is_err());
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn cancelled_requests_are_never_delivered() {
+    let (agency, handle) = Agency::new();
+    let (client, mut responses) = hire(&agency);
+
+    client.send(ClientMsg::Fetch(2)).await.unwrap();
+    client.send(ClientMsg::Fetch(1)).await.unwrap();
+    client.send(ClientMsg::Cancel(2)).await.unwrap();
+
+    assert_eq!(responses.recv().await, Some((1, Ok("value 1".to_string()))));
+    time::sleep(Duration::from_secs(1)).await;
+    assert!(
+        responses.try_recv().is_err(),
+        "cancelled response delivered"
+    );
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,138 @@
+use agency::{prelude::*, AgencyLayer, BoxFuture, Dispatch, Layer, Next};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+
+type Log = Arc<Mutex<Vec<String>>>;
+
+/// Logs every message it sees, numbered by which instance of the layer saw it.
+struct Counting {
+    instance: usize,
+    log: Log,
+}
+
+#[async_trait]
+impl AgencyLayer for Counting {
+    async fn handle(&mut self, dispatch: &Dispatch, next: BoxFuture<'_, ()>) {
+        let actor_type = dispatch.actor_type.rsplit("::").next().unwrap();
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("{} {}", actor_type, self.instance));
+        next.await
+    }
+}
+
+/// Answers a request with its payload.
+struct Echo(Log);
+
+#[async_trait]
+impl Actor for Echo {
+    type Msg = Request<u32, u32>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        ctx.dispatch(self).await
+    }
+}
+
+#[async_trait]
+impl Handler for Echo {
+    async fn handle(&mut self, _ctx: &mut Context<Self>, request: Request<u32, u32>) {
+        self.0.lock().unwrap().push("echoed".to_string());
+        let n = *request.payload();
+        let _ = request.respond(n);
+    }
+}
+
+/// Answers a request with a greeting, and is hired with setup args.
+struct Greeter;
+
+#[async_trait]
+impl Setup for Greeter {
+    type Args = ();
+
+    async fn setup(_ctx: &mut Context<Self>, _args: ()) -> Option<Self> {
+        Some(Self)
+    }
+}
+
+#[async_trait]
+impl Actor for Greeter {
+    type Msg = Request<&'static str, String>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        ctx.dispatch(self).await
+    }
+}
+
+#[async_trait]
+impl Handler for Greeter {
+    async fn handle(&mut self, _ctx: &mut Context<Self>, request: Request<&'static str, String>) {
+        let greeting = format!("hello {}", request.payload());
+        let _ = request.respond(greeting);
+    }
+}
+
+/// A per-actor layer, to check it's wrapped by the default ones.
+struct Inner(Log);
+
+#[async_trait]
+impl Layer<Echo> for Inner {
+    async fn handle(
+        &mut self,
+        actor: &mut Echo,
+        ctx: &mut Context<Echo>,
+        msg: Request<u32, u32>,
+        next: Next<'_, Echo>,
+    ) {
+        self.0.lock().unwrap().push("inner".to_string());
+        next.run(actor, ctx, msg).await
+    }
+}
+
+#[tokio::test]
+async fn default_layers_see_every_actor_type() {
+    let log = Log::default();
+    let instances = Arc::new(AtomicUsize::new(0));
+    let (agency, handle) = Agency::builder()
+        .default_layer({
+            let log = log.clone();
+            let instances = instances.clone();
+            move || Counting {
+                instance: instances.fetch_add(1, Ordering::SeqCst),
+                log: log.clone(),
+            }
+        })
+        .build();
+
+    let echo = agency.hire(Echo(log.clone()));
+    assert_eq!(echo.request(1u32).await.unwrap(), 1);
+    let greeter = agency.hire_with::<Greeter>(());
+    assert_eq!(greeter.request("ada").await.unwrap(), "hello ada");
+    let layered = agency
+        .hire_builder(Echo(log.clone()))
+        .layer(Inner(log.clone()))
+        .hire();
+    assert_eq!(layered.request(2u32).await.unwrap(), 2);
+    assert_eq!(echo.request(3u32).await.unwrap(), 3);
+
+    // Each actor gets its own layer, which runs before any of the actor's own
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            "Echo 0",
+            "echoed",
+            "Greeter 1",
+            "Echo 2",
+            "inner",
+            "echoed",
+            "Echo 0",
+            "echoed",
+        ]
+    );
+    assert_eq!(instances.load(Ordering::SeqCst), 3);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,295 @@
+use agency::{
+    prelude::*, BoxFuture, DeliveryError, Envelope, Mailbox, MailboxPermit, MailboxSender,
+    RequestError,
+};
+use std::{
+    sync::Arc,
+    task::{Context as TaskContext, Poll, Waker},
+    time::Duration,
+};
+use tokio::sync::oneshot;
+
+/// Doesn't touch its mailbox until released.
+struct Gated(Option<oneshot::Receiver<()>>);
+
+#[async_trait]
+impl Actor for Gated {
+    type Msg = u32;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        if let Some(gate) = self.0.take() {
+            let _ = gate.await;
+        }
+        ctx.message().await;
+    }
+}
+
+/// Answers requests with their payload, once released.
+struct GatedEcho(Option<oneshot::Receiver<()>>);
+
+#[async_trait]
+impl Actor for GatedEcho {
+    type Msg = Request<u32, u32>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        if let Some(gate) = self.0.take() {
+            let _ = gate.await;
+        }
+        let request = ctx.message().await;
+        let n = *request.payload();
+        let _ = request.respond(n);
+    }
+}
+
+/// A mailbox that refuses everything sent to it.
+#[derive(Default)]
+struct Refusing {
+    closed: bool,
+    waker: Option<Waker>,
+}
+
+struct RefusingSender;
+
+impl Mailbox<Envelope<u32>> for Refusing {
+    fn sender(&self) -> Arc<dyn MailboxSender<Envelope<u32>>> {
+        Arc::new(RefusingSender)
+    }
+
+    fn poll_recv(&mut self, cx: &mut TaskContext<'_>) -> Poll<Option<Envelope<u32>>> {
+        if self.closed {
+            return Poll::Ready(None);
+        }
+        self.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+
+    fn try_recv(&mut self) -> Option<Envelope<u32>> {
+        None
+    }
+
+    fn close(&mut self) {
+        self.closed = true;
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn len(&self) -> usize {
+        0
+    }
+}
+
+impl MailboxSender<Envelope<u32>> for RefusingSender {
+    fn reserve(
+        self: Arc<Self>,
+    ) -> BoxFuture<'static, Result<MailboxPermit<Envelope<u32>>, DeliveryError<()>>> {
+        Box::pin(async { Err(DeliveryError::Rejected(())) })
+    }
+
+    fn try_reserve(&self) -> Result<MailboxPermit<Envelope<u32>>, DeliveryError<()>> {
+        Err(DeliveryError::Rejected(()))
+    }
+
+    fn is_closed(&self) -> bool {
+        false
+    }
+
+    fn len(&self) -> usize {
+        0
+    }
+
+    fn capacity(&self) -> usize {
+        1
+    }
+}
+
+/// Check the error is the expected variant, handing back the message it was sent.
+fn assert_variant(err: Result<(), DeliveryError<u32>>, variant: &str, msg: u32) {
+    let err = err.expect_err("the send succeeded");
+    assert_eq!(format!("{:?}", err), format!("{}(..)", variant));
+    assert_eq!(err.is_closed(), variant == "Closed");
+    assert_eq!(err.into_inner(), msg);
+}
+
+#[tokio::test]
+async fn sends_to_a_stopped_actor_are_closed() {
+    let (agency, handle) = Agency::new();
+    let addr = agency.hire(Gated(None));
+    let recipient: Recipient<u32> = addr.clone().recipient();
+    addr.stop();
+    addr.watch().await;
+
+    assert_variant(addr.send(1u32).await, "Closed", 1);
+    assert_variant(addr.try_send(2u32), "Closed", 2);
+    assert_variant(
+        addr.send_timeout(3u32, Duration::from_secs(1)).await,
+        "Closed",
+        3,
+    );
+    assert_variant(addr.send_priority(4u32), "Closed", 4);
+    assert_variant(recipient.send(5u32).await, "Closed", 5);
+    assert_variant(recipient.try_send(6u32), "Closed", 6);
+    assert_variant(
+        recipient.send_timeout(7u32, Duration::from_secs(1)).await,
+        "Closed",
+        7,
+    );
+    assert_variant(recipient.send_priority(8u32), "Closed", 8);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn sends_to_a_full_mailbox_are_full_or_time_out() {
+    let (agency, handle) = Agency::builder().capacity(1).build();
+    let (open, gate) = oneshot::channel();
+    let addr = agency.hire(Gated(Some(gate)));
+    let recipient: Recipient<u32> = addr.clone().recipient();
+    addr.send(0u32).await.unwrap();
+
+    assert_variant(addr.try_send(1u32), "Full", 1);
+    assert_variant(
+        addr.send_timeout(2u32, Duration::from_millis(10)).await,
+        "Timeout",
+        2,
+    );
+    assert_variant(recipient.try_send(3u32), "Full", 3);
+    assert_variant(
+        recipient
+            .send_timeout(4u32, Duration::from_millis(10))
+            .await,
+        "Timeout",
+        4,
+    );
+    // The priority mailbox is never full
+    addr.send_priority(5u32).unwrap();
+
+    open.send(()).unwrap();
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn recipients_waiting_for_room_are_closed_when_the_actor_stops() {
+    let (agency, handle) = Agency::builder().capacity(1).build();
+    let (open, gate) = oneshot::channel();
+    let addr = agency.hire(Gated(Some(gate)));
+    let recipient: Recipient<u32> = addr.clone().recipient();
+    addr.send(0u32).await.unwrap();
+
+    let waiting = tokio::spawn(async move { recipient.send(1u32).await });
+    tokio::task::yield_now().await;
+    assert!(!waiting.is_finished());
+    addr.stop();
+    open.send(()).unwrap();
+    assert_variant(waiting.await.unwrap(), "Closed", 1);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn recipient_requests_waiting_for_room_fail_as_stopped() {
+    let (agency, handle) = Agency::builder().capacity(1).build();
+    let (open, gate) = oneshot::channel();
+    let addr = agency.hire(GatedEcho(Some(gate)));
+    let recipient: Recipient<Request<u32, u32>> = addr.clone().recipient();
+    let (reply_to, _response) = oneshot::channel();
+    addr.send(Request::from_parts(0, reply_to))
+        .await
+        .ok()
+        .unwrap();
+
+    let waiting = tokio::spawn(async move { recipient.request(1).await });
+    tokio::task::yield_now().await;
+    assert!(!waiting.is_finished());
+    addr.stop();
+    open.send(()).unwrap();
+    assert_eq!(waiting.await.unwrap(), Err(RequestError::ActorStopped));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn sends_the_mailbox_refuses_are_rejected() {
+    let (agency, handle) = Agency::new();
+    let addr = agency
+        .hire_builder(Gated(None))
+        .mailbox(|_| Refusing::default())
+        .hire();
+    let recipient: Recipient<u32> = addr.clone().recipient();
+
+    assert_variant(addr.send(1u32).await, "Rejected", 1);
+    assert_variant(addr.try_send(2u32), "Rejected", 2);
+    assert_variant(
+        addr.send_timeout(3u32, Duration::from_secs(1)).await,
+        "Rejected",
+        3,
+    );
+    assert_variant(recipient.send(4u32).await, "Rejected", 4);
+    assert_variant(recipient.try_send(5u32), "Rejected", 5);
+    assert_variant(
+        recipient.send_timeout(6u32, Duration::from_secs(1)).await,
+        "Rejected",
+        6,
+    );
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn recipient_priority_sends_notice_the_actor_stopped_even_if_the_mailbox_doesnt() {
+    let (agency, handle) = Agency::new();
+    let addr = agency
+        .hire_builder(Gated(None))
+        .mailbox(|_| Refusing::default())
+        .hire();
+    let recipient: Recipient<u32> = addr.clone().recipient();
+    addr.stop();
+    addr.watch().await;
+
+    // The mailbox never says it's closed, so only the priority mailbox can tell
+    assert_variant(recipient.send_priority(1u32), "Closed", 1);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[test]
+fn display_strings_are_stable() {
+    let cases: [(DeliveryError<()>, &str); 5] = [
+        (DeliveryError::Closed(()), "actor stopped"),
+        (DeliveryError::Full(()), "mailbox full"),
+        (
+            DeliveryError::Timeout(()),
+            "timed out waiting for room in the mailbox",
+        ),
+        (
+            DeliveryError::Rejected(()),
+            "message rejected by the mailbox",
+        ),
+        (
+            DeliveryError::Incompatible(()),
+            "message not accepted by the actor",
+        ),
+    ];
+    for (err, display) in cases {
+        assert_eq!(err.to_string(), display);
+    }
+}
+
+#[test]
+#[allow(deprecated)]
+fn delivery_errors_convert_into_the_old_send_error() {
+    let err: agency::SendError = DeliveryError::Full(1u32).into();
+    assert_eq!(err.to_string(), "actor stopped");
+
+    fn send() -> Result<(), agency::SendError> {
+        Err(DeliveryError::Closed(1u32))?;
+        Ok(())
+    }
+    assert!(send().is_err());
+}
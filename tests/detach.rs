@@ -0,0 +1,89 @@
+use agency::{prelude::*, Observer, TaskUntracked};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+/// Counts the tasks spawned with nothing left to wait on them.
+#[derive(Clone, Default)]
+struct Untracked(Arc<AtomicUsize>);
+
+impl Observer for Untracked {
+    fn task_untracked(&self, _event: &TaskUntracked) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+impl Untracked {
+    fn count(&self) -> usize {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+struct Doubler;
+
+#[async_trait]
+impl Actor for Doubler {
+    type Msg = Request<u32, u32>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let request = ctx.message().await;
+        let doubled = request.payload() * 2;
+        let _ = request.respond(doubled);
+    }
+}
+
+/// Hires a child for each request to answer it, and spawns a task to send the answer back.
+struct Parent;
+
+#[async_trait]
+impl Actor for Parent {
+    type Msg = Request<u32, u32>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let request = ctx.message().await;
+        let child = ctx.hire_child(Doubler);
+        ctx.spawn(async move {
+            let doubled: u32 = child.request(*request.payload()).await.unwrap();
+            let _ = request.respond(doubled);
+        });
+    }
+}
+
+/// Hires both directly and through a parent, checking everything hired still answers.
+async fn keep_hiring(agency: &Agency) {
+    let parent = agency.hire(Parent);
+    for n in 0..5u32 {
+        let doubled: u32 = agency.hire(Doubler).request(n).await.unwrap();
+        assert_eq!(doubled, n * 2);
+        let doubled: u32 = parent.request(n).await.unwrap();
+        assert_eq!(doubled, n * 2);
+    }
+}
+
+#[tokio::test]
+async fn hiring_carries_on_once_the_handle_is_dropped() {
+    let untracked = Untracked::default();
+    let (agency, handle) = Agency::builder().observer(untracked.clone()).build();
+    keep_hiring(&agency).await;
+    assert_eq!(untracked.count(), 0);
+
+    drop(handle);
+    keep_hiring(&agency).await;
+    // Each hire and each of the parent's tasks was reported
+    assert!(untracked.count() >= 15, "{}", untracked.count());
+
+    agency.shutdown();
+}
+
+#[tokio::test]
+async fn hiring_carries_on_quietly_once_the_handle_is_detached() {
+    let untracked = Untracked::default();
+    let (agency, handle) = Agency::builder().observer(untracked.clone()).build();
+
+    handle.detach();
+    keep_hiring(&agency).await;
+    assert_eq!(untracked.count(), 0);
+
+    agency.shutdown();
+}
@@ -0,0 +1,104 @@
+use agency::{prelude::*, DeadLetter, Observer};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, oneshot};
+
+/// Records the actor id of every dead letter.
+#[derive(Clone, Default)]
+struct DeadLetters(Arc<Mutex<Vec<agency::ActorId>>>);
+
+impl Observer for DeadLetters {
+    fn dead_letter(&self, event: &DeadLetter) {
+        self.0.lock().unwrap().push(event.actor_id);
+    }
+}
+
+/// Holds off receiving anything until its gate opens, then either reports each message or stops.
+struct Held {
+    gate: Option<oneshot::Receiver<()>>,
+    seen: mpsc::UnboundedSender<u32>,
+    stop_when_opened: bool,
+}
+
+impl Held {
+    fn new(stop_when_opened: bool) -> (Self, oneshot::Sender<()>, mpsc::UnboundedReceiver<u32>) {
+        let (open, gate) = oneshot::channel();
+        let (seen, rx) = mpsc::unbounded_channel();
+        let held = Self {
+            gate: Some(gate),
+            seen,
+            stop_when_opened,
+        };
+        (held, open, rx)
+    }
+}
+
+#[async_trait]
+impl Actor for Held {
+    type Msg = u32;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        if let Some(gate) = self.gate.take() {
+            let _ = gate.await;
+            if self.stop_when_opened {
+                ctx.stop();
+                return;
+            }
+        }
+        let msg = ctx.message().await;
+        let _ = self.seen.send(msg);
+    }
+}
+
+#[tokio::test]
+async fn do_send_delivers_once_a_full_mailbox_has_room() {
+    let dead = DeadLetters::default();
+    let (agency, handle) = Agency::builder().capacity(1).observer(dead.clone()).build();
+    let (held, open, mut seen) = Held::new(false);
+    let addr = agency.hire(held);
+
+    addr.try_send(1u32).unwrap();
+    // Returns straight away, even though there's no room yet
+    addr.do_send(2u32);
+    assert_eq!(addr.mailbox_len(), 1);
+
+    open.send(()).unwrap();
+    assert_eq!(seen.recv().await, Some(1));
+    assert_eq!(seen.recv().await, Some(2));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+    assert!(dead.0.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn do_send_to_a_stopped_actor_is_a_dead_letter() {
+    let dead = DeadLetters::default();
+    let (agency, handle) = Agency::builder().observer(dead.clone()).build();
+    let (held, open, _seen) = Held::new(true);
+    let addr = agency.hire(held);
+
+    open.send(()).unwrap();
+    addr.watch().await;
+    addr.do_send(1u32);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+    assert_eq!(*dead.0.lock().unwrap(), vec![addr.id()]);
+}
+
+#[tokio::test]
+async fn do_send_waiting_for_room_is_a_dead_letter_if_the_actor_stops() {
+    let dead = DeadLetters::default();
+    let (agency, handle) = Agency::builder().capacity(1).observer(dead.clone()).build();
+    let (held, open, _seen) = Held::new(true);
+    let addr = agency.hire(held);
+
+    addr.try_send(1u32).unwrap();
+    addr.do_send(2u32);
+    open.send(()).unwrap();
+    addr.watch().await;
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+    assert_eq!(*dead.0.lock().unwrap(), vec![addr.id()]);
+}
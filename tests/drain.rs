@@ -0,0 +1,170 @@
+use agency::{prelude::*, Drained, RequestError, StopReason, Stopped};
+use std::time::Duration;
+use tokio::{
+    sync::{mpsc, oneshot},
+    task::{self, JoinHandle},
+    time,
+};
+
+enum Msg {
+    /// Wait to be released, then stop.
+    Hold(oneshot::Receiver<()>),
+    Ask(Request<usize, usize>),
+    Panic,
+}
+
+impl From<Request<usize, usize>> for Msg {
+    fn from(request: Request<usize, usize>) -> Self {
+        Self::Ask(request)
+    }
+}
+
+/// What an inbox found once it had stopped.
+struct Report {
+    reason: Option<StopReason>,
+    drained: Drained<Msg>,
+}
+
+/// Answers with whatever it's asked, keeping up to `max` of what's left once stopped.
+struct Inbox {
+    max: usize,
+    reports: mpsc::UnboundedSender<Report>,
+}
+
+#[async_trait]
+impl Actor for Inbox {
+    type Msg = Msg;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        match ctx.message().await {
+            Msg::Hold(release) => {
+                let _ = release.await;
+                ctx.stop();
+            }
+            Msg::Ask(request) => {
+                let n = *request.payload();
+                let _ = request.respond(n);
+            }
+            Msg::Panic => panic!("told to"),
+        }
+    }
+
+    async fn stopped(self, ctx: Context<Self, Stopped>) {
+        let reason = ctx.stop_reason();
+        let drained = ctx.drain_up_to(self.max).await;
+        let _ = self.reports.send(Report { reason, drained });
+    }
+}
+
+fn inbox(agency: &Agency, max: usize) -> (Addr<Inbox>, mpsc::UnboundedReceiver<Report>) {
+    let (reports, rx) = mpsc::unbounded_channel();
+    (agency.hire(Inbox { max, reports }), rx)
+}
+
+/// Hold the inbox, then queue up `n` requests behind it.
+async fn backlog(
+    addr: &Addr<Inbox>,
+    n: usize,
+) -> (
+    oneshot::Sender<()>,
+    Vec<JoinHandle<Result<usize, RequestError>>>,
+) {
+    let (release, held) = oneshot::channel();
+    addr.send(Msg::Hold(held)).await.unwrap();
+    let requests = (0..n)
+        .map(|i| {
+            let addr = addr.clone();
+            tokio::spawn(async move { addr.request::<_, usize>(i).await })
+        })
+        .collect();
+    while addr.mailbox_len() < n {
+        task::yield_now().await;
+    }
+    (release, requests)
+}
+
+fn payloads(messages: &[Msg]) -> Vec<usize> {
+    messages
+        .iter()
+        .map(|msg| match msg {
+            Msg::Ask(request) => *request.payload(),
+            _ => unreachable!("only requests were left"),
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn draining_keeps_up_to_the_cap_and_counts_the_rest() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut reports) = inbox(&agency, 2);
+    let (release, _requests) = backlog(&addr, 5).await;
+
+    release.send(()).unwrap();
+    let report = reports.recv().await.unwrap();
+    assert_eq!(payloads(&report.drained.messages), vec![0, 1]);
+    assert_eq!(report.drained.discarded, 3);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn discarded_requests_fail_straight_away() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut reports) = inbox(&agency, 2);
+    let (release, mut requests) = backlog(&addr, 5).await;
+
+    release.send(()).unwrap();
+    // Still holding on to the ones that were kept
+    let report = reports.recv().await.unwrap();
+    for request in requests.drain(2..) {
+        let answer = time::timeout(Duration::from_secs(1), request)
+            .await
+            .expect("a discarded request was left waiting")
+            .unwrap();
+        assert!(matches!(answer, Err(RequestError::SenderDropped)));
+    }
+    assert!(requests.iter().all(|request| !request.is_finished()));
+
+    for msg in report.drained.messages {
+        if let Msg::Ask(request) = msg {
+            let n = *request.payload();
+            let _ = request.respond(n * 10);
+        }
+    }
+    for (i, request) in requests.into_iter().enumerate() {
+        assert_eq!(request.await.unwrap().unwrap(), i * 10);
+    }
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn the_stop_reason_is_kept_once_stopped() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut reports) = inbox(&agency, usize::MAX);
+    let (release, _) = backlog(&addr, 0).await;
+
+    release.send(()).unwrap();
+    let report = reports.recv().await.unwrap();
+    assert_eq!(report.reason, Some(StopReason::SelfStopped));
+    assert!(report.drained.messages.is_empty());
+    assert_eq!(report.drained.discarded, 0);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn a_panic_is_the_stop_reason_once_stopped() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut reports) = inbox(&agency, usize::MAX);
+
+    addr.send(Msg::Panic).await.unwrap();
+    let report = reports.recv().await.unwrap();
+    assert_eq!(report.reason, Some(StopReason::Panicked));
+
+    agency.shutdown();
+    assert_eq!(handle.wait().await.len(), 1);
+}
@@ -0,0 +1,82 @@
+use agency::{prelude::*, DynRecipient, DynSendError};
+use std::{any::Any, collections::HashMap};
+use tokio::sync::mpsc;
+
+/// Passes on every message it receives, formatted so different types can share a channel.
+struct Forward<M>(mpsc::UnboundedSender<String>, std::marker::PhantomData<M>);
+
+impl<M> Forward<M> {
+    fn new(tx: mpsc::UnboundedSender<String>) -> Self {
+        Self(tx, std::marker::PhantomData)
+    }
+}
+
+#[async_trait]
+impl<M> Actor for Forward<M>
+where
+    M: 'static + Send + Sync + std::fmt::Debug,
+{
+    type Msg = M;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let msg = ctx.message().await;
+        let _ = self.0.send(format!("{:?}", msg));
+    }
+}
+
+fn routes(
+    agency: &Agency,
+    tx: &mpsc::UnboundedSender<String>,
+) -> HashMap<&'static str, DynRecipient> {
+    let numbers: Addr<Forward<u32>> = agency.hire(Forward::new(tx.clone()));
+    let names: Addr<Forward<String>> = agency.hire(Forward::new(tx.clone()));
+    let mut routes = HashMap::new();
+    routes.insert("numbers", numbers.recipient::<u32>().erase());
+    routes.insert("names", names.recipient::<String>().erase());
+    routes
+}
+
+#[tokio::test]
+async fn payloads_are_downcast_to_each_recipients_type() {
+    let (agency, handle) = Agency::new();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let routes = routes(&agency, &tx);
+    assert!(routes["numbers"].message_type().ends_with("u32"));
+    assert!(routes["names"].message_type().ends_with("String"));
+
+    routes["numbers"].send_any(Box::new(7u32)).await.unwrap();
+    assert_eq!(rx.recv().await.unwrap(), "7");
+    routes["names"]
+        .send_any(Box::new(String::from("ada")))
+        .await
+        .unwrap();
+    assert_eq!(rx.recv().await.unwrap(), "\"ada\"");
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn mismatched_payloads_are_handed_back() {
+    let (agency, handle) = Agency::new();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let routes = routes(&agency, &tx);
+
+    let err = routes["numbers"]
+        .send_any(Box::new("seven"))
+        .await
+        .unwrap_err();
+    assert_eq!(err.to_string(), "message type doesn't match the recipient");
+    let payload: Box<dyn Any + Send> = match err {
+        DynSendError::Mismatch(payload) => payload,
+        DynSendError::Stopped => panic!("expected a mismatch"),
+    };
+    assert_eq!(*payload.downcast::<&str>().unwrap(), "seven");
+
+    // Nothing was delivered, and the recipient still takes the right type afterwards
+    routes["numbers"].send_any(Box::new(8u32)).await.unwrap();
+    assert_eq!(rx.recv().await.unwrap(), "8");
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
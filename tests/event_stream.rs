@@ -0,0 +1,89 @@
+use agency::{prelude::*, EventSink, EventStream, LagPolicy};
+use futures_util::StreamExt;
+use tokio::sync::oneshot;
+
+enum Msg {
+    /// Emit an event, answering with how many subscribers got it.
+    Emit(Request<u32, usize>),
+    Subscribe(Request<LagPolicy, EventStream<u32>>),
+}
+
+impl From<Request<u32, usize>> for Msg {
+    fn from(request: Request<u32, usize>) -> Self {
+        Self::Emit(request)
+    }
+}
+
+impl From<Request<LagPolicy, EventStream<u32>>> for Msg {
+    fn from(request: Request<LagPolicy, EventStream<u32>>) -> Self {
+        Self::Subscribe(request)
+    }
+}
+
+/// Emits whatever it's asked to, handing its first stream out when it starts.
+struct Emitter {
+    sink: Option<EventSink<u32>>,
+    first: Option<oneshot::Sender<EventStream<u32>>>,
+}
+
+#[async_trait]
+impl Actor for Emitter {
+    type Msg = Msg;
+
+    async fn init(&mut self, ctx: &mut Context<Self>) {
+        let (sink, stream) = ctx.event_stream(4);
+        self.sink = Some(sink);
+        let _ = self.first.take().unwrap().send(stream);
+    }
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let sink = self.sink.as_ref().unwrap();
+        match ctx.message().await {
+            Msg::Emit(request) => {
+                let event = *request.payload();
+                let _ = request.respond(sink.send(event));
+            }
+            Msg::Subscribe(request) => {
+                let policy = *request.payload();
+                let _ = request.respond(sink.subscribe_with(policy));
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn subscribers_fall_behind_on_their_own_and_end_when_the_actor_stops() {
+    let (agency, handle) = Agency::new();
+    let (first, fast) = oneshot::channel();
+    let addr = agency.hire(Emitter {
+        sink: None,
+        first: Some(first),
+    });
+    let mut fast = fast.await.unwrap();
+    let mut dropping: EventStream<u32> = addr.request(LagPolicy::DropOldest).await.unwrap();
+    let mut disconnecting: EventStream<u32> = addr.request(LagPolicy::Disconnect).await.unwrap();
+
+    // The fast subscriber keeps up, while the slow ones don't read anything
+    for n in 1..=8u32 {
+        let subscribers: usize = addr.request(n).await.unwrap();
+        assert_eq!(subscribers, 3);
+        assert_eq!(fast.next().await, Some(n));
+    }
+
+    // Only the last buffer's worth is left for the slow subscriber that skips ahead
+    let caught_up: Vec<_> = dropping.by_ref().take(4).collect().await;
+    assert_eq!(caught_up, vec![5, 6, 7, 8]);
+    assert_eq!(disconnecting.next().await, None);
+
+    // Events sent before stopping are still delivered, then every stream ends
+    let _: usize = addr.request(9u32).await.unwrap();
+    addr.stop();
+    addr.watch().await;
+    assert_eq!(fast.next().await, Some(9));
+    assert_eq!(fast.next().await, None);
+    assert_eq!(dropping.next().await, Some(9));
+    assert_eq!(dropping.next().await, None);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
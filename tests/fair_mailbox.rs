@@ -0,0 +1,101 @@
+use agency::{prelude::*, FairMailbox};
+use std::time::Duration;
+use tokio::{
+    sync::mpsc,
+    time::{self, Instant},
+};
+
+const WORK: Duration = Duration::from_millis(1);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Producer {
+    Flood,
+    Slow,
+}
+
+/// Takes its time over each message, reporting who it was from and how long it waited.
+struct Shared(mpsc::UnboundedSender<(Producer, Duration)>);
+
+#[async_trait]
+impl Actor for Shared {
+    type Msg = (Producer, Instant);
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let (producer, sent) = ctx.message().await;
+        let _ = self.0.send((producer, sent.elapsed()));
+        time::sleep(WORK).await;
+    }
+}
+
+/// Floods the actor with `flood` messages while sending `slow` more at a leisurely pace from
+/// another task, returning how long each of the slow ones waited, and how many of the flood were
+/// still to come once the last slow one arrived.
+async fn run(mailbox: Option<usize>, flood: usize, slow: usize) -> (Vec<Duration>, usize) {
+    let (agency, handle) = Agency::builder().capacity(4).build();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let builder = agency.hire_builder(Shared(tx));
+    let addr = match mailbox {
+        Some(per_sender) => builder
+            .mailbox(move |_| FairMailbox::new(per_sender))
+            .hire(),
+        None => builder.hire(),
+    };
+
+    tokio::spawn({
+        let addr = addr.clone();
+        async move {
+            for _ in 0..flood {
+                // Cut short by the shutdown once the slow sender is done
+                if addr.send((Producer::Flood, Instant::now())).await.is_err() {
+                    return;
+                }
+            }
+        }
+    });
+    tokio::spawn({
+        let addr = addr.clone();
+        async move {
+            for _ in 0..slow {
+                time::sleep(Duration::from_millis(10)).await;
+                addr.send((Producer::Slow, Instant::now())).await.unwrap();
+            }
+        }
+    });
+
+    let mut latencies = Vec::new();
+    let mut flood_left = flood;
+    while latencies.len() < slow {
+        match rx.recv().await.unwrap() {
+            (Producer::Slow, waited) => latencies.push(waited),
+            (Producer::Flood, _) => flood_left -= 1,
+        }
+    }
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+    (latencies, flood_left)
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_flooding_sender_cant_hold_up_a_slow_one() {
+    let (latencies, flood_left) = run(Some(4), 500, 10).await;
+
+    // Never more than the message being handled and one of the flood's ahead of it
+    assert!(
+        latencies.iter().all(|waited| *waited <= WORK * 2),
+        "{:?}",
+        latencies
+    );
+    assert!(flood_left > 0, "the slow sender waited for the whole flood");
+}
+
+#[tokio::test(start_paused = true)]
+async fn the_default_mailbox_makes_the_slow_sender_queue_behind_the_flood() {
+    let (latencies, _) = run(None, 500, 10).await;
+    assert!(latencies.iter().any(|waited| *waited > WORK * 2));
+}
+
+#[test]
+#[should_panic(expected = "mailbox capacity must be at least 1")]
+fn a_per_sender_capacity_of_zero_is_refused() {
+    let _ = FairMailbox::<u32>::new(0);
+}
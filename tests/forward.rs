@@ -0,0 +1,227 @@
+use agency::{prelude::*, DeliveryError, RequestError};
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+/// Answers each request with double its payload, once its gate has opened.
+struct Backend(Option<oneshot::Receiver<()>>);
+
+#[async_trait]
+impl Actor for Backend {
+    type Msg = Request<u32, u32>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        if let Some(gate) = self.0.take() {
+            let _ = gate.await;
+        }
+        let request = ctx.message().await;
+        let doubled = request.payload() * 2;
+        let _ = request.respond(doubled);
+    }
+}
+
+enum Msg {
+    Double(Request<u32, u32>),
+    /// Answered by the facade itself.
+    Ping(Request<(), ()>),
+}
+
+impl From<Request<u32, u32>> for Msg {
+    fn from(request: Request<u32, u32>) -> Self {
+        Self::Double(request)
+    }
+}
+
+impl From<Request<(), ()>> for Msg {
+    fn from(request: Request<(), ()>) -> Self {
+        Self::Ping(request)
+    }
+}
+
+/// Passes requests on to the backend without waiting for it, answering with `fallback` if
+/// there's one and the backend has gone, or dropping the request if there isn't.
+struct Facade {
+    backend: Recipient<Request<u32, u32>>,
+    fallback: Option<u32>,
+}
+
+#[async_trait]
+impl Actor for Facade {
+    type Msg = Msg;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        match ctx.message().await {
+            Msg::Double(request) => {
+                if let Err(DeliveryError::Closed(request)) = request.forward_to(&self.backend).await
+                {
+                    if let Some(fallback) = self.fallback {
+                        let _ = request.respond(fallback);
+                    }
+                }
+            }
+            Msg::Ping(request) => {
+                let _ = request.respond(());
+            }
+        }
+    }
+}
+
+fn facade(agency: &Agency, backend: &Addr<Backend>, fallback: Option<u32>) -> Addr<Facade> {
+    agency.hire(Facade {
+        backend: backend.clone().recipient(),
+        fallback,
+    })
+}
+
+#[tokio::test]
+async fn the_requester_gets_the_backends_answer() {
+    let (agency, handle) = Agency::new();
+    let backend = agency.hire(Backend(None));
+    let facade = facade(&agency, &backend, None);
+
+    for n in 0..5 {
+        let doubled: u32 = facade.request(n).await.unwrap();
+        assert_eq!(doubled, n * 2);
+    }
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn the_facade_is_free_while_the_backend_works() {
+    let (agency, handle) = Agency::new();
+    let (open, gate) = oneshot::channel();
+    let backend = agency.hire(Backend(Some(gate)));
+    let facade = facade(&agency, &backend, None);
+
+    let pending = tokio::spawn({
+        let facade = facade.clone();
+        async move { facade.request::<_, u32>(21).await }
+    });
+    while backend.mailbox_len() == 0 {
+        tokio::task::yield_now().await;
+    }
+    // Answered by the facade while the backend still holds the forwarded request
+    facade.request(()).await.unwrap();
+    assert!(!pending.is_finished());
+
+    open.send(()).unwrap();
+    assert_eq!(pending.await.unwrap().unwrap(), 42);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn forwarding_to_a_stopped_backend_fails_the_request() {
+    let (agency, handle) = Agency::new();
+    let backend = agency.hire(Backend(None));
+    let dropping = facade(&agency, &backend, None);
+    let answering = facade(&agency, &backend, Some(0));
+    backend.stop();
+    backend.watch().await;
+
+    let res: Result<u32, _> = dropping.request(1).await;
+    assert_eq!(res, Err(RequestError::SenderDropped));
+    // The request is handed back, so it can be answered some other way
+    let res: Result<u32, _> = answering.request(1).await;
+    assert_eq!(res, Ok(0));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn requests_rebuilt_from_parts_are_answered_through_the_original_channel() {
+    let (agency, handle) = Agency::new();
+    let backend = agency.hire(Backend(None));
+    let recipient: Recipient<Request<u32, u32>> = backend.clone().recipient();
+
+    let (reply_to, response) = oneshot::channel();
+    Request::from_parts(4, reply_to)
+        .forward_to(&recipient)
+        .await
+        .ok()
+        .unwrap();
+    assert_eq!(response.await.unwrap(), 8);
+
+    let (reply_to, response) = oneshot::channel();
+    backend
+        .forward_request(Request::from_parts(5, reply_to))
+        .await
+        .ok()
+        .unwrap();
+    assert_eq!(response.await.unwrap(), 10);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn requests_nobody_is_waiting_for_are_not_forwarded() {
+    let (agency, handle) = Agency::new();
+    let (open, gate) = oneshot::channel();
+    let backend = agency.hire(Backend(Some(gate)));
+    let recipient: Recipient<Request<u32, u32>> = backend.clone().recipient();
+
+    let (reply_to, response) = oneshot::channel();
+    drop(response);
+    Request::from_parts(1, reply_to)
+        .forward_to(&recipient)
+        .await
+        .ok()
+        .unwrap();
+    let (reply_to, response) = oneshot::channel::<u32>();
+    drop(response);
+    backend
+        .forward_request(Request::from_parts(2, reply_to))
+        .await
+        .ok()
+        .unwrap();
+    assert_eq!(backend.mailbox_len(), 0);
+
+    open.send(()).unwrap();
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+/// Translates each word into its length for the backend, leaving the backend to answer.
+struct Router {
+    backend: Addr<Backend>,
+    /// Told whether the first mapped request still has its deadline.
+    deadline_kept: Option<oneshot::Sender<bool>>,
+}
+
+#[async_trait]
+impl Actor for Router {
+    type Msg = Request<&'static str, u32>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let request = ctx.message().await.map_payload(|word| word.len() as u32);
+        if let Some(deadline_kept) = self.deadline_kept.take() {
+            let _ = deadline_kept.send(request.deadline().is_some());
+        }
+        let _ = self.backend.forward_request(request).await;
+    }
+}
+
+#[tokio::test]
+async fn mapped_requests_carry_the_converted_payload() {
+    let (agency, handle) = Agency::new();
+    let backend = agency.hire(Backend(None));
+    let (deadline_kept, kept) = oneshot::channel();
+    let router = agency.hire(Router {
+        backend: backend.clone(),
+        deadline_kept: Some(deadline_kept),
+    });
+
+    let doubled: u32 = router
+        .request_timeout("four", Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert_eq!(doubled, 8);
+    assert!(kept.await.unwrap());
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,71 @@
+use agency::{prelude::*, ActorId, GetMembers, Group, Join, Leave, Publish};
+use tokio::sync::mpsc;
+
+/// Reports each event it gets.
+struct Member(mpsc::UnboundedSender<u32>);
+
+#[async_trait]
+impl Actor for Member {
+    type Msg = u32;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let event = ctx.message().await;
+        let _ = self.0.send(event);
+    }
+}
+
+fn member(agency: &Agency) -> (Recipient<u32>, mpsc::UnboundedReceiver<u32>) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    (agency.hire(Member(tx)).recipient(), rx)
+}
+
+#[tokio::test]
+async fn publishes_reach_exactly_the_members_at_the_time() {
+    let (agency, handle) = Agency::new();
+    let group = agency.hire(Group::<u32>::new());
+    let (a, mut a_rx) = member(&agency);
+    let (b, mut b_rx) = member(&agency);
+
+    group.send(Publish(0)).await.unwrap();
+    group.request(Join(a.clone())).await.unwrap();
+    group.send(Publish(1)).await.unwrap();
+    group.request(Join(b.clone())).await.unwrap();
+    group.send(Publish(2)).await.unwrap();
+    assert!(group.request(Leave(a.id())).await.unwrap());
+    group.send(Publish(3)).await.unwrap();
+    assert!(!group.request(Leave(a.id())).await.unwrap());
+    group.request(Join(a)).await.unwrap();
+    group.send(Publish(4)).await.unwrap();
+
+    // Each member handles its events in order, so anything it shouldn't have got would show up
+    // before the last event
+    for n in [1, 2, 4] {
+        assert_eq!(a_rx.recv().await, Some(n));
+    }
+    for n in [2, 3, 4] {
+        assert_eq!(b_rx.recv().await, Some(n));
+    }
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn snapshots_list_the_current_members() {
+    let (agency, handle) = Agency::new();
+    let group = agency.hire(Group::<u32>::new());
+    let (a, _a_rx) = member(&agency);
+    let (b, _b_rx) = member(&agency);
+    let (c, _c_rx) = member(&agency);
+
+    for member in [&a, &b, &c] {
+        group.request(Join(member.clone())).await.unwrap();
+    }
+    let members: Vec<ActorId> = group.request(GetMembers).await.unwrap();
+    assert_eq!(members, vec![a.id(), b.id(), c.id()]);
+
+    group.request(Leave(b.id())).await.unwrap();
+    let members: Vec<ActorId> = group.request(GetMembers).await.unwrap();
+    assert_eq!(members, vec![a.id(), c.id()]);
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,60 @@
+use agency::prelude::*;
+use std::collections::HashSet;
+
+/// Does nothing but wait for messages.
+#[derive(Default)]
+struct Idle;
+
+#[async_trait]
+impl Actor for Idle {
+    type Msg = ();
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        ctx.message().await;
+    }
+}
+
+#[tokio::test]
+async fn hire_many_hires_distinct_actors() {
+    let (agency, handle) = Agency::new();
+    let workers = agency.hire_many(4, || Idle);
+    assert_eq!(workers.len(), 4);
+
+    let ids: HashSet<_> = workers.iter().map(Addr::id).collect();
+    let tasks: HashSet<_> = workers.iter().map(|addr| addr.task_id().unwrap()).collect();
+    assert_eq!((ids.len(), tasks.len()), (4, 4));
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn hire_many_named_suffixes_each_name_with_its_index() {
+    let (agency, handle) = Agency::new();
+    let workers = agency.hire_many_named(3, "worker", Idle::default);
+
+    for (i, addr) in workers.iter().enumerate() {
+        let actor = agency
+            .actors()
+            .into_iter()
+            .find(|actor| actor.id == addr.id())
+            .unwrap();
+        assert_eq!(actor.name, Some(format!("worker-{}", i)));
+    }
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn hire_default_hires_the_default_actor() {
+    let (agency, handle) = Agency::builder().capacity(3).build();
+    let addr = agency.hire_default::<Idle>();
+    addr.send(()).await.unwrap();
+    assert_eq!(addr.stats().processed + addr.mailbox_len() as u64, 1);
+    let actor = agency
+        .actors()
+        .into_iter()
+        .find(|actor| actor.id == addr.id());
+    assert_eq!(actor.map(|actor| actor.mailbox_capacity), Some(3));
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
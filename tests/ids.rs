@@ -0,0 +1,72 @@
+use agency::{prelude::*, ActorId};
+use std::collections::HashSet;
+
+struct Idle;
+
+#[async_trait]
+impl Actor for Idle {
+    type Msg = ();
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        ctx.message().await;
+    }
+}
+
+#[tokio::test]
+async fn sequential_ids_count_up_from_zero() {
+    let (agency, handle) = Agency::builder().sequential_ids().build();
+
+    let addrs: Vec<_> = (0..3).map(|_| agency.hire(Idle)).collect();
+    let ids: Vec<_> = addrs.iter().map(|addr| addr.id().to_string()).collect();
+    assert_eq!(ids, vec!["#0", "#1", "#2"]);
+    assert!(addrs.iter().all(|addr| addr.id().as_uuid().is_none()));
+    assert!(addrs[0].id() < addrs[1].id());
+    // Shared by everything referring to the same actor
+    assert_eq!(addrs[1].clone().recipient::<()>().id(), addrs[1].id());
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn sequential_ids_stay_unique_when_hiring_concurrently() {
+    const TASKS: u64 = 8;
+    const PER_TASK: u64 = 100;
+
+    let (agency, handle) = Agency::builder().sequential_ids().build();
+    let hirers: Vec<_> = (0..TASKS)
+        .map(|_| {
+            let agency = agency.clone();
+            tokio::spawn(
+                async move { (0..PER_TASK).map(|_| agency.hire(Idle)).collect::<Vec<_>>() },
+            )
+        })
+        .collect();
+    let mut addrs = Vec::new();
+    for hirer in hirers {
+        addrs.extend(hirer.await.unwrap());
+    }
+
+    let ids: HashSet<ActorId> = addrs.iter().map(Addr::id).collect();
+    assert_eq!(ids.len() as u64, TASKS * PER_TASK);
+    // With no gaps either
+    let numbers: HashSet<_> = ids.iter().map(ToString::to_string).collect();
+    let expected: HashSet<_> = (0..TASKS * PER_TASK).map(|n| format!("#{}", n)).collect();
+    assert_eq!(numbers, expected);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn ids_are_random_uuids_by_default() {
+    let (agency, handle) = Agency::new();
+
+    let addrs: Vec<_> = (0..100).map(|_| agency.hire(Idle)).collect();
+    assert!(addrs.iter().all(|addr| addr.id().as_uuid().is_some()));
+    let ids: HashSet<_> = addrs.iter().map(Addr::id).collect();
+    assert_eq!(ids.len(), 100);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
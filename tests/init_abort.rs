@@ -0,0 +1,106 @@
+use agency::{prelude::*, DeadLetter, InitAbort, InitAborted, JoinError, Observer};
+use std::{
+    ops::ControlFlow,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+/// Records aborted starts and the dead letters they leave behind.
+#[derive(Clone, Default)]
+struct Aborts {
+    aborted: Arc<Mutex<Vec<InitAborted>>>,
+    dead_letters: Arc<AtomicUsize>,
+}
+
+impl Observer for Aborts {
+    fn init_aborted(&self, event: &InitAborted) {
+        self.aborted.lock().unwrap().push(event.clone());
+    }
+
+    fn dead_letter(&self, _event: &DeadLetter) {
+        self.dead_letters.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Refuses to start without a config, counting any lifecycle calls that get through anyway.
+struct Configured {
+    config: Option<&'static str>,
+    calls: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl Actor for Configured {
+    type Msg = u32;
+
+    async fn try_init(&mut self, _ctx: &mut Context<Self>) -> ControlFlow<InitAbort> {
+        match self.config {
+            Some(_) => ControlFlow::Continue(()),
+            None => ControlFlow::Break(InitAbort::new("no config")),
+        }
+    }
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        ctx.message().await;
+        self.calls.fetch_add(1, Ordering::SeqCst);
+    }
+
+    async fn stopping(&mut self, _ctx: &mut Context<Self>) -> StoppingResult {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        StoppingResult::Stop
+    }
+}
+
+#[tokio::test]
+async fn an_aborted_init_dead_letters_everything_queued() {
+    let aborts = Aborts::default();
+    let (agency, handle) = Agency::builder().observer(aborts.clone()).build();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let (addr, actor) = agency.hire_joinable(Configured {
+        config: None,
+        calls: calls.clone(),
+    });
+
+    // Queued before the actor gets to start
+    for n in 0..3u32 {
+        addr.try_send(n).unwrap();
+    }
+    assert_eq!(actor.join().await.err(), Some(JoinError::NeverStarted));
+
+    assert_eq!(
+        calls.load(Ordering::SeqCst),
+        0,
+        "ran or stopped after aborting"
+    );
+    assert_eq!(aborts.dead_letters.load(Ordering::SeqCst), 3);
+    let aborted = aborts.aborted.lock().unwrap().clone();
+    assert_eq!(aborted.len(), 1);
+    assert_eq!(aborted[0].actor_id, addr.id());
+    assert_eq!(aborted[0].reason, "no config");
+    assert!(addr.is_stopped());
+    assert!(addr.send(4u32).await.is_err());
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn a_continued_init_starts_as_usual() {
+    let aborts = Aborts::default();
+    let (agency, handle) = Agency::builder().observer(aborts.clone()).build();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let (addr, actor) = agency.hire_joinable(Configured {
+        config: Some("prod"),
+        calls: calls.clone(),
+    });
+
+    addr.stop();
+    assert_eq!(actor.join().await.ok().unwrap().config, Some("prod"));
+    // Went through stopping like any other actor
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert!(aborts.aborted.lock().unwrap().is_empty());
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
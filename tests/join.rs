@@ -0,0 +1,247 @@
+use agency::{prelude::*, JoinError, Setup, Stopped};
+use futures_util::future::pending;
+use tokio::sync::mpsc;
+
+/// Adds up the numbers it's sent, answering requests with the total so far.
+#[derive(Default)]
+struct Counter {
+    total: u32,
+    messages: usize,
+    keep: bool,
+}
+
+enum Msg {
+    Add(u32),
+    Total(Request<(), u32>),
+    Panic,
+}
+
+impl From<Request<(), u32>> for Msg {
+    fn from(request: Request<(), u32>) -> Self {
+        Self::Total(request)
+    }
+}
+
+#[async_trait]
+impl Actor for Counter {
+    type Msg = Msg;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        match ctx.message().await {
+            Msg::Add(n) => {
+                self.total += n;
+                self.messages += 1;
+            }
+            Msg::Total(request) => {
+                let _ = request.respond(self.total);
+            }
+            Msg::Panic => panic!("asked to"),
+        }
+    }
+
+    async fn on_stopped(self, _ctx: Context<Self, Stopped>) -> Option<Self> {
+        if self.keep {
+            None
+        } else {
+            Some(self)
+        }
+    }
+}
+
+#[tokio::test]
+async fn join_hands_back_the_final_state() {
+    let (agency, handle) = Agency::new();
+    let (addr, actor) = agency.hire_joinable(Counter::default());
+
+    for n in 1..=100 {
+        addr.send(Msg::Add(n)).await.ok().unwrap();
+    }
+    // Stopping doesn't wait for what's queued, so make sure it's all been handled first
+    assert_eq!(addr.request(()).await.unwrap(), 5050);
+    addr.stop();
+    let counter = actor.join().await.ok().unwrap();
+    assert_eq!(counter.total, 5050);
+    assert_eq!(counter.messages, 100);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn one_actor_can_be_waited_for_while_the_rest_carry_on() {
+    let (agency, handle) = Agency::new();
+    let (first, first_handle) = agency.hire_joinable(Counter::default());
+    let (second, second_handle) = agency.hire_joinable(Counter::default());
+    assert!(!first_handle.is_finished());
+
+    first.stop();
+    first.watch().await;
+    assert!(first_handle.is_finished());
+    assert!(!second_handle.is_finished());
+    first_handle.join().await.ok().unwrap();
+
+    second.send(Msg::Add(3)).await.ok().unwrap();
+    assert_eq!(second.request(()).await.unwrap(), 3);
+    assert!(!second_handle.is_finished());
+
+    agency.shutdown();
+    assert_eq!(second_handle.join().await.ok().unwrap().total, 3);
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn shutting_down_hands_actors_back_too() {
+    let (agency, handle) = Agency::new();
+    let (addr, actor) = agency.hire_joinable(Counter::default());
+    addr.send(Msg::Add(7)).await.ok().unwrap();
+    assert_eq!(addr.request(()).await.unwrap(), 7);
+
+    agency.shutdown();
+    assert_eq!(actor.join().await.ok().unwrap().total, 7);
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn actors_on_stopped_keeps_arent_handed_back() {
+    let (agency, handle) = Agency::new();
+    let (addr, actor) = agency.hire_joinable(Counter {
+        keep: true,
+        ..Counter::default()
+    });
+
+    addr.stop();
+    assert_eq!(actor.join().await.err(), Some(JoinError::Kept));
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn actors_that_panic_arent_handed_back() {
+    let (agency, handle) = Agency::new();
+    let (addr, actor) = agency.hire_joinable(Counter::default());
+
+    addr.send(Msg::Panic).await.ok().unwrap();
+    assert_eq!(actor.join().await.err(), Some(JoinError::Panicked));
+    agency.shutdown();
+    assert_eq!(handle.wait().await.len(), 1);
+}
+
+/// Starts from a total, or not at all without one.
+#[async_trait]
+impl Setup for Counter {
+    type Args = Option<u32>;
+
+    async fn setup(_ctx: &mut Context<Self>, total: Option<u32>) -> Option<Self> {
+        Some(Self {
+            total: total?,
+            ..Self::default()
+        })
+    }
+}
+
+#[tokio::test]
+async fn actors_hired_with_args_are_handed_back() {
+    let (agency, handle) = Agency::new();
+    let (addr, actor) = agency.hire_with_joinable::<Counter>(Some(10));
+
+    addr.send(Msg::Add(5)).await.ok().unwrap();
+    assert_eq!(addr.request(()).await.unwrap(), 15);
+    addr.stop();
+    assert_eq!(actor.join().await.ok().unwrap().total, 15);
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn actors_whose_setup_fails_never_started() {
+    let (agency, handle) = Agency::new();
+    let (addr, actor) = agency.hire_with_joinable::<Counter>(None);
+
+    addr.watch().await;
+    assert!(actor.is_finished());
+    assert_eq!(actor.join().await.err(), Some(JoinError::NeverStarted));
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+/// Reports when its `stopped` hook runs, written as it would have been before actors could be
+/// handed back.
+struct Reporter(mpsc::UnboundedSender<&'static str>);
+
+#[async_trait]
+impl Actor for Reporter {
+    type Msg = ();
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        ctx.message().await;
+    }
+
+    async fn stopped(self, _ctx: Context<Self, Stopped>) {
+        let _ = self.0.send("stopped");
+    }
+}
+
+#[tokio::test]
+async fn stopped_still_runs_for_actors_without_a_handle() {
+    let (agency, handle) = Agency::new();
+    let (tx, mut reports) = mpsc::unbounded_channel();
+    let addr = agency.hire(Reporter(tx));
+
+    addr.stop();
+    assert_eq!(reports.recv().await, Some("stopped"));
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn actors_with_a_handle_are_handed_back_instead() {
+    let (agency, handle) = Agency::new();
+    let (tx, mut reports) = mpsc::unbounded_channel();
+    let (addr, actor) = agency.hire_joinable(Reporter(tx));
+
+    addr.stop();
+    let reporter = actor.join().await.ok().unwrap();
+    assert!(reports.try_recv().is_err());
+    drop(reporter);
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+/// Never finishes starting, so it can only be aborted.
+struct Stuck;
+
+#[async_trait]
+impl Actor for Stuck {
+    type Msg = ();
+
+    async fn init(&mut self, _ctx: &mut Context<Self>) {
+        pending::<()>().await;
+    }
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        ctx.message().await;
+    }
+}
+
+#[tokio::test]
+async fn aborted_actors_arent_handed_back() {
+    let (agency, handle) = Agency::new();
+    let (_addr, actor) = agency.hire_joinable(Stuck);
+    tokio::task::yield_now().await;
+
+    actor.abort();
+    assert_eq!(actor.join().await.err(), Some(JoinError::Aborted));
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[test]
+fn join_errors_say_why() {
+    assert_eq!(JoinError::Panicked.to_string(), "actor panicked");
+    assert_eq!(JoinError::Aborted.to_string(), "actor's task was aborted");
+    assert_eq!(JoinError::NeverStarted.to_string(), "actor never started");
+    assert_eq!(
+        JoinError::Kept.to_string(),
+        "actor wasn't handed back when it stopped"
+    );
+}
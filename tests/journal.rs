@@ -0,0 +1,101 @@
+#![cfg(feature = "journal")]
+
+use agency::{prelude::*, MemoryJournal, PanicInfo};
+use tokio::sync::mpsc;
+
+#[derive(Debug)]
+struct Command(u32);
+
+/// Handles commands, reporting each one, but panicking on 13 and recovering from it if asked to.
+struct Durable {
+    recover: bool,
+    handled: mpsc::UnboundedSender<u32>,
+}
+
+#[async_trait]
+impl Actor for Durable {
+    type Msg = Command;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        ctx.dispatch(self).await
+    }
+
+    async fn on_panic(&mut self, _ctx: &mut Context<Self>, _panic: PanicInfo) -> StoppingResult {
+        if self.recover {
+            StoppingResult::Recover
+        } else {
+            StoppingResult::Stop
+        }
+    }
+}
+
+#[async_trait]
+impl Handler for Durable {
+    async fn handle(&mut self, _ctx: &mut Context<Self>, Command(n): Command) {
+        if n == 13 {
+            panic!("unlucky");
+        }
+        let _ = self.handled.send(n);
+    }
+}
+
+#[tokio::test]
+async fn the_message_being_handled_in_a_panic_stays_uncommitted() {
+    let (agency, handle) = Agency::new();
+    let journal = MemoryJournal::new();
+    let (handled, _) = mpsc::unbounded_channel();
+    let addr = agency
+        .hire_builder(Durable {
+            recover: false,
+            handled,
+        })
+        .journal(journal.clone())
+        .hire();
+
+    for n in [1, 2, 13, 4] {
+        addr.send(Command(n)).await.unwrap();
+    }
+    addr.watch().await;
+
+    assert_eq!(journal.recorded(), 4);
+    // The crashed command, then the one still queued when the actor stopped
+    assert_eq!(
+        journal.uncommitted(),
+        vec![
+            (2, "Command(13)".to_string()),
+            (3, "Command(4)".to_string())
+        ]
+    );
+
+    agency.shutdown();
+    assert_eq!(handle.wait().await.len(), 1);
+}
+
+#[tokio::test]
+async fn handling_carries_on_committing_after_recovering() {
+    let (agency, handle) = Agency::new();
+    let journal = MemoryJournal::new();
+    let (handled, mut done) = mpsc::unbounded_channel();
+    let addr = agency
+        .hire_builder(Durable {
+            recover: true,
+            handled,
+        })
+        .journal(journal.clone())
+        .hire();
+
+    for n in [1, 2, 13, 4] {
+        addr.send(Command(n)).await.unwrap();
+    }
+    for n in [1, 2, 4] {
+        assert_eq!(done.recv().await, Some(n));
+    }
+    addr.stop();
+    addr.watch().await;
+
+    assert_eq!(journal.recorded(), 4);
+    assert_eq!(journal.uncommitted(), vec![(2, "Command(13)".to_string())]);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,183 @@
+use agency::{prelude::*, CatchPanicLayer, Layer, Next, TimingLayer};
+use std::sync::{Arc, Mutex};
+
+type Log = Arc<Mutex<Vec<String>>>;
+
+enum Msg {
+    Record(u32),
+    Crash,
+    Get(Request<(), Vec<String>>),
+}
+
+impl From<Request<(), Vec<String>>> for Msg {
+    fn from(request: Request<(), Vec<String>>) -> Self {
+        Self::Get(request)
+    }
+}
+
+/// Logs each number it handles to the log shared with its layers.
+struct Recorder(Log);
+
+#[async_trait]
+impl Actor for Recorder {
+    type Msg = Msg;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        ctx.dispatch(self).await
+    }
+}
+
+#[async_trait]
+impl Handler for Recorder {
+    async fn handle(&mut self, _ctx: &mut Context<Self>, msg: Msg) {
+        match msg {
+            Msg::Record(n) => self.0.lock().unwrap().push(format!("actor {}", n)),
+            Msg::Crash => panic!("crashed"),
+            Msg::Get(request) => {
+                let log = std::mem::take(&mut *self.0.lock().unwrap());
+                let _ = request.respond(log);
+            }
+        }
+    }
+
+    fn message_name(msg: &Msg) -> &'static str {
+        match msg {
+            Msg::Record(_) => "Record",
+            Msg::Crash => "Crash",
+            Msg::Get(_) => "Get",
+        }
+    }
+}
+
+/// Logs around everything beneath it.
+struct Around(&'static str, Log);
+
+#[async_trait]
+impl Layer<Recorder> for Around {
+    async fn handle(
+        &mut self,
+        actor: &mut Recorder,
+        ctx: &mut Context<Recorder>,
+        msg: Msg,
+        next: Next<'_, Recorder>,
+    ) {
+        let record = matches!(msg, Msg::Record(_));
+        if record {
+            self.1.lock().unwrap().push(format!("{} before", self.0));
+        }
+        next.run(actor, ctx, msg).await;
+        if record {
+            self.1.lock().unwrap().push(format!("{} after", self.0));
+        }
+    }
+}
+
+/// Drops odd numbers before they reach anything beneath it.
+struct EvenOnly;
+
+#[async_trait]
+impl Layer<Recorder> for EvenOnly {
+    async fn handle(
+        &mut self,
+        actor: &mut Recorder,
+        ctx: &mut Context<Recorder>,
+        msg: Msg,
+        next: Next<'_, Recorder>,
+    ) {
+        if let Msg::Record(n) = msg {
+            if n % 2 != 0 {
+                return;
+            }
+        }
+        next.run(actor, ctx, msg).await
+    }
+}
+
+#[tokio::test]
+async fn layers_run_outermost_first() {
+    let (agency, handle) = Agency::new();
+    let log = Log::default();
+    let addr = agency
+        .hire_builder(Recorder(log.clone()))
+        .layer(Around("outer", log.clone()))
+        .layer(Around("inner", log.clone()))
+        .hire();
+
+    addr.send(Msg::Record(1)).await.unwrap();
+    assert_eq!(
+        addr.request(()).await.unwrap(),
+        vec![
+            "outer before",
+            "inner before",
+            "actor 1",
+            "inner after",
+            "outer after"
+        ]
+    );
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn layers_can_stop_messages_reaching_the_actor() {
+    let (agency, handle) = Agency::new();
+    let log = Log::default();
+    let addr = agency
+        .hire_builder(Recorder(log.clone()))
+        .layer(Around("outer", log.clone()))
+        .layer(EvenOnly)
+        .layer(Around("inner", log.clone()))
+        .hire();
+
+    addr.send(Msg::Record(1)).await.unwrap();
+    addr.send(Msg::Record(2)).await.unwrap();
+    assert_eq!(
+        addr.request(()).await.unwrap(),
+        vec![
+            // Layers outside the short-circuit still see the message
+            "outer before",
+            "outer after",
+            "outer before",
+            "inner before",
+            "actor 2",
+            "inner after",
+            "outer after",
+        ]
+    );
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn built_in_layers_time_and_catch_panics() {
+    let (agency, handle) = Agency::new();
+    let timed = Arc::new(Mutex::new(Vec::new()));
+    let caught = Arc::new(Mutex::new(Vec::new()));
+    let addr = agency
+        .hire_builder(Recorder(Log::default()))
+        .layer(TimingLayer::new({
+            let timed = timed.clone();
+            move |message, _| timed.lock().unwrap().push(message)
+        }))
+        .layer(CatchPanicLayer::new({
+            let caught = caught.clone();
+            move |panic| caught.lock().unwrap().push(panic.clone())
+        }))
+        .hire();
+
+    addr.send(Msg::Record(1)).await.unwrap();
+    addr.send(Msg::Crash).await.unwrap();
+    // Still running after the panic
+    assert_eq!(addr.request(()).await.unwrap(), vec!["actor 1"]);
+
+    let caught = caught.lock().unwrap().clone();
+    assert_eq!(caught.len(), 1);
+    assert_eq!(caught[0].payload.as_deref(), Some("crashed"));
+    assert_eq!(caught[0].message, Some("Crash"));
+    assert_eq!(timed.lock().unwrap()[..2], ["Record", "Crash"]);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,114 @@
+use agency::{prelude::*, DeliveryError};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::{
+    sync::{mpsc, oneshot, Mutex},
+    time,
+};
+
+/// Counts how often it's set up, which waits for a gate if there is one, then reports each
+/// message.
+struct Expensive(mpsc::UnboundedSender<u32>);
+
+struct Args {
+    setups: Arc<AtomicUsize>,
+    gate: Mutex<Option<oneshot::Receiver<()>>>,
+    seen: mpsc::UnboundedSender<u32>,
+}
+
+#[async_trait]
+impl Setup for Expensive {
+    type Args = Args;
+
+    async fn setup(_ctx: &mut Context<Self>, args: Args) -> Option<Self> {
+        args.setups.fetch_add(1, Ordering::SeqCst);
+        let gate = args.gate.lock().await.take();
+        if let Some(gate) = gate {
+            let _ = gate.await;
+        }
+        Some(Self(args.seen))
+    }
+}
+
+#[async_trait]
+impl Actor for Expensive {
+    type Msg = u32;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let _ = self.0.send(ctx.message().await);
+    }
+}
+
+fn args(
+    gate: Option<oneshot::Receiver<()>>,
+) -> (Args, Arc<AtomicUsize>, mpsc::UnboundedReceiver<u32>) {
+    let setups = Arc::new(AtomicUsize::new(0));
+    let (seen, rx) = mpsc::unbounded_channel();
+    let args = Args {
+        setups: setups.clone(),
+        gate: Mutex::new(gate),
+        seen,
+    };
+    (args, setups, rx)
+}
+
+#[tokio::test(start_paused = true)]
+async fn setup_waits_for_the_first_message() {
+    let (agency, handle) = Agency::new();
+    let (args, setups, mut seen) = args(None);
+    let addr = agency.hire_lazy_with::<Expensive>(args);
+
+    time::sleep(Duration::from_secs(60)).await;
+    assert_eq!(setups.load(Ordering::SeqCst), 0);
+
+    for n in 1..=3u32 {
+        addr.send(n).await.unwrap();
+    }
+    for n in 1..=3 {
+        assert_eq!(seen.recv().await, Some(n));
+    }
+    assert_eq!(setups.load(Ordering::SeqCst), 1);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn senders_wait_for_room_before_the_actor_starts() {
+    let (agency, handle) = Agency::builder().capacity(2).build();
+    let (open, gate) = oneshot::channel();
+    let (args, setups, mut seen) = args(Some(gate));
+    let addr = agency.hire_lazy_with::<Expensive>(args);
+
+    // Not started yet, so these just fill the mailbox
+    addr.try_send(1u32).unwrap();
+    addr.try_send(2u32).unwrap();
+    assert_eq!(addr.mailbox_len(), 2);
+    assert!(matches!(addr.try_send(3u32), Err(DeliveryError::Full(3))));
+    // Starting takes the first message out of the mailbox, so there's room for one more
+    let blocked = tokio::spawn({
+        let addr = addr.clone();
+        async move {
+            addr.send(3u32).await?;
+            addr.send(4u32).await
+        }
+    });
+    time::sleep(Duration::from_secs(1)).await;
+    assert!(!blocked.is_finished(), "send didn't wait for room");
+    assert_eq!(addr.mailbox_len(), 2);
+    assert_eq!(setups.load(Ordering::SeqCst), 1);
+
+    open.send(()).unwrap();
+    blocked.await.unwrap().unwrap();
+    for n in 1..=4 {
+        assert_eq!(seen.recv().await, Some(n));
+    }
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,107 @@
+use agency::{prelude::*, LoadShed, LoadShedConfig, LoadShedError};
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::time;
+
+/// Takes however many milliseconds it's been told to to answer each request.
+struct Slow(Arc<AtomicU64>);
+
+#[async_trait]
+impl Actor for Slow {
+    type Msg = Request<(), ()>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let request = ctx.message().await;
+        time::sleep(Duration::from_millis(self.0.load(Ordering::Relaxed))).await;
+        if let Some(((), reply_to)) = request.handle() {
+            let _ = reply_to.send(());
+        }
+    }
+}
+
+fn slow(agency: &Agency, config: LoadShedConfig) -> (LoadShed<(), ()>, Arc<AtomicU64>) {
+    let delay = Arc::new(AtomicU64::new(0));
+    let recipient = agency.hire(Slow(delay.clone())).recipient();
+    (LoadShed::new(recipient, config), delay)
+}
+
+fn config(max_shed_ratio: f64) -> LoadShedConfig {
+    LoadShedConfig {
+        target_latency: Duration::from_millis(100),
+        percentile: 0.9,
+        window: Duration::from_secs(1),
+        max_shed_ratio,
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn sheds_in_proportion_to_the_overshoot() {
+    let (agency, handle) = Agency::new();
+    let (shed, delay) = slow(&agency, config(0.5));
+
+    delay.store(300, Ordering::Relaxed);
+    shed.request(()).await.unwrap();
+    assert_eq!(shed.shed_ratio(), 0.5);
+
+    let mut overloaded = 0;
+    for _ in 0..10 {
+        match shed.request(()).await {
+            Ok(()) => {}
+            Err(LoadShedError::Overloaded) => overloaded += 1,
+            Err(err) => panic!("unexpected error: {}", err),
+        }
+    }
+    assert_eq!(overloaded, 5);
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn engages_while_slow_and_disengages_once_recovered() {
+    let (agency, handle) = Agency::new();
+    let (shed, delay) = slow(&agency, config(0.9));
+
+    delay.store(10, Ordering::Relaxed);
+    shed.request(()).await.unwrap();
+    assert_eq!(shed.shed_ratio(), 0.0);
+
+    delay.store(500, Ordering::Relaxed);
+    shed.request(()).await.unwrap();
+    assert_eq!(shed.shed_ratio(), 0.9);
+
+    // Back to fast responses, which bring the percentile down as the slow one's outnumbered, or
+    // ages out of the window
+    delay.store(10, Ordering::Relaxed);
+    while shed.shed_ratio() > 0.0 {
+        let _ = shed.request(()).await;
+    }
+    shed.request(()).await.unwrap();
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn shedding_everything_stops_once_the_window_has_passed() {
+    let (agency, handle) = Agency::new();
+    let (shed, delay) = slow(&agency, config(1.0));
+
+    delay.store(500, Ordering::Relaxed);
+    shed.request(()).await.unwrap();
+    assert_eq!(shed.shed_ratio(), 1.0);
+    for _ in 0..10 {
+        assert_eq!(shed.request(()).await, Err(LoadShedError::Overloaded));
+    }
+
+    delay.store(10, Ordering::Relaxed);
+    time::sleep(Duration::from_secs(2)).await;
+    assert_eq!(shed.shed_ratio(), 0.0);
+    shed.request(()).await.unwrap();
+    assert_eq!(shed.shed_ratio(), 0.0);
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,141 @@
+use agency::{prelude::*, DeliveryError, DropOldest};
+use tokio::sync::{mpsc, oneshot};
+
+/// Reports each message it gets, waiting for its gate to open before taking the first.
+struct Display {
+    gate: Option<oneshot::Receiver<()>>,
+    seen: mpsc::UnboundedSender<u32>,
+}
+
+#[async_trait]
+impl Actor for Display {
+    type Msg = u32;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        if let Some(gate) = self.gate.take() {
+            let _ = gate.await;
+        }
+        let _ = self.seen.send(ctx.message().await);
+    }
+}
+
+fn display(
+    agency: &Agency,
+    capacity: usize,
+) -> (
+    Addr<Display>,
+    oneshot::Sender<()>,
+    mpsc::UnboundedReceiver<u32>,
+) {
+    let (open, gate) = oneshot::channel();
+    let (seen, rx) = mpsc::unbounded_channel();
+    let addr = agency
+        .hire_builder(Display {
+            gate: Some(gate),
+            seen,
+        })
+        .capacity(capacity)
+        .mailbox(DropOldest::new)
+        .hire();
+    (addr, open, rx)
+}
+
+async fn received(rx: &mut mpsc::UnboundedReceiver<u32>, count: usize) -> Vec<u32> {
+    let mut received = Vec::new();
+    for _ in 0..count {
+        received.push(rx.recv().await.unwrap());
+    }
+    received
+}
+
+#[tokio::test]
+async fn senders_never_wait_and_the_oldest_messages_make_way() {
+    let (agency, handle) = Agency::new();
+    let (addr, open, mut seen) = display(&agency, 3);
+
+    for n in 1..=6u32 {
+        addr.send(n).await.unwrap();
+    }
+    addr.try_send(7u32).unwrap();
+    assert_eq!(addr.mailbox_len(), 3);
+    assert_eq!(addr.mailbox_capacity(), 3);
+
+    open.send(()).unwrap();
+    assert_eq!(received(&mut seen, 3).await, vec![5, 6, 7]);
+    assert!(seen.try_recv().is_err());
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn priority_messages_skip_ahead_as_with_the_default_mailbox() {
+    let (agency, handle) = Agency::new();
+    let (addr, open, mut seen) = display(&agency, 2);
+
+    for n in 1..=3u32 {
+        addr.send(n).await.unwrap();
+    }
+    addr.send_priority(9u32).unwrap();
+    open.send(()).unwrap();
+    assert_eq!(received(&mut seen, 3).await, vec![9, 2, 3]);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn sends_fail_once_the_actor_has_stopped() {
+    let (agency, handle) = Agency::new();
+    let (addr, open, _seen) = display(&agency, 2);
+    let recipient: Recipient<u32> = addr.clone().recipient();
+
+    addr.stop();
+    drop(open);
+    addr.watch().await;
+    assert!(matches!(
+        addr.send(1u32).await,
+        Err(DeliveryError::Closed(1))
+    ));
+    assert!(matches!(
+        recipient.try_send(2u32),
+        Err(DeliveryError::Closed(2))
+    ));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+/// Answers each request with double the number it was sent.
+struct Doubler;
+
+#[async_trait]
+impl Actor for Doubler {
+    type Msg = Request<u32, u32>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        if let Some((n, reply_to)) = ctx.message().await.handle() {
+            let _ = reply_to.send(n * 2);
+        }
+    }
+}
+
+#[tokio::test]
+async fn requests_work_through_another_mailbox() {
+    let (agency, handle) = Agency::new();
+    let addr = agency.hire_builder(Doubler).mailbox(DropOldest::new).hire();
+
+    for n in 0..10 {
+        assert_eq!(addr.request(n).await.unwrap(), n * 2);
+    }
+    assert_eq!(addr.stats().processed, 10);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[test]
+#[should_panic(expected = "mailbox capacity must be at least 1")]
+fn a_capacity_of_zero_is_refused() {
+    let _ = DropOldest::<u32>::new(0);
+}
@@ -0,0 +1,81 @@
+use agency::{prelude::*, TooManyActors};
+use std::time::Duration;
+use tokio::time;
+
+/// Answers each request with its tag.
+struct Tagged(u32);
+
+#[async_trait]
+impl Actor for Tagged {
+    type Msg = Request<(), u32>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let _ = ctx.message().await.respond(self.0);
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn try_hire_fails_fast_once_every_slot_is_taken() {
+    let (agency, handle) = Agency::builder().max_actors(2).build();
+    let first = agency.try_hire(Tagged(1)).unwrap();
+    let second = agency.try_hire(Tagged(2)).unwrap();
+
+    let refused = match agency.try_hire(Tagged(3)) {
+        Ok(_) => panic!("hired past the limit"),
+        Err(TooManyActors(actor)) => actor,
+    };
+    assert_eq!(refused.0, 3);
+    assert_eq!(second.request(()).await.unwrap(), 2);
+
+    first.stop();
+    let third = time::timeout(Duration::from_secs(1), agency.hire_when_available(refused))
+        .await
+        .expect("the stopped actor's slot wasn't released");
+    assert_eq!(third.request(()).await.unwrap(), 3);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn hire_when_available_waits_for_a_slot() {
+    let (agency, handle) = Agency::builder().max_actors(2).build();
+    let first = agency.hire_when_available(Tagged(1)).await;
+    let _second = agency.hire_when_available(Tagged(2)).await;
+
+    let waiting = tokio::spawn({
+        let agency = agency.clone();
+        async move { agency.hire_when_available(Tagged(3)).await }
+    });
+    time::sleep(Duration::from_secs(1)).await;
+    assert!(!waiting.is_finished(), "hired past the limit");
+
+    first.stop();
+    let third = waiting.await.unwrap();
+    assert_eq!(third.request(()).await.unwrap(), 3);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn hire_past_the_limit_starts_once_a_slot_frees_up() {
+    let (agency, handle) = Agency::builder().max_actors(2).build();
+    let first = agency.hire(Tagged(1));
+    let _second = agency.hire(Tagged(2));
+    let third = agency.hire(Tagged(3));
+
+    // The third's address works straight away, but nothing answers until it gets a slot
+    let answer = tokio::spawn({
+        let third = third.clone();
+        async move { third.request(()).await }
+    });
+    time::sleep(Duration::from_secs(1)).await;
+    assert!(!answer.is_finished(), "started past the limit");
+
+    first.stop();
+    assert_eq!(answer.await.unwrap().unwrap(), 3);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
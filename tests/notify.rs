@@ -0,0 +1,78 @@
+use agency::prelude::*;
+use tokio::sync::{mpsc, oneshot};
+
+/// Holds off until its gate opens, then reports each message, sending itself the configured
+/// messages while handling the first.
+struct Notifier {
+    gate: Option<oneshot::Receiver<()>>,
+    seen: mpsc::UnboundedSender<u32>,
+    queued: Vec<u32>,
+    priority: Option<u32>,
+}
+
+#[async_trait]
+impl Actor for Notifier {
+    type Msg = u32;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        if let Some(gate) = self.gate.take() {
+            let _ = gate.await;
+        }
+        let msg = ctx.message().await;
+        if msg == 1 {
+            for queued in self.queued.drain(..) {
+                ctx.notify_queued(queued);
+            }
+            if let Some(priority) = self.priority.take() {
+                ctx.notify(priority);
+            }
+        }
+        let _ = self.seen.send(msg);
+    }
+}
+
+/// Hires a notifier with the given mailbox capacity, sends it `external` while it's held, then
+/// returns the order in which it received everything.
+async fn received(
+    capacity: usize,
+    external: &[u32],
+    queued: Vec<u32>,
+    priority: Option<u32>,
+) -> Vec<u32> {
+    let (agency, handle) = Agency::builder().capacity(capacity).build();
+    let (open, gate) = oneshot::channel();
+    let (seen, mut rx) = mpsc::unbounded_channel();
+    let total = external.len() + queued.len() + priority.iter().count();
+    let addr = agency.hire(Notifier {
+        gate: Some(gate),
+        seen,
+        queued,
+        priority,
+    });
+    for msg in external {
+        addr.try_send(*msg).unwrap();
+    }
+    open.send(()).unwrap();
+
+    let mut received = Vec::new();
+    while received.len() < total {
+        received.push(rx.recv().await.unwrap());
+    }
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+    received
+}
+
+#[tokio::test]
+async fn queued_notifications_wait_behind_external_messages() {
+    let order = received(16, &[1, 2, 3], vec![10, 11], Some(20)).await;
+    // Priority notifications jump the queue, regular ones wait their turn
+    assert_eq!(order, vec![1, 20, 2, 3, 10, 11]);
+}
+
+#[tokio::test]
+async fn queued_notifications_overflow_a_full_mailbox() {
+    let order = received(2, &[1, 2], vec![10, 11, 12], None).await;
+    // 10 takes the room left by 1, while 11 and 12 overflow and are drained before the mailbox
+    assert_eq!(order, vec![1, 11, 12, 2, 10]);
+}
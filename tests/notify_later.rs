@@ -0,0 +1,157 @@
+use agency::{prelude::*, TimerHandle};
+use std::time::Duration;
+use tokio::{
+    sync::mpsc,
+    time::{self, Instant},
+};
+
+enum Timer {
+    Later(u32, Duration),
+    Every(Duration),
+}
+
+enum Msg {
+    Ping(u32),
+    Beat(u32),
+    Schedule(Request<Timer, TimerHandle>),
+}
+
+impl From<Request<Timer, TimerHandle>> for Msg {
+    fn from(request: Request<Timer, TimerHandle>) -> Self {
+        Self::Schedule(request)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Seen {
+    Ping(u32, Duration),
+    Beat(u32, Duration),
+}
+
+/// Schedules messages back to itself when asked, recording when each one arrives.
+struct Pinger {
+    started: Instant,
+    seen: mpsc::UnboundedSender<Seen>,
+}
+
+#[async_trait]
+impl Actor for Pinger {
+    type Msg = Msg;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        ctx.dispatch(self).await
+    }
+}
+
+#[async_trait]
+impl Handler for Pinger {
+    async fn handle(&mut self, ctx: &mut Context<Self>, msg: Msg) {
+        let at = self.started.elapsed();
+        match msg {
+            Msg::Ping(n) => {
+                let _ = self.seen.send(Seen::Ping(n, at));
+            }
+            Msg::Beat(n) => {
+                let _ = self.seen.send(Seen::Beat(n, at));
+            }
+            Msg::Schedule(request) => {
+                let timer = match *request.payload() {
+                    Timer::Later(n, delay) => ctx.notify_later(Msg::Ping(n), delay),
+                    Timer::Every(interval) => {
+                        let mut beats = 0;
+                        let factory = move || {
+                            beats += 1;
+                            Msg::Beat(beats)
+                        };
+                        ctx.notify_interval(factory, interval)
+                    }
+                };
+                let _ = request.respond(timer);
+            }
+        }
+    }
+}
+
+fn hire(agency: &Agency) -> (Addr<Pinger>, mpsc::UnboundedReceiver<Seen>) {
+    let (seen, rx) = mpsc::unbounded_channel();
+    let addr = agency.hire(Pinger {
+        started: Instant::now(),
+        seen,
+    });
+    (addr, rx)
+}
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[tokio::test(start_paused = true)]
+async fn delayed_notifications_arrive_once_their_delay_is_up() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut seen) = hire(&agency);
+
+    let _slow = addr.request(Timer::Later(1, ms(100))).await.unwrap();
+    let _fast = addr.request(Timer::Later(2, ms(50))).await.unwrap();
+
+    time::sleep(ms(30)).await;
+    assert!(seen.try_recv().is_err(), "notified before the delay");
+    assert_eq!(seen.recv().await, Some(Seen::Ping(2, ms(50))));
+    assert_eq!(seen.recv().await, Some(Seen::Ping(1, ms(100))));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn cancelled_delayed_notifications_never_arrive() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut seen) = hire(&agency);
+
+    let cancelled = addr.request(Timer::Later(1, ms(100))).await.unwrap();
+    let _kept = addr.request(Timer::Later(2, ms(200))).await.unwrap();
+    cancelled.cancel();
+    assert!(cancelled.is_cancelled());
+
+    assert_eq!(seen.recv().await, Some(Seen::Ping(2, ms(200))));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn intervals_tick_until_cancelled() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut seen) = hire(&agency);
+
+    let heartbeat = addr.request(Timer::Every(ms(100))).await.unwrap();
+    for n in 1..=3 {
+        assert_eq!(seen.recv().await, Some(Seen::Beat(n, ms(100) * n)));
+    }
+
+    heartbeat.cancel();
+    assert!(heartbeat.is_cancelled());
+    time::sleep(ms(500)).await;
+    assert!(seen.try_recv().is_err(), "ticked after being cancelled");
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn pending_notifications_are_dropped_when_the_actor_stops() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut seen) = hire(&agency);
+
+    let later = addr.request(Timer::Later(1, ms(100))).await.unwrap();
+    let heartbeat = addr.request(Timer::Every(ms(100))).await.unwrap();
+    addr.stop();
+    addr.watch().await;
+    assert!(later.is_cancelled());
+    assert!(heartbeat.is_cancelled());
+
+    time::sleep(ms(500)).await;
+    assert_eq!(seen.recv().await, None);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,114 @@
+use agency::{prelude::*, PanicInfo};
+use tokio::sync::mpsc;
+
+enum Msg {
+    Add(u32),
+    Crash(&'static str),
+    CrashWith(String),
+    Total(Request<(), u32>),
+}
+
+impl From<Request<(), u32>> for Msg {
+    fn from(request: Request<(), u32>) -> Self {
+        Self::Total(request)
+    }
+}
+
+/// Keeps a running total, recovering from a set number of panics.
+struct Tally {
+    total: u32,
+    recoveries: u32,
+    panics: mpsc::UnboundedSender<PanicInfo>,
+}
+
+#[async_trait]
+impl Actor for Tally {
+    type Msg = Msg;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        ctx.dispatch(self).await
+    }
+
+    async fn on_panic(&mut self, _ctx: &mut Context<Self>, panic: PanicInfo) -> StoppingResult {
+        let _ = self.panics.send(panic);
+        if self.recoveries == 0 {
+            return StoppingResult::Stop;
+        }
+        self.recoveries -= 1;
+        StoppingResult::Recover
+    }
+}
+
+#[async_trait]
+impl Handler for Tally {
+    async fn handle(&mut self, _ctx: &mut Context<Self>, msg: Msg) {
+        match msg {
+            Msg::Add(n) => self.total += n,
+            Msg::Crash(reason) => panic!("{}", reason),
+            Msg::CrashWith(reason) => std::panic::panic_any(reason),
+            Msg::Total(request) => {
+                let _ = request.respond(self.total);
+            }
+        }
+    }
+
+    fn message_name(msg: &Msg) -> &'static str {
+        match msg {
+            Msg::Add(_) => "Add",
+            Msg::Crash(_) | Msg::CrashWith(_) => "Crash",
+            Msg::Total(_) => "Total",
+        }
+    }
+}
+
+fn hire(agency: &Agency, recoveries: u32) -> (Addr<Tally>, mpsc::UnboundedReceiver<PanicInfo>) {
+    let (panics, rx) = mpsc::unbounded_channel();
+    let addr = agency.hire(Tally {
+        total: 0,
+        recoveries,
+        panics,
+    });
+    (addr, rx)
+}
+
+#[tokio::test]
+async fn the_hook_sees_the_payload_and_can_recover() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut panics) = hire(&agency, 2);
+
+    addr.send(Msg::Add(2)).await.unwrap();
+    addr.send(Msg::Crash("formatted payload")).await.unwrap();
+    let panic = panics.recv().await.unwrap();
+    assert_eq!(panic.payload.as_deref(), Some("formatted payload"));
+    assert_eq!(panic.message, Some("Crash"));
+
+    addr.send(Msg::CrashWith(String::from("owned payload")))
+        .await
+        .unwrap();
+    let panic = panics.recv().await.unwrap();
+    assert_eq!(panic.payload.as_deref(), Some("owned payload"));
+
+    // Recovered with its state intact
+    addr.send(Msg::Add(3)).await.unwrap();
+    assert_eq!(addr.request(()).await.unwrap(), 5);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn stopping_from_the_hook_counts_as_a_panic() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut panics) = hire(&agency, 0);
+
+    addr.send(Msg::Crash("fatal")).await.unwrap();
+    assert_eq!(
+        panics.recv().await.unwrap().payload.as_deref(),
+        Some("fatal")
+    );
+    addr.watch().await;
+    assert!(addr.is_stopped());
+
+    agency.shutdown();
+    assert_eq!(handle.wait().await.len(), 1);
+}
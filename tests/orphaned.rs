@@ -0,0 +1,139 @@
+use agency::{prelude::*, StopReason, WeakAddr};
+use std::time::Duration;
+use tokio::{
+    sync::{mpsc, oneshot},
+    time,
+};
+
+/// Handles messages until it's stopped, then holds up stopping until its gate opens, reporting
+/// why it stopped and whether its own weak address still upgraded by then.
+struct Lonely {
+    own: Option<WeakAddr<Lonely>>,
+    seen: mpsc::UnboundedSender<u32>,
+    stopping: Option<oneshot::Sender<(Option<StopReason>, bool)>>,
+    gate: Option<oneshot::Receiver<()>>,
+    detach: bool,
+}
+
+#[async_trait]
+impl Actor for Lonely {
+    type Msg = u32;
+
+    async fn init(&mut self, ctx: &mut Context<Self>) {
+        self.own = Some(ctx.address_weak());
+        if self.detach {
+            ctx.detach();
+        }
+    }
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let _ = self.seen.send(ctx.message().await);
+    }
+
+    async fn stopping(&mut self, ctx: &mut Context<Self>) -> StoppingResult {
+        let upgrades = self.own.as_ref().unwrap().upgrade().is_some();
+        let _ = self
+            .stopping
+            .take()
+            .unwrap()
+            .send((ctx.stop_reason(), upgrades));
+        if let Some(gate) = self.gate.take() {
+            let _ = gate.await;
+        }
+        StoppingResult::Stop
+    }
+}
+
+struct Hired {
+    addr: Addr<Lonely>,
+    seen: mpsc::UnboundedReceiver<u32>,
+    stopping: oneshot::Receiver<(Option<StopReason>, bool)>,
+}
+
+fn hire(agency: &Agency, gate: Option<oneshot::Receiver<()>>, detach: bool) -> Hired {
+    let (seen, seen_rx) = mpsc::unbounded_channel();
+    let (stopping, stopping_rx) = oneshot::channel();
+    let addr = agency.hire(Lonely {
+        own: None,
+        seen,
+        stopping: Some(stopping),
+        gate,
+        detach,
+    });
+    Hired {
+        addr,
+        seen: seen_rx,
+        stopping: stopping_rx,
+    }
+}
+
+#[tokio::test]
+async fn dropping_the_last_address_stops_the_actor() {
+    let (agency, handle) = Agency::new();
+    let mut hired = hire(&agency, None, false);
+    let clone = hired.addr.clone();
+
+    hired.addr.send(1u32).await.unwrap();
+    assert_eq!(hired.seen.recv().await, Some(1));
+    drop(hired.addr);
+    clone.send(2u32).await.unwrap();
+    assert_eq!(hired.seen.recv().await, Some(2));
+
+    drop(clone);
+    let (reason, upgrades) = hired.stopping.await.unwrap();
+    assert_eq!(reason, Some(StopReason::Orphaned));
+    assert!(!upgrades);
+
+    // Without ever being asked to shut down
+    let panicked = time::timeout(Duration::from_secs(1), handle.wait())
+        .await
+        .expect("the orphaned actor kept running");
+    assert!(panicked.is_empty());
+}
+
+#[tokio::test]
+async fn weak_addresses_dont_keep_the_actor_alive() {
+    let (agency, handle) = Agency::new();
+    let (open, gate) = oneshot::channel();
+    let hired = hire(&agency, Some(gate), false);
+    let weak = hired.addr.downgrade();
+
+    let upgraded = weak.upgrade().unwrap();
+    assert_eq!(upgraded.id(), hired.addr.id());
+    drop(upgraded);
+    drop(hired.addr);
+
+    // Orphaned, but still held up in stopping
+    let (reason, _) = hired.stopping.await.unwrap();
+    assert_eq!(reason, Some(StopReason::Orphaned));
+    assert!(weak.upgrade().is_none());
+
+    open.send(()).unwrap();
+    let panicked = time::timeout(Duration::from_secs(1), handle.wait())
+        .await
+        .expect("the orphaned actor kept running");
+    assert!(panicked.is_empty());
+    assert!(weak.upgrade().is_none());
+}
+
+#[tokio::test]
+async fn detached_actors_outlive_their_addresses() {
+    let (agency, handle) = Agency::new();
+    let mut hired = hire(&agency, None, true);
+    let weak = hired.addr.downgrade();
+
+    hired.addr.send(1u32).await.unwrap();
+    assert_eq!(hired.seen.recv().await, Some(1));
+    drop(hired.addr);
+    time::sleep(Duration::from_millis(50)).await;
+
+    let addr = weak.upgrade().expect("the detached actor was stopped");
+    addr.send(2u32).await.unwrap();
+    assert_eq!(hired.seen.recv().await, Some(2));
+    drop(addr);
+
+    agency.shutdown();
+    let (reason, _) = hired.stopping.await.unwrap();
+    assert_eq!(reason, Some(StopReason::AgencyShutdown));
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,78 @@
+use agency::prelude::*;
+use std::time::Duration;
+use tokio::{
+    sync::{mpsc, oneshot},
+    time,
+};
+
+/// Reports each message it handles, holding on to the first until its gate opens.
+struct Worker {
+    busy: Option<oneshot::Sender<()>>,
+    gate: Option<oneshot::Receiver<()>>,
+    seen: mpsc::UnboundedSender<u32>,
+}
+
+#[async_trait]
+impl Actor for Worker {
+    type Msg = u32;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let msg = ctx.message().await;
+        if let Some(gate) = self.gate.take() {
+            let _ = self.busy.take().unwrap().send(());
+            let _ = gate.await;
+        }
+        let _ = self.seen.send(msg);
+    }
+}
+
+async fn until_paused(addr: &Addr<Worker>) {
+    while !addr.is_paused() {
+        time::sleep(Duration::from_millis(1)).await;
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_paused_actor_leaves_its_backlog_until_resumed() {
+    let (agency, handle) = Agency::new();
+    let (busy, on_busy) = oneshot::channel();
+    let (open, gate) = oneshot::channel();
+    let (seen, mut rx) = mpsc::unbounded_channel();
+    let addr = agency.hire(Worker {
+        busy: Some(busy),
+        gate: Some(gate),
+        seen,
+    });
+
+    for n in 1..=5u32 {
+        addr.send(n).await.unwrap();
+    }
+    on_busy.await.unwrap();
+    // Asked while it's still handling the first message, so it finishes that one first
+    addr.pause();
+    assert!(!addr.is_paused());
+    open.send(()).unwrap();
+    until_paused(&addr).await;
+    assert_eq!(rx.recv().await, Some(1));
+
+    addr.send_priority(9u32).unwrap();
+    time::sleep(Duration::from_secs(1)).await;
+    assert!(rx.try_recv().is_err(), "handled a message while paused");
+    let snapshot = agency
+        .actors()
+        .into_iter()
+        .find(|actor| actor.id == addr.id())
+        .unwrap();
+    assert!(snapshot.paused);
+    assert_eq!(snapshot.mailbox_depth, 4);
+    assert_eq!(snapshot.priority_depth, 1);
+
+    addr.resume();
+    for n in [9, 2, 3, 4, 5] {
+        assert_eq!(rx.recv().await, Some(n));
+    }
+    assert!(!addr.is_paused());
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
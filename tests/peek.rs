@@ -0,0 +1,82 @@
+use agency::prelude::*;
+use tokio::sync::oneshot;
+
+type Log = Vec<(&'static str, Option<u32>)>;
+
+/// Waits for its gate to open, then runs through a fixed mix of peeks and receives, reporting
+/// what each returned.
+struct Peeker {
+    gate: Option<oneshot::Receiver<()>>,
+    report: Option<oneshot::Sender<Log>>,
+}
+
+#[async_trait]
+impl Actor for Peeker {
+    type Msg = u32;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let gate = match self.gate.take() {
+            Some(gate) => gate,
+            None => return ctx.stop(),
+        };
+        let _ = gate.await;
+
+        let mut log = Vec::new();
+        log.push(("peek", ctx.peek().copied()));
+        log.push(("peek", ctx.peek().copied()));
+        log.push(("message", Some(ctx.message().await)));
+        log.push(("try_message", ctx.try_message()));
+        log.push(("peek", ctx.peek().copied()));
+        log.push(("take_peeked", ctx.take_peeked()));
+        log.push(("take_peeked", ctx.take_peeked()));
+        log.push(("peek", ctx.peek().copied()));
+        log.push(("try_message", ctx.try_message()));
+        log.push(("peek", ctx.peek().copied()));
+        log.push(("message", Some(ctx.message().await)));
+        log.push(("try_message", ctx.try_message()));
+        log.push(("try_message", ctx.try_message()));
+        log.push(("peek", ctx.peek().copied()));
+        let _ = self.report.take().unwrap().send(log);
+    }
+}
+
+#[tokio::test]
+async fn peeked_messages_are_delivered_exactly_once() {
+    let (agency, handle) = Agency::new();
+    let (open, gate) = oneshot::channel();
+    let (report, log) = oneshot::channel();
+    let addr = agency.hire(Peeker {
+        gate: Some(gate),
+        report: Some(report),
+    });
+
+    for n in 1..=5u32 {
+        addr.send(n).await.unwrap();
+    }
+    addr.send_priority(9u32).unwrap();
+    open.send(()).unwrap();
+
+    assert_eq!(
+        log.await.unwrap(),
+        vec![
+            // Priority messages are peeked first, and peeking again doesn't move on
+            ("peek", Some(9)),
+            ("peek", Some(9)),
+            ("message", Some(9)),
+            ("try_message", Some(1)),
+            ("peek", Some(2)),
+            ("take_peeked", Some(2)),
+            ("take_peeked", None),
+            ("peek", Some(3)),
+            ("try_message", Some(3)),
+            ("peek", Some(4)),
+            ("message", Some(4)),
+            ("try_message", Some(5)),
+            ("try_message", None),
+            ("peek", None),
+        ]
+    );
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,122 @@
+#![cfg(feature = "serde")]
+
+use agency::{prelude::*, MemorySnapshots, Persistent};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Totals {
+    total: u32,
+    adds: u32,
+}
+
+enum Msg {
+    Add(u32),
+    Save(Request<(), bool>),
+    Get(Request<(), Totals>),
+}
+
+impl From<Request<(), bool>> for Msg {
+    fn from(request: Request<(), bool>) -> Self {
+        Self::Save(request)
+    }
+}
+
+impl From<Request<(), Totals>> for Msg {
+    fn from(request: Request<(), Totals>) -> Self {
+        Self::Get(request)
+    }
+}
+
+/// Keeps a running total, starting from its args when there's no snapshot to restore.
+struct Counter(Totals);
+
+#[async_trait]
+impl Setup for Counter {
+    type Args = u32;
+
+    async fn setup(_ctx: &mut Context<Self>, start: u32) -> Option<Self> {
+        Some(Self(Totals {
+            total: start,
+            adds: 0,
+        }))
+    }
+}
+
+impl Persistent for Counter {
+    type Snapshot = Totals;
+
+    fn snapshot(&self) -> Totals {
+        self.0.clone()
+    }
+
+    fn restore(snapshot: Totals, _start: u32) -> Self {
+        Self(snapshot)
+    }
+}
+
+#[async_trait]
+impl Actor for Counter {
+    type Msg = Msg;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        match ctx.message().await {
+            Msg::Add(n) => {
+                self.0.total += n;
+                self.0.adds += 1;
+            }
+            Msg::Save(request) => {
+                let _ = request.respond(ctx.save_snapshot(self));
+            }
+            Msg::Get(request) => {
+                let _ = request.respond(self.0.clone());
+            }
+        }
+    }
+}
+
+async fn stop(addr: Addr<Counter>) {
+    addr.stop();
+    addr.watch().await;
+}
+
+#[tokio::test]
+async fn restored_actors_carry_on_from_their_snapshot() {
+    let (agency, handle) = Agency::new();
+    let store = MemorySnapshots::new();
+
+    // Without a snapshot it's set up from its args
+    let addr = agency.hire_restored::<Counter>("counter", 5, store.clone());
+    addr.send(Msg::Add(3)).await.unwrap();
+    let saved: bool = addr.request(()).await.unwrap();
+    assert!(saved);
+    // Not saved, so lost on restart
+    addr.send(Msg::Add(100)).await.unwrap();
+    stop(addr).await;
+
+    let addr = agency.hire_restored::<Counter>("counter", 0, store.clone());
+    let totals: Totals = addr.request(()).await.unwrap();
+    assert_eq!(totals, Totals { total: 8, adds: 1 });
+    addr.send(Msg::Add(1)).await.unwrap();
+    let totals: Totals = addr.request(()).await.unwrap();
+    assert_eq!(totals, Totals { total: 9, adds: 2 });
+    stop(addr).await;
+
+    // Other keys are unaffected
+    let other = agency.hire_restored::<Counter>("other", 7, store);
+    let totals: Totals = other.request(()).await.unwrap();
+    assert_eq!(totals, Totals { total: 7, adds: 0 });
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn actors_hired_without_a_store_cant_save() {
+    let (agency, handle) = Agency::new();
+    let addr = agency.hire_with::<Counter>(1);
+    let saved: bool = addr.request(()).await.unwrap();
+    assert!(!saved);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
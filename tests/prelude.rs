@@ -0,0 +1,84 @@
+//! A typical set of actors, written with nothing but the prelude imported.
+
+use agency::prelude::*;
+
+struct Counter {
+    count: u32,
+    report_to: Recipient<u32>,
+}
+
+enum CounterMsg {
+    Add(u32),
+    Get(Request<(), u32>),
+}
+
+impl From<Request<(), u32>> for CounterMsg {
+    fn from(request: Request<(), u32>) -> Self {
+        Self::Get(request)
+    }
+}
+
+#[async_trait]
+impl Setup for Counter {
+    type Args = Recipient<u32>;
+
+    async fn setup(_ctx: &mut Context<Self>, report_to: Recipient<u32>) -> Option<Self> {
+        Some(Self {
+            count: 0,
+            report_to,
+        })
+    }
+}
+
+#[async_trait]
+impl Actor for Counter {
+    type Msg = CounterMsg;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        ctx.dispatch(self).await
+    }
+
+    async fn stopping(&mut self, _ctx: &mut Context<Self>) -> StoppingResult {
+        let _ = self.report_to.send(self.count).await;
+        StoppingResult::Stop
+    }
+}
+
+#[async_trait]
+impl Handler for Counter {
+    async fn handle(&mut self, _ctx: &mut Context<Self>, msg: CounterMsg) {
+        match msg {
+            CounterMsg::Add(n) => self.count += n,
+            CounterMsg::Get(request) => {
+                let _ = request.respond(self.count);
+            }
+        }
+    }
+}
+
+struct Sink(tokio::sync::mpsc::UnboundedSender<u32>);
+
+#[async_trait]
+impl Actor for Sink {
+    type Msg = u32;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let _ = self.0.send(ctx.message().await);
+    }
+}
+
+#[tokio::test]
+async fn a_typical_actor_needs_only_the_prelude() {
+    let (agency, handle): (Agency, AgencyHandle) = Agency::new();
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let sink: Addr<Sink> = agency.hire(Sink(tx));
+    let counter = agency.hire_with::<Counter>(sink.recipient());
+
+    counter.send(CounterMsg::Add(2)).await.unwrap();
+    counter.send(CounterMsg::Add(3)).await.unwrap();
+    assert_eq!(counter.request(()).await.unwrap(), 5);
+    counter.stop();
+    assert_eq!(rx.recv().await, Some(5));
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
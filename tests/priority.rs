@@ -0,0 +1,202 @@
+use agency::{prelude::*, DeliveryError, Stopped};
+use tokio::sync::{mpsc, oneshot};
+
+/// Reports each message it gets, waiting for its gate to open before taking the first.
+struct Worker {
+    gate: Option<oneshot::Receiver<()>>,
+    seen: mpsc::UnboundedSender<u32>,
+}
+
+#[async_trait]
+impl Actor for Worker {
+    type Msg = u32;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        if let Some(gate) = self.gate.take() {
+            let _ = gate.await;
+        }
+        let _ = self.seen.send(ctx.message().await);
+    }
+}
+
+fn worker(
+    agency: &Agency,
+    gate: Option<oneshot::Receiver<()>>,
+) -> (Addr<Worker>, mpsc::UnboundedReceiver<u32>) {
+    let (seen, rx) = mpsc::unbounded_channel();
+    (agency.hire(Worker { gate, seen }), rx)
+}
+
+#[tokio::test]
+async fn the_first_priority_send_preempts_queued_messages() {
+    let (agency, handle) = Agency::new();
+    let (open, gate) = oneshot::channel();
+    let (addr, mut seen) = worker(&agency, Some(gate));
+
+    for n in 1..=3u32 {
+        addr.send(n).await.unwrap();
+    }
+    assert_eq!(addr.priority_depth(), 0);
+    addr.send_priority(9u32).unwrap();
+    addr.send_priority(10u32).unwrap();
+    assert_eq!(addr.priority_depth(), 2);
+    open.send(()).unwrap();
+
+    let mut order = Vec::new();
+    for _ in 0..5 {
+        order.push(seen.recv().await.unwrap());
+    }
+    assert_eq!(order, vec![9, 10, 1, 2, 3]);
+    assert_eq!(addr.priority_depth(), 0);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn the_first_priority_send_wakes_an_idle_actor() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut seen) = worker(&agency, None);
+    // Waiting on an empty mailbox, before there's a priority channel to wait on
+    addr.send(1u32).await.unwrap();
+    assert_eq!(seen.recv().await, Some(1));
+    tokio::task::yield_now().await;
+
+    addr.send_priority(2u32).unwrap();
+    assert_eq!(seen.recv().await, Some(2));
+    addr.send_priority(3u32).unwrap();
+    assert_eq!(seen.recv().await, Some(3));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn racing_first_sends_all_arrive() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut seen) = worker(&agency, None);
+
+    let senders: Vec<_> = (0..8u32)
+        .map(|n| {
+            let addr = addr.clone();
+            tokio::spawn(async move { addr.send_priority(n).unwrap() })
+        })
+        .collect();
+    for sender in senders {
+        sender.await.unwrap();
+    }
+    let mut received = Vec::new();
+    for _ in 0..8 {
+        received.push(seen.recv().await.unwrap());
+    }
+    received.sort_unstable();
+    assert_eq!(received, (0..8).collect::<Vec<_>>());
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn priority_sends_fail_once_stopped_without_ever_having_been_used() {
+    let (agency, handle) = Agency::new();
+    let (addr, _seen) = worker(&agency, None);
+
+    addr.stop();
+    addr.watch().await;
+    assert!(matches!(
+        addr.send_priority(1u32),
+        Err(DeliveryError::Closed(1))
+    ));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn recipients_send_priority_messages_past_a_full_mailbox() {
+    let (agency, handle) = Agency::builder().capacity(2).build();
+    let (open, gate) = oneshot::channel();
+    let (addr, mut seen) = worker(&agency, Some(gate));
+    let recipient: Recipient<u32> = addr.clone().recipient();
+
+    recipient.try_send(1u32).ok().unwrap();
+    recipient.try_send(2u32).ok().unwrap();
+    assert!(matches!(
+        recipient.try_send(3u32),
+        Err(DeliveryError::Full(3))
+    ));
+    recipient.send_priority(9u32).ok().unwrap();
+    open.send(()).unwrap();
+
+    let mut order = Vec::new();
+    for _ in 0..3 {
+        order.push(seen.recv().await.unwrap());
+    }
+    assert_eq!(order, vec![9, 1, 2]);
+
+    addr.stop();
+    addr.watch().await;
+    assert!(matches!(
+        recipient.send_priority(4u32),
+        Err(DeliveryError::Closed(4))
+    ));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+/// Counts every message it gets, including those left over once it's stopped.
+struct Tally(mpsc::UnboundedSender<usize>, usize);
+
+#[async_trait]
+impl Actor for Tally {
+    type Msg = u32;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        ctx.message().await;
+        self.1 += 1;
+    }
+
+    async fn stopped(self, ctx: Context<Self, Stopped>) {
+        let left = ctx.drain().await.len();
+        let _ = self.0.send(self.1 + left);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn recipient_priority_sends_racing_a_stop_are_delivered_or_refused() {
+    let (agency, handle) = Agency::new();
+    let (tx, mut tally) = mpsc::unbounded_channel();
+    let addr = agency.hire(Tally(tx, 0));
+
+    let senders: Vec<_> = (0..4)
+        .map(|_| {
+            let recipient: Recipient<u32> = addr.clone().recipient();
+            tokio::spawn(async move {
+                let mut sent = 0;
+                loop {
+                    match recipient.send_priority(1u32) {
+                        Ok(()) => sent += 1,
+                        Err(err) => {
+                            assert!(err.is_closed());
+                            return sent;
+                        }
+                    }
+                    tokio::task::yield_now().await;
+                }
+            })
+        })
+        .collect();
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    addr.stop();
+
+    let mut sent = 0;
+    for sender in senders {
+        sent += sender.await.unwrap();
+    }
+    // Every send that claimed to be delivered was
+    assert_eq!(tally.recv().await.unwrap(), sent);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
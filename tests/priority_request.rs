@@ -0,0 +1,131 @@
+use agency::{prelude::*, RequestError, RequestTimeoutError};
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+enum Msg {
+    Work,
+    /// Answered with how much work had been done by then.
+    State(Request<(), usize>),
+}
+
+impl From<Request<(), usize>> for Msg {
+    fn from(request: Request<(), usize>) -> Self {
+        Self::State(request)
+    }
+}
+
+/// Does its work once its gate has opened, answering queries about it as it goes.
+struct Busy {
+    gate: Option<oneshot::Receiver<()>>,
+    done: usize,
+}
+
+#[async_trait]
+impl Actor for Busy {
+    type Msg = Msg;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        if let Some(gate) = self.gate.take() {
+            let _ = gate.await;
+        }
+        match ctx.message().await {
+            Msg::Work => self.done += 1,
+            Msg::State(request) => {
+                let _ = request.respond(self.done);
+            }
+        }
+    }
+}
+
+/// A busy actor with a full mailbox, and a sender blocked waiting for room in it.
+async fn saturated(
+    agency: &Agency,
+) -> (Addr<Busy>, oneshot::Sender<()>, tokio::task::JoinHandle<()>) {
+    let (open, gate) = oneshot::channel();
+    let addr = agency
+        .hire_builder(Busy {
+            gate: Some(gate),
+            done: 0,
+        })
+        .capacity(2)
+        .hire();
+    addr.send(Msg::Work).await.ok().unwrap();
+    addr.send(Msg::Work).await.ok().unwrap();
+    let blocked = tokio::spawn({
+        let addr = addr.clone();
+        async move { addr.send(Msg::Work).await.ok().unwrap() }
+    });
+    tokio::task::yield_now().await;
+    assert!(!blocked.is_finished());
+    (addr, open, blocked)
+}
+
+#[tokio::test]
+async fn priority_requests_skip_a_full_mailbox() {
+    let (agency, handle) = Agency::new();
+    let (addr, open, blocked) = saturated(&agency).await;
+
+    let query = tokio::spawn({
+        let addr = addr.clone();
+        async move { addr.request_priority::<_, usize>(()).await }
+    });
+    while addr.priority_depth() == 0 {
+        tokio::task::yield_now().await;
+    }
+    assert!(!blocked.is_finished());
+    open.send(()).unwrap();
+    // Answered before any of the queued work, let alone the blocked sender's
+    assert_eq!(query.await.unwrap(), Ok(0));
+
+    blocked.await.unwrap();
+    assert_eq!(addr.request(()).await, Ok(3));
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn recipients_make_priority_requests_too() {
+    let (agency, handle) = Agency::new();
+    let (addr, open, blocked) = saturated(&agency).await;
+    let recipient: Recipient<Request<(), usize>> = addr.clone().recipient();
+
+    let query = tokio::spawn(async move {
+        recipient
+            .request_priority_timeout((), Duration::from_secs(5))
+            .await
+    });
+    while addr.priority_depth() == 0 {
+        tokio::task::yield_now().await;
+    }
+    open.send(()).unwrap();
+    assert_eq!(query.await.unwrap(), Ok(0));
+
+    blocked.await.unwrap();
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn priority_requests_still_time_out_and_fail() {
+    let (agency, handle) = Agency::new();
+    let (addr, open, blocked) = saturated(&agency).await;
+
+    let res: Result<usize, _> = addr
+        .request_priority_timeout((), Duration::from_secs(1))
+        .await;
+    assert_eq!(res, Err(RequestTimeoutError::Timeout));
+    open.send(()).unwrap();
+    blocked.await.unwrap();
+
+    addr.stop();
+    addr.watch().await;
+    let res: Result<usize, _> = addr.request_priority(()).await;
+    assert_eq!(res, Err(RequestError::ActorStopped));
+    let res: Result<usize, _> = addr
+        .request_priority_timeout((), Duration::from_secs(1))
+        .await;
+    assert_eq!(res, Err(RequestTimeoutError::ActorStopped));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
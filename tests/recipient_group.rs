@@ -0,0 +1,143 @@
+use agency::{prelude::*, RecipientGroup, SharedRecipient};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// A stand in for a large event.
+struct Snapshot(Vec<u8>);
+
+/// Hands each snapshot it's sent back to the test.
+struct Subscriber(mpsc::UnboundedSender<Arc<Snapshot>>);
+
+#[async_trait]
+impl Actor for Subscriber {
+    type Msg = Arc<Snapshot>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let _ = self.0.send(ctx.message().await);
+    }
+}
+
+enum Event {
+    Snapshot(Arc<Snapshot>),
+}
+
+impl From<Arc<Snapshot>> for Event {
+    fn from(snapshot: Arc<Snapshot>) -> Self {
+        Self::Snapshot(snapshot)
+    }
+}
+
+/// Like [`Subscriber`], but taking snapshots as one of its own events.
+struct EventSubscriber(mpsc::UnboundedSender<Arc<Snapshot>>);
+
+#[async_trait]
+impl Actor for EventSubscriber {
+    type Msg = Event;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let Event::Snapshot(snapshot) = ctx.message().await;
+        let _ = self.0.send(snapshot);
+    }
+}
+
+async fn received(
+    rx: &mut mpsc::UnboundedReceiver<Arc<Snapshot>>,
+    count: usize,
+) -> Vec<Arc<Snapshot>> {
+    let mut received = Vec::new();
+    for _ in 0..count {
+        received.push(rx.recv().await.unwrap());
+    }
+    received
+}
+
+#[tokio::test]
+async fn shared_members_all_see_the_same_allocation() {
+    let (agency, handle) = Agency::new();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut group = RecipientGroup::new();
+    for _ in 0..5 {
+        assert!(group.add(SharedRecipient::new(agency.hire(Subscriber(tx.clone())))));
+    }
+
+    let snapshot = Arc::new(Snapshot(vec![0; 1024 * 1024]));
+    assert_eq!(group.send_shared(snapshot.clone()).await, 5);
+    let received = received(&mut rx, 5).await;
+    assert!(received.iter().all(|seen| Arc::ptr_eq(seen, &snapshot)));
+    assert_eq!(Arc::strong_count(&snapshot), 6);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn members_converting_from_an_arc_share_it_too() {
+    let (agency, handle) = Agency::new();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut group: RecipientGroup<Event> = RecipientGroup::new();
+    for _ in 0..3 {
+        group.add(agency.hire(EventSubscriber(tx.clone())));
+    }
+
+    let snapshot = Arc::new(Snapshot(vec![1; 1024]));
+    assert_eq!(group.send_shared(snapshot.clone()).await, 3);
+    let received = received(&mut rx, 3).await;
+    assert!(received.iter().all(|seen| Arc::ptr_eq(seen, &snapshot)));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn sending_an_arc_only_clones_the_pointer() {
+    let (agency, handle) = Agency::new();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut group = RecipientGroup::new();
+    for _ in 0..3 {
+        group.add(agency.hire(Subscriber(tx.clone())));
+    }
+
+    let snapshot = Arc::new(Snapshot(vec![2; 1024]));
+    assert_eq!(group.send(snapshot.clone()).await, 3);
+    let received = received(&mut rx, 3).await;
+    assert!(received.iter().all(|seen| Arc::ptr_eq(seen, &snapshot)));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn stopped_members_are_dropped_from_shared_sends() {
+    let (agency, handle) = Agency::new();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let stopped = agency.hire(Subscriber(tx.clone()));
+    let mut group = RecipientGroup::new();
+    group.add(SharedRecipient::new(stopped.clone()));
+    group.add(SharedRecipient::new(agency.hire(Subscriber(tx))));
+    stopped.stop();
+    stopped.watch().await;
+
+    let snapshot = Arc::new(Snapshot(Vec::new()));
+    assert_eq!(group.send_shared(snapshot.clone()).await, 1);
+    assert_eq!(group.ids().len(), 1);
+    assert!(!group.ids().contains(&stopped.id()));
+    assert!(Arc::ptr_eq(&rx.recv().await.unwrap(), &snapshot));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn shared_recipients_share_unshared_events_once() {
+    let (agency, handle) = Agency::new();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let recipient = SharedRecipient::new(agency.hire(Subscriber(tx)));
+
+    recipient.send(Snapshot(vec![3; 16])).await.ok().unwrap();
+    let snapshot = rx.recv().await.unwrap();
+    assert_eq!(snapshot.0, vec![3; 16]);
+    assert_eq!(Arc::strong_count(&snapshot), 1);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,143 @@
+use agency::{prelude::*, DeliveryError};
+use std::{collections::HashSet, convert::TryFrom};
+use tokio::sync::mpsc;
+
+/// A message type from somewhere else, that can't be given an `Into` impl for `Msg`.
+struct Reading {
+    sensor: &'static str,
+    value: i64,
+}
+
+#[derive(Debug, PartialEq)]
+enum Msg {
+    Temperature(i64),
+    Humidity(u8),
+}
+
+/// Records everything it's sent.
+struct Station(mpsc::UnboundedSender<Msg>);
+
+#[async_trait]
+impl Actor for Station {
+    type Msg = Msg;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let _ = self.0.send(ctx.message().await);
+    }
+}
+
+fn hire(agency: &Agency) -> (Addr<Station>, mpsc::UnboundedReceiver<Msg>) {
+    let (seen, rx) = mpsc::unbounded_channel();
+    (agency.hire(Station(seen)), rx)
+}
+
+/// Only the readings the station knows what to do with.
+fn known(reading: Reading) -> Option<Msg> {
+    match reading.sensor {
+        "temperature" => Some(Msg::Temperature(reading.value)),
+        "humidity" => u8::try_from(reading.value).ok().map(Msg::Humidity),
+        _ => None,
+    }
+}
+
+#[tokio::test]
+async fn mapped_recipients_deliver_the_converted_message() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut seen) = hire(&agency);
+    let celsius: Recipient<i64> = addr.clone().recipient_map(Msg::Temperature);
+
+    celsius.send(21).await.unwrap();
+    assert_eq!(seen.recv().await, Some(Msg::Temperature(21)));
+    celsius.try_send(22).unwrap();
+    assert_eq!(seen.recv().await, Some(Msg::Temperature(22)));
+    celsius.send_priority(23).unwrap();
+    assert_eq!(seen.recv().await, Some(Msg::Temperature(23)));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn filtered_out_messages_are_dropped_without_an_error() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut seen) = hire(&agency);
+    let readings: Recipient<Reading> = Recipient::<Msg>::from(addr.clone()).filter_map(known);
+
+    let unknown = Reading {
+        sensor: "wind",
+        value: 3,
+    };
+    assert!(readings.send(unknown).await.is_ok());
+    let out_of_range = Reading {
+        sensor: "humidity",
+        value: 300,
+    };
+    assert!(readings.try_send(out_of_range).is_ok());
+    readings
+        .send(Reading {
+            sensor: "humidity",
+            value: 40,
+        })
+        .await
+        .unwrap();
+
+    // Only the one that converted arrives
+    assert_eq!(seen.recv().await, Some(Msg::Humidity(40)));
+    assert!(seen.try_recv().is_err());
+    assert_eq!(addr.mailbox_len(), 0);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn adapted_recipients_compare_equal_to_the_actor() {
+    let (agency, handle) = Agency::new();
+    let (addr, _seen) = hire(&agency);
+    let (other, _other_seen) = hire(&agency);
+    let plain = Recipient::<Msg>::from(addr.clone());
+    let celsius: Recipient<i64> = addr.clone().recipient_map(Msg::Temperature);
+    let kelvin: Recipient<i64> = addr
+        .clone()
+        .recipient_map(|kelvin| Msg::Temperature(kelvin - 273));
+    let elsewhere: Recipient<i64> = other.recipient_map(Msg::Temperature);
+
+    assert_eq!(celsius.id(), plain.id());
+    assert!(celsius == kelvin);
+    assert!(celsius != elsewhere);
+    let unique: HashSet<_> = vec![celsius.clone(), kelvin, celsius.clone(), elsewhere]
+        .into_iter()
+        .collect();
+    assert_eq!(unique.len(), 2);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn sending_to_a_stopped_actor_hands_the_original_message_back() {
+    let (agency, handle) = Agency::new();
+    let (addr, _seen) = hire(&agency);
+    let readings: Recipient<Reading> = Recipient::<Msg>::from(addr.clone()).filter_map(known);
+    addr.stop();
+    addr.watch().await;
+
+    let reading = Reading {
+        sensor: "temperature",
+        value: 21,
+    };
+    match readings.send(reading).await {
+        Err(DeliveryError::Closed(reading)) => assert_eq!(reading.value, 21),
+        _ => panic!("sent to a stopped actor"),
+    }
+    assert!(matches!(
+        readings.send_priority(Reading {
+            sensor: "wind",
+            value: 3,
+        }),
+        Err(DeliveryError::Closed(_))
+    ));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,66 @@
+use agency::prelude::*;
+use tokio::sync::mpsc;
+
+/// A distinct message type per `N`, each arriving as its number.
+struct Tag<const N: u8>;
+
+impl<const N: u8> From<Tag<N>> for u8 {
+    fn from(_: Tag<N>) -> Self {
+        N
+    }
+}
+
+struct Sink(mpsc::UnboundedSender<u8>);
+
+#[async_trait]
+impl Actor for Sink {
+    type Msg = u8;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let _ = self.0.send(ctx.message().await);
+    }
+}
+
+macro_rules! check_arity {
+    ($addr:expr, $rx:expr, $($n:literal => $r:ident),+) => {{
+        let ($($r,)+) = $addr.recipients::<($(Tag<$n>,)+)>();
+        $(
+            assert_eq!($r.id(), $addr.id());
+            $r.send(Tag::<$n>).await.unwrap();
+            assert_eq!($rx.recv().await, Some($n));
+        )+
+    }};
+}
+
+#[tokio::test]
+async fn every_arity_shares_the_addrs_identity() {
+    let (agency, handle) = Agency::new();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let addr = agency.hire(Sink(tx));
+
+    check_arity!(addr, rx, 1 => a);
+    check_arity!(addr, rx, 1 => a, 2 => b);
+    check_arity!(addr, rx, 1 => a, 2 => b, 3 => c);
+    check_arity!(addr, rx, 1 => a, 2 => b, 3 => c, 4 => d);
+    check_arity!(addr, rx, 1 => a, 2 => b, 3 => c, 4 => d, 5 => e);
+    check_arity!(addr, rx, 1 => a, 2 => b, 3 => c, 4 => d, 5 => e, 6 => f);
+    check_arity!(addr, rx, 1 => a, 2 => b, 3 => c, 4 => d, 5 => e, 6 => f, 7 => g);
+    check_arity!(addr, rx, 1 => a, 2 => b, 3 => c, 4 => d, 5 => e, 6 => f, 7 => g, 8 => h);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn recipients_compare_equal_to_the_addrs_own() {
+    let (agency, handle) = Agency::new();
+    let (tx, _rx) = mpsc::unbounded_channel();
+    let addr = agency.hire(Sink(tx));
+
+    let (a, b) = addr.recipients::<(Tag<1>, Tag<1>)>();
+    assert!(a == b);
+    assert!(a == addr.clone().recipient::<Tag<1>>());
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
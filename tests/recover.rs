@@ -0,0 +1,139 @@
+use agency::{prelude::*, PanicInfo};
+use tokio::sync::{mpsc, oneshot};
+
+/// Reports each message it handles, stopping itself on 0 and panicking on 13. Recovers the first
+/// time either way, but only once it's been let out of `stopping`, optionally taking one message
+/// there first.
+struct Resilient {
+    seen: mpsc::UnboundedSender<u32>,
+    stopping: Option<oneshot::Sender<()>>,
+    release: Option<oneshot::Receiver<()>>,
+    take_while_stopping: bool,
+}
+
+impl Resilient {
+    async fn deliberate(&mut self, ctx: &mut Context<Self>) -> StoppingResult {
+        let release = match self.release.take() {
+            Some(release) => release,
+            None => return StoppingResult::Stop,
+        };
+        let _ = self.stopping.take().unwrap().send(());
+        let _ = release.await;
+        if self.take_while_stopping {
+            let msg = ctx.message().await;
+            let _ = self.seen.send(1000 + msg);
+        }
+        StoppingResult::Recover
+    }
+}
+
+#[async_trait]
+impl Actor for Resilient {
+    type Msg = u32;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        match ctx.message().await {
+            0 => ctx.stop(),
+            13 => panic!("unlucky"),
+            msg => {
+                let _ = self.seen.send(msg);
+            }
+        }
+    }
+
+    async fn stopping(&mut self, ctx: &mut Context<Self>) -> StoppingResult {
+        self.deliberate(ctx).await
+    }
+
+    async fn on_panic(&mut self, ctx: &mut Context<Self>, _panic: PanicInfo) -> StoppingResult {
+        self.deliberate(ctx).await
+    }
+}
+
+struct Hired {
+    addr: Addr<Resilient>,
+    seen: mpsc::UnboundedReceiver<u32>,
+    stopping: oneshot::Receiver<()>,
+    release: oneshot::Sender<()>,
+}
+
+fn hire(agency: &Agency, take_while_stopping: bool) -> Hired {
+    let (seen, seen_rx) = mpsc::unbounded_channel();
+    let (stopping, stopping_rx) = oneshot::channel();
+    let (release, release_rx) = oneshot::channel();
+    let addr = agency.hire(Resilient {
+        seen,
+        stopping: Some(stopping),
+        release: Some(release_rx),
+        take_while_stopping,
+    });
+    Hired {
+        addr,
+        seen: seen_rx,
+        stopping: stopping_rx,
+        release,
+    }
+}
+
+async fn received(seen: &mut mpsc::UnboundedReceiver<u32>, n: usize) -> Vec<u32> {
+    let mut received = Vec::new();
+    for _ in 0..n {
+        received.push(seen.recv().await.unwrap());
+    }
+    received
+}
+
+/// Sends `trigger` with two messages queued behind it, then two more, one of them priority,
+/// while the actor is deciding whether to recover.
+async fn interleave(agency: &Agency, trigger: u32, take_while_stopping: bool) -> Vec<u32> {
+    let Hired {
+        addr,
+        mut seen,
+        stopping,
+        release,
+    } = hire(agency, take_while_stopping);
+    addr.send(1u32).await.unwrap();
+    addr.send(trigger).await.unwrap();
+    addr.send(2u32).await.unwrap();
+    addr.send(3u32).await.unwrap();
+
+    stopping.await.unwrap();
+    addr.send(4u32).await.unwrap();
+    addr.send_priority(5u32).unwrap();
+    release.send(()).unwrap();
+
+    let order = received(&mut seen, 5).await;
+    // Still running afterwards
+    addr.send(6u32).await.unwrap();
+    assert_eq!(seen.recv().await, Some(6));
+    order
+}
+
+#[tokio::test]
+async fn nothing_is_lost_across_a_recover_from_stopping() {
+    let (agency, handle) = Agency::new();
+    // Priority messages sent while stopping still go first, then the regular ones in arrival order
+    assert_eq!(interleave(&agency, 0, false).await, vec![1, 5, 2, 3, 4]);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn nothing_is_lost_across_a_recover_from_a_panic() {
+    let (agency, handle) = Agency::new();
+    assert_eq!(interleave(&agency, 13, false).await, vec![1, 5, 2, 3, 4]);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn messages_taken_while_stopping_are_not_seen_again() {
+    let (agency, handle) = Agency::new();
+    // `stopping` takes the priority message, and the recovered run loop carries on from there
+    assert_eq!(interleave(&agency, 0, true).await, vec![1, 1005, 2, 3, 4]);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,145 @@
+use agency::{prelude::*, NameTaken};
+use tokio::sync::mpsc;
+
+/// Forwards whatever it's sent, tagged with its own tag.
+struct Tagged<M> {
+    tag: &'static str,
+    seen: mpsc::UnboundedSender<(&'static str, M)>,
+}
+
+#[async_trait]
+impl<M> Actor for Tagged<M>
+where
+    M: 'static + Send + Sync,
+{
+    type Msg = M;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let _ = self.seen.send((self.tag, ctx.message().await));
+    }
+}
+
+fn tagged<M>(tag: &'static str) -> (Tagged<M>, mpsc::UnboundedReceiver<(&'static str, M)>) {
+    let (seen, rx) = mpsc::unbounded_channel();
+    (Tagged { tag, seen }, rx)
+}
+
+#[tokio::test]
+async fn lookups_find_registered_recipients_from_any_clone_of_the_agency() {
+    let (agency, handle) = Agency::new();
+    let (metrics, mut seen) = tagged::<u32>("metrics");
+    let addr = agency.hire(metrics);
+    assert!(agency
+        .register("metrics", addr.recipient::<u32>())
+        .is_none());
+
+    let clone = agency.clone();
+    let recipient = clone.lookup::<u32>("metrics").unwrap();
+    recipient.send(1u32).await.unwrap();
+    assert_eq!(seen.recv().await, Some(("metrics", 1)));
+
+    assert!(agency.lookup::<u32>("logs").is_none());
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn lookups_with_the_wrong_message_type_find_nothing() {
+    let (agency, handle) = Agency::new();
+    let (metrics, _seen) = tagged::<u32>("metrics");
+    agency.hire_named("metrics", metrics).unwrap();
+
+    assert!(agency.lookup::<String>("metrics").is_none());
+    assert!(agency.deregister::<String>("metrics").is_none());
+    assert!(agency.lookup::<u32>("metrics").is_some());
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn names_can_only_be_hired_under_once_per_message_type() {
+    let (agency, handle) = Agency::new();
+    let (first, mut first_seen) = tagged::<u32>("first");
+    let (second, _second_seen) = tagged::<u32>("second");
+    let (logs, mut logs_seen) = tagged::<String>("logs");
+
+    let first = agency.hire_named("metrics", first).unwrap();
+    let NameTaken(second) = agency.hire_named("metrics", second).err().unwrap();
+    assert_eq!(second.tag, "second");
+    agency.hire_named("metrics", logs).unwrap();
+
+    agency
+        .lookup::<u32>("metrics")
+        .unwrap()
+        .send(1u32)
+        .await
+        .unwrap();
+    assert_eq!(first_seen.recv().await, Some(("first", 1)));
+    agency
+        .lookup::<String>("metrics")
+        .unwrap()
+        .send("hello".to_string())
+        .await
+        .unwrap();
+    assert_eq!(logs_seen.recv().await, Some(("logs", "hello".to_string())));
+
+    // Registering takes the name over, handing back what was there
+    let (third, mut third_seen) = tagged::<u32>("third");
+    let third = agency.hire(third);
+    let replaced = agency
+        .register("metrics", third.recipient::<u32>())
+        .unwrap();
+    assert_eq!(replaced.id(), first.id());
+    agency
+        .lookup::<u32>("metrics")
+        .unwrap()
+        .send(2u32)
+        .await
+        .unwrap();
+    assert_eq!(third_seen.recv().await, Some(("third", 2)));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn stopped_actors_are_forgotten() {
+    let (agency, handle) = Agency::new();
+    let (first, _first_seen) = tagged::<u32>("first");
+    let first = agency.hire_named("metrics", first).unwrap();
+
+    first.stop();
+    first.watch().await;
+    assert!(agency.lookup::<u32>("metrics").is_none());
+
+    // Which frees the name up again
+    let (second, mut second_seen) = tagged::<u32>("second");
+    agency.hire_named("metrics", second).unwrap();
+    agency
+        .lookup::<u32>("metrics")
+        .unwrap()
+        .send(1u32)
+        .await
+        .unwrap();
+    assert_eq!(second_seen.recv().await, Some(("second", 1)));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn deregistering_hands_back_the_recipient() {
+    let (agency, handle) = Agency::new();
+    let (metrics, _seen) = tagged::<u32>("metrics");
+    let addr = agency.hire_named("metrics", metrics).unwrap();
+
+    let recipient = agency.deregister::<u32>("metrics").unwrap();
+    assert_eq!(recipient.id(), addr.id());
+    assert!(agency.lookup::<u32>("metrics").is_none());
+    assert!(agency.deregister::<u32>("metrics").is_none());
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
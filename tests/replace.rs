@@ -0,0 +1,99 @@
+use agency::{prelude::*, PanicInfo};
+
+/// Answers each request with its tag, so it's clear which actor answered. Recovers once if its
+/// init panics.
+struct Tagged {
+    tag: &'static str,
+    panic_in_init: bool,
+}
+
+impl Tagged {
+    fn new(tag: &'static str) -> Self {
+        Self {
+            tag,
+            panic_in_init: false,
+        }
+    }
+}
+
+#[async_trait]
+impl Actor for Tagged {
+    type Msg = Request<u32, (&'static str, u32)>;
+
+    async fn init(&mut self, _ctx: &mut Context<Self>) {
+        if self.panic_in_init {
+            panic!("init failed");
+        }
+    }
+
+    async fn on_panic(&mut self, _ctx: &mut Context<Self>, _panic: PanicInfo) -> StoppingResult {
+        if self.panic_in_init {
+            self.panic_in_init = false;
+            StoppingResult::Recover
+        } else {
+            StoppingResult::Stop
+        }
+    }
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        if let Some((n, reply_to)) = ctx.message().await.handle() {
+            let _ = reply_to.send((self.tag, n));
+        }
+    }
+}
+
+#[tokio::test]
+async fn messages_sent_around_a_swap_are_all_answered() {
+    let (agency, handle) = Agency::new();
+    let addr = agency.hire(Tagged::new("old"));
+
+    assert_eq!(addr.request(0).await.unwrap(), ("old", 0));
+
+    let during: Vec<_> = (1..=10)
+        .map(|n| {
+            let addr = addr.clone();
+            tokio::spawn(async move { addr.request(n).await })
+        })
+        .collect();
+    tokio::task::yield_now().await;
+    agency.replace(&addr, Tagged::new("new")).ok().unwrap();
+    for (n, res) in (1..=10).zip(during) {
+        let (tag, answered) = res.await.unwrap().unwrap();
+        assert!(tag == "old" || tag == "new");
+        assert_eq!(answered, n);
+    }
+
+    assert_eq!(addr.request(11).await.unwrap(), ("new", 11));
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn replacing_a_stopped_actor_hands_it_back() {
+    let (agency, handle) = Agency::new();
+    let addr = agency.hire(Tagged::new("old"));
+    addr.stop();
+    addr.watch().await;
+
+    let refused = agency.replace(&addr, Tagged::new("new")).unwrap_err();
+    assert_eq!(refused.tag, "new");
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn a_replacement_that_panics_in_init_goes_through_on_panic() {
+    let (agency, handle) = Agency::new();
+    let addr = agency.hire(Tagged::new("old"));
+    assert_eq!(addr.request(0).await.unwrap(), ("old", 0));
+
+    let replacement = Tagged {
+        tag: "new",
+        panic_in_init: true,
+    };
+    agency.replace(&addr, replacement).ok().unwrap();
+
+    assert_eq!(addr.request(1).await.unwrap(), ("new", 1));
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,57 @@
+use agency::prelude::*;
+use tokio::sync::mpsc;
+
+/// Reports each message it handles.
+struct Recorder(mpsc::UnboundedSender<u32>);
+
+#[async_trait]
+impl Actor for Recorder {
+    type Msg = u32;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let _ = self.0.send(ctx.message().await);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn the_replayed_backlog_is_handled_before_anything_sent() {
+    let (agency, handle) = Agency::new();
+    let (seen, mut rx) = mpsc::unbounded_channel();
+    let addr = agency.hire_with_replay(Recorder(seen), (1..=5).collect());
+
+    let senders: Vec<_> = (0..2u32)
+        .map(|sender| {
+            let addr = addr.clone();
+            tokio::spawn(async move {
+                for n in 0..5 {
+                    addr.send(100 + sender * 10 + n).await.unwrap();
+                }
+            })
+        })
+        .collect();
+    addr.send_priority(99u32).unwrap();
+
+    let mut order = Vec::new();
+    for _ in 0..16 {
+        order.push(rx.recv().await.unwrap());
+    }
+    assert_eq!(order[..5], [1, 2, 3, 4, 5]);
+    // Everything sent afterwards still arrives, in order for each sender
+    let mut rest = order[5..].to_vec();
+    for sender in [100, 110] {
+        let sent: Vec<_> = rest
+            .iter()
+            .copied()
+            .filter(|n| n / 10 * 10 == sender)
+            .collect();
+        assert_eq!(sent, (sender..sender + 5).collect::<Vec<_>>());
+    }
+    rest.sort_unstable();
+    assert_eq!(rest[0], 99);
+
+    for sender in senders {
+        sender.await.unwrap();
+    }
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,152 @@
+use agency::prelude::*;
+use futures_util::FutureExt;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use tokio::sync::{mpsc, oneshot};
+
+enum Msg {
+    Inline(Request<u32, u32>),
+    WithContext(Request<u32, u32>),
+    Spawned(Request<u32, u32>, oneshot::Receiver<()>),
+}
+
+struct Doubler {
+    delivered: mpsc::UnboundedSender<bool>,
+    built: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl Actor for Doubler {
+    type Msg = Msg;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        loop {
+            match ctx.message().await {
+                Msg::Inline(request) => {
+                    let built = self.built.clone();
+                    let delivered = ctx
+                        .reply(request, |n| {
+                            built.store(true, Ordering::SeqCst);
+                            async move { n * 2 }
+                        })
+                        .await;
+                    let _ = self.delivered.send(delivered);
+                }
+                Msg::WithContext(request) => {
+                    let delivered = ctx
+                        .reply_with(request, |ctx, n| {
+                            async move {
+                                // The context is still usable while the response is computed
+                                assert!(ctx.stop_reason().is_none());
+                                n * 2
+                            }
+                            .boxed()
+                        })
+                        .await;
+                    let _ = self.delivered.send(delivered);
+                }
+                Msg::Spawned(request, release) => {
+                    ctx.reply_spawned(request, |n| async move {
+                        let _ = release.await;
+                        n * 2
+                    });
+                }
+            }
+        }
+    }
+}
+
+fn hire(
+    agency: &Agency,
+) -> (
+    Addr<Doubler>,
+    mpsc::UnboundedReceiver<bool>,
+    Arc<AtomicBool>,
+) {
+    let (delivered, rx) = mpsc::unbounded_channel();
+    let built = Arc::new(AtomicBool::new(false));
+    let addr = agency.hire(Doubler {
+        delivered,
+        built: built.clone(),
+    });
+    (addr, rx, built)
+}
+
+fn request(n: u32) -> (Request<u32, u32>, oneshot::Receiver<u32>) {
+    let (tx, rx) = oneshot::channel();
+    (Request::from_parts(n, tx), rx)
+}
+
+#[tokio::test]
+async fn inline_replies_are_delivered() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut delivered, built) = hire(&agency);
+
+    let (req, res) = request(21);
+    addr.send(Msg::Inline(req)).await.unwrap();
+    assert_eq!(res.await.unwrap(), 42);
+    assert_eq!(delivered.recv().await, Some(true));
+    assert!(built.load(Ordering::SeqCst));
+
+    let (req, res) = request(5);
+    addr.send(Msg::WithContext(req)).await.unwrap();
+    assert_eq!(res.await.unwrap(), 10);
+    assert_eq!(delivered.recv().await, Some(true));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn spawned_replies_do_not_block_the_actor() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut delivered, _) = hire(&agency);
+
+    let (release, released) = oneshot::channel();
+    let (slow, slow_res) = request(4);
+    addr.send(Msg::Spawned(slow, released)).await.unwrap();
+
+    // The actor carries on with the next message while the spawned reply is still waiting
+    let (fast, fast_res) = request(1);
+    addr.send(Msg::Inline(fast)).await.unwrap();
+    assert_eq!(fast_res.await.unwrap(), 2);
+    assert_eq!(delivered.recv().await, Some(true));
+
+    release.send(()).unwrap();
+    assert_eq!(slow_res.await.unwrap(), 8);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn replies_to_a_gone_requester_are_suppressed() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut delivered, built) = hire(&agency);
+
+    let (req, res) = request(1);
+    drop(res);
+    addr.send(Msg::Inline(req)).await.unwrap();
+    assert_eq!(delivered.recv().await, Some(false));
+    assert!(
+        !built.load(Ordering::SeqCst),
+        "future built for a closed request"
+    );
+
+    let (req, res) = request(1);
+    drop(res);
+    addr.send(Msg::WithContext(req)).await.unwrap();
+    assert_eq!(delivered.recv().await, Some(false));
+
+    // The spawned future is dropped once the requester goes away, even though it never finishes
+    let (mut release, released) = oneshot::channel();
+    let (req, res) = request(1);
+    addr.send(Msg::Spawned(req, released)).await.unwrap();
+    drop(res);
+    release.closed().await;
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
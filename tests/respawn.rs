@@ -0,0 +1,51 @@
+use agency::{prelude::*, RespawnError};
+
+/// Answers each request with its incarnation.
+struct Singleton(u32);
+
+#[async_trait]
+impl Actor for Singleton {
+    type Msg = Request<(), u32>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let _ = ctx.message().await.respond(self.0);
+    }
+}
+
+#[tokio::test]
+async fn old_addresses_deliver_to_the_new_incarnation() {
+    let (agency, handle) = Agency::new();
+    let addr = agency.hire(Singleton(1));
+    let old = addr.clone();
+    let old_recipient: Recipient<Request<(), u32>> = addr.clone().recipient();
+    assert_eq!(old.request(()).await.unwrap(), 1);
+
+    addr.stop();
+    addr.watch().await;
+    // Sends in the gap fail, and stay failed
+    assert!(old.request(()).await.is_err());
+
+    agency.respawn(&addr, Singleton(2)).ok().unwrap();
+    assert_eq!(old.request(()).await.unwrap(), 2);
+    assert_eq!(old_recipient.request(()).await.unwrap(), 2);
+    assert_eq!(old.id(), addr.id());
+    assert!(!old.is_stopped());
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn respawning_a_running_actor_hands_it_back() {
+    let (agency, handle) = Agency::new();
+    let addr = agency.hire(Singleton(1));
+
+    match agency.respawn(&addr, Singleton(2)) {
+        Err(RespawnError(actor)) => assert_eq!(actor.0, 2),
+        Ok(()) => panic!("respawned a running actor"),
+    }
+    assert_eq!(addr.request(()).await.unwrap(), 1);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,73 @@
+use agency::{prelude::*, Observer, ResponseUndelivered};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, oneshot};
+
+/// Records the response types that couldn't be delivered.
+#[derive(Clone, Default)]
+struct Undelivered(Arc<Mutex<Vec<&'static str>>>);
+
+impl Observer for Undelivered {
+    fn response_undelivered(&self, event: &ResponseUndelivered) {
+        self.0.lock().unwrap().push(event.response);
+    }
+}
+
+/// Takes a request, then waits to be released before responding, reporting whatever response
+/// comes back undelivered.
+struct Slow {
+    taken: mpsc::UnboundedSender<()>,
+    release: mpsc::UnboundedReceiver<()>,
+    returned: Option<oneshot::Sender<Option<u32>>>,
+}
+
+#[async_trait]
+impl Actor for Slow {
+    type Msg = Request<u32, u32>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let request = ctx.message().await;
+        let n = *request.payload();
+        let _ = self.taken.send(());
+        self.release.recv().await;
+        let returned = request.respond(n * 2).err();
+        if let Some(report) = self.returned.take() {
+            let _ = report.send(returned);
+        }
+    }
+}
+
+#[tokio::test]
+async fn a_requester_dropping_after_the_request_is_taken_gets_counted() {
+    let undelivered = Undelivered::default();
+    let (agency, handle) = Agency::builder().observer(undelivered.clone()).build();
+    let (taken, mut on_taken) = mpsc::unbounded_channel();
+    let (release, released) = mpsc::unbounded_channel();
+    let (report, returned) = oneshot::channel();
+    let addr = agency.hire(Slow {
+        taken,
+        release: released,
+        returned: Some(report),
+    });
+
+    let requester = tokio::spawn({
+        let addr = addr.clone();
+        async move { addr.request(21u32).await }
+    });
+    on_taken.recv().await.unwrap();
+    requester.abort();
+    assert!(requester.await.unwrap_err().is_cancelled());
+    release.send(()).unwrap();
+
+    // The response comes back to the handler rather than vanishing
+    assert_eq!(returned.await.unwrap(), Some(42));
+    assert_eq!(addr.stats().undelivered_responses, 1);
+    assert_eq!(*undelivered.0.lock().unwrap(), vec!["u32"]);
+
+    // Responses that do get through aren't counted
+    release.send(()).unwrap();
+    assert_eq!(addr.request(1u32).await.unwrap(), 2);
+    assert_eq!(addr.stats().undelivered_responses, 1);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
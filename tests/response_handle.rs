@@ -0,0 +1,141 @@
+use agency::{prelude::*, RequestTimeoutError, ResponseHandle};
+use std::{collections::HashMap, time::Duration};
+use tokio::{sync::oneshot, time::Instant};
+
+/// The jobs being watched, with when their watchers stop waiting.
+type Parked = Vec<(u32, Option<Instant>)>;
+
+enum Msg {
+    /// Answered once the job completes.
+    Watch(Request<u32, String>),
+    Complete(u32),
+    /// Answered straight away.
+    Parked(Request<(), Parked>),
+}
+
+impl From<Request<u32, String>> for Msg {
+    fn from(request: Request<u32, String>) -> Self {
+        Self::Watch(request)
+    }
+}
+
+impl From<Request<(), Parked>> for Msg {
+    fn from(request: Request<(), Parked>) -> Self {
+        Self::Parked(request)
+    }
+}
+
+/// Parks a watch per job, answering each when a later message says the job is done.
+#[derive(Default)]
+struct Jobs {
+    watching: HashMap<u32, ResponseHandle<String>>,
+    gave_up: Vec<u32>,
+}
+
+#[async_trait]
+impl Actor for Jobs {
+    type Msg = Msg;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        match ctx.message().await {
+            Msg::Watch(request) => {
+                let (job, handle) = request.into_parts();
+                self.watching.insert(job, handle);
+            }
+            Msg::Complete(job) => {
+                if let Some(handle) = self.watching.remove(&job) {
+                    if handle.is_closed() {
+                        self.gave_up.push(job);
+                    }
+                    let _ = handle.respond(format!("job {} done", job));
+                }
+            }
+            Msg::Parked(request) => {
+                let mut parked: Vec<_> = self
+                    .watching
+                    .iter()
+                    .map(|(job, handle)| (*job, handle.deadline()))
+                    .collect();
+                parked.sort_unstable();
+                let _ = request.respond(parked);
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn parked_requests_are_answered_out_of_order() {
+    let (agency, handle) = Agency::new();
+    let (addr, jobs) = agency.hire_joinable(Jobs::default());
+
+    let watches: Vec<_> = (1..=3u32)
+        .map(|job| {
+            let addr = addr.clone();
+            tokio::spawn(async move { addr.request::<_, String>(job).await })
+        })
+        .collect();
+    loop {
+        let parked: Parked = addr.request(()).await.unwrap();
+        if parked.len() == 3 {
+            assert!(parked.iter().all(|(_, deadline)| deadline.is_none()));
+            break;
+        }
+        tokio::task::yield_now().await;
+    }
+
+    for job in [2, 3, 1] {
+        addr.send(Msg::Complete(job)).await.ok().unwrap();
+    }
+    for (job, watch) in (1..=3).zip(watches) {
+        assert_eq!(watch.await.unwrap().unwrap(), format!("job {} done", job));
+    }
+
+    addr.stop();
+    let jobs = jobs.join().await.ok().unwrap();
+    assert!(jobs.watching.is_empty());
+    assert!(jobs.gave_up.is_empty());
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn requesters_that_gave_up_are_noticed() {
+    let (agency, handle) = Agency::new();
+    let (addr, jobs) = agency.hire_joinable(Jobs::default());
+
+    let sent = Instant::now();
+    let res: Result<String, _> = addr.request_timeout(7u32, Duration::from_secs(1)).await;
+    assert_eq!(res, Err(RequestTimeoutError::Timeout));
+    let parked: Parked = addr.request(()).await.unwrap();
+    assert_eq!(parked, vec![(7, Some(sent + Duration::from_secs(1)))]);
+
+    addr.send(Msg::Complete(7)).await.ok().unwrap();
+    assert_eq!(addr.request::<_, Parked>(()).await.unwrap(), vec![]);
+    assert_eq!(addr.stats().undelivered_responses, 1);
+
+    addr.stop();
+    assert_eq!(jobs.join().await.ok().unwrap().gave_up, vec![7]);
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn handles_can_wait_for_the_requester_to_give_up() {
+    let (reply_to, response) = oneshot::channel::<u32>();
+    let (_, mut handle) = Request::from_parts((), reply_to).into_parts();
+    assert!(!handle.is_closed());
+    assert_eq!(handle.deadline(), None);
+    assert_eq!(handle.remaining_budget(), None);
+
+    let closed = tokio::spawn(async move {
+        handle.closed().await;
+        handle
+    });
+    tokio::task::yield_now().await;
+    assert!(!closed.is_finished());
+
+    drop(response);
+    let handle = closed.await.unwrap();
+    assert!(handle.is_closed());
+    assert_eq!(handle.respond(1), Err(1));
+}
@@ -0,0 +1,111 @@
+use agency::{prelude::*, TimerHandle};
+use std::time::Duration;
+use tokio::{sync::mpsc, time};
+
+enum Msg {
+    Push(u32),
+    FlushIn(Request<Duration, TimerHandle>),
+}
+
+impl From<Request<Duration, TimerHandle>> for Msg {
+    fn from(request: Request<Duration, TimerHandle>) -> Self {
+        Self::FlushIn(request)
+    }
+}
+
+/// Buffers numbers until a scheduled flush hands them on.
+struct Buffer {
+    pending: Vec<u32>,
+    flushed: mpsc::UnboundedSender<Vec<u32>>,
+}
+
+#[async_trait]
+impl Actor for Buffer {
+    type Msg = Msg;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        ctx.dispatch(self).await
+    }
+}
+
+#[async_trait]
+impl Handler for Buffer {
+    async fn handle(&mut self, ctx: &mut Context<Self>, msg: Msg) {
+        match msg {
+            Msg::Push(n) => self.pending.push(n),
+            Msg::FlushIn(request) => {
+                let delay = *request.payload();
+                let timer = ctx.run_later(delay, |buffer: &mut Buffer, _ctx| {
+                    let _ = buffer.flushed.send(std::mem::take(&mut buffer.pending));
+                });
+                let _ = request.respond(timer);
+            }
+        }
+    }
+}
+
+fn hire(agency: &Agency) -> (Addr<Buffer>, mpsc::UnboundedReceiver<Vec<u32>>) {
+    let (flushed, rx) = mpsc::unbounded_channel();
+    let addr = agency.hire(Buffer {
+        pending: Vec::new(),
+        flushed,
+    });
+    (addr, rx)
+}
+
+#[tokio::test(start_paused = true)]
+async fn closures_run_against_the_actor_after_the_delay() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut flushed) = hire(&agency);
+
+    addr.send(Msg::Push(1)).await.unwrap();
+    let _timer = addr.request(Duration::from_millis(100)).await.unwrap();
+    addr.send(Msg::Push(2)).await.unwrap();
+
+    time::sleep(Duration::from_millis(50)).await;
+    assert!(flushed.try_recv().is_err(), "flushed before the delay");
+    addr.send(Msg::Push(3)).await.unwrap();
+
+    // The closure sees everything buffered up to when it runs
+    assert_eq!(flushed.recv().await, Some(vec![1, 2, 3]));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn cancelled_closures_never_run() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut flushed) = hire(&agency);
+
+    addr.send(Msg::Push(1)).await.unwrap();
+    let cancelled = addr.request(Duration::from_millis(100)).await.unwrap();
+    let _kept = addr.request(Duration::from_millis(200)).await.unwrap();
+    cancelled.cancel();
+    assert!(cancelled.is_cancelled());
+
+    time::sleep(Duration::from_millis(150)).await;
+    assert!(flushed.try_recv().is_err(), "cancelled closure ran");
+    assert_eq!(flushed.recv().await, Some(vec![1]));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn pending_closures_are_dropped_when_the_actor_stops() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut flushed) = hire(&agency);
+
+    addr.send(Msg::Push(1)).await.unwrap();
+    let timer = addr.request(Duration::from_millis(100)).await.unwrap();
+    addr.stop();
+    addr.watch().await;
+    assert!(timer.is_cancelled());
+
+    time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(flushed.recv().await, None);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,124 @@
+use agency::{prelude::*, Cancel, Schedule, ScheduleId, Scheduler};
+use std::time::Duration;
+use tokio::{
+    sync::mpsc,
+    time::{self, Instant},
+};
+
+/// Reports each message it gets along with when it got it.
+struct Recorder(mpsc::UnboundedSender<(u32, Instant)>);
+
+#[async_trait]
+impl Actor for Recorder {
+    type Msg = u32;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let msg = ctx.message().await;
+        let _ = self.0.send((msg, Instant::now()));
+    }
+}
+
+fn recorder(agency: &Agency) -> (Recipient<u32>, mpsc::UnboundedReceiver<(u32, Instant)>) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    (agency.hire(Recorder(tx)).recipient(), rx)
+}
+
+#[tokio::test(start_paused = true)]
+async fn delivers_once_the_delay_has_passed() {
+    let (agency, handle) = Agency::new();
+    let (recipient, mut seen) = recorder(&agency);
+    let scheduler = agency.hire(Scheduler::new());
+    let start = Instant::now();
+
+    let _: ScheduleId = scheduler
+        .request(Schedule::after(
+            Duration::from_secs(10),
+            recipient.clone(),
+            1,
+        ))
+        .await
+        .unwrap();
+    scheduler
+        .request::<_, ScheduleId>(Schedule::at(start + Duration::from_secs(5), recipient, 2))
+        .await
+        .unwrap();
+
+    assert_eq!(seen.recv().await, Some((2, start + Duration::from_secs(5))));
+    assert_eq!(
+        seen.recv().await,
+        Some((1, start + Duration::from_secs(10)))
+    );
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn repeats_every_interval() {
+    let (agency, handle) = Agency::new();
+    let (recipient, mut seen) = recorder(&agency);
+    let scheduler = agency.hire(Scheduler::new());
+    let start = Instant::now();
+
+    let mut count = 0;
+    let schedule = Schedule::every(Duration::from_secs(5), recipient, move || {
+        count += 1;
+        count
+    });
+    scheduler.request::<_, ScheduleId>(schedule).await.unwrap();
+
+    for n in 1..=3 {
+        let at = start + Duration::from_secs(5 * u64::from(n));
+        assert_eq!(seen.recv().await, Some((n, at)));
+    }
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn cancelled_deliveries_are_never_made() {
+    let (agency, handle) = Agency::new();
+    let (recipient, mut seen) = recorder(&agency);
+    let scheduler = agency.hire(Scheduler::new());
+
+    let once: ScheduleId = scheduler
+        .request(Schedule::after(
+            Duration::from_secs(10),
+            recipient.clone(),
+            1,
+        ))
+        .await
+        .unwrap();
+    let repeating: ScheduleId = scheduler
+        .request(Schedule::every(Duration::from_secs(3), recipient, || 2))
+        .await
+        .unwrap();
+
+    assert_eq!(seen.recv().await.map(|(msg, _)| msg), Some(2));
+    assert!(scheduler.request(Cancel(once)).await.unwrap());
+    assert!(scheduler.request(Cancel(repeating)).await.unwrap());
+    assert!(!scheduler.request(Cancel(once)).await.unwrap());
+
+    time::sleep(Duration::from_secs(60)).await;
+    assert!(seen.try_recv().is_err());
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+#[should_panic(expected = "interval must be greater than zero")]
+async fn rejects_a_zero_interval() {
+    let (agency, _handle) = Agency::new();
+    let (recipient, _seen) = recorder(&agency);
+    let _ = Schedule::every(Duration::ZERO, recipient, || 1);
+}
+
+#[cfg(feature = "cron")]
+#[tokio::test]
+async fn cron_schedules_without_upcoming_times_are_refused() {
+    use std::str::FromStr;
+
+    let (agency, _handle) = Agency::new();
+    let (recipient, _seen) = recorder(&agency);
+    let past = cron::Schedule::from_str("0 0 0 1 1 * 2000").unwrap();
+    assert!(Schedule::cron(past, recipient, || 1).is_none());
+}
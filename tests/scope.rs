@@ -0,0 +1,117 @@
+use agency::prelude::*;
+use std::{future, time::Duration};
+use tokio::{
+    sync::{mpsc, oneshot},
+    time::{self, Instant},
+};
+
+struct Echo;
+
+#[async_trait]
+impl Actor for Echo {
+    type Msg = Request<u32, u32>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let request = ctx.message().await;
+        let n = *request.payload();
+        let _ = request.respond(n);
+    }
+}
+
+/// Never gets round to reading its mailbox, so it can only be aborted.
+struct Stuck;
+
+#[async_trait]
+impl Actor for Stuck {
+    type Msg = ();
+
+    async fn run(&mut self, _ctx: &mut Context<Self>) {
+        future::pending::<()>().await;
+    }
+}
+
+#[tokio::test]
+async fn scoped_actors_are_gone_once_the_scope_returns() {
+    let (agency, handle) = Agency::new();
+    let outside = agency.hire(Echo);
+
+    let (answer, helpers) = agency
+        .scope(|scoped| async move {
+            let helpers = vec![scoped.hire(Echo), scoped.hire(Echo)];
+            let mut answer = 0;
+            for helper in &helpers {
+                answer += helper.request(21).await.unwrap();
+            }
+            (answer, helpers)
+        })
+        .await;
+    assert_eq!(answer, 42);
+    assert!(helpers.iter().all(Addr::is_stopped));
+
+    // The agency the scope ran in carries on as before
+    assert_eq!(outside.request(1).await, Ok(1));
+    agency.scope(|_| async {}).await;
+    assert_eq!(agency.hire(Echo).request(2).await, Ok(2));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn cancelling_the_scope_stops_its_actors() {
+    let (agency, handle) = Agency::new();
+    let outside = agency.hire(Echo);
+    let (hired, mut helpers) = mpsc::unbounded_channel();
+    let (_never, cancelled) = oneshot::channel::<()>();
+
+    let scope = tokio::spawn({
+        let agency = agency.clone();
+        async move {
+            agency
+                .scope(|scoped| async move {
+                    for _ in 0..3 {
+                        hired.send(scoped.hire(Echo)).unwrap();
+                    }
+                    let _ = cancelled.await;
+                })
+                .await
+        }
+    });
+    let mut running = Vec::new();
+    for _ in 0..3 {
+        let helper = helpers.recv().await.unwrap();
+        assert_eq!(helper.request(7).await, Ok(7));
+        running.push(helper);
+    }
+
+    scope.abort();
+    assert!(scope.await.unwrap_err().is_cancelled());
+    for helper in &running {
+        time::timeout(Duration::from_secs(1), helper.watch())
+            .await
+            .expect("a scoped actor outlived its cancelled scope");
+    }
+
+    assert_eq!(outside.request(1).await, Ok(1));
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn actors_that_wont_stop_are_aborted_after_the_grace_period() {
+    let (agency, handle) = Agency::new();
+    let grace = Duration::from_millis(100);
+
+    let start = Instant::now();
+    let (stuck, echo) = agency
+        .scope_with_grace(grace, |scoped| async move {
+            (scoped.hire(Stuck), scoped.hire(Echo))
+        })
+        .await;
+    assert!(start.elapsed() >= grace);
+    assert!(stuck.is_stopped());
+    assert!(echo.is_stopped());
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,177 @@
+use agency::{prelude::*, Either};
+use std::{future, time::Duration};
+use tokio::{
+    sync::{mpsc, oneshot},
+    time::{self, Instant},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Source {
+    Mailbox,
+    Priority,
+    Side,
+}
+
+/// Races its mailbox against a channel of its own, reporting everything it gets from either.
+struct Racer {
+    side: Option<mpsc::UnboundedReceiver<u32>>,
+    seen: mpsc::UnboundedSender<(Source, u32)>,
+}
+
+#[async_trait]
+impl Actor for Racer {
+    type Msg = (Source, u32);
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let got = match &mut self.side {
+            Some(side) => match ctx.select(side.recv()).await {
+                Either::Left(msg) => msg,
+                Either::Right(Some(n)) => (Source::Side, n),
+                Either::Right(None) => {
+                    // Otherwise the closed channel would win every race from now on
+                    self.side = None;
+                    return;
+                }
+            },
+            None => ctx.message().await,
+        };
+        let _ = self.seen.send(got);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn nothing_is_lost_when_both_sides_are_ready() {
+    const N: u32 = 2000;
+
+    let (agency, handle) = Agency::new();
+    let (side, side_rx) = mpsc::unbounded_channel();
+    let (seen_tx, mut seen) = mpsc::unbounded_channel();
+    let addr = agency.hire(Racer {
+        side: Some(side_rx),
+        seen: seen_tx,
+    });
+
+    let senders = vec![
+        tokio::spawn({
+            let addr = addr.clone();
+            async move {
+                for n in 0..N {
+                    addr.send((Source::Mailbox, n)).await.ok().unwrap();
+                }
+            }
+        }),
+        tokio::spawn({
+            let addr = addr.clone();
+            async move {
+                for n in 0..N {
+                    addr.send_priority((Source::Priority, n)).ok().unwrap();
+                    tokio::task::yield_now().await;
+                }
+            }
+        }),
+        tokio::spawn(async move {
+            for n in 0..N {
+                side.send(n).unwrap();
+                tokio::task::yield_now().await;
+            }
+        }),
+    ];
+    for sender in senders {
+        sender.await.unwrap();
+    }
+
+    let mut got = [Vec::new(), Vec::new(), Vec::new()];
+    for _ in 0..N * 3 {
+        let (source, n) = seen.recv().await.unwrap();
+        got[source as usize].push(n);
+    }
+    // Each source arrives whole, and in the order it was sent
+    for from in &got {
+        assert_eq!(*from, (0..N).collect::<Vec<_>>());
+    }
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+/// Checks a ready future wins over a waiting message, then reports the message.
+struct Biased {
+    gate: Option<oneshot::Receiver<()>>,
+    seen: mpsc::UnboundedSender<(Source, u32)>,
+}
+
+#[async_trait]
+impl Actor for Biased {
+    type Msg = (Source, u32);
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        if let Some(gate) = self.gate.take() {
+            let _ = gate.await;
+            assert!(matches!(
+                ctx.select(future::ready(())).await,
+                Either::Right(())
+            ));
+        }
+        let _ = self.seen.send(ctx.message().await);
+    }
+}
+
+#[tokio::test]
+async fn a_ready_future_wins_and_leaves_the_messages_queued() {
+    let (agency, handle) = Agency::new();
+    let (open, gate) = oneshot::channel();
+    let (seen_tx, mut seen) = mpsc::unbounded_channel();
+    let addr = agency.hire(Biased {
+        gate: Some(gate),
+        seen: seen_tx,
+    });
+
+    addr.send((Source::Mailbox, 1)).await.ok().unwrap();
+    addr.send_priority((Source::Priority, 2)).ok().unwrap();
+    open.send(()).unwrap();
+    // The priority message still comes first
+    assert_eq!(seen.recv().await.unwrap(), (Source::Priority, 2));
+    assert_eq!(seen.recv().await.unwrap(), (Source::Mailbox, 1));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+/// Handles messages while a timer it started keeps running, reporting how long the timer took
+/// and how many messages came in meanwhile.
+struct Patient(mpsc::UnboundedSender<(Duration, usize)>);
+
+#[async_trait]
+impl Actor for Patient {
+    type Msg = ();
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let start = Instant::now();
+        let timer = time::sleep(Duration::from_secs(1));
+        tokio::pin!(timer);
+        let mut handled = 0;
+        while let Either::Left(()) = ctx.select_pinned(timer.as_mut()).await {
+            handled += 1;
+        }
+        let _ = self.0.send((start.elapsed(), handled));
+        ctx.stop();
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_pinned_future_isnt_restarted_by_messages() {
+    let (agency, handle) = Agency::new();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let addr = agency.hire(Patient(tx));
+
+    for _ in 0..3 {
+        time::sleep(Duration::from_millis(300)).await;
+        addr.send(()).await.ok().unwrap();
+    }
+    let (waited, handled) = rx.recv().await.unwrap();
+    assert_eq!(handled, 3);
+    assert!(waited < Duration::from_millis(1100), "{:?}", waited);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,139 @@
+use agency::{prelude::*, DeliveryError};
+use std::convert::TryFrom;
+use tokio::sync::{mpsc, oneshot};
+
+/// Holds off receiving anything until its gate opens, then reports each message it gets.
+struct Held {
+    gate: Option<oneshot::Receiver<()>>,
+    seen: mpsc::UnboundedSender<u32>,
+}
+
+impl Held {
+    fn new() -> (Self, oneshot::Sender<()>, mpsc::UnboundedReceiver<u32>) {
+        let (open, gate) = oneshot::channel();
+        let (seen, rx) = mpsc::unbounded_channel();
+        let held = Self {
+            gate: Some(gate),
+            seen,
+        };
+        (held, open, rx)
+    }
+}
+
+#[async_trait]
+impl Actor for Held {
+    type Msg = u32;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        if let Some(gate) = self.gate.take() {
+            let _ = gate.await;
+        }
+        let msg = ctx.message().await;
+        let _ = self.seen.send(msg);
+    }
+}
+
+/// Only even numbers convert into the actor's messages.
+#[derive(Debug)]
+struct Even(u32);
+
+impl TryFrom<Even> for u32 {
+    type Error = Even;
+
+    fn try_from(even: Even) -> Result<Self, Even> {
+        if even.0 % 2 == 1 {
+            Err(even)
+        } else {
+            Ok(even.0)
+        }
+    }
+}
+
+#[tokio::test]
+async fn recipient_try_send_reports_a_full_mailbox() {
+    let (agency, handle) = Agency::builder().capacity(1).build();
+    let (held, open, mut seen) = Held::new();
+    let addr = agency.hire(held);
+    let recipient: Recipient<u32> = addr.clone().recipient();
+
+    recipient.try_send(1u32).unwrap();
+    match recipient.try_send(2u32) {
+        Err(DeliveryError::Full(msg)) => assert_eq!(msg, 2),
+        other => panic!("expected a full mailbox, got {:?}", other),
+    }
+
+    open.send(()).unwrap();
+    assert_eq!(seen.recv().await, Some(1));
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn recipient_try_send_reports_a_stopped_actor() {
+    let (agency, handle) = Agency::new();
+    let (held, open, _seen) = Held::new();
+    let addr = agency.hire(held);
+    let recipient: Recipient<u32> = addr.clone().recipient();
+    let converting: Recipient<Even> = addr.clone().try_recipient();
+
+    addr.stop();
+    drop(open);
+    addr.watch().await;
+
+    match recipient.try_send(3u32) {
+        Err(DeliveryError::Closed(msg)) => assert_eq!(msg, 3),
+        other => panic!("expected a closed mailbox, got {:?}", other),
+    }
+    match converting.try_send(Even(4)) {
+        Err(DeliveryError::Closed(msg)) => assert_eq!(msg.0, 4),
+        other => panic!("expected a closed mailbox, got {:?}", other),
+    }
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn converting_recipient_try_send_hands_back_what_it_cant_convert_or_fit() {
+    let (agency, handle) = Agency::builder().capacity(1).build();
+    let (held, open, mut seen) = Held::new();
+    let converting: Recipient<Even> = agency.hire(held).try_recipient();
+
+    assert!(matches!(
+        converting.try_send(Even(1)),
+        Err(DeliveryError::Incompatible(Even(1)))
+    ));
+    converting.try_send(Even(2)).unwrap();
+    assert!(matches!(
+        converting.try_send(Even(4)),
+        Err(DeliveryError::Full(Even(4)))
+    ));
+
+    open.send(()).unwrap();
+    assert_eq!(seen.recv().await, Some(2));
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn a_capacity_of_one_holds_the_second_send_until_the_actor_receives() {
+    let (agency, handle) = Agency::new();
+    let (held, open, mut seen) = Held::new();
+    let addr = agency.hire_builder(held).capacity(1).hire();
+
+    addr.send(1u32).await.unwrap();
+    let second = tokio::spawn({
+        let addr = addr.clone();
+        async move { addr.send(2u32).await }
+    });
+    for _ in 0..10 {
+        tokio::task::yield_now().await;
+    }
+    assert!(!second.is_finished());
+
+    open.send(()).unwrap();
+    assert_eq!(seen.recv().await, Some(1));
+    second.await.unwrap().unwrap();
+    assert_eq!(seen.recv().await, Some(2));
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,70 @@
+use agency::{prelude::*, Session, SessionClosed};
+
+type Adding = Session<u32, u32, u32>;
+
+/// Adds up whatever the requester sends, replying with the running total each time, and with a
+/// final total once the requester finishes.
+struct Adder;
+
+#[async_trait]
+impl Actor for Adder {
+    type Msg = Adding;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let mut session = ctx.message().await;
+        let mut total = *session.payload();
+        // Zero hangs up without a final total
+        while let Some(n) = session.recv().await {
+            if n == 0 {
+                return;
+            }
+            total += n;
+            if session.send(total).await.is_err() {
+                return;
+            }
+        }
+        let _ = session.send(total * 100).await;
+        session.finish();
+        assert!(session.is_closed());
+    }
+}
+
+#[tokio::test]
+async fn three_rounds_then_half_close() {
+    let (agency, handle) = Agency::new();
+    let addr = agency.hire(Adder);
+
+    let mut session = addr.open_session::<u32, u32, u32>(10).await.ok().unwrap();
+    for (n, total) in [(1, 11), (2, 13), (3, 16)] {
+        session.send(n).await.unwrap();
+        assert_eq!(session.recv().await, Some(total));
+    }
+
+    // Finishing only closes our side, so the handler's last word still arrives
+    session.finish();
+    assert!(session.is_closed());
+    assert_eq!(session.send(4).await, Err(SessionClosed));
+    assert_eq!(session.recv().await, Some(1600));
+    assert_eq!(session.recv().await, None);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn dropping_the_session_closes_both_directions() {
+    let (agency, handle) = Agency::new();
+    let addr = agency.hire(Adder);
+
+    let mut session = addr.open_session::<u32, u32, u32>(0).await.ok().unwrap();
+    session.send(5).await.unwrap();
+    assert_eq!(session.recv().await, Some(5));
+    session.send(0).await.unwrap();
+
+    assert_eq!(session.recv().await, None);
+    assert!(session.is_closed());
+    assert_eq!(session.send(1).await, Err(SessionClosed));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,92 @@
+use agency::{prelude::*, SetupFailed};
+use tokio::sync::{mpsc, oneshot, Mutex};
+
+/// Set up only once its gate opens, failing if the gate says so, then reports each message.
+struct Service(mpsc::UnboundedSender<u32>);
+
+struct Args {
+    gate: Mutex<Option<oneshot::Receiver<bool>>>,
+    seen: mpsc::UnboundedSender<u32>,
+}
+
+#[async_trait]
+impl Setup for Service {
+    type Args = Args;
+
+    async fn setup(_ctx: &mut Context<Self>, args: Args) -> Option<Self> {
+        let gate = args.gate.lock().await.take().unwrap();
+        match gate.await {
+            Ok(true) => Some(Self(args.seen)),
+            _ => None,
+        }
+    }
+}
+
+#[async_trait]
+impl Actor for Service {
+    type Msg = u32;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let _ = self.0.send(ctx.message().await);
+    }
+}
+
+fn args() -> (Args, oneshot::Sender<bool>, mpsc::UnboundedReceiver<u32>) {
+    let (open, gate) = oneshot::channel();
+    let (seen, rx) = mpsc::unbounded_channel();
+    let args = Args {
+        gate: Mutex::new(Some(gate)),
+        seen,
+    };
+    (args, open, rx)
+}
+
+#[tokio::test]
+async fn the_watch_resolves_once_the_actor_is_ready() {
+    let (agency, handle) = Agency::new();
+    let (args, open, mut seen) = args();
+    let (addr, watch) = agency.hire_with_watch::<Service>(args);
+
+    // Messages queue up as usual until it's ready
+    addr.send(1u32).await.unwrap();
+    tokio::task::yield_now().await;
+    assert!(!watch.is_ready());
+
+    open.send(true).unwrap();
+    watch.ready().await.unwrap();
+    assert_eq!(seen.recv().await, Some(1));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn the_watch_reports_a_failed_setup() {
+    let (agency, handle) = Agency::new();
+    let (args, open, _seen) = args();
+    let (addr, watch) = agency.hire_with_watch::<Service>(args);
+
+    open.send(false).unwrap();
+    assert_eq!(watch.ready().await, Err(SetupFailed));
+    assert!(addr.send(1u32).await.is_err());
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn the_watch_can_be_ignored() {
+    let (agency, handle) = Agency::new();
+    let (args, open, mut seen) = args();
+    let (addr, watch) = agency.hire_with_watch::<Service>(args);
+    drop(watch);
+
+    addr.send(1u32).await.unwrap();
+    open.send(true).unwrap();
+    addr.send(2u32).await.unwrap();
+    assert_eq!(seen.recv().await, Some(1));
+    assert_eq!(seen.recv().await, Some(2));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
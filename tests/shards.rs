@@ -0,0 +1,126 @@
+use agency::{prelude::*, Shards};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::time;
+
+/// How many times each key's shard has been hired.
+type Hired = Arc<Mutex<HashMap<String, usize>>>;
+
+enum Msg {
+    Add(u32),
+    Total(Request<(), u32>),
+}
+
+impl From<Request<(), u32>> for Msg {
+    fn from(request: Request<(), u32>) -> Self {
+        Self::Total(request)
+    }
+}
+
+/// Keeps a running total for its key.
+struct Account(u32);
+
+#[async_trait]
+impl Actor for Account {
+    type Msg = Msg;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        match ctx.message().await {
+            Msg::Add(n) => self.0 += n,
+            Msg::Total(request) => {
+                let _ = request.respond(self.0);
+            }
+        }
+    }
+}
+
+fn accounts(agency: &Agency) -> (Shards<String, Account>, Hired) {
+    let hired = Hired::default();
+    let shards = agency.hire_shards({
+        let hired = hired.clone();
+        move |key: &String| {
+            *hired.lock().unwrap().entry(key.clone()).or_default() += 1;
+            Account(0)
+        }
+    });
+    (shards, hired)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_first_sends_share_one_shard() {
+    let (agency, handle) = Agency::new();
+    let (shards, hired) = accounts(&agency);
+    let key = "hot".to_string();
+
+    let senders: Vec<_> = (0..32)
+        .map(|_| {
+            let shards = shards.clone();
+            let key = key.clone();
+            tokio::spawn(async move {
+                for _ in 0..10 {
+                    shards.send(&key, Msg::Add(1)).await.ok().unwrap();
+                }
+            })
+        })
+        .collect();
+    for sender in senders {
+        sender.await.unwrap();
+    }
+
+    assert_eq!(shards.request(&key, ()).await.unwrap(), 320);
+    assert_eq!(hired.lock().unwrap().get(&key), Some(&1));
+    assert_eq!(shards.len(), 1);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn each_key_gets_its_own_shard() {
+    let (agency, handle) = Agency::new();
+    let (shards, hired) = accounts(&agency);
+    let (a, b) = ("a".to_string(), "b".to_string());
+
+    shards.send(&a, Msg::Add(1)).await.ok().unwrap();
+    shards.send(&b, Msg::Add(10)).await.ok().unwrap();
+    shards.send(&a, Msg::Add(2)).await.ok().unwrap();
+    assert_eq!(shards.request(&a, ()).await.unwrap(), 3);
+    assert_eq!(shards.request(&b, ()).await.unwrap(), 10);
+    assert_ne!(shards.shard(&a).id(), shards.shard(&b).id());
+    assert_eq!(shards.len(), 2);
+    assert_eq!(hired.lock().unwrap().values().sum::<usize>(), 2);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn idle_shards_are_retired_and_hired_again_for_later_traffic() {
+    let (agency, handle) = Agency::new();
+    let (shards, hired) = accounts(&agency);
+    let shards = shards.idle_timeout(Duration::from_secs(1));
+    let key = "quiet".to_string();
+
+    shards.send(&key, Msg::Add(5)).await.ok().unwrap();
+    assert_eq!(shards.request(&key, ()).await.unwrap(), 5);
+    let first = shards.shard(&key);
+
+    time::sleep(Duration::from_secs(2)).await;
+    first.watch().await;
+    while !shards.is_empty() {
+        time::sleep(Duration::from_millis(10)).await;
+    }
+
+    // A fresh shard, starting from scratch
+    shards.send(&key, Msg::Add(1)).await.ok().unwrap();
+    assert_eq!(shards.request(&key, ()).await.unwrap(), 1);
+    assert_ne!(shards.shard(&key).id(), first.id());
+    assert_eq!(hired.lock().unwrap().get(&key), Some(&2));
+    assert_eq!(shards.len(), 1);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,129 @@
+use agency::{prelude::*, StopReason};
+use std::{future, time::Duration};
+use tokio::{sync::mpsc, time};
+
+/// Parks in a wait that never finishes, like a read from a quiet socket, until it's asked to
+/// stop.
+struct Reader {
+    parked: mpsc::UnboundedSender<()>,
+    woken: usize,
+    stopping: Option<StopReason>,
+}
+
+#[async_trait]
+impl Actor for Reader {
+    type Msg = ();
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let _ = self.parked.send(());
+        tokio::select! {
+            _ = future::pending::<()>() => unreachable!(),
+            _ = ctx.shutdown_requested() => self.woken += 1,
+        }
+    }
+
+    async fn stopping(&mut self, ctx: &mut Context<Self>) -> StoppingResult {
+        self.stopping = ctx.stop_reason();
+        StoppingResult::Stop
+    }
+}
+
+fn reader() -> (Reader, mpsc::UnboundedReceiver<()>) {
+    let (parked, rx) = mpsc::unbounded_channel();
+    let reader = Reader {
+        parked,
+        woken: 0,
+        stopping: None,
+    };
+    (reader, rx)
+}
+
+#[tokio::test]
+async fn shutdown_wakes_an_actor_parked_outside_its_mailbox() {
+    let (agency, handle) = Agency::new();
+    let (reader, mut parked) = reader();
+    let (_addr, reader) = agency.hire_joinable(reader);
+    parked.recv().await.unwrap();
+
+    agency.shutdown();
+    let panicked = time::timeout(Duration::from_secs(1), handle.wait())
+        .await
+        .expect("the parked actor didn't notice the shutdown");
+    assert!(panicked.is_empty());
+
+    let reader = reader.join().await.ok().unwrap();
+    assert_eq!(reader.woken, 1);
+    assert_eq!(reader.stopping, Some(StopReason::AgencyShutdown));
+}
+
+#[tokio::test]
+async fn stopping_one_actor_wakes_just_that_one() {
+    let (agency, handle) = Agency::new();
+    let (first, mut first_parked) = reader();
+    let (second, mut second_parked) = reader();
+    let (first_addr, first) = agency.hire_joinable(first);
+    let second_addr = agency.hire(second);
+    first_parked.recv().await.unwrap();
+    second_parked.recv().await.unwrap();
+
+    first_addr.stop();
+    let first = time::timeout(Duration::from_secs(1), first.join())
+        .await
+        .expect("the parked actor didn't notice being stopped")
+        .ok()
+        .unwrap();
+    assert_eq!(first.woken, 1);
+    assert_eq!(first.stopping, Some(StopReason::Requested));
+    assert!(!second_addr.is_stopped());
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+/// Parks forever without ever checking for a shutdown.
+struct Stubborn {
+    parked: mpsc::UnboundedSender<()>,
+}
+
+#[async_trait]
+impl Actor for Stubborn {
+    type Msg = ();
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        ctx.set_name("stubborn");
+        let _ = self.parked.send(());
+        future::pending::<()>().await;
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn shutdown_timeout_gives_up_on_actors_that_ignore_it() {
+    let (agency, handle) = Agency::new();
+    let (reader, mut reader_parked) = reader();
+    let (parked, mut stubborn_parked) = mpsc::unbounded_channel();
+    let stubborn = agency.hire(Stubborn { parked });
+    agency.hire(reader);
+    reader_parked.recv().await.unwrap();
+    stubborn_parked.recv().await.unwrap();
+
+    let started = time::Instant::now();
+    let alive = handle.shutdown_timeout(Duration::from_secs(5)).await;
+    assert_eq!(started.elapsed(), Duration::from_secs(5));
+    assert_eq!(alive.len(), 1);
+    assert_eq!(alive[0].id, stubborn.id());
+    assert_eq!(alive[0].name.as_deref(), Some("stubborn"));
+    assert!(stubborn.is_stopped());
+}
+
+#[tokio::test(start_paused = true)]
+async fn shutdown_timeout_reports_nothing_when_every_actor_stops_in_time() {
+    let (agency, handle) = Agency::new();
+    let (reader, mut parked) = reader();
+    agency.hire(reader);
+    parked.recv().await.unwrap();
+
+    let started = time::Instant::now();
+    let alive = handle.shutdown_timeout(Duration::from_secs(5)).await;
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert!(alive.is_empty());
+}
@@ -0,0 +1,98 @@
+use agency::{prelude::*, TimerHandle};
+use std::{future, time::Duration};
+use tokio::{
+    sync::oneshot,
+    time::{self, Instant},
+};
+
+/// Held by a spawned task, so the receiver finds out once the task is dropped.
+type Guard = oneshot::Sender<()>;
+
+enum Msg {
+    /// Answer with the delay, after waiting that many milliseconds.
+    Slow(Request<u64, u64>),
+    /// Spawn a task that never finishes, holding the guard.
+    Hang(Request<Guard, TimerHandle>),
+}
+
+impl From<Request<u64, u64>> for Msg {
+    fn from(request: Request<u64, u64>) -> Self {
+        Self::Slow(request)
+    }
+}
+
+impl From<Request<Guard, TimerHandle>> for Msg {
+    fn from(request: Request<Guard, TimerHandle>) -> Self {
+        Self::Hang(request)
+    }
+}
+
+/// Answers each request from a task of its own.
+struct Database;
+
+#[async_trait]
+impl Actor for Database {
+    type Msg = Msg;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        match ctx.message().await {
+            Msg::Slow(request) => {
+                ctx.spawn(async move {
+                    let delay = *request.payload();
+                    time::sleep(Duration::from_millis(delay)).await;
+                    let _ = request.respond(delay);
+                });
+            }
+            Msg::Hang(request) => {
+                if let Some((guard, reply_to)) = request.handle() {
+                    let task = ctx.spawn(async move {
+                        let _guard = guard;
+                        future::pending::<()>().await;
+                    });
+                    let _ = reply_to.send(task);
+                }
+            }
+        }
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn spawned_tasks_answer_requests_concurrently() {
+    let (agency, handle) = Agency::new();
+    let addr = agency.hire(Database);
+
+    let started = Instant::now();
+    let answers = futures_util::future::join_all(
+        vec![300u64, 100, 200]
+            .into_iter()
+            .map(|delay| addr.request::<_, u64>(delay)),
+    )
+    .await;
+    assert_eq!(started.elapsed(), Duration::from_millis(300));
+    let answers: Vec<_> = answers.into_iter().map(Result::unwrap).collect();
+    assert_eq!(answers, vec![300, 100, 200]);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn spawned_tasks_are_dropped_when_the_actor_stops() {
+    let (agency, handle) = Agency::new();
+    let addr = agency.hire(Database);
+    let (guard, dropped) = oneshot::channel();
+
+    let task: TimerHandle = addr.request(guard).await.unwrap();
+    assert!(!task.is_cancelled());
+
+    addr.stop();
+    addr.watch().await;
+    assert!(task.is_cancelled());
+    time::timeout(Duration::from_secs(1), dropped)
+        .await
+        .expect("the spawned task is still running")
+        .unwrap_err();
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,122 @@
+use agency::prelude::*;
+use futures_util::StreamExt;
+use std::time::Duration;
+use tokio::{sync::mpsc, time};
+
+#[derive(Debug, PartialEq)]
+enum Event {
+    Priority(u32),
+    Regular(u32),
+    TimedOut,
+    Message(u32),
+}
+
+/// Runs a custom loop over the split mailboxes first, favouring regular messages, until it's
+/// handled `limit` of them or a timer fires. After that it goes back to `ctx.message()`.
+struct Custom {
+    limit: usize,
+    split: bool,
+    seen: mpsc::UnboundedSender<Event>,
+}
+
+#[async_trait]
+impl Actor for Custom {
+    type Msg = u32;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        if self.split {
+            let _ = self.seen.send(Event::Message(ctx.message().await));
+            return;
+        }
+        self.split = true;
+
+        let (priority, regular) = ctx.split_streams();
+        futures_util::pin_mut!(priority, regular);
+        let timer = time::sleep(Duration::from_millis(100));
+        tokio::pin!(timer);
+        let mut handled = 0;
+        while handled < self.limit {
+            tokio::select! {
+                biased;
+                Some(n) = regular.next() => {
+                    handled += 1;
+                    let _ = self.seen.send(Event::Regular(n));
+                }
+                Some(n) = priority.next() => {
+                    let _ = self.seen.send(Event::Priority(n));
+                }
+                () = &mut timer => {
+                    let _ = self.seen.send(Event::TimedOut);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+fn custom(agency: &Agency, limit: usize) -> (Addr<Custom>, mpsc::UnboundedReceiver<Event>) {
+    let (seen, rx) = mpsc::unbounded_channel();
+    let addr = agency.hire(Custom {
+        limit,
+        split: false,
+        seen,
+    });
+    (addr, rx)
+}
+
+async fn received(rx: &mut mpsc::UnboundedReceiver<Event>, count: usize) -> Vec<Event> {
+    let mut received = Vec::new();
+    for _ in 0..count {
+        received.push(rx.recv().await.unwrap());
+    }
+    received
+}
+
+#[tokio::test(start_paused = true)]
+async fn messages_left_by_the_custom_loop_go_to_ctx_message() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut seen) = custom(&agency, 2);
+
+    for n in 1..=4u32 {
+        addr.send(n).await.unwrap();
+    }
+    addr.send_priority(10u32).unwrap();
+
+    // The custom loop takes regular messages first, leaving the priority one for `ctx.message()`
+    assert_eq!(
+        received(&mut seen, 5).await,
+        vec![
+            Event::Regular(1),
+            Event::Regular(2),
+            Event::Message(10),
+            Event::Message(3),
+            Event::Message(4),
+        ]
+    );
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn the_custom_loop_can_end_on_its_own_timer() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut seen) = custom(&agency, usize::MAX);
+
+    addr.send_priority(1u32).unwrap();
+    addr.send(2u32).await.unwrap();
+    assert_eq!(
+        received(&mut seen, 2).await,
+        vec![Event::Regular(2), Event::Priority(1)]
+    );
+    assert_eq!(seen.recv().await, Some(Event::TimedOut));
+
+    // Back to normal once the streams have been dropped
+    addr.send(3u32).await.unwrap();
+    assert_eq!(seen.recv().await, Some(Event::Message(3)));
+    addr.send_priority(4u32).unwrap();
+    assert_eq!(seen.recv().await, Some(Event::Message(4)));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
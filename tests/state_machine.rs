@@ -0,0 +1,137 @@
+use agency::{
+    prelude::*, CurrentState, State, StateMachine, StateMachineMsg, Transition, UnhandledPolicy,
+};
+use tokio::sync::mpsc;
+
+enum Event {
+    Connected,
+    Send(u32),
+    Drain,
+    Flushed,
+}
+
+impl From<Event> for StateMachineMsg<Event> {
+    fn from(event: Event) -> Self {
+        Self::Event(event)
+    }
+}
+
+type Ctx = Context<StateMachine<Event>>;
+type Sent = mpsc::UnboundedSender<u32>;
+
+struct Connecting(Sent);
+
+#[async_trait]
+impl State<Event> for Connecting {
+    fn name(&self) -> &'static str {
+        "connecting"
+    }
+
+    async fn on_message(self: Box<Self>, _ctx: &mut Ctx, msg: Event) -> Transition<Event> {
+        match msg {
+            Event::Connected => Transition::to(Ready(self.0)),
+            msg => Transition::Unhandled(self, msg),
+        }
+    }
+}
+
+struct Ready(Sent);
+
+#[async_trait]
+impl State<Event> for Ready {
+    fn name(&self) -> &'static str {
+        "ready"
+    }
+
+    async fn on_message(self: Box<Self>, _ctx: &mut Ctx, msg: Event) -> Transition<Event> {
+        match msg {
+            Event::Send(n) => {
+                let _ = self.0.send(n);
+                Transition::Stay(self)
+            }
+            Event::Drain => Transition::to(Draining),
+            msg => Transition::Unhandled(self, msg),
+        }
+    }
+}
+
+struct Draining;
+
+#[async_trait]
+impl State<Event> for Draining {
+    fn name(&self) -> &'static str {
+        "draining"
+    }
+
+    async fn on_message(self: Box<Self>, _ctx: &mut Ctx, msg: Event) -> Transition<Event> {
+        match msg {
+            Event::Flushed => Transition::Stop,
+            msg => Transition::Unhandled(self, msg),
+        }
+    }
+}
+
+fn connection(
+    agency: &Agency,
+    policy: UnhandledPolicy,
+) -> (Addr<StateMachine<Event>>, mpsc::UnboundedReceiver<u32>) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let machine = StateMachine::new(Connecting(tx)).on_unhandled(policy);
+    (agency.hire(machine), rx)
+}
+
+async fn state(addr: &Addr<StateMachine<Event>>) -> &'static str {
+    addr.request(CurrentState).await.unwrap()
+}
+
+#[tokio::test]
+async fn moves_through_its_states() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut sent) = connection(&agency, UnhandledPolicy::Reject);
+    assert_eq!(state(&addr).await, "connecting");
+
+    addr.send(Event::Connected).await.unwrap();
+    assert_eq!(state(&addr).await, "ready");
+    addr.send(Event::Send(1)).await.unwrap();
+    assert_eq!(sent.recv().await, Some(1));
+
+    addr.send(Event::Drain).await.unwrap();
+    assert_eq!(state(&addr).await, "draining");
+    addr.send(Event::Flushed).await.unwrap();
+    addr.watch().await;
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn rejected_messages_are_dropped() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut sent) = connection(&agency, UnhandledPolicy::Reject);
+
+    addr.send(Event::Send(1)).await.unwrap();
+    addr.send(Event::Connected).await.unwrap();
+    addr.send(Event::Send(2)).await.unwrap();
+    assert_eq!(sent.recv().await, Some(2));
+    assert_eq!(state(&addr).await, "ready");
+    assert!(sent.try_recv().is_err());
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn stashed_messages_are_redelivered_in_order_after_the_next_transition() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut sent) = connection(&agency, UnhandledPolicy::Stash);
+
+    addr.send(Event::Send(1)).await.unwrap();
+    addr.send(Event::Send(2)).await.unwrap();
+    assert_eq!(state(&addr).await, "connecting");
+    addr.send(Event::Connected).await.unwrap();
+    addr.send(Event::Send(3)).await.unwrap();
+
+    for n in 1..=3 {
+        assert_eq!(sent.recv().await, Some(n));
+    }
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,113 @@
+use agency::{prelude::*, PanicInfo};
+use tokio::sync::mpsc;
+
+/// Reports each message it gets, panicking on zero and recovering afterwards.
+struct Worker(mpsc::UnboundedSender<u32>);
+
+#[async_trait]
+impl Actor for Worker {
+    type Msg = u32;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let msg = ctx.message().await;
+        if msg == 0 {
+            panic!("can't work on nothing");
+        }
+        let _ = self.0.send(msg);
+    }
+
+    async fn on_panic(&mut self, _ctx: &mut Context<Self>, _panic: PanicInfo) -> StoppingResult {
+        StoppingResult::Recover
+    }
+}
+
+fn worker(agency: &Agency) -> (Addr<Worker>, mpsc::UnboundedReceiver<u32>) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    (agency.hire(Worker(tx)), rx)
+}
+
+#[tokio::test]
+async fn counts_a_known_workload() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut done) = worker(&agency);
+    assert_eq!(addr.stats().processed, 0);
+
+    for n in 1..=5u32 {
+        addr.send(n).await.unwrap();
+    }
+    addr.send_priority(6u32).unwrap();
+    addr.send_priority(7u32).unwrap();
+    addr.send(0u32).await.unwrap();
+    addr.send(8u32).await.unwrap();
+    for _ in 0..8 {
+        done.recv().await.unwrap();
+    }
+
+    let stats = addr.stats();
+    assert_eq!(stats.processed, 9);
+    assert_eq!(stats.processed_priority, 2);
+    assert_eq!(stats.errors, 1);
+    assert_eq!(stats.restarts, 1);
+    assert!(stats.started_at.is_some());
+    assert!(stats.last_active >= stats.started_at);
+    assert!(stats.stopped_at.is_none());
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn counts_are_frozen_once_the_actor_stops() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut done) = worker(&agency);
+    for n in 1..=3u32 {
+        addr.send(n).await.unwrap();
+    }
+    for _ in 0..3 {
+        done.recv().await.unwrap();
+    }
+
+    addr.stop();
+    addr.watch().await;
+    let stopped = addr.stats();
+    assert_eq!(stopped.processed, 3);
+    assert!(stopped.stopped_at.is_some());
+
+    assert!(addr.send(4u32).await.is_err());
+    let later = addr.stats();
+    assert_eq!(later.processed, 3);
+    assert_eq!(later.last_active, stopped.last_active);
+    assert_eq!(later.stopped_at, stopped.stopped_at);
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn priority_depth_counts_what_a_paused_actor_has_waiting() {
+    let (agency, handle) = Agency::new();
+    let (addr, mut done) = worker(&agency);
+    addr.pause();
+    while !addr.is_paused() {
+        tokio::task::yield_now().await;
+    }
+
+    for n in 1..=5u32 {
+        addr.send_priority(n).unwrap();
+    }
+    addr.send(6u32).await.unwrap();
+    assert_eq!(addr.priority_depth(), 5);
+    assert_eq!(addr.stats().priority_depth, 5);
+    assert_eq!(addr.mailbox_len(), 1);
+    let snapshot = agency
+        .actors()
+        .into_iter()
+        .find(|actor| actor.id == addr.id());
+    assert_eq!(snapshot.map(|actor| actor.priority_depth), Some(5));
+
+    addr.resume();
+    for _ in 0..6 {
+        done.recv().await.unwrap();
+    }
+    assert_eq!(addr.priority_depth(), 0);
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
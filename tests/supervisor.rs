@@ -0,0 +1,136 @@
+use agency::{prelude::*, ChildSpec, GetChildren, SupervisionStrategy, Supervisor};
+use std::time::Duration;
+use tokio::{
+    sync::mpsc,
+    time::{self, Instant},
+};
+
+type Hired = mpsc::UnboundedReceiver<(&'static str, Addr<Child>, Instant)>;
+
+/// Reports its address whenever it's started, panics on any message, and takes its time over
+/// stopping.
+struct Child {
+    name: &'static str,
+    stop_delay: Duration,
+    hired: mpsc::UnboundedSender<(&'static str, Addr<Child>, Instant)>,
+}
+
+#[async_trait]
+impl Actor for Child {
+    type Msg = ();
+
+    async fn init(&mut self, ctx: &mut Context<Self>) {
+        let _ = self.hired.send((self.name, ctx.address(), Instant::now()));
+    }
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        ctx.message().await;
+        panic!("{} crashed", self.name);
+    }
+
+    async fn stopping(&mut self, _ctx: &mut Context<Self>) -> StoppingResult {
+        time::sleep(self.stop_delay).await;
+        StoppingResult::Stop
+    }
+}
+
+fn supervise(
+    agency: &Agency,
+    strategy: SupervisionStrategy,
+    children: &[(&'static str, Duration)],
+) -> (Addr<Supervisor>, Hired) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let mut supervisor = Supervisor::new(strategy).stop_timeout(Duration::from_secs(1));
+    for &(name, stop_delay) in children {
+        let hired = tx.clone();
+        supervisor = supervisor.child(ChildSpec::new(name, move || Child {
+            name,
+            stop_delay,
+            hired: hired.clone(),
+        }));
+    }
+    (agency.hire(supervisor), rx)
+}
+
+async fn next_hired(hired: &mut Hired) -> (&'static str, Addr<Child>) {
+    let (name, addr, _) = hired.recv().await.unwrap();
+    (name, addr)
+}
+
+#[tokio::test(start_paused = true)]
+async fn one_for_one_restarts_only_the_child_that_failed() {
+    let (agency, handle) = Agency::new();
+    let (supervisor, mut hired) = supervise(
+        &agency,
+        SupervisionStrategy::OneForOne,
+        &[("a", Duration::ZERO), ("b", Duration::ZERO)],
+    );
+    let (_, a) = next_hired(&mut hired).await;
+    let (_, b) = next_hired(&mut hired).await;
+
+    a.send(()).await.unwrap();
+    a.watch().await;
+    let (name, restarted) = next_hired(&mut hired).await;
+    assert_eq!(name, "a");
+
+    let children = supervisor.request(GetChildren).await.unwrap();
+    assert_eq!(children, vec![restarted.id(), b.id()]);
+    assert!(hired.try_recv().is_err());
+    agency.shutdown();
+    assert_eq!(handle.wait().await.len(), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn all_for_one_restarts_every_child() {
+    let (agency, handle) = Agency::new();
+    let (supervisor, mut hired) = supervise(
+        &agency,
+        SupervisionStrategy::AllForOne,
+        &[("a", Duration::ZERO), ("b", Duration::ZERO)],
+    );
+    let (_, a) = next_hired(&mut hired).await;
+    let (_, b) = next_hired(&mut hired).await;
+
+    a.send(()).await.unwrap();
+    b.watch().await;
+    let (first, a2) = next_hired(&mut hired).await;
+    let (second, b2) = next_hired(&mut hired).await;
+    assert_eq!((first, second), ("a", "b"));
+
+    let children = supervisor.request(GetChildren).await.unwrap();
+    assert_eq!(children, vec![a2.id(), b2.id()]);
+    assert!(!children.contains(&a.id()) && !children.contains(&b.id()));
+    agency.shutdown();
+    assert_eq!(handle.wait().await.len(), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn all_for_one_stops_siblings_together_and_aborts_stragglers() {
+    let (agency, handle) = Agency::new();
+    let (supervisor, mut hired) = supervise(
+        &agency,
+        SupervisionStrategy::AllForOne,
+        &[
+            ("a", Duration::ZERO),
+            ("slow", Duration::from_millis(800)),
+            ("slower", Duration::from_millis(800)),
+            ("stuck", Duration::from_secs(3600)),
+        ],
+    );
+    let (_, a) = next_hired(&mut hired).await;
+    for _ in 0..3 {
+        next_hired(&mut hired).await;
+    }
+
+    let crashed = Instant::now();
+    a.send(()).await.unwrap();
+    for _ in 0..4 {
+        let (_, _, at) = hired.recv().await.unwrap();
+        // Waiting for each in turn would take over a second and a half, even with the stuck one
+        // aborted
+        assert_eq!(at - crashed, Duration::from_secs(1));
+    }
+    assert_eq!(supervisor.request(GetChildren).await.unwrap().len(), 4);
+    agency.shutdown();
+    assert_eq!(handle.wait().await.len(), 1);
+}
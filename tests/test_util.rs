@@ -0,0 +1,197 @@
+#![cfg(feature = "test-util")]
+
+use agency::{
+    assert_delivered_within,
+    prelude::*,
+    test_util::{time, Probe},
+    Aggregator, Cancel, Item, Schedule, ScheduleId, Scheduler,
+};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Sends the probe a number once each requested delay has passed, using [`Context::run_later`].
+struct Delayed(Recipient<u32>);
+
+#[async_trait]
+impl Actor for Delayed {
+    type Msg = Request<(u32, Duration), ()>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        ctx.dispatch(self).await
+    }
+}
+
+#[async_trait]
+impl Handler for Delayed {
+    async fn handle(&mut self, ctx: &mut Context<Self>, request: Self::Msg) {
+        let (n, delay) = *request.payload();
+        ctx.run_later(delay, move |delayed: &mut Delayed, _ctx| {
+            let _ = delayed.0.try_send(n);
+        });
+        let _ = request.respond(());
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn advancing_until_idle_fires_each_timer_at_its_deadline() {
+    let (agency, handle) = Agency::new();
+    let mut probe = Probe::new(&agency);
+    let scheduler = agency.hire(Scheduler::new());
+    let start = Instant::now();
+
+    let _: ScheduleId = scheduler
+        .request(Schedule::after(
+            Duration::from_secs(10),
+            probe.recipient(),
+            1,
+        ))
+        .await
+        .unwrap();
+    let _: ScheduleId = scheduler
+        .request(Schedule::at(
+            start + Duration::from_secs(5),
+            probe.recipient(),
+            2,
+        ))
+        .await
+        .unwrap();
+    time::settle(&agency).await;
+    assert_eq!(
+        time::next_deadline(&agency),
+        Some(start + Duration::from_secs(5))
+    );
+
+    time::advance_until_idle(&agency).await;
+    // Exactly the last deadline, not a guess past it
+    assert_eq!(Instant::now(), start + Duration::from_secs(10));
+    assert_eq!(probe.try_recv(), Some(2));
+    assert_eq!(probe.try_recv(), Some(1));
+    assert_eq!(probe.try_recv(), None);
+    assert_eq!(time::next_deadline(&agency), None);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn repeating_schedules_are_advanced_through_up_to_the_end() {
+    let (agency, handle) = Agency::new();
+    let mut probe = Probe::new(&agency);
+    let scheduler = agency.hire(Scheduler::new());
+    let start = Instant::now();
+
+    let mut count = 0;
+    let schedule = Schedule::every(Duration::from_secs(5), probe.recipient(), move || {
+        count += 1;
+        count
+    });
+    scheduler.request::<_, ScheduleId>(schedule).await.unwrap();
+
+    time::advance_until(&agency, start + Duration::from_secs(16)).await;
+    assert_eq!(Instant::now(), start + Duration::from_secs(16));
+    for n in 1..=3 {
+        assert_eq!(probe.try_recv(), Some(n));
+    }
+    assert_eq!(probe.try_recv(), None);
+
+    let n = assert_delivered_within!(probe, Duration::from_secs(5), |n| *n == 4);
+    assert_eq!(n, 4);
+    assert_eq!(Instant::now(), start + Duration::from_secs(20));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn run_later_deadlines_are_known() {
+    let (agency, handle) = Agency::new();
+    let mut probe = Probe::new(&agency);
+    let delayed = agency.hire(Delayed(probe.recipient()));
+    let start = Instant::now();
+
+    delayed
+        .request((1, Duration::from_millis(300)))
+        .await
+        .unwrap();
+    delayed
+        .request((2, Duration::from_millis(100)))
+        .await
+        .unwrap();
+
+    // Messages that don't match are skipped over
+    assert_delivered_within!(probe, Duration::from_secs(1), |n| *n == 1);
+    assert_eq!(Instant::now(), start + Duration::from_millis(300));
+    assert_eq!(probe.try_recv(), None);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn aggregator_windows_are_known() {
+    let (agency, handle) = Agency::new();
+    let mut probe = Probe::new(&agency);
+    let aggregator = Aggregator::new(probe.recipient(), Vec::new, |batch: &mut Vec<u32>, n| {
+        batch.push(n)
+    })
+    .max_items(100)
+    .window(Duration::from_secs(1));
+    let addr = agency.hire(aggregator);
+    let start = Instant::now();
+
+    addr.send(Item(1)).await.unwrap();
+    addr.send(Item(2)).await.unwrap();
+    let batch = assert_delivered_within!(probe, Duration::from_secs(2), |_| true);
+    assert_eq!(batch, vec![1, 2]);
+    assert_eq!(Instant::now(), start + Duration::from_secs(1));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn cancelled_deliveries_are_never_made() {
+    let (agency, handle) = Agency::new();
+    let mut probe = Probe::new(&agency);
+    let scheduler = agency.hire(Scheduler::new());
+    let start = Instant::now();
+
+    let once: ScheduleId = scheduler
+        .request(Schedule::after(
+            Duration::from_secs(10),
+            probe.recipient(),
+            1,
+        ))
+        .await
+        .unwrap();
+    assert!(scheduler.request(Cancel(once)).await.unwrap());
+
+    assert_eq!(
+        probe
+            .delivered_within(Duration::from_secs(60), |_| true)
+            .await,
+        None
+    );
+    assert_eq!(Instant::now(), start + Duration::from_secs(60));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+#[should_panic(expected = "no message matching `|n| *n == 2` was delivered within 5s")]
+async fn asserting_a_missing_delivery_panics() {
+    let (agency, _handle) = Agency::new();
+    let mut probe = Probe::new(&agency);
+    let scheduler = agency.hire(Scheduler::new());
+
+    let _: ScheduleId = scheduler
+        .request(Schedule::after(
+            Duration::from_secs(1),
+            probe.recipient(),
+            1,
+        ))
+        .await
+        .unwrap();
+    assert_delivered_within!(probe, Duration::from_secs(5), |n| *n == 2);
+}
@@ -0,0 +1,118 @@
+use agency::prelude::*;
+use std::{
+    future,
+    sync::{Arc, Mutex},
+    thread::{self, ThreadId},
+    time::Duration,
+};
+use tokio::time;
+
+type Seen = Arc<Mutex<Vec<(&'static str, ThreadId)>>>;
+
+/// Records the thread each of its callbacks runs on, and answers each request with it.
+struct Affine(Seen);
+
+impl Affine {
+    fn record(&self, callback: &'static str) -> ThreadId {
+        let id = thread::current().id();
+        self.0.lock().unwrap().push((callback, id));
+        id
+    }
+}
+
+#[async_trait]
+impl Actor for Affine {
+    type Msg = Request<(), ThreadId>;
+
+    async fn init(&mut self, _ctx: &mut Context<Self>) {
+        self.record("init");
+    }
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let id = self.record("run");
+        let _ = ctx.message().await.respond(id);
+    }
+
+    async fn stopping(&mut self, _ctx: &mut Context<Self>) -> StoppingResult {
+        self.record("stopping");
+        StoppingResult::Stop
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn every_callback_runs_on_the_same_dedicated_thread() {
+    let (agency, handle) = Agency::new();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let addr = agency.hire_on_thread(Affine(seen.clone()));
+
+    // Requests from several tasks, which may be polled on any of the runtime's workers
+    let requests: Vec<_> = (0..20)
+        .map(|_| {
+            let addr = addr.clone();
+            tokio::spawn(async move { addr.request(()).await.unwrap() })
+        })
+        .collect();
+    let mut answers = Vec::new();
+    for request in requests {
+        answers.push(request.await.unwrap());
+    }
+
+    addr.stop();
+    addr.watch().await;
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.first().map(|(callback, _)| *callback), Some("init"));
+    assert_eq!(seen.last().map(|(callback, _)| *callback), Some("stopping"));
+    assert!(
+        seen.iter()
+            .filter(|(callback, _)| *callback == "run")
+            .count()
+            >= 20
+    );
+    let thread = seen[0].1;
+    assert_ne!(thread, thread::current().id());
+    assert!(seen.iter().all(|(_, id)| *id == thread));
+    assert!(answers.iter().all(|id| *id == thread));
+}
+
+#[tokio::test]
+async fn the_agency_handle_waits_for_the_thread() {
+    let (agency, handle) = Agency::new();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let addr = agency.hire_on_thread(Affine(seen.clone()));
+    let thread = addr.request(()).await.unwrap();
+
+    // Stopped by the shutdown alone, so only the handle can have waited for it
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+    assert!(addr.is_stopped());
+    assert_eq!(seen.lock().unwrap().last(), Some(&("stopping", thread)));
+    assert!(addr.request(()).await.is_err());
+}
+
+/// Parks forever without ever checking for a shutdown.
+struct Stubborn;
+
+#[async_trait]
+impl Actor for Stubborn {
+    type Msg = ();
+
+    async fn run(&mut self, _ctx: &mut Context<Self>) {
+        future::pending::<()>().await;
+    }
+}
+
+#[tokio::test]
+async fn actors_that_ignore_the_shutdown_are_aborted_on_their_thread() {
+    let (agency, handle) = Agency::new();
+    let addr = agency.hire_on_thread(Stubborn);
+
+    let alive = handle.shutdown_timeout(Duration::from_millis(100)).await;
+    assert_eq!(alive.len(), 1);
+    assert_eq!(alive[0].id, addr.id());
+    time::timeout(Duration::from_secs(1), addr.watch())
+        .await
+        .expect("the actor kept running on its thread");
+}
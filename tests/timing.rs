@@ -0,0 +1,121 @@
+use agency::{prelude::*, MessageHandled, Observer, SlowMessage};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{sync::mpsc, time};
+
+/// Records the messages handled, and which of them were slow.
+#[derive(Clone, Default)]
+struct Timings {
+    handled: Arc<Mutex<Vec<&'static str>>>,
+    slow: Arc<Mutex<Vec<SlowMessage>>>,
+}
+
+impl Observer for Timings {
+    fn slow_message_threshold(&self) -> Option<Duration> {
+        Some(Duration::from_millis(100))
+    }
+
+    fn message_handled(&self, event: &MessageHandled) {
+        self.handled.lock().unwrap().push(event.message);
+    }
+
+    fn slow_message(&self, event: &SlowMessage) {
+        self.slow.lock().unwrap().push(event.clone());
+    }
+}
+
+enum Work {
+    Quick,
+    Slow,
+}
+
+/// Handles each message, reporting when it's done.
+struct Worker(mpsc::UnboundedSender<()>);
+
+#[async_trait]
+impl Actor for Worker {
+    type Msg = Work;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        ctx.dispatch(self).await
+    }
+}
+
+#[async_trait]
+impl Handler for Worker {
+    async fn handle(&mut self, _ctx: &mut Context<Self>, msg: Work) {
+        if let Work::Slow = msg {
+            time::sleep(Duration::from_millis(250)).await;
+        }
+        let _ = self.0.send(());
+    }
+
+    fn message_name(msg: &Work) -> &'static str {
+        match msg {
+            Work::Quick => "quick",
+            Work::Slow => "slow",
+        }
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn only_slow_messages_are_reported_as_slow() {
+    let timings = Timings::default();
+    let (agency, handle) = Agency::builder().observer(timings.clone()).build();
+    let (tx, mut done) = mpsc::unbounded_channel();
+    let addr = agency.hire(Worker(tx));
+
+    addr.send(Work::Quick).await.unwrap();
+    addr.send(Work::Slow).await.unwrap();
+    addr.send(Work::Quick).await.unwrap();
+    for _ in 0..3 {
+        done.recv().await.unwrap();
+    }
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+
+    assert_eq!(*timings.handled.lock().unwrap(), ["quick", "slow", "quick"]);
+    let slow = timings.slow.lock().unwrap();
+    assert_eq!(slow.len(), 1);
+    assert_eq!(slow[0].message, "slow");
+    assert_eq!(slow[0].duration, Duration::from_millis(250));
+    // The second quick message was waiting behind it
+    assert_eq!(slow[0].mailbox_depth, 1);
+}
+
+/// Does its work in `run`, timing each block by hand.
+struct Manual(mpsc::UnboundedSender<()>);
+
+#[async_trait]
+impl Actor for Manual {
+    type Msg = Duration;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let work = ctx.message().await;
+        ctx.time_block("manual", time::sleep(work)).await;
+        let _ = self.0.send(());
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn timed_blocks_are_reported_like_messages() {
+    let timings = Timings::default();
+    let (agency, handle) = Agency::builder().observer(timings.clone()).build();
+    let (tx, mut done) = mpsc::unbounded_channel();
+    let addr = agency.hire(Manual(tx));
+
+    addr.send(Duration::from_millis(10)).await.unwrap();
+    addr.send(Duration::from_millis(500)).await.unwrap();
+    for _ in 0..2 {
+        done.recv().await.unwrap();
+    }
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+
+    assert_eq!(*timings.handled.lock().unwrap(), ["manual", "manual"]);
+    let slow = timings.slow.lock().unwrap();
+    assert_eq!(slow.len(), 1);
+    assert_eq!(slow[0].duration, Duration::from_millis(500));
+}
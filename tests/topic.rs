@@ -0,0 +1,130 @@
+use agency::{prelude::*, Publish, Subscribe, SubscriptionId, Topic, Unsubscribe};
+use tokio::sync::mpsc;
+
+/// Reports each event it gets.
+struct Subscriber(mpsc::UnboundedSender<u32>);
+
+#[async_trait]
+impl Actor for Subscriber {
+    type Msg = u32;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let event = ctx.message().await;
+        let _ = self.0.send(event);
+    }
+}
+
+fn subscriber(agency: &Agency) -> (Addr<Subscriber>, mpsc::UnboundedReceiver<u32>) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    (agency.hire(Subscriber(tx)), rx)
+}
+
+#[tokio::test]
+async fn publishes_to_every_subscriber_once() {
+    let (agency, handle) = Agency::new();
+    let topic = agency.hire(Topic::<u32>::new());
+    let mut received = Vec::new();
+    for _ in 0..3 {
+        let (addr, rx) = subscriber(&agency);
+        let _: SubscriptionId = topic
+            .request(Subscribe(addr.clone().recipient()))
+            .await
+            .unwrap();
+        received.push((addr, rx));
+    }
+
+    // Subscribing again doesn't lead to duplicate deliveries
+    let first = received[0].0.clone();
+    let _: SubscriptionId = topic
+        .request(Subscribe(first.clone().recipient()))
+        .await
+        .unwrap();
+
+    topic.send(Publish(7)).await.unwrap();
+    topic.send(Publish(8)).await.unwrap();
+    for (_, rx) in &mut received {
+        assert_eq!(rx.recv().await, Some(7));
+        assert_eq!(rx.recv().await, Some(8));
+    }
+    let _: SubscriptionId = topic
+        .request(Subscribe(first.clone().recipient()))
+        .await
+        .unwrap();
+    for (_, rx) in &mut received {
+        assert!(rx.try_recv().is_err());
+    }
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn unsubscribed_recipients_stop_receiving() {
+    let (agency, handle) = Agency::new();
+    let topic = agency.hire(Topic::<u32>::new());
+    let (leaving, mut left) = subscriber(&agency);
+    let (staying, mut stayed) = subscriber(&agency);
+    let id = topic
+        .request(Subscribe(leaving.clone().recipient()))
+        .await
+        .unwrap();
+    let _: SubscriptionId = topic
+        .request(Subscribe(staying.clone().recipient()))
+        .await
+        .unwrap();
+
+    topic.send(Unsubscribe(id)).await.unwrap();
+    topic.send(Publish(1)).await.unwrap();
+
+    assert_eq!(stayed.recv().await, Some(1));
+    // The subscription came before the publish was handled, so nothing's on its way
+    assert!(left.try_recv().is_err());
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn stopped_subscribers_are_evicted() {
+    let (agency, handle) = Agency::new();
+    let topic = agency.hire(Topic::<u32>::new());
+    let (stopping, _) = subscriber(&agency);
+    let (live, mut received) = subscriber(&agency);
+    let evicted = topic
+        .request(Subscribe(stopping.clone().recipient()))
+        .await
+        .unwrap();
+    let _: SubscriptionId = topic
+        .request(Subscribe(live.clone().recipient()))
+        .await
+        .unwrap();
+
+    stopping.stop();
+    stopping.watch().await;
+    topic.send(Publish(1)).await.unwrap();
+    assert_eq!(received.recv().await, Some(1));
+
+    // An evicted recipient is no longer known, so subscribing it again is a new subscription
+    let resubscribed = topic
+        .request(Subscribe(stopping.clone().recipient()))
+        .await
+        .unwrap();
+    assert_ne!(resubscribed, evicted);
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn sticky_topics_replay_the_last_event() {
+    let (agency, handle) = Agency::new();
+    let topic = agency.hire(Topic::<u32>::sticky());
+    topic.send(Publish(1)).await.unwrap();
+    topic.send(Publish(2)).await.unwrap();
+
+    let (late, mut received) = subscriber(&agency);
+    let _: SubscriptionId = topic
+        .request(Subscribe(late.clone().recipient()))
+        .await
+        .unwrap();
+    assert_eq!(received.recv().await, Some(2));
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,95 @@
+#![cfg(feature = "tower")]
+
+use agency::{prelude::*, ActorService, AskError, RequestError, ServiceActor};
+use std::time::Duration;
+use tokio::{sync::oneshot, time};
+use tower::{service_fn, timeout::Timeout, Service, ServiceExt};
+
+/// Doubles each number, taking as many tenths of a second as the number to do it, and holding off
+/// entirely until its gate opens, if it has one.
+struct Doubler(Option<oneshot::Receiver<()>>);
+
+#[async_trait]
+impl Actor for Doubler {
+    type Msg = Request<u32, u32>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        if let Some(gate) = self.0.take() {
+            let _ = gate.await;
+        }
+        let request = ctx.message().await;
+        let n = *request.payload();
+        time::sleep(Duration::from_millis(100) * n).await;
+        let _ = request.respond(n * 2);
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn requests_go_through_the_service() {
+    let (agency, handle) = Agency::new();
+    let addr = agency.hire(Doubler(None));
+    let service = ActorService::new(addr);
+
+    assert_eq!(service.clone().oneshot(2).await.unwrap(), 4);
+    assert_eq!(service.oneshot(3).await.unwrap(), 6);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn timeouts_compose_around_the_service() {
+    let (agency, handle) = Agency::new();
+    let addr = agency.hire(Doubler(None));
+    let service = Timeout::new(ActorService::new(addr), Duration::from_millis(500));
+
+    assert_eq!(service.clone().oneshot(4).await.unwrap(), 8);
+    let err = service.oneshot(6).await.unwrap_err();
+    assert!(err.is::<tower::timeout::error::Elapsed>());
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn readiness_follows_the_mailbox() {
+    let (agency, handle) = Agency::builder().capacity(1).build();
+    let (open, gate) = oneshot::channel();
+    let addr = agency.hire(Doubler(Some(gate)));
+    let mut service = ActorService::new(addr.clone());
+
+    let (reply_to, _first) = oneshot::channel();
+    addr.try_send(Request::from_parts(1u32, reply_to)).unwrap();
+    let ready = time::timeout(Duration::from_secs(1), service.ready()).await;
+    assert!(ready.is_err(), "ready with a full mailbox");
+
+    open.send(()).unwrap();
+    let response = service.ready().await.unwrap().call(2);
+    assert_eq!(response.await.unwrap(), 4);
+
+    // A stopped actor fails readiness with a request error
+    addr.stop();
+    addr.watch().await;
+    let err = service.ready().await.err().unwrap();
+    assert_eq!(
+        *err.downcast::<RequestError>().unwrap(),
+        RequestError::ActorStopped
+    );
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn an_actor_can_delegate_to_a_service() {
+    let (agency, handle) = Agency::new();
+    let addr = agency.hire(ServiceActor::new(service_fn(|n: u32| async move {
+        100u32.checked_div(n).ok_or("zero")
+    })));
+
+    assert_eq!(addr.ask(4u32).await.unwrap(), 25);
+    assert_eq!(addr.ask(0u32).await, Err(AskError::Failed("zero")));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
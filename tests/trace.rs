@@ -0,0 +1,121 @@
+#![cfg(feature = "trace")]
+
+use agency::{prelude::*, TraceId};
+use std::sync::{Arc, Mutex};
+
+type Seen = Arc<Mutex<Vec<(&'static str, Option<TraceId>)>>>;
+
+/// Notes the trace of each request it gets, passing it on to the next stage before answering.
+struct Stage {
+    name: &'static str,
+    next: Option<Addr<Stage>>,
+    seen: Seen,
+}
+
+#[async_trait]
+impl Actor for Stage {
+    type Msg = Request<u32, ()>;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let request = ctx.message().await;
+        self.seen
+            .lock()
+            .unwrap()
+            .push((self.name, ctx.current_trace()));
+        if let Some(next) = &self.next {
+            next.request(*request.payload()).await.unwrap();
+        }
+        let _ = request.respond(());
+    }
+}
+
+fn chain(agency: &Agency) -> (Addr<Stage>, Seen) {
+    let seen = Seen::default();
+    let back = agency.hire(Stage {
+        name: "back",
+        next: None,
+        seen: seen.clone(),
+    });
+    let middle = agency.hire(Stage {
+        name: "middle",
+        next: Some(back),
+        seen: seen.clone(),
+    });
+    let front = agency.hire(Stage {
+        name: "front",
+        next: Some(middle),
+        seen: seen.clone(),
+    });
+    (front, seen)
+}
+
+/// Each flow's trace, checking every stage saw the same one, in order.
+fn traces(seen: &Seen) -> Vec<TraceId> {
+    let seen = seen.lock().unwrap();
+    seen.chunks(3)
+        .map(|flow| {
+            let names: Vec<_> = flow.iter().map(|(name, _)| *name).collect();
+            assert_eq!(names, vec!["front", "middle", "back"]);
+            let trace = flow[0].1.expect("the message wasn't traced");
+            assert!(flow.iter().all(|(_, seen)| *seen == Some(trace)));
+            trace
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn a_trace_follows_a_flow_end_to_end() {
+    let (agency, handle) = Agency::new();
+    let (front, seen) = chain(&agency);
+
+    front.request(1).await.unwrap();
+    front.request(2).await.unwrap();
+    let traces = traces(&seen);
+    assert_eq!(traces.len(), 2);
+    // Each message from outside the agency starts a trace of its own
+    assert_ne!(traces[0], traces[1]);
+    assert_eq!(TraceId::current(), None);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn an_existing_trace_can_be_continued() {
+    let (agency, handle) = Agency::new();
+    let (front, seen) = chain(&agency);
+
+    let trace = TraceId::new();
+    trace
+        .scope(async {
+            assert_eq!(TraceId::current(), Some(trace));
+            front.request(1).await.unwrap();
+        })
+        .await;
+    assert_eq!(traces(&seen), vec![trace]);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn priority_sends_carry_the_trace_too() {
+    let (agency, handle) = Agency::new();
+    let (front, seen) = chain(&agency);
+
+    let trace = TraceId::new();
+    trace
+        .scope(async { front.request_priority(1).await.unwrap() })
+        .await;
+    assert_eq!(traces(&seen), vec![trace]);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[test]
+fn trace_ids_display_as_their_uuid() {
+    let trace = TraceId::new();
+    assert_eq!(trace.to_string(), trace.as_uuid().to_string());
+    assert_eq!(TraceId::from(trace.as_uuid()), trace);
+}
@@ -0,0 +1,279 @@
+#![cfg(feature = "tracing")]
+
+use agency::{prelude::*, ActorId};
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+use tracing::{
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+    Event, Metadata, Subscriber,
+};
+use tracing_core::span::Current;
+
+type Fields = HashMap<&'static str, String>;
+
+struct Visitor<'a>(&'a mut Fields);
+
+impl Visit for Visitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0.insert(field.name(), format!("{:?}", value));
+    }
+}
+
+struct Span {
+    metadata: &'static Metadata<'static>,
+    fields: Fields,
+}
+
+#[derive(Debug, Clone)]
+struct Recorded {
+    message: String,
+    fields: Fields,
+    /// The fields of the span the event was recorded in.
+    span: Option<Fields>,
+}
+
+#[derive(Default)]
+struct State {
+    spans: HashMap<u64, Span>,
+    entered: Vec<u64>,
+    events: Vec<Recorded>,
+}
+
+/// Records every span and event, for a test on a single-threaded runtime.
+#[derive(Clone, Default)]
+struct Recorder {
+    next_id: Arc<AtomicU64>,
+    state: Arc<Mutex<State>>,
+}
+
+impl Recorder {
+    fn events(&self) -> Vec<Recorded> {
+        self.state.lock().unwrap().events.clone()
+    }
+
+    /// The fields of every actor span with the given name.
+    fn actor_spans(&self, name: &str) -> Vec<Fields> {
+        let state = self.state.lock().unwrap();
+        state
+            .spans
+            .values()
+            .filter(|span| span.metadata.name() == "actor")
+            .filter(|span| span.fields.get("name").map(String::as_str) == Some(name))
+            .map(|span| span.fields.clone())
+            .collect()
+    }
+
+    /// The lifecycle events recorded in the span of the actor with the given id, in order.
+    fn lifecycle(&self, id: ActorId) -> Vec<String> {
+        let id = id.to_string();
+        self.events()
+            .into_iter()
+            .filter(|event| {
+                event.span.as_ref().and_then(|span| span.get("id")) == Some(&id)
+                    && !event.message.starts_with("sending")
+            })
+            .map(|event| event.message)
+            .collect()
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let mut fields = Fields::new();
+        span.record(&mut Visitor(&mut fields));
+        let span = Span {
+            metadata: span.metadata(),
+            fields,
+        };
+        self.state.lock().unwrap().spans.insert(id, span);
+        Id::from_u64(id)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(span) = state.spans.get_mut(&span.into_u64()) {
+            values.record(&mut Visitor(&mut span.fields));
+        }
+    }
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Fields::new();
+        event.record(&mut Visitor(&mut fields));
+        let message = fields.remove("message").unwrap_or_default();
+        let mut state = self.state.lock().unwrap();
+        let span = state
+            .entered
+            .last()
+            .and_then(|id| state.spans.get(id))
+            .map(|span| span.fields.clone());
+        state.events.push(Recorded {
+            message,
+            fields,
+            span,
+        });
+    }
+
+    fn enter(&self, span: &Id) {
+        self.state.lock().unwrap().entered.push(span.into_u64());
+    }
+
+    fn exit(&self, span: &Id) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(at) = state.entered.iter().rposition(|id| *id == span.into_u64()) {
+            state.entered.remove(at);
+        }
+    }
+
+    fn current_span(&self) -> Current {
+        let state = self.state.lock().unwrap();
+        match state
+            .entered
+            .last()
+            .and_then(|id| Some((*id, state.spans.get(id)?)))
+        {
+            Some((id, span)) => Current::new(Id::from_u64(id), span.metadata),
+            None => Current::none(),
+        }
+    }
+}
+
+/// Answers nothing, just takes whatever it's sent.
+struct Echo;
+
+#[async_trait]
+impl Actor for Echo {
+    type Msg = ();
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        ctx.message().await
+    }
+}
+
+enum Msg {
+    Greet(Request<(), ()>),
+    StopSelf,
+}
+
+impl From<Request<(), ()>> for Msg {
+    fn from(request: Request<(), ()>) -> Self {
+        Self::Greet(request)
+    }
+}
+
+/// Passes each greeting on to the echo, recovering from the first few stops.
+struct Greeter {
+    echo: Addr<Echo>,
+    recoveries: usize,
+}
+
+#[async_trait]
+impl Actor for Greeter {
+    type Msg = Msg;
+
+    fn name(&self) -> &str {
+        "greeter"
+    }
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        match ctx.message().await {
+            Msg::Greet(request) => {
+                let _ = self.echo.send(()).await;
+                let _ = request.respond(());
+            }
+            Msg::StopSelf => ctx.stop(),
+        }
+    }
+
+    async fn stopping(&mut self, _ctx: &mut Context<Self>) -> StoppingResult {
+        if self.recoveries == 0 {
+            return StoppingResult::Stop;
+        }
+        self.recoveries -= 1;
+        StoppingResult::Recover
+    }
+}
+
+#[tokio::test]
+async fn actor_spans_carry_the_name_id_and_who_was_last_sent_to() {
+    let recorder = Recorder::default();
+    let _default = tracing::subscriber::set_default(recorder.clone());
+    let (agency, handle) = Agency::new();
+    let echo = agency.hire(Echo);
+    let greeter = agency.hire(Greeter {
+        echo: echo.clone(),
+        recoveries: 0,
+    });
+
+    greeter.request(()).await.unwrap();
+
+    let spans = recorder.actor_spans("greeter");
+    assert_eq!(spans.len(), 1);
+    assert_eq!(spans[0]["id"], greeter.id().to_string());
+    assert_eq!(spans[0]["sent_to"], echo.id().to_string());
+    let echoes = recorder.actor_spans(std::any::type_name::<Echo>());
+    assert_eq!(echoes.len(), 1);
+    assert_eq!(echoes[0]["id"], echo.id().to_string());
+    assert!(!echoes[0].contains_key("sent_to"));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn lifecycle_events_are_recorded_in_the_actors_span() {
+    let recorder = Recorder::default();
+    let _default = tracing::subscriber::set_default(recorder.clone());
+    let (agency, handle) = Agency::new();
+    let echo = agency.hire(Echo);
+    let greeter = agency.hire(Greeter {
+        echo,
+        recoveries: 1,
+    });
+
+    greeter.send(Msg::StopSelf).await.unwrap();
+    greeter.request(()).await.unwrap();
+    greeter.stop();
+    greeter.watch().await;
+
+    assert_eq!(
+        recorder.lifecycle(greeter.id()),
+        vec![
+            "hired",
+            "initialised",
+            "stop requested",
+            "recovering",
+            "stopping",
+            "stopped",
+        ]
+    );
+    // Asked to stop from outside, so outside its span, but naming it
+    let id = greeter.id().to_string();
+    let requested: Vec<_> = recorder
+        .events()
+        .into_iter()
+        .filter(|event| event.message == "stop requested" && event.span.is_none())
+        .collect();
+    assert_eq!(requested.len(), 1);
+    assert_eq!(requested[0].fields["actor_id"], id);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
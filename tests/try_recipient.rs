@@ -0,0 +1,120 @@
+use agency::{prelude::*, DeliveryError, RequestError};
+use std::{collections::HashMap, convert::TryFrom};
+
+enum Msg {
+    Put(&'static str, u32),
+    Get(Request<&'static str, Option<u32>>),
+}
+
+/// What a pipeline stage passes on, only some of which the store wants.
+#[derive(Debug, PartialEq)]
+enum Event {
+    Put(&'static str, u32),
+    Log(&'static str),
+}
+
+impl TryFrom<Event> for Msg {
+    type Error = Event;
+
+    fn try_from(event: Event) -> Result<Self, Event> {
+        match event {
+            Event::Put(key, value) => Ok(Self::Put(key, value)),
+            event => Err(event),
+        }
+    }
+}
+
+/// Only lookups with a key are accepted.
+impl TryFrom<Request<&'static str, Option<u32>>> for Msg {
+    type Error = Request<&'static str, Option<u32>>;
+
+    fn try_from(request: Request<&'static str, Option<u32>>) -> Result<Self, Self::Error> {
+        if request.payload().is_empty() {
+            Err(request)
+        } else {
+            Ok(Self::Get(request))
+        }
+    }
+}
+
+#[derive(Default)]
+struct Store(HashMap<&'static str, u32>);
+
+#[async_trait]
+impl Actor for Store {
+    type Msg = Msg;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        match ctx.message().await {
+            Msg::Put(key, value) => {
+                self.0.insert(key, value);
+            }
+            Msg::Get(request) => {
+                let value = self.0.get(request.payload()).copied();
+                let _ = request.respond(value);
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn convertible_messages_are_delivered() {
+    let (agency, handle) = Agency::new();
+    let store = agency.hire(Store::default());
+    let events: Recipient<Event> = store.clone().try_recipient();
+    let lookups: Recipient<Request<&'static str, Option<u32>>> = store.clone().try_recipient();
+    assert_eq!(events.id(), store.id());
+
+    events.send(Event::Put("a", 1)).await.ok().unwrap();
+    events.try_send(Event::Put("b", 2)).ok().unwrap();
+    assert_eq!(lookups.request("a").await, Ok(Some(1)));
+    assert_eq!(lookups.request("b").await, Ok(Some(2)));
+    assert_eq!(lookups.request("c").await, Ok(None));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn rejected_messages_are_handed_back() {
+    let (agency, handle) = Agency::new();
+    let store = agency.hire(Store::default());
+    let events: Recipient<Event> = store.clone().try_recipient();
+
+    match events.send(Event::Log("hello")).await {
+        Err(DeliveryError::Incompatible(event)) => assert_eq!(event, Event::Log("hello")),
+        _ => panic!("the log event was delivered"),
+    }
+    match events.try_send(Event::Log("again")) {
+        Err(DeliveryError::Incompatible(event)) => assert_eq!(event, Event::Log("again")),
+        _ => panic!("the log event was delivered"),
+    }
+    assert!(matches!(
+        events.send_priority(Event::Log("urgent")),
+        Err(DeliveryError::Incompatible(Event::Log("urgent")))
+    ));
+    assert_eq!(store.mailbox_len(), 0);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn rejected_requests_fail_as_incompatible() {
+    let (agency, handle) = Agency::new();
+    let store = agency.hire(Store::default());
+    let lookups: Recipient<Request<&'static str, Option<u32>>> = store.clone().try_recipient();
+
+    assert_eq!(lookups.request("").await, Err(RequestError::Incompatible));
+    assert_eq!(
+        lookups.request_priority("").await,
+        Err(RequestError::Incompatible)
+    );
+    // Told apart from the actor having stopped
+    store.stop();
+    store.watch().await;
+    assert_eq!(lookups.request("a").await, Err(RequestError::ActorStopped));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,114 @@
+use agency::{prelude::*, WaitError};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+#[derive(Debug, PartialEq, Eq)]
+enum Msg {
+    Reply(u32),
+    Other(u32),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Seen {
+    Matched(Result<Msg, WaitError>),
+    Later(Msg),
+}
+
+/// Holds off until its gate opens, then waits for a reply before handling anything else.
+struct Protocol {
+    gate: Option<oneshot::Receiver<()>>,
+    seen: mpsc::UnboundedSender<Seen>,
+}
+
+#[async_trait]
+impl Actor for Protocol {
+    type Msg = Msg;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        if let Some(gate) = self.gate.take() {
+            let _ = gate.await;
+            let matched = ctx
+                .wait_for(|msg| matches!(msg, Msg::Reply(_)), Duration::from_secs(1))
+                .await;
+            let _ = self.seen.send(Seen::Matched(matched));
+            return;
+        }
+        let _ = self.seen.send(Seen::Later(ctx.message().await));
+    }
+}
+
+fn hire(
+    agency: &Agency,
+) -> (
+    Addr<Protocol>,
+    oneshot::Sender<()>,
+    mpsc::UnboundedReceiver<Seen>,
+) {
+    let (open, gate) = oneshot::channel();
+    let (seen, rx) = mpsc::unbounded_channel();
+    let addr = agency.hire(Protocol {
+        gate: Some(gate),
+        seen,
+    });
+    (addr, open, rx)
+}
+
+#[tokio::test]
+async fn non_matching_messages_are_delivered_afterwards_in_order() {
+    let (agency, handle) = Agency::new();
+    let (addr, open, mut seen) = hire(&agency);
+
+    addr.send(Msg::Other(1)).await.unwrap();
+    addr.send(Msg::Other(2)).await.unwrap();
+    addr.send(Msg::Reply(7)).await.unwrap();
+    addr.send(Msg::Other(3)).await.unwrap();
+    open.send(()).unwrap();
+
+    assert_eq!(seen.recv().await, Some(Seen::Matched(Ok(Msg::Reply(7)))));
+    for n in 1..=3 {
+        assert_eq!(seen.recv().await, Some(Seen::Later(Msg::Other(n))));
+    }
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn priority_messages_can_match() {
+    let (agency, handle) = Agency::new();
+    let (addr, open, mut seen) = hire(&agency);
+
+    addr.send(Msg::Other(1)).await.unwrap();
+    addr.send_priority(Msg::Reply(7)).unwrap();
+    open.send(()).unwrap();
+
+    assert_eq!(seen.recv().await, Some(Seen::Matched(Ok(Msg::Reply(7)))));
+    assert_eq!(seen.recv().await, Some(Seen::Later(Msg::Other(1))));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn nothing_is_lost_when_no_match_arrives_in_time() {
+    let (agency, handle) = Agency::new();
+    let (addr, open, mut seen) = hire(&agency);
+
+    addr.send(Msg::Other(1)).await.unwrap();
+    open.send(()).unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    addr.send(Msg::Other(2)).await.unwrap();
+
+    assert_eq!(
+        seen.recv().await,
+        Some(Seen::Matched(Err(WaitError::Timeout)))
+    );
+    assert_eq!(seen.recv().await, Some(Seen::Later(Msg::Other(1))));
+    assert_eq!(seen.recv().await, Some(Seen::Later(Msg::Other(2))));
+    // A late reply is just the next message
+    addr.send(Msg::Reply(7)).await.unwrap();
+    assert_eq!(seen.recv().await, Some(Seen::Later(Msg::Reply(7))));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
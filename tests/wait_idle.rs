@@ -0,0 +1,114 @@
+use agency::prelude::*;
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::time::{self, Instant};
+
+/// Passes each number on to the next stage, taking its time over each one.
+struct Stage {
+    work: Duration,
+    next: Recipient<u32>,
+}
+
+#[async_trait]
+impl Actor for Stage {
+    type Msg = u32;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        ctx.dispatch(self).await
+    }
+}
+
+#[async_trait]
+impl Handler for Stage {
+    async fn handle(&mut self, _ctx: &mut Context<Self>, n: u32) {
+        time::sleep(self.work).await;
+        let _ = self.next.send(n + 1).await;
+    }
+}
+
+/// The end of the pipeline, recording everything that makes it through.
+struct Sink(Arc<Mutex<Vec<u32>>>);
+
+#[async_trait]
+impl Actor for Sink {
+    type Msg = u32;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        ctx.dispatch(self).await
+    }
+}
+
+#[async_trait]
+impl Handler for Sink {
+    async fn handle(&mut self, _ctx: &mut Context<Self>, n: u32) {
+        time::sleep(Duration::from_millis(2)).await;
+        self.0.lock().unwrap().push(n);
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn resolves_once_a_burst_has_made_it_through_the_chain() {
+    let (agency, handle) = Agency::builder().capacity(4).build();
+    let effects = Arc::new(Mutex::new(Vec::new()));
+    let sink = agency.hire(Sink(effects.clone()));
+    let middle = agency.hire(Stage {
+        work: Duration::from_millis(5),
+        next: sink.recipient(),
+    });
+    let source = agency.hire(Stage {
+        work: Duration::from_millis(1),
+        next: middle.recipient(),
+    });
+
+    for n in 0..50u32 {
+        source.send(n).await.unwrap();
+    }
+    // Still making its way through, with the slowest stage well behind
+    assert!(effects.lock().unwrap().len() < 50);
+
+    agency.wait_idle().await;
+    assert_eq!(*effects.lock().unwrap(), (2..52).collect::<Vec<_>>());
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn resolves_straight_away_with_nothing_to_do() {
+    let (agency, handle) = Agency::new();
+    let effects = Arc::new(Mutex::new(Vec::new()));
+    agency.hire(Sink(effects));
+
+    let start = Instant::now();
+    agency.wait_idle().await;
+    assert!(start.elapsed() <= Duration::from_millis(50));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_paused_actor_with_work_waiting_keeps_it_from_resolving() {
+    let (agency, handle) = Agency::new();
+    let effects = Arc::new(Mutex::new(Vec::new()));
+    let sink = agency.hire(Sink(effects.clone()));
+    agency.wait_idle().await;
+
+    sink.pause();
+    while !sink.is_paused() {
+        time::sleep(Duration::from_millis(1)).await;
+    }
+    sink.send(1u32).await.unwrap();
+    assert!(time::timeout(Duration::from_secs(1), agency.wait_idle())
+        .await
+        .is_err());
+
+    sink.resume();
+    agency.wait_idle().await;
+    assert_eq!(*effects.lock().unwrap(), vec![1]);
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
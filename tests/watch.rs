@@ -0,0 +1,149 @@
+use agency::{prelude::*, InitAbort};
+use std::{ops::ControlFlow, time::Duration};
+use tokio::{sync::mpsc, time};
+
+#[derive(Clone, Copy)]
+enum Fate {
+    Run,
+    AbortInit,
+    Panic,
+}
+
+/// Goes however it's told to, or fails to set up without being told.
+struct Dependency {
+    fate: Fate,
+}
+
+#[async_trait]
+impl Actor for Dependency {
+    type Msg = ();
+
+    async fn try_init(&mut self, _ctx: &mut Context<Self>) -> ControlFlow<InitAbort> {
+        match self.fate {
+            Fate::AbortInit => ControlFlow::Break(InitAbort::new("told to")),
+            _ => ControlFlow::Continue(()),
+        }
+    }
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        if let Fate::Panic = self.fate {
+            panic!("told to");
+        }
+        ctx.message().await
+    }
+}
+
+#[async_trait]
+impl Setup for Dependency {
+    type Args = Option<Fate>;
+
+    async fn setup(_ctx: &mut Context<Self>, fate: Option<Fate>) -> Option<Self> {
+        Some(Self { fate: fate? })
+    }
+}
+
+enum Msg {
+    Watch(Addr<Dependency>, &'static str),
+    Down(&'static str),
+}
+
+/// Watches whatever it's told to, recording each one that goes down.
+struct Watcher(mpsc::UnboundedSender<&'static str>);
+
+#[async_trait]
+impl Actor for Watcher {
+    type Msg = Msg;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        match ctx.message().await {
+            Msg::Watch(addr, name) => {
+                ctx.watch(&addr, Msg::Down(name));
+            }
+            Msg::Down(name) => {
+                let _ = self.0.send(name);
+            }
+        }
+    }
+}
+
+fn watcher(agency: &Agency) -> (Addr<Watcher>, mpsc::UnboundedReceiver<&'static str>) {
+    let (down, rx) = mpsc::unbounded_channel();
+    (agency.hire(Watcher(down)), rx)
+}
+
+async fn stops(addr: &Addr<Dependency>) {
+    time::timeout(Duration::from_secs(1), addr.watch())
+        .await
+        .expect("the watch never fired");
+}
+
+#[tokio::test]
+async fn watching_an_actor_that_already_stopped_fires_straight_away() {
+    let (agency, handle) = Agency::new();
+    let (watcher, mut down) = watcher(&agency);
+    let dependency = agency.hire_with::<Dependency>(Some(Fate::Run));
+    dependency.stop();
+    stops(&dependency).await;
+
+    // Again, once it's long gone
+    stops(&dependency).await;
+    watcher
+        .send(Msg::Watch(dependency, "dependency"))
+        .await
+        .unwrap();
+    assert_eq!(down.recv().await, Some("dependency"));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn watching_an_actor_that_fails_to_set_up_fires() {
+    let (agency, handle) = Agency::new();
+    let (watcher, mut down) = watcher(&agency);
+    let dependency = agency.hire_with::<Dependency>(None);
+    watcher
+        .send(Msg::Watch(dependency.clone(), "dependency"))
+        .await
+        .unwrap();
+
+    stops(&dependency).await;
+    assert_eq!(down.recv().await, Some("dependency"));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn watching_an_actor_that_aborts_its_init_fires() {
+    let (agency, handle) = Agency::new();
+    let (watcher, mut down) = watcher(&agency);
+    let dependency = agency.hire_with::<Dependency>(Some(Fate::AbortInit));
+    watcher
+        .send(Msg::Watch(dependency.clone(), "dependency"))
+        .await
+        .unwrap();
+
+    stops(&dependency).await;
+    assert_eq!(down.recv().await, Some("dependency"));
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
+
+#[tokio::test]
+async fn watching_an_actor_that_panics_fires() {
+    let (agency, handle) = Agency::new();
+    let (watcher, mut down) = watcher(&agency);
+    let dependency = agency.hire_with::<Dependency>(Some(Fate::Panic));
+    watcher
+        .send(Msg::Watch(dependency.clone(), "dependency"))
+        .await
+        .unwrap();
+
+    stops(&dependency).await;
+    assert_eq!(down.recv().await, Some("dependency"));
+
+    agency.shutdown();
+    assert_eq!(handle.wait().await.len(), 1);
+}
@@ -0,0 +1,62 @@
+use agency::{prelude::*, ActorStalled, Observer};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::time;
+
+/// Records every stall.
+#[derive(Clone, Default)]
+struct Stalls(Arc<Mutex<Vec<ActorStalled>>>);
+
+impl Observer for Stalls {
+    fn stall_threshold(&self) -> Option<Duration> {
+        Some(Duration::from_secs(1))
+    }
+
+    fn actor_stalled(&self, event: &ActorStalled) {
+        self.0.lock().unwrap().push(event.clone());
+    }
+}
+
+/// Sleeps for as long as each message says.
+struct Sleeper;
+
+#[async_trait]
+impl Actor for Sleeper {
+    type Msg = Duration;
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let nap = ctx.message().await;
+        time::sleep(nap).await;
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn reports_an_actor_wedged_with_messages_waiting_once() {
+    let stalls = Stalls::default();
+    let (agency, handle) = Agency::builder().observer(stalls.clone()).build();
+    let wedged = agency.hire(Sleeper);
+    let idle = agency.hire(Sleeper);
+
+    wedged.send(Duration::from_secs(10)).await.unwrap();
+    wedged.send(Duration::ZERO).await.unwrap();
+    wedged.send(Duration::ZERO).await.unwrap();
+    time::sleep(Duration::from_secs(5)).await;
+
+    {
+        let stalls = stalls.0.lock().unwrap();
+        assert_eq!(stalls.len(), 1);
+        assert_eq!(stalls[0].actor_id, wedged.id());
+        assert_eq!(stalls[0].mailbox_depth, 2);
+        assert!(stalls[0].stalled_for >= Duration::from_secs(1));
+    }
+
+    // Once it gets going again there's nothing more to report, and the idle actor never stalls
+    time::sleep(Duration::from_secs(20)).await;
+    let stalls = stalls.0.lock().unwrap().clone();
+    assert_eq!(stalls.len(), 1);
+    assert!(stalls.iter().all(|stall| stall.actor_id != idle.id()));
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}
@@ -0,0 +1,76 @@
+use agency::{prelude::*, WeakAddr, WeakRecipient};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, oneshot};
+
+type Registration = (WeakAddr<Service>, WeakRecipient<u32>);
+
+/// Where actors register themselves, without the registration keeping them alive.
+#[derive(Clone, Default)]
+struct Registry(Arc<Mutex<Option<Registration>>>);
+
+impl Registry {
+    fn addr(&self) -> Option<Addr<Service>> {
+        self.0.lock().unwrap().as_ref().unwrap().0.upgrade()
+    }
+
+    fn recipient(&self) -> Option<Recipient<u32>> {
+        self.0.lock().unwrap().as_ref().unwrap().1.upgrade()
+    }
+}
+
+struct Service {
+    registry: Registry,
+    seen: mpsc::UnboundedSender<u32>,
+    stopped: Option<oneshot::Sender<()>>,
+}
+
+#[async_trait]
+impl Actor for Service {
+    type Msg = u32;
+
+    async fn init(&mut self, ctx: &mut Context<Self>) {
+        *self.registry.0.lock().unwrap() = Some((ctx.address_weak(), ctx.weak_recipient()));
+    }
+
+    async fn run(&mut self, ctx: &mut Context<Self>) {
+        let _ = self.seen.send(ctx.message().await);
+    }
+
+    async fn stopping(&mut self, _ctx: &mut Context<Self>) -> StoppingResult {
+        let _ = self.stopped.take().unwrap().send(());
+        StoppingResult::Stop
+    }
+}
+
+#[tokio::test]
+async fn weak_registrations_stop_upgrading_once_the_actor_stops() {
+    let (agency, handle) = Agency::new();
+    let registry = Registry::default();
+    let (seen, mut rx) = mpsc::unbounded_channel();
+    let (stopped, on_stop) = oneshot::channel();
+    let addr = agency.hire(Service {
+        registry: registry.clone(),
+        seen,
+        stopped: Some(stopped),
+    });
+
+    // Use the strong address to make sure init has registered
+    addr.send(1u32).await.unwrap();
+    assert_eq!(rx.recv().await, Some(1));
+
+    // The registrations work while the actor is running
+    registry.addr().unwrap().send(2u32).await.unwrap();
+    assert_eq!(rx.recv().await, Some(2));
+    registry.recipient().unwrap().send(3u32).await.unwrap();
+    assert_eq!(rx.recv().await, Some(3));
+    assert_eq!(registry.addr().unwrap().id(), addr.id());
+
+    // Neither the registrations nor the actor's own address keep it running
+    drop(addr);
+    on_stop.await.unwrap();
+    assert!(registry.addr().is_none());
+    assert!(registry.recipient().is_none());
+
+    agency.shutdown();
+    assert!(handle.wait().await.is_empty());
+}